@@ -1,68 +1,330 @@
 use proc_macro::TokenStream;
 use syn::DeriveInput;
 
+const CONTAINER_USAGE: &str = "Only #[fitsio(rename_all = \"...\")] and, for FitsRow, \
+                               #[fitsio(writable)] are supported on structs";
+const FIELD_USAGE: &str = "Only #[fitsio(colname = \"...\")], #[fitsio(skip)], \
+                           #[fitsio(with = \"...\")] and #[fitsio(repr = \"...\")] are supported";
+const HEADER_FIELD_USAGE: &str =
+    "Only #[fitsio(key = \"...\")] and #[fitsio(skip)] are supported on FitsHeader fields";
+
+/// How field names are converted to column names when no `colname` is given
+enum RenameRule {
+    None,
+    Lowercase,
+    Uppercase,
+    PascalCase,
+    CamelCase,
+    ScreamingSnakeCase,
+}
+
+impl RenameRule {
+    fn from_str(rule: &str) -> Self {
+        match rule {
+            "lowercase" => RenameRule::Lowercase,
+            "UPPERCASE" => RenameRule::Uppercase,
+            "PascalCase" => RenameRule::PascalCase,
+            "camelCase" => RenameRule::CamelCase,
+            "SCREAMING_SNAKE_CASE" => RenameRule::ScreamingSnakeCase,
+            other => panic!(
+                "Unknown rename_all rule {:?}, expected one of \"lowercase\", \"UPPERCASE\", \
+                 \"PascalCase\", \"camelCase\" or \"SCREAMING_SNAKE_CASE\"",
+                other
+            ),
+        }
+    }
+
+    fn apply(&self, field: &str) -> String {
+        match self {
+            RenameRule::None => field.to_string(),
+            RenameRule::Lowercase => field.to_lowercase(),
+            RenameRule::Uppercase | RenameRule::ScreamingSnakeCase => field.to_uppercase(),
+            RenameRule::PascalCase => field
+                .split('_')
+                .map(|word| {
+                    let mut chars = word.chars();
+                    match chars.next() {
+                        Some(first) => first.to_uppercase().chain(chars).collect(),
+                        None => String::new(),
+                    }
+                })
+                .collect(),
+            RenameRule::CamelCase => {
+                let pascal = RenameRule::PascalCase.apply(field);
+                let mut chars = pascal.chars();
+                match chars.next() {
+                    Some(first) => first.to_lowercase().chain(chars).collect(),
+                    None => String::new(),
+                }
+            }
+        }
+    }
+}
+
+/// Options given to a single field with `#[fitsio(...)]`
+#[derive(Default)]
+struct FieldOptions {
+    colname: Option<syn::LitStr>,
+    key: Option<syn::LitStr>,
+    skip: bool,
+    with: Option<syn::Path>,
+    repr: Option<syn::Type>,
+}
+
+/// Iterate over the entries of every `#[fitsio(...)]` attribute
+fn fitsio_attribute_entries(attrs: &[syn::Attribute], usage: &str) -> Vec<syn::NestedMeta> {
+    let mut entries = Vec::new();
+    for attr in attrs {
+        if !attr.path.is_ident("fitsio") {
+            continue;
+        }
+        match attr.parse_meta() {
+            Ok(syn::Meta::List(l)) => entries.extend(l.nested),
+            _ => panic!("{}", usage),
+        }
+    }
+    entries
+}
+
+/// Options given to a struct with `#[fitsio(...)]`
+struct ContainerOptions {
+    rename_rule: RenameRule,
+    writable: bool,
+}
+
+fn container_options(attrs: &[syn::Attribute]) -> ContainerOptions {
+    let mut options = ContainerOptions {
+        rename_rule: RenameRule::None,
+        writable: false,
+    };
+    for entry in fitsio_attribute_entries(attrs, CONTAINER_USAGE) {
+        match entry {
+            syn::NestedMeta::Meta(syn::Meta::NameValue(syn::MetaNameValue {
+                path,
+                lit: syn::Lit::Str(ls),
+                ..
+            })) if path.is_ident("rename_all") => {
+                options.rename_rule = RenameRule::from_str(&ls.value())
+            }
+            syn::NestedMeta::Meta(syn::Meta::Path(path)) if path.is_ident("writable") => {
+                options.writable = true;
+            }
+            _ => panic!("{}", CONTAINER_USAGE),
+        }
+    }
+    options
+}
+
+fn field_options(field: &syn::Field, usage: &str) -> FieldOptions {
+    let mut options = FieldOptions::default();
+    for entry in fitsio_attribute_entries(&field.attrs, usage) {
+        match entry {
+            syn::NestedMeta::Meta(syn::Meta::Path(path)) if path.is_ident("skip") => {
+                options.skip = true;
+            }
+            syn::NestedMeta::Meta(syn::Meta::NameValue(syn::MetaNameValue {
+                path,
+                lit: syn::Lit::Str(ls),
+                ..
+            })) => {
+                if path.is_ident("colname") {
+                    options.colname = Some(ls);
+                } else if path.is_ident("key") {
+                    options.key = Some(ls);
+                } else if path.is_ident("with") {
+                    let with = ls
+                        .parse()
+                        .unwrap_or_else(|_| panic!("Invalid path {:?} for with", ls.value()));
+                    options.with = Some(with);
+                } else if path.is_ident("repr") {
+                    let repr = ls
+                        .parse()
+                        .unwrap_or_else(|_| panic!("Invalid type {:?} for repr", ls.value()));
+                    options.repr = Some(repr);
+                } else {
+                    panic!("{}", usage);
+                }
+            }
+            _ => panic!("{}", usage),
+        }
+    }
+    options
+}
+
+/// How a `FitsRow` field finds its column
+enum ColumnRef {
+    /// The column with the given name
+    Name(syn::LitStr),
+    /// The column at the position of a tuple struct field, counting from 0
+    Position(usize),
+}
+
 #[proc_macro_derive(FitsRow, attributes(fitsio))]
 pub fn read_row(input: TokenStream) -> TokenStream {
     let input: DeriveInput = syn::parse(input).unwrap();
     let name = &input.ident;
+    let container = container_options(&input.attrs);
+    let rename_rule = container.rename_rule;
 
     let mut tokens = Vec::new();
+    let mut range_reads = Vec::new();
+    let mut range_tokens = Vec::new();
+    let mut write_tokens = Vec::new();
 
-    match input.data {
+    // Each field with the member accessing it and the column it is read from by default
+    let fields: Vec<(&syn::Field, syn::Member, ColumnRef)> = match input.data {
         syn::Data::Struct(ref s) => match s.fields {
-            syn::Fields::Named(ref fields) => {
-                for field in &fields.named {
-                    let ident = &field.ident.as_ref().unwrap();
-                    let ident_str = ident.to_string();
-                    if field.attrs.is_empty() {
-                        let src = quote::quote! {
-                            out.#ident = tbl.read_cell_value(fits_file, #ident_str, idx)?;
-                        };
-                        tokens.push(src);
-                    } else {
-                        for attr in &field.attrs {
-                            match attr.parse_meta() {
-                                Ok(syn::Meta::List(l)) => {
-                                    for entry in l.nested {
-                                        match entry {
-                                            syn::NestedMeta::Meta(syn::Meta::NameValue(
-                                                syn::MetaNameValue { path, lit, .. },
-                                            )) => {
-                                                if !path.is_ident("colname") {
-                                                    continue;
-                                                }
-
-                                                match lit {
-                                                    syn::Lit::Str(ls) => {
-                                                        tokens.push(quote::quote! {
-                                                            out.#ident = tbl.read_cell_value(
-                                                                fits_file,
-                                                                #ls,
-                                                                idx)?;
-                                                        });
-                                                    }
-                                                    _ => panic!(
-                                                "Only #[fitsio(colname = \"...\")] is supported"
-                                            ),
-                                                }
-                                            }
-                                            _ => panic!(
-                                                "Only #[fitsio(colname = \"...\")] is supported"
-                                            ),
-                                        }
-                                    }
-                                }
-                                _ => panic!("Only #[fitsio(colname = \"...\")] is supported"),
-                            }
-                        }
-                    }
-                }
-            }
-            _ => panic!("Only #[fitsio(colname = \"...\")] is supported"),
+            syn::Fields::Named(ref fields) => fields
+                .named
+                .iter()
+                .map(|field| {
+                    let ident = field.ident.as_ref().unwrap();
+                    let colname =
+                        syn::LitStr::new(&rename_rule.apply(&ident.to_string()), ident.span());
+                    (
+                        field,
+                        syn::Member::Named(ident.clone()),
+                        ColumnRef::Name(colname),
+                    )
+                })
+                .collect(),
+            syn::Fields::Unnamed(ref fields) => fields
+                .unnamed
+                .iter()
+                .enumerate()
+                .map(|(i, field)| {
+                    let index = syn::Index::from(i);
+                    (field, syn::Member::Unnamed(index), ColumnRef::Position(i))
+                })
+                .collect(),
+            syn::Fields::Unit => panic!("{}", FIELD_USAGE),
         },
         _ => panic!("derive only possible for structs"),
+    };
+
+    let mut positional = false;
+    for (field, accessor, column) in fields {
+        let options = field_options(field, FIELD_USAGE);
+        if options.key.is_some() || (options.with.is_some() && options.repr.is_some()) {
+            panic!("{}", FIELD_USAGE);
+        }
+        if options.skip {
+            continue;
+        }
+
+        let column = match options.colname {
+            Some(colname) => ColumnRef::Name(colname),
+            None => column,
+        };
+        let (colname, write_colname, set) = match column {
+            ColumnRef::Name(colname) => (
+                quote::quote! { #colname },
+                quote::quote! { #colname },
+                quote::quote! { row.set(#colname, value)?; },
+            ),
+            ColumnRef::Position(i) => {
+                positional = true;
+                let position = i.to_string();
+                (
+                    quote::quote! { ::fitsio::tables::column_at(&column_names, #i)? },
+                    quote::quote! { #position },
+                    quote::quote! { row.set_at(#i, value)?; },
+                )
+            }
+        };
+
+        let column = quote::format_ident!("column_{}", range_reads.len());
+        let next_cell = quote::quote! { ::fitsio::tables::next_cell(&mut #column)? };
+        let (src, range_read, range_src, write_src) = match (options.with, options.repr) {
+            (Some(with), _) => (
+                quote::quote! {
+                    out.#accessor = #with(tbl.read_cell_value(fits_file, #colname, idx)?)?;
+                },
+                quote::quote! {
+                    let mut #column =
+                        ::fitsio::tables::ReadsCol::read_col_range(fits_file, #colname, range)?
+                            .into_iter();
+                },
+                quote::quote! {
+                    out.#accessor = #with(#next_cell)?;
+                },
+                quote::quote! {
+                    row.reject_converted(#write_colname)?;
+                },
+            ),
+            (None, Some(repr)) => {
+                let convert = quote::quote! {
+                    out.#accessor = ::std::convert::TryFrom::try_from(value).map_err(|_| {
+                        ::fitsio::errors::Error::Message(format!(
+                            "invalid value {} in column {:?}",
+                            value, #write_colname
+                        ))
+                    })?;
+                };
+                (
+                    quote::quote! {
+                        let value: #repr = tbl.read_cell_value(fits_file, #colname, idx)?;
+                        #convert
+                    },
+                    quote::quote! {
+                        let mut #column =
+                            <#repr as ::fitsio::tables::ReadsCol>::read_col_range(
+                                fits_file, #colname, range
+                            )?
+                            .into_iter();
+                    },
+                    quote::quote! {
+                        let value = #next_cell;
+                        #convert
+                    },
+                    quote::quote! {
+                        let value = ::std::clone::Clone::clone(&self.#accessor) as #repr;
+                        #set
+                    },
+                )
+            }
+            (None, None) => (
+                quote::quote! {
+                    out.#accessor = tbl.read_cell_value(fits_file, #colname, idx)?;
+                },
+                quote::quote! {
+                    let mut #column =
+                        ::fitsio::tables::ReadsCol::read_col_range(fits_file, #colname, range)?
+                            .into_iter();
+                },
+                quote::quote! {
+                    out.#accessor = #next_cell;
+                },
+                quote::quote! {
+                    let value = ::std::clone::Clone::clone(&self.#accessor);
+                    #set
+                },
+            ),
+        };
+        tokens.push(src);
+        range_reads.push(range_read);
+        range_tokens.push(range_src);
+        write_tokens.push(quote::quote! { { #write_src } });
     }
 
+    // Writing rows needs more of each field type than reading, so is only generated on request
+    let write_row = if container.writable {
+        quote::quote! {
+            fn write_row(&self, row: &mut ::fitsio::tables::RowBuffer) -> ::fitsio::errors::Result<()> {
+                #(#write_tokens)*
+                Ok(())
+            }
+        }
+    } else {
+        quote::quote! {}
+    };
+
+    let column_names = if positional {
+        quote::quote! { let column_names = tbl.column_names(fits_file)?; }
+    } else {
+        quote::quote! {}
+    };
+
     let expanded = quote::quote! {
         impl FitsRow for #name {
             fn from_table(
@@ -70,11 +332,100 @@ pub fn read_row(input: TokenStream) -> TokenStream {
                 fits_file: &mut ::fitsio::FitsFile, idx: usize) ->
                     ::fitsio::errors::Result<Self> where Self: Sized  {
                 let mut out = Self::default();
+                #column_names
 
-                #(#tokens)*
+                #({ #tokens })*
 
                 Ok(out)
             }
+
+            fn from_table_range(
+                tbl: &::fitsio::hdu::FitsHdu,
+                fits_file: &mut ::fitsio::FitsFile,
+                range: &::std::ops::Range<usize>) ->
+                    ::fitsio::errors::Result<::std::vec::Vec<Self>> where Self: Sized {
+                #column_names
+                #(#range_reads)*
+
+                let mut rows = ::std::vec::Vec::with_capacity(range.len());
+                for _ in range.clone() {
+                    let mut out = Self::default();
+                    #({ #range_tokens })*
+                    rows.push(out);
+                }
+                Ok(rows)
+            }
+
+            #write_row
+        }
+    };
+    expanded.into()
+}
+
+#[proc_macro_derive(FitsHeader, attributes(fitsio))]
+pub fn header(input: TokenStream) -> TokenStream {
+    let input: DeriveInput = syn::parse(input).unwrap();
+    let name = &input.ident;
+    let container = container_options(&input.attrs);
+    if container.writable {
+        panic!("{}", CONTAINER_USAGE);
+    }
+    let rename_rule = container.rename_rule;
+
+    let mut read_tokens = Vec::new();
+    let mut write_tokens = Vec::new();
+
+    match input.data {
+        syn::Data::Struct(ref s) => match s.fields {
+            syn::Fields::Named(ref fields) => {
+                for field in &fields.named {
+                    let ident = &field.ident.as_ref().unwrap();
+                    let options = field_options(field, HEADER_FIELD_USAGE);
+                    if options.colname.is_some() || options.with.is_some() {
+                        panic!("{}", HEADER_FIELD_USAGE);
+                    }
+                    if options.skip {
+                        read_tokens.push(quote::quote! {
+                            #ident: ::std::default::Default::default(),
+                        });
+                        continue;
+                    }
+
+                    let key = options.key.unwrap_or_else(|| {
+                        syn::LitStr::new(&rename_rule.apply(&ident.to_string()), ident.span())
+                    });
+
+                    read_tokens.push(quote::quote! {
+                        #ident: hdu.read_key(fits_file, #key)?,
+                    });
+                    write_tokens.push(quote::quote! {
+                        hdu.write_key(fits_file, #key, ::std::clone::Clone::clone(&self.#ident))?;
+                    });
+                }
+            }
+            _ => panic!("{}", HEADER_FIELD_USAGE),
+        },
+        _ => panic!("derive only possible for structs"),
+    }
+
+    let expanded = quote::quote! {
+        impl ::fitsio::headers::FitsHeader for #name {
+            fn from_header(
+                hdu: &::fitsio::hdu::FitsHdu,
+                fits_file: &mut ::fitsio::FitsFile) ->
+                    ::fitsio::errors::Result<Self> where Self: Sized {
+                Ok(#name {
+                    #(#read_tokens)*
+                })
+            }
+
+            fn write_to_header(
+                &self,
+                hdu: &::fitsio::hdu::FitsHdu,
+                fits_file: &mut ::fitsio::FitsFile) -> ::fitsio::errors::Result<()> {
+                #(#write_tokens)*
+                Ok(())
+            }
         }
     };
     expanded.into()
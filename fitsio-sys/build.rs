@@ -1,24 +1,243 @@
 use std::path::PathBuf;
 
+/// Record which optional libraries cfitsio links against, for `fitsio_sys::capabilities`
+fn emit_capabilities(curl: bool, bzip2: bool) {
+    println!("cargo:rustc-env=FITSIO_SYS_CFITSIO_CURL={}", curl as u8);
+    println!("cargo:rustc-env=FITSIO_SYS_CFITSIO_BZIP2={}", bzip2 as u8);
+}
+
+/// Inspect the private (static) link libraries of a system cfitsio
 #[allow(dead_code)]
-fn compile_cfitsio() -> PathBuf {
-    use autotools::Config;
+fn emit_system_capabilities(package_name: &str) {
+    let libs = pkg_config::Config::new()
+        .statik(true)
+        .cargo_metadata(false)
+        .probe(package_name)
+        .map(|lib| lib.libs)
+        .unwrap_or_default();
+    let links = |name: &str| libs.iter().any(|l| l == name);
+    emit_capabilities(links("curl"), links("bz2"));
+}
+
+/// Version of the `cfitsio` source bundled in `ext/cfitsio`
+#[allow(dead_code)]
+const BUNDLED_CFITSIO_VERSION: &str = "3.49";
+
+/// Where `cfitsio` release tarballs are downloaded from
+#[allow(dead_code)]
+const CFITSIO_RELEASES_URL: &str = "https://heasarc.gsfc.nasa.gov/FTP/software/fitsio/c";
+
+/// Read the `CFITSIO_VERSION` definition from the `fitsio.h` of a source tree
+#[allow(dead_code)]
+fn source_version(source_dir: &std::path::Path) -> Option<String> {
+    let header = std::fs::read_to_string(source_dir.join("fitsio.h")).ok()?;
+    header.lines().find_map(|line| {
+        let mut parts = line.split_whitespace();
+        match (parts.next(), parts.next(), parts.next()) {
+            (Some("#define"), Some("CFITSIO_VERSION"), Some(version)) => Some(version.to_string()),
+            _ => None,
+        }
+    })
+}
+
+/// Print an error message for the user and stop the build
+#[allow(dead_code)]
+fn fail(message: &str) -> ! {
+    use std::io::Write;
+
+    std::io::stderr()
+        .write_all(format!("\n{}\n", message).as_bytes())
+        .unwrap();
+    std::process::exit(1);
+}
+
+/// Compute the SHA-256 digest of a file with `sha256sum`, or `shasum` where that is missing
+#[allow(dead_code)]
+fn sha256_digest(path: &std::path::Path) -> Result<String, String> {
+    use std::process::Command;
+
+    let output = Command::new("sha256sum")
+        .arg(path)
+        .output()
+        .or_else(|_| {
+            Command::new("shasum")
+                .args(["-a", "256"])
+                .arg(path)
+                .output()
+        })
+        .map_err(|e| {
+            format!(
+                "Could not run sha256sum or shasum to verify the download: {}",
+                e
+            )
+        })?;
+    if !output.status.success() {
+        return Err(format!(
+            "Could not compute the digest of {}",
+            path.display()
+        ));
+    }
+    String::from_utf8_lossy(&output.stdout)
+        .split_whitespace()
+        .next()
+        .map(|digest| digest.to_lowercase())
+        .ok_or_else(|| format!("Could not compute the digest of {}", path.display()))
+}
 
-    let cfitsio_project_dir = PathBuf::from("ext/cfitsio");
-    if !cfitsio_project_dir.exists() {
+/**
+Download a `cfitsio` release, check its SHA-256 digest and unpack it, returning its source
+directory
+
+The tarball is only unpacked if its digest matches `expected_sha256`, so a release cannot be
+changed or replaced in transit without the build failing.
+*/
+#[allow(dead_code)]
+fn fetch_cfitsio(version: &str, expected_sha256: &str) -> Result<PathBuf, String> {
+    use std::process::Command;
+
+    let out_dir = PathBuf::from(std::env::var("OUT_DIR").unwrap());
+    let unpack_dir = out_dir.join(format!("cfitsio-src-{version}-{expected_sha256}"));
+    if !unpack_dir.exists() {
+        let tarball = out_dir.join(format!("cfitsio-{version}.tar.gz"));
+        let url = format!("{CFITSIO_RELEASES_URL}/cfitsio-{version}.tar.gz");
+        let status = Command::new("curl")
+            .args([
+                "--fail",
+                "--silent",
+                "--show-error",
+                "--location",
+                "--output",
+            ])
+            .arg(&tarball)
+            .arg(&url)
+            .status()
+            .map_err(|e| format!("Could not run curl to download {}: {}", url, e))?;
+        if !status.success() {
+            return Err(format!(
+                "Could not download cfitsio {} from {}. For offline builds, set FITSIO_SRC_DIR to an unpacked source tree instead",
+                version, url
+            ));
+        }
+
+        let digest = sha256_digest(&tarball)?;
+        if digest != expected_sha256.to_lowercase() {
+            let _ = std::fs::remove_file(&tarball);
+            return Err(format!(
+                "The SHA-256 digest of {} is {}, but FITSIO_SRC_SHA256 is {}. Refusing to build it",
+                url, digest, expected_sha256
+            ));
+        }
+
+        std::fs::create_dir_all(&unpack_dir).map_err(|e| e.to_string())?;
+        let status = Command::new("tar")
+            .arg("xzf")
+            .arg(&tarball)
+            .arg("-C")
+            .arg(&unpack_dir)
+            .status()
+            .map_err(|e| format!("Could not run tar to unpack {}: {}", tarball.display(), e))?;
+        if !status.success() {
+            let _ = std::fs::remove_dir_all(&unpack_dir);
+            return Err(format!("Could not unpack {}", tarball.display()));
+        }
+    }
+
+    // Older releases unpack to `cfitsio`, newer ones to `cfitsio-<version>`
+    std::fs::read_dir(&unpack_dir)
+        .map_err(|e| e.to_string())?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .find(|path| path.join("configure").exists())
+        .ok_or_else(|| format!("No cfitsio source found in {}", unpack_dir.display()))
+}
+
+/**
+Choose the `cfitsio` source to compile, and its version
+
+`FITSIO_SRC_DIR` selects an existing source tree, for example an already unpacked release for
+offline builds. Otherwise `FITSIO_SRC_VERSION` selects a release to download, unless it is the
+version bundled in `ext/cfitsio`, which is used by default. A downloaded release must be pinned
+with its SHA-256 digest in `FITSIO_SRC_SHA256`.
+*/
+#[allow(dead_code)]
+fn cfitsio_source() -> (PathBuf, String) {
+    // Emitting these disables the default of rerunning on any change, so list the inputs too.
+    // The source directory itself is not watched, as it is built in place.
+    println!("cargo:rerun-if-env-changed=FITSIO_SRC_DIR");
+    println!("cargo:rerun-if-env-changed=FITSIO_SRC_VERSION");
+    println!("cargo:rerun-if-env-changed=FITSIO_SRC_SHA256");
+    for path in [
+        "build.rs",
+        "wrapper.h",
+        "src/bindings_32.rs",
+        "src/bindings_64.rs",
+        "ext/cfitsio/fitsio.h",
+        "ext/cfitsio/longnam.h",
+    ] {
+        println!("cargo:rerun-if-changed={}", path);
+    }
+
+    let requested = std::env::var("FITSIO_SRC_VERSION").ok();
+    let source_dir = if let Some(dir) = std::env::var_os("FITSIO_SRC_DIR") {
+        PathBuf::from(dir)
+    } else {
+        match requested.as_deref() {
+            Some(version) if version != BUNDLED_CFITSIO_VERSION => {
+                let expected_sha256 = std::env::var("FITSIO_SRC_SHA256").unwrap_or_else(|_| {
+                    fail(&format!(
+                        "FITSIO_SRC_VERSION is {version}, which must be downloaded, but FITSIO_SRC_SHA256 is not set.
+Set FITSIO_SRC_SHA256 to the SHA-256 digest of {CFITSIO_RELEASES_URL}/cfitsio-{version}.tar.gz,
+or set FITSIO_SRC_DIR to an unpacked source tree instead."
+                    ))
+                });
+                fetch_cfitsio(version, &expected_sha256).unwrap_or_else(|e| fail(&e))
+            }
+            _ => PathBuf::from("ext/cfitsio"),
+        }
+    };
+
+    if !source_dir.exists() {
         panic!(
             "Expected to find cfitsio source directory {}",
-            cfitsio_project_dir.display()
+            source_dir.display()
         );
     }
     // Make sure the source directory isn't empty.
-    match std::fs::read_dir(&cfitsio_project_dir) {
+    match std::fs::read_dir(&source_dir) {
         Ok(mut d) => {
             if d.next().is_none() {
-                panic!("cfitsio source directory ext/cfitsio is empty!");
+                panic!(
+                    "cfitsio source directory {} is empty!",
+                    source_dir.display()
+                );
             }
         }
-        _ => panic!("Could not read from cfitsio source directory ext/cfitsio !"),
+        _ => panic!(
+            "Could not read from cfitsio source directory {} !",
+            source_dir.display()
+        ),
+    }
+
+    let version = source_version(&source_dir).unwrap_or_else(|| "unknown".to_string());
+    if let Some(requested) = requested {
+        if requested != version {
+            println!(
+                "cargo:warning=FITSIO_SRC_VERSION is {requested}, but the cfitsio source in {} is version {version}",
+                source_dir.display()
+            );
+        }
+    }
+    (source_dir, version)
+}
+
+#[allow(dead_code)]
+fn compile_cfitsio() -> PathBuf {
+    use autotools::Config;
+
+    let (cfitsio_project_dir, version) = cfitsio_source();
+    println!("cargo:rustc-env=FITSIO_SYS_CFITSIO_SRC_VERSION={version}");
+
+    if std::env::var("CARGO_CFG_TARGET_ARCH").as_deref() == Ok("wasm32") {
+        return compile_cfitsio_wasm(&cfitsio_project_dir);
     }
 
     // Translate rustc optimisation levels to things a C compiler can
@@ -35,7 +254,8 @@ fn compile_cfitsio() -> PathBuf {
 
     let opt_flag = format!("-O{opt_level}");
 
-    let dst = Config::new("ext/cfitsio")
+    let mut config = Config::new(&cfitsio_project_dir);
+    config
         .disable("curl", None)
         .enable_shared()
         .forbid("--enable-shared")
@@ -43,11 +263,159 @@ fn compile_cfitsio() -> PathBuf {
         .enable("reentrant", None)
         .cflag(opt_flag)
         .cflag("-fPIE")
-        .insource(true)
-        .build();
+        .insource(true);
+
+    // cfitsio 3 bundles zlib, later versions link against it
+    if !cfitsio_project_dir.join("zlib").exists() {
+        match (
+            std::env::var_os("DEP_Z_INCLUDE"),
+            std::env::var_os("DEP_Z_ROOT"),
+        ) {
+            // zlib built by `libz-sys`, which also emits the link flags
+            (Some(include), Some(root)) => {
+                let mut include_flag = std::ffi::OsString::from("-I");
+                include_flag.push(include);
+                let mut lib_flag = std::ffi::OsString::from("-L");
+                lib_flag.push(PathBuf::from(root).join("lib"));
+                config.cflag(include_flag).ldflag(lib_flag);
+            }
+            _ if link_statically() => println!("cargo:rustc-link-lib=static=z"),
+            _ => println!("cargo:rustc-link-lib=z"),
+        }
+    }
+
+    let dst = config.build();
+    emit_capabilities(false, false);
     dst
 }
 
+/// Read a list of source files, such as `CORE_SOURCES`, from the `cfitsio` `Makefile.in`
+#[allow(dead_code)]
+fn makefile_sources(makefile: &str, variable: &str) -> Vec<String> {
+    let mut sources = Vec::new();
+    let lines = makefile
+        .lines()
+        .skip_while(|line| !line.starts_with(variable));
+    for line in lines {
+        let line = line
+            .trim_start_matches(variable)
+            .trim_start_matches(|c: char| c.is_whitespace() || c == '=');
+        sources.extend(
+            line.split_whitespace()
+                .filter(|word| word.ends_with(".c"))
+                .map(|word| word.to_string()),
+        );
+        if !line.trim_end().ends_with('\\') {
+            break;
+        }
+    }
+    sources
+}
+
+/**
+Compile `cfitsio` for WebAssembly (WASI) targets
+
+`configure` cannot target WebAssembly, so the library sources listed in `Makefile.in` are
+compiled directly with the C compiler for the target, for example `clang` from the WASI SDK.
+There are no network or shared memory drivers, and the library is not reentrant, as WASI has no
+sockets or threads. Files are read with the memory driver, or the disk driver where the WASI
+runtime gives access to files.
+*/
+#[allow(dead_code)]
+fn compile_cfitsio_wasm(source_dir: &std::path::Path) -> PathBuf {
+    let makefile =
+        std::fs::read_to_string(source_dir.join("Makefile.in")).expect("Couldn't read Makefile.in");
+    let mut sources = makefile_sources(&makefile, "CORE_SOURCES");
+    sources.extend(makefile_sources(&makefile, "ZLIB_SOURCES"));
+    sources.retain(|source| source != "drvrnet.c" && source != "drvrsmem.c");
+
+    let out_dir = PathBuf::from(std::env::var("OUT_DIR").unwrap());
+    cc::Build::new()
+        .files(sources.iter().map(|source| source_dir.join(source)))
+        .include(source_dir)
+        .define("HAVE_UNISTD_H", None)
+        .define("HAVE_FTRUNCATE", None)
+        .warnings(false)
+        .cargo_metadata(false)
+        .out_dir(&out_dir)
+        .compile("cfitsio");
+
+    // Install the headers next to the library, as the autotools build does
+    for header in ["fitsio.h", "longnam.h"] {
+        std::fs::copy(source_dir.join(header), out_dir.join(header))
+            .expect("Couldn't copy cfitsio headers");
+    }
+    emit_capabilities(false, false);
+    out_dir
+}
+
+/// Whether to link `cfitsio` and its dependencies statically
+///
+/// This is the case with the `static` feature, and for musl targets, which are usually built as
+/// fully static binaries.
+#[allow(dead_code)]
+fn link_statically() -> bool {
+    cfg!(feature = "static") || std::env::var("CARGO_CFG_TARGET_ENV").as_deref() == Ok("musl")
+}
+
+/**
+Generate the long name aliases from `longnam.h`
+
+`longnam.h` defines each long name as a preprocessor macro for its short name, which bindgen
+cannot see, so the aliases are generated here rather than kept in a hand written list. Only
+names whose short name is a function in the bindings are aliased, so both backends expose the
+long names of exactly the functions they bind.
+*/
+fn write_long_names(include_dirs: &[PathBuf], bindings: &str, out_path: &std::path::Path) {
+    use std::collections::{BTreeMap, BTreeSet};
+    use std::fmt::Write;
+
+    let header = include_dirs
+        .iter()
+        .map(|dir| dir.join("longnam.h"))
+        .find(|path| path.exists())
+        .map(|path| std::fs::read_to_string(path).expect("Couldn't read longnam.h"))
+        .unwrap_or_else(|| {
+            println!("cargo:warning=Could not find longnam.h, long name aliases are not available");
+            String::new()
+        });
+
+    let mut aliases = BTreeMap::new();
+    for line in header.lines() {
+        let mut parts = line.split_whitespace();
+        if let (Some("#define"), Some(long_name), Some(short_name), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        {
+            aliases.insert(long_name, short_name);
+        }
+    }
+    // `fits_open_file` is a macro which checks the library version, so point it at `ffopen`
+    aliases.insert("fits_open_file", "ffopen");
+
+    // The bindings may or may not have been formatted, so find function names from the tokens
+    let functions: BTreeSet<&str> = bindings
+        .split("fn ")
+        .skip(1)
+        .map(|rest| {
+            let end = rest
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .unwrap_or(rest.len());
+            &rest[..end]
+        })
+        .collect();
+
+    let mut contents = String::from("// Generated from longnam.h by build.rs\n");
+    for (long_name, short_name) in aliases {
+        let is_function = long_name.starts_with("fits_")
+            && functions.contains(short_name)
+            && !functions.contains(long_name);
+        if is_function {
+            writeln!(contents, "pub use crate::{} as {};", short_name, long_name).unwrap();
+        }
+    }
+    std::fs::write(out_path.join("aliases.rs"), contents).expect("Couldn't write aliases");
+}
+
 #[cfg(all(feature = "fitsio-src", feature = "bindgen"))]
 fn bind_cfitsio() {
     use bindgen::RustTarget;
@@ -68,6 +436,15 @@ fn bind_cfitsio() {
     bindings
         .write_to_file(out_path.join("bindings.rs"))
         .expect("Couldn't write bindings");
+    write_long_names(
+        &[
+            dst.join("include"),
+            dst.clone(),
+            PathBuf::from("ext/cfitsio"),
+        ],
+        &bindings.to_string(),
+        &out_path,
+    );
     println!("cargo:rustc-link-search=native={}", dst.display());
     println!("cargo:rustc-link-lib=static=cfitsio");
 }
@@ -98,11 +475,13 @@ fn bind_cfitsio() {
     let mut config = pkg_config::Config::new();
     config.print_system_libs(true);
     config.print_system_cflags(true);
+    config.statik(link_statically());
     match config.probe(package_name) {
         Ok(lib) => {
+            emit_system_capabilities(package_name);
             let include_args: Vec<_> = lib
                 .include_paths
-                .into_iter()
+                .iter()
                 .map(|p| format!("-I{}", p.to_str().unwrap()))
                 .collect();
             let bindings = bindgen::builder()
@@ -118,6 +497,9 @@ fn bind_cfitsio() {
             bindings
                 .write_to_file(out_path.join("bindings.rs"))
                 .expect("Couldn't write bindings");
+            let mut include_dirs = lib.include_paths;
+            include_dirs.push(PathBuf::from("/usr/include"));
+            write_long_names(&include_dirs, &bindings.to_string(), &out_path);
         }
         Err(Error::Failure { output, .. }) => {
             // Handle the case where the user has not installed cfitsio, and thusly it is not on
@@ -159,8 +541,9 @@ fn bind_cfitsio() {
     let mut config = pkg_config::Config::new();
     config.print_system_libs(true);
     config.print_system_cflags(true);
+    config.statik(link_statically());
     match config.probe(package_name) {
-        Ok(_) => {}
+        Ok(_) => emit_system_capabilities(package_name),
         Err(Error::Failure { output, .. }) => {
             // Handle the case where the user has not installed cfitsio, and thusly it is not on
             // the PKG_CONFIG_PATH
@@ -185,6 +568,23 @@ PKG_CONFIG_PATH=<blah> cargo build
     };
 }
 
+/// Generate the long name aliases for the pregenerated bindings, from the bundled `longnam.h`
+#[cfg(not(feature = "bindgen"))]
+fn write_pregenerated_long_names() {
+    let pointer_width = std::env::var("CARGO_CFG_TARGET_POINTER_WIDTH").unwrap();
+    let bindings_path = format!("src/bindings_{}.rs", pointer_width);
+    let bindings = std::fs::read_to_string(&bindings_path).expect("Couldn't read bindings");
+    let out_path = PathBuf::from(std::env::var("OUT_DIR").unwrap());
+    write_long_names(&[PathBuf::from("ext/cfitsio")], &bindings, &out_path);
+}
+
 fn main() {
+    if std::env::var("CARGO_CFG_TARGET_ARCH").as_deref() == Ok("wasm32")
+        && !cfg!(feature = "fitsio-src")
+    {
+        panic!("WebAssembly targets require the `fitsio-src` feature to compile cfitsio");
+    }
     bind_cfitsio();
+    #[cfg(not(feature = "bindgen"))]
+    write_pregenerated_long_names();
 }
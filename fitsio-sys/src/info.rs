@@ -0,0 +1,64 @@
+//! Runtime information about the linked `cfitsio` library
+
+use crate::sys::{ffvers, fits_is_reentrant};
+
+/// Return the `(major, minor)` version of the linked `cfitsio` library
+///
+/// The version is queried from the library at runtime, so it reflects the library that was
+/// actually linked rather than the headers the bindings were generated from.
+///
+/// ```rust
+/// let (major, _minor) = fitsio_sys::cfitsio_version();
+/// assert!(major >= 3);
+/// ```
+pub fn cfitsio_version() -> (u32, u32) {
+    let mut version = 0.0;
+    let version = unsafe { ffvers(&mut version) };
+    let major = version.trunc();
+    let minor = ((version - major) * 100.0).round();
+    (major as u32, minor as u32)
+}
+
+/// Version of the `cfitsio` source compiled with the `fitsio-src` feature
+///
+/// This is the `CFITSIO_VERSION` of the compiled source, chosen with the `FITSIO_SRC_VERSION`
+/// or `FITSIO_SRC_DIR` environment variables when building. Returns `None` when linking against
+/// a system `cfitsio`, see [`cfitsio_version`] for the version of the library in use.
+///
+/// ```rust
+/// if let Some(version) = fitsio_sys::compiled_cfitsio_version() {
+///     println!("compiled cfitsio {}", version);
+/// }
+/// ```
+pub fn compiled_cfitsio_version() -> Option<&'static str> {
+    option_env!("FITSIO_SYS_CFITSIO_SRC_VERSION")
+}
+
+/// Optional features the linked `cfitsio` library was built with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Capabilities {
+    /// Remote files can be opened with `libcurl`
+    pub curl: bool,
+    /// `bzip2` compressed files can be read
+    pub bzip2: bool,
+    /// The library was built with `--enable-reentrant`, so separate files may be accessed from
+    /// multiple threads
+    pub reentrant: bool,
+}
+
+/// Return the optional features the linked `cfitsio` library supports
+///
+/// `curl` and `bzip2` are determined when `fitsio-sys` is built, from the libraries `cfitsio`
+/// links against. `reentrant` is queried from the library at runtime.
+///
+/// ```rust
+/// let capabilities = fitsio_sys::capabilities();
+/// println!("curl support: {}", capabilities.curl);
+/// ```
+pub fn capabilities() -> Capabilities {
+    Capabilities {
+        curl: option_env!("FITSIO_SYS_CFITSIO_CURL") == Some("1"),
+        bzip2: option_env!("FITSIO_SYS_CFITSIO_BZIP2") == Some("1"),
+        reentrant: unsafe { fits_is_reentrant() } != 0,
+    }
+}
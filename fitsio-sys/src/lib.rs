@@ -9,10 +9,11 @@
 //!
 //! ## Note about function names
 //!
-//! Unfortunately we must use fits short names throughout. The C-api exposes long names for
-//! functions which are more descriptive, for example `fits_open_file` instead of `ffopen`, but the
-//! symbols available in the library have only short names, and the long names are merely
-//! preprocessor definitions.
+//! The symbols available in the library have only short names, for example `ffopen`, and the more
+//! descriptive long names of the C-api, for example `fits_open_file`, are merely preprocessor
+//! definitions. This crate re-exports the functions under their long names as well. The long names
+//! are generated from `longnam.h` for every function in the bindings, so the same names are
+//! available whether the bindings are pregenerated or generated with the `with-bindgen` feature.
 //!
 //! ## Examples
 //!
@@ -50,8 +51,17 @@
 
 #![allow(improper_ctypes)]
 
-mod aliases;
+// Link the zlib built by `libz-sys`, which `cfitsio` 4 needs when linked statically
+#[cfg(feature = "static")]
+use libz_sys as _;
+
+// Long name aliases generated from `longnam.h` by the build script
+mod aliases {
+    include!(concat!(env!("OUT_DIR"), "/aliases.rs"));
+}
+mod info;
 pub use aliases::*;
+pub use info::{capabilities, cfitsio_version, compiled_cfitsio_version, Capabilities};
 
 #[cfg(not(feature = "bindgen"))]
 #[allow(non_camel_case_types, non_upper_case_globals, non_snake_case)]
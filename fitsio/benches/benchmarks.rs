@@ -3,7 +3,78 @@ use fitsio::images::{ImageDescription, ImageType};
 use fitsio::tables::{ColumnDataType, ColumnDescription, FitsRow};
 use fitsio::FitsFile;
 use fitsio_derive::FitsRow;
-use tempfile::Builder;
+use std::path::{Path, PathBuf};
+use tempfile::{Builder, TempDir};
+
+const IMAGE_SHAPE: [usize; 2] = [1024, 1024];
+const TABLE_ROWS: usize = 100_000;
+
+/* Fixture files shared by the benchmarks below. The files are removed when the returned
+ * `TempDir` is dropped. */
+struct Fixtures {
+    _dir: TempDir,
+    image: PathBuf,
+    compressed: PathBuf,
+    table: PathBuf,
+}
+
+fn image_data() -> Vec<f32> {
+    (0..IMAGE_SHAPE[0] * IMAGE_SHAPE[1])
+        .map(|i| ((i % 4096) as f32) * 0.5)
+        .collect()
+}
+
+fn create_image_file(path: &Path) {
+    let description = ImageDescription {
+        data_type: ImageType::Float,
+        dimensions: &IMAGE_SHAPE,
+    };
+    let mut f = FitsFile::create(path).open().unwrap();
+    let hdu = f.create_image("IMAGE", &description).unwrap();
+    hdu.write_image(&mut f, &image_data()).unwrap();
+    for i in 0..50 {
+        hdu.write_key(&mut f, &format!("KEY{}", i), i as i64)
+            .unwrap();
+    }
+}
+
+fn generate_fixtures() -> Fixtures {
+    let dir = Builder::new().prefix("fitsio-bench").tempdir().unwrap();
+
+    let image = dir.path().join("image.fits");
+    create_image_file(&image);
+
+    /* cfitsio's extended filename syntax creates tile compressed images */
+    let compressed = dir.path().join("compressed.fits");
+    create_image_file(Path::new(&format!("{}[compress]", compressed.display())));
+
+    let table = dir.path().join("table.fits");
+    {
+        let columns = &[
+            ColumnDescription::new("ID")
+                .with_type(ColumnDataType::Int)
+                .create()
+                .unwrap(),
+            ColumnDescription::new("FLUX")
+                .with_type(ColumnDataType::Double)
+                .create()
+                .unwrap(),
+        ];
+        let mut f = FitsFile::create(&table).open().unwrap();
+        let hdu = f.create_table("DATA", columns).unwrap();
+        let ids: Vec<i32> = (0..TABLE_ROWS as i32).collect();
+        let fluxes: Vec<f64> = (0..TABLE_ROWS).map(|i| i as f64 * 1.5).collect();
+        hdu.write_col(&mut f, "ID", &ids).unwrap();
+        hdu.write_col(&mut f, "FLUX", &fluxes).unwrap();
+    }
+
+    Fixtures {
+        _dir: dir,
+        image,
+        compressed,
+        table,
+    }
+}
 
 fn opening_files(c: &mut Criterion) {
     let filename = "../testdata/full_example.fits";
@@ -20,7 +91,7 @@ fn opening_files(c: &mut Criterion) {
 /* Benchmark the example code */
 fn full_example(c: &mut Criterion) {
     /* Create a temporary directory to work from */
-    let tmp_dir = Builder::new().prefix("fitsio").tempfile().unwrap();
+    let tmp_dir = Builder::new().prefix("fitsio").tempdir().unwrap();
     let file_path = tmp_dir.path().join("example.fits");
 
     c.bench_function("full example", move |b| {
@@ -159,5 +230,96 @@ fn full_example(c: &mut Criterion) {
     });
 }
 
-criterion::criterion_group!(benches, opening_files, full_example);
+fn images(c: &mut Criterion) {
+    let fixtures = generate_fixtures();
+    let data = image_data();
+
+    let mut f = FitsFile::edit(&fixtures.image).unwrap();
+    let hdu = f.hdu(1).unwrap();
+    c.bench_function("read image", |b| {
+        b.iter(|| {
+            let _data: Vec<f32> = hdu.read_image(&mut f).unwrap();
+        })
+    });
+    c.bench_function("read image region", |b| {
+        b.iter(|| {
+            let _data: Vec<f32> = hdu
+                .read_region(&mut f, &[&(256..768), &(256..768)])
+                .unwrap();
+        })
+    });
+
+    c.bench_function("write image", |b| {
+        b.iter(|| hdu.write_image(&mut f, &data).unwrap())
+    });
+
+    let mut f = FitsFile::open(&fixtures.compressed).unwrap();
+    let hdu = f.hdu(1).unwrap();
+    c.bench_function("read compressed image", |b| {
+        b.iter(|| {
+            let _data: Vec<f32> = hdu.read_image(&mut f).unwrap();
+        })
+    });
+
+    /* Compressed tiles cannot be rewritten in place, so each iteration creates a new file */
+    let path = fixtures.compressed.with_file_name("write_compressed.fits");
+    let compressed_path = format!("{}[compress]", path.display());
+    let description = ImageDescription {
+        data_type: ImageType::Float,
+        dimensions: &IMAGE_SHAPE,
+    };
+    c.bench_function("write compressed image", |b| {
+        b.iter(|| {
+            let _ = std::fs::remove_file(&path);
+            let mut f = FitsFile::create(&compressed_path).open().unwrap();
+            let hdu = f.create_image("IMAGE", &description).unwrap();
+            hdu.write_image(&mut f, &data).unwrap();
+        })
+    });
+}
+
+fn tables(c: &mut Criterion) {
+    let fixtures = generate_fixtures();
+    let fluxes: Vec<f64> = (0..TABLE_ROWS).map(|i| i as f64 * 2.5).collect();
+
+    let mut f = FitsFile::edit(&fixtures.table).unwrap();
+    let hdu = f.hdu("DATA").unwrap();
+    c.bench_function("read column", |b| {
+        b.iter(|| {
+            let _data: Vec<f64> = hdu.read_col(&mut f, "FLUX").unwrap();
+        })
+    });
+    c.bench_function("read column range", |b| {
+        b.iter(|| {
+            let _data: Vec<i32> = hdu.read_col_range(&mut f, "ID", &(1000..2000)).unwrap();
+        })
+    });
+    c.bench_function("write column", |b| {
+        b.iter(|| hdu.write_col(&mut f, "FLUX", &fluxes).unwrap())
+    });
+}
+
+fn headers(c: &mut Criterion) {
+    let fixtures = generate_fixtures();
+
+    let mut f = FitsFile::edit(&fixtures.image).unwrap();
+    let hdu = f.hdu(1).unwrap();
+    c.bench_function("read header key", |b| {
+        b.iter(|| {
+            let _value: i64 = hdu.read_key(&mut f, "KEY25").unwrap();
+        })
+    });
+    c.bench_function("write header key", |b| {
+        b.iter(|| hdu.write_key(&mut f, "KEY25", 25i64).unwrap())
+    });
+}
+
+criterion::criterion_group!(
+    benches,
+    opening_files,
+    full_example,
+    images,
+    tables,
+    headers
+);
 criterion::criterion_main!(benches);
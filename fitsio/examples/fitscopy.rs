@@ -0,0 +1,33 @@
+/* This example documents the following things:
+ *
+ * reading a file from standard input
+ * writing a file to standard output
+ * copying an hdu between files
+ *
+ * Usage: cargo run --example fitscopy -- <hdu> < in.fits > out.fits
+ */
+
+use std::error::Error;
+
+use fitsio::FitsFile;
+
+fn run() -> Result<(), Box<dyn Error>> {
+    let hdu_number: usize = match std::env::args().nth(1) {
+        Some(arg) => arg.parse()?,
+        None => 0,
+    };
+
+    let mut src = FitsFile::open_stdin()?;
+    let mut dest = FitsFile::create_stdout().open()?;
+
+    let hdu = src.hdu(hdu_number)?;
+    hdu.copy_to(&mut src, &mut dest)?;
+
+    // The file is written to standard output when it is closed
+    dest.close()?;
+    Ok(())
+}
+
+fn main() {
+    run().unwrap();
+}
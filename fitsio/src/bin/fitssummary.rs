@@ -1,19 +1,320 @@
+use fitsio::hdu::HduInfo;
+use fitsio::verify::ChecksumStatus;
+use fitsio::FitsFile;
+use rayon::prelude::*;
+use std::collections::HashSet;
 use std::env;
+use std::error::Error;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
 use std::process;
 
+const USAGE: &str = "usage: fitssummary [--verify] [--stats] [--table] <file|directory|pattern>...
+
+Directories are searched recursively for fits files, and glob patterns such as
+'data/*.fits' are expanded. These, or --table, print one line per file instead of
+the full summary of each file, processing the files in parallel.";
+
+#[derive(Default)]
+struct Options {
+    verify: bool,
+    stats: bool,
+    table: bool,
+}
+
+fn checksum_label(status: ChecksumStatus) -> &'static str {
+    match status {
+        ChecksumStatus::Valid => "valid",
+        ChecksumStatus::Missing => "missing",
+        ChecksumStatus::Invalid => "INVALID",
+    }
+}
+
+/// Print the verification report of a file, returning whether the file is valid
+fn print_verification(f: &mut FitsFile) -> Result<bool, Box<dyn Error>> {
+    let report = f.verify()?;
+    println!("  verification:");
+    for hdu in &report.hdus {
+        println!(
+            "  {:<6} checksum: {}, datasum: {}",
+            hdu.index,
+            checksum_label(hdu.checksum),
+            checksum_label(hdu.datasum)
+        );
+        if !hdu.missing_keywords.is_empty() {
+            println!(
+                "         missing keywords: {}",
+                hdu.missing_keywords.join(", ")
+            );
+        }
+        for error in &hdu.errors {
+            println!("         error: {}", error);
+        }
+    }
+    for error in &report.errors {
+        println!("  error: {}", error);
+    }
+    println!(
+        "  result: {}",
+        if report.is_valid() { "OK" } else { "FAILED" }
+    );
+    Ok(report.is_valid())
+}
+
+fn print_stats(f: &mut FitsFile) -> Result<(), Box<dyn Error>> {
+    println!("  statistics:");
+    println!(
+        "  {:<6} {:<14} {:<14} {:<14}",
+        "extnum", "min", "max", "mean"
+    );
+    for index in 0..f.num_hdus()? {
+        let hdu = f.hdu(index)?;
+        let is_image = match hdu.info {
+            HduInfo::ImageInfo { ref shape, .. } => !shape.is_empty(),
+            _ => false,
+        };
+        if !is_image {
+            continue;
+        }
+        match hdu.image_stats(f) {
+            Ok(stats) => println!(
+                "  {:<6} {:<14} {:<14} {:<14.6}",
+                index, stats.min, stats.max, stats.mean
+            ),
+            Err(e) => println!("  {:<6} {}", index, e),
+        }
+    }
+    Ok(())
+}
+
+/// Summarise a single file, returning whether it passed verification
+fn summarise(filename: &str, options: &Options) -> Result<bool, Box<dyn Error>> {
+    let mut f = FitsFile::open(filename)?;
+    f.pretty_print()?;
+
+    let mut valid = true;
+    if options.verify {
+        valid = print_verification(&mut f)?;
+    }
+    if options.stats {
+        print_stats(&mut f)?;
+    }
+    Ok(valid)
+}
+
+/// One line of the table printed for directories and glob patterns
+struct TableRow {
+    num_hdus: usize,
+    primary_shape: String,
+    size: u64,
+    valid: Option<bool>,
+}
+
+fn table_row(path: &Path, options: &Options) -> Result<TableRow, Box<dyn Error>> {
+    let size = fs::metadata(path)?.len();
+    let mut f = FitsFile::open(path)?;
+    let num_hdus = f.num_hdus()?;
+    let primary_shape = match f.primary_hdu()?.info {
+        HduInfo::ImageInfo { ref shape, .. } if !shape.is_empty() => shape
+            .iter()
+            .map(|dim| dim.to_string())
+            .collect::<Vec<_>>()
+            .join("x"),
+        _ => "-".to_string(),
+    };
+    let valid = if options.verify {
+        Some(f.verify()?.is_valid())
+    } else {
+        None
+    };
+    Ok(TableRow {
+        num_hdus,
+        primary_shape,
+        size,
+        valid,
+    })
+}
+
+fn human_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
+
+/// Summarise many files in parallel, one line per file, returning the number of files read and
+/// whether they all passed verification
+fn print_table(paths: &[PathBuf], options: &Options) -> Result<(usize, bool), Box<dyn Error>> {
+    // Without reentrant support, cfitsio must only be used from one thread at a time
+    let num_threads = if fitsio::sys_info().capabilities.reentrant {
+        0
+    } else {
+        1
+    };
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(num_threads)
+        .build()?;
+    let rows: Vec<_> = pool.install(|| {
+        paths
+            .par_iter()
+            .map(|path| table_row(path, options).map_err(|e| e.to_string()))
+            .collect()
+    });
+
+    let mut nfiles = 0;
+    let mut all_valid = true;
+    println!("{:<8} {:<16} {:<12} file", "n_hdus", "primary", "size");
+    for (path, row) in paths.iter().zip(rows) {
+        match row {
+            Ok(row) => {
+                nfiles += 1;
+                let status = match row.valid {
+                    Some(true) => "  [OK]",
+                    Some(false) => "  [FAILED]",
+                    None => "",
+                };
+                all_valid &= row.valid.unwrap_or(true);
+                println!(
+                    "{:<8} {:<16} {:<12} {}{}",
+                    row.num_hdus,
+                    row.primary_shape,
+                    human_size(row.size),
+                    path.display(),
+                    status
+                );
+            }
+            Err(e) => eprintln!("{}: {}", path.display(), e),
+        }
+    }
+    Ok((nfiles, all_valid))
+}
+
+fn is_fits_filename(path: &Path) -> bool {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let name = name.strip_suffix(".gz").unwrap_or(&name);
+    [".fits", ".fit", ".fts", ".fz"]
+        .iter()
+        .any(|extension| name.ends_with(extension))
+}
+
+/// Collect the fits files below a directory, sorted by path
+fn walk_directory(dir: &Path, paths: &mut Vec<PathBuf>) -> io::Result<()> {
+    let mut entries = fs::read_dir(dir)?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<io::Result<Vec<_>>>()?;
+    entries.sort();
+    for path in entries {
+        if path.is_dir() {
+            walk_directory(&path, paths)?;
+        } else if is_fits_filename(&path) {
+            paths.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Expand a directory or glob pattern into the files it contains
+fn expand(arg: &str, paths: &mut Vec<PathBuf>) -> Result<(), Box<dyn Error>> {
+    let path = Path::new(arg);
+    if path.is_dir() {
+        walk_directory(path, paths)?;
+    } else {
+        for path in glob::glob(arg)? {
+            let path = path?;
+            if path.is_dir() {
+                walk_directory(&path, paths)?;
+            } else {
+                paths.push(path);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Remove paths to a file which is already in the list, such as a file given by name and matched
+/// by a pattern, keeping the first. Paths which cannot be canonicalised are compared as given
+fn dedup_paths(paths: Vec<PathBuf>) -> Vec<PathBuf> {
+    let mut seen = HashSet::new();
+    paths
+        .into_iter()
+        .filter(|path| seen.insert(fs::canonicalize(path).unwrap_or_else(|_| path.clone())))
+        .collect()
+}
+
+/// Whether an argument should be expanded, rather than opened as a single file. Square
+/// brackets are not treated as a glob, as they are used by cfitsio's extended filename syntax
+fn is_pattern(arg: &str) -> bool {
+    Path::new(arg).is_dir() || (!Path::new(arg).exists() && arg.contains(&['*', '?'][..]))
+}
+
 fn main() {
+    let mut options = Options::default();
+    let mut filenames = Vec::new();
+    let mut patterns = Vec::new();
+    for arg in env::args().skip(1) {
+        match arg.as_str() {
+            "--verify" => options.verify = true,
+            "--stats" => options.stats = true,
+            "--table" => options.table = true,
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return;
+            }
+            flag if flag.starts_with("--") => {
+                eprintln!("Unknown option {}\n{}", flag, USAGE);
+                process::exit(2);
+            }
+            _ if is_pattern(&arg) => patterns.push(arg),
+            _ => filenames.push(arg),
+        }
+    }
+
     let mut nfiles = 0;
-    env::args().skip(1).for_each(|arg| {
-        if fitsio::FitsFile::open(arg)
-            .map(|mut f| f.pretty_print())
-            .is_ok()
-        {
-            nfiles += 1;
+    let mut all_valid = true;
+    if options.table || !patterns.is_empty() {
+        let mut paths: Vec<PathBuf> = filenames.iter().map(PathBuf::from).collect();
+        for pattern in &patterns {
+            if let Err(e) = expand(pattern, &mut paths) {
+                eprintln!("{}: {}", pattern, e);
+            }
         }
-    });
+        let paths = dedup_paths(paths);
+        match print_table(&paths, &options) {
+            Ok((n, valid)) => {
+                nfiles = n;
+                all_valid = valid;
+            }
+            Err(e) => eprintln!("{}", e),
+        }
+    } else {
+        for filename in &filenames {
+            match summarise(filename, &options) {
+                Ok(valid) => {
+                    nfiles += 1;
+                    all_valid &= valid;
+                }
+                Err(e) => eprintln!("{}: {}", filename, e),
+            }
+        }
+    }
 
     if nfiles == 0 {
         eprintln!("No valid fits files supplied");
         process::exit(1);
     }
+
+    if !all_valid {
+        process::exit(1);
+    }
 }
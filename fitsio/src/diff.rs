@@ -0,0 +1,667 @@
+/*!
+Comparison of FITS files
+
+[`compare`](fn.compare.html) walks two files HDU by HDU, and reports every keyword, image pixel
+and table cell which differs between them. Floating point values can be compared within a
+tolerance, so the report can be used directly as the assertion of a regression test:
+
+```rust
+# fn main() -> Result<(), Box<dyn std::error::Error>> {
+# let filename = "../testdata/full_example.fits";
+use fitsio::diff::{compare, DiffOptions};
+use fitsio::FitsFile;
+
+let mut expected = FitsFile::open(filename)?;
+let mut actual = FitsFile::open(filename)?;
+
+let options = DiffOptions {
+    rtol: 1e-6,
+    ignore_keywords: vec!["DATE".to_string(), "CHECKSUM".to_string()],
+    ..Default::default()
+};
+let report = compare(&mut expected, &mut actual, options)?;
+assert!(report.is_identical(), "{}", report);
+# Ok(())
+# }
+```
+*/
+use crate::errors::Result;
+use crate::fitsfile::FitsFile;
+use crate::hdu::{FitsHdu, HduInfo};
+use crate::headers::{read_keys_matching, HeaderCard};
+use crate::tables::{ColumnDataType, ConcreteColumnDescription, ReadsCol};
+use std::fmt;
+
+/// Number of pixels or table values read at a time
+const CHUNK_VALUES: usize = 1 << 16;
+
+/// Options controlling how two files are compared
+#[derive(Debug, Clone, PartialEq)]
+pub struct DiffOptions {
+    /// Relative tolerance for floating point keywords, pixels and cells
+    pub rtol: f64,
+    /// Absolute tolerance for floating point keywords, pixels and cells
+    pub atol: f64,
+    /// Keywords which are not compared, e.g. `DATE` or `CHECKSUM`
+    pub ignore_keywords: Vec<String>,
+    /// Compare image pixels and table cells, rather than only the headers
+    pub compare_data: bool,
+    /// Maximum number of differing cells listed per table, all differences are still counted
+    pub max_reported_cells: usize,
+}
+
+impl Default for DiffOptions {
+    fn default() -> Self {
+        DiffOptions {
+            rtol: 0.0,
+            atol: 0.0,
+            ignore_keywords: Vec::new(),
+            compare_data: true,
+            max_reported_cells: 10,
+        }
+    }
+}
+
+impl DiffOptions {
+    /// Are two values equal within the tolerances?
+    ///
+    /// Two NaN values are considered equal.
+    fn close(&self, a: f64, b: f64) -> bool {
+        if a.is_nan() || b.is_nan() {
+            return a.is_nan() && b.is_nan();
+        }
+        a == b || (a - b).abs() <= self.atol + self.rtol * b.abs()
+    }
+}
+
+/// Differences between two files
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileDiff {
+    /// The number of HDUs in each file
+    pub num_hdus: (usize, usize),
+    /// The HDUs which differ, of those present in both files
+    pub hdus: Vec<HduDiff>,
+}
+
+impl FileDiff {
+    /// Are the files the same, within the tolerances given?
+    pub fn is_identical(&self) -> bool {
+        self.num_hdus.0 == self.num_hdus.1 && self.hdus.is_empty()
+    }
+}
+
+/// Differences between a pair of HDUs
+#[derive(Debug, Clone, PartialEq)]
+pub struct HduDiff {
+    /// The zero-indexed position of the HDU in both files
+    pub index: usize,
+    /// Keywords which differ
+    pub keywords: Vec<KeywordDiff>,
+    /// Difference of the image or table data, if any
+    pub data: Option<DataDiff>,
+}
+
+/**
+A keyword which differs between two HDUs
+
+Keywords which are repeated, such as `COMMENT` and `HISTORY`, have all of their values listed
+in order. A keyword which is missing from one HDU has no values.
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeywordDiff {
+    /// Name of the keyword
+    pub name: String,
+    /// Values in the first HDU
+    pub first: Vec<String>,
+    /// Values in the second HDU
+    pub second: Vec<String>,
+}
+
+/// Difference of the data of two HDUs
+#[derive(Debug, Clone, PartialEq)]
+pub enum DataDiff {
+    /// One HDU is an image and the other a table
+    HduType,
+    /// The images have different shapes, so their pixels are not compared
+    ImageShape {
+        /// Shape of the first image
+        first: Vec<usize>,
+        /// Shape of the second image
+        second: Vec<usize>,
+    },
+    /// The images have pixels which differ
+    Pixels {
+        /// Number of differing pixels
+        count: usize,
+        /// Largest absolute difference between two pixels
+        max_deviation: f64,
+    },
+    /// The tables have different columns, so their cells are not compared
+    Columns {
+        /// Column names of the first table
+        first: Vec<String>,
+        /// Column names of the second table
+        second: Vec<String>,
+    },
+    /// The tables have different numbers of rows, so their cells are not compared
+    NumRows {
+        /// Number of rows of the first table
+        first: usize,
+        /// Number of rows of the second table
+        second: usize,
+    },
+    /// The tables have cells which differ
+    Cells {
+        /// Number of differing cells
+        count: usize,
+        /// Largest absolute difference between two numeric cells
+        max_deviation: f64,
+        /// Column name and zero-indexed row of the first differing cells
+        cells: Vec<(String, usize)>,
+    },
+}
+
+impl fmt::Display for FileDiff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.is_identical() {
+            return write!(f, "files are identical");
+        }
+        if self.num_hdus.0 != self.num_hdus.1 {
+            writeln!(
+                f,
+                "number of hdus differs: {} != {}",
+                self.num_hdus.0, self.num_hdus.1
+            )?;
+        }
+        for hdu in &self.hdus {
+            for keyword in &hdu.keywords {
+                writeln!(
+                    f,
+                    "hdu {}: keyword {}: {:?} != {:?}",
+                    hdu.index, keyword.name, keyword.first, keyword.second
+                )?;
+            }
+            if let Some(data) = &hdu.data {
+                writeln!(f, "hdu {}: {}", hdu.index, data)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for DataDiff {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DataDiff::HduType => write!(f, "hdu types differ"),
+            DataDiff::ImageShape { first, second } => {
+                write!(f, "image shapes differ: {:?} != {:?}", first, second)
+            }
+            DataDiff::Pixels {
+                count,
+                max_deviation,
+            } => write!(
+                f,
+                "{} pixels differ, maximum deviation {}",
+                count, max_deviation
+            ),
+            DataDiff::Columns { first, second } => {
+                write!(f, "columns differ: {:?} != {:?}", first, second)
+            }
+            DataDiff::NumRows { first, second } => {
+                write!(f, "number of rows differs: {} != {}", first, second)
+            }
+            DataDiff::Cells {
+                count,
+                max_deviation,
+                cells,
+            } => write!(
+                f,
+                "{} cells differ, maximum deviation {}, first at {:?}",
+                count, max_deviation, cells
+            ),
+        }
+    }
+}
+
+/**
+Compare two files
+
+HDUs are compared pairwise by position, up to the number of HDUs in the shorter file. For each
+pair:
+
+* keywords are compared by name, with values which both parse as numbers compared within the
+  tolerances of `options`,
+* images of the same shape are compared pixel by pixel as `f64`, within the tolerances, and
+* tables with the same columns and number of rows are compared cell by cell. Numeric columns
+  are compared as `f64` within the tolerances, string columns as strings, and all other columns
+  (including 64 bit integers and variable length arrays) by their raw bytes.
+
+Data are read in chunks, so large files can be compared without reading them into memory.
+*/
+pub fn compare(
+    first: &mut FitsFile,
+    second: &mut FitsFile,
+    options: DiffOptions,
+) -> Result<FileDiff> {
+    let num_hdus = (first.num_hdus()?, second.num_hdus()?);
+    let mut hdus = Vec::new();
+    for index in 0..num_hdus.0.min(num_hdus.1) {
+        let first_hdu = first.hdu(index)?;
+        let second_hdu = second.hdu(index)?;
+
+        let keywords = compare_keywords(first, second, &options)?;
+        let data = if options.compare_data {
+            compare_data(first, &first_hdu, second, &second_hdu, &options)?
+        } else {
+            None
+        };
+
+        if !keywords.is_empty() || data.is_some() {
+            hdus.push(HduDiff {
+                index,
+                keywords,
+                data,
+            });
+        }
+    }
+    Ok(FileDiff { num_hdus, hdus })
+}
+
+/// Group the values of the keywords of the current HDU by name, in header order
+fn header_values(
+    fits_file: &mut FitsFile,
+    options: &DiffOptions,
+) -> Result<Vec<(String, Vec<String>)>> {
+    let mut grouped: Vec<(String, Vec<String>)> = Vec::new();
+    for HeaderCard { name, value, .. } in read_keys_matching(fits_file, "*")? {
+        if name.is_empty()
+            || options
+                .ignore_keywords
+                .iter()
+                .any(|ignored| ignored.eq_ignore_ascii_case(&name))
+        {
+            continue;
+        }
+        match grouped.iter_mut().find(|(existing, _)| *existing == name) {
+            Some((_, values)) => values.push(value),
+            None => grouped.push((name, vec![value])),
+        }
+    }
+    Ok(grouped)
+}
+
+/// Compare keyword values, numerically if both are numbers
+fn values_match(a: &str, b: &str, options: &DiffOptions) -> bool {
+    if a == b {
+        return true;
+    }
+    // Fortran style exponents are allowed in headers
+    let parse = |value: &str| value.trim().replace(['D', 'd'], "E").parse::<f64>().ok();
+    match (parse(a), parse(b)) {
+        (Some(a), Some(b)) => options.close(a, b),
+        _ => false,
+    }
+}
+
+fn compare_keywords(
+    first: &mut FitsFile,
+    second: &mut FitsFile,
+    options: &DiffOptions,
+) -> Result<Vec<KeywordDiff>> {
+    let first_values = header_values(first, options)?;
+    let mut second_values = header_values(second, options)?;
+
+    let mut diffs = Vec::new();
+    for (name, first) in first_values {
+        let second = match second_values.iter().position(|(other, _)| *other == name) {
+            Some(position) => second_values.remove(position).1,
+            None => Vec::new(),
+        };
+        let matches = first.len() == second.len()
+            && first
+                .iter()
+                .zip(&second)
+                .all(|(a, b)| values_match(a, b, options));
+        if !matches {
+            diffs.push(KeywordDiff {
+                name,
+                first,
+                second,
+            });
+        }
+    }
+    // Keywords only present in the second HDU
+    diffs.extend(second_values.into_iter().map(|(name, second)| KeywordDiff {
+        name,
+        first: Vec::new(),
+        second,
+    }));
+    Ok(diffs)
+}
+
+fn compare_data(
+    first: &mut FitsFile,
+    first_hdu: &FitsHdu,
+    second: &mut FitsFile,
+    second_hdu: &FitsHdu,
+    options: &DiffOptions,
+) -> Result<Option<DataDiff>> {
+    match (&first_hdu.info, &second_hdu.info) {
+        (
+            HduInfo::ImageInfo {
+                shape: first_shape, ..
+            },
+            HduInfo::ImageInfo {
+                shape: second_shape,
+                ..
+            },
+        ) => {
+            if first_shape != second_shape {
+                return Ok(Some(DataDiff::ImageShape {
+                    first: first_shape.clone(),
+                    second: second_shape.clone(),
+                }));
+            }
+            let num_pixels = if first_shape.is_empty() {
+                0
+            } else {
+                first_shape.iter().product()
+            };
+            compare_pixels(first, first_hdu, second, second_hdu, num_pixels, options)
+        }
+        (
+            HduInfo::TableInfo {
+                column_descriptions: first_columns,
+                num_rows: first_rows,
+            },
+            HduInfo::TableInfo {
+                column_descriptions: second_columns,
+                num_rows: second_rows,
+            },
+        ) => {
+            let names = |columns: &[ConcreteColumnDescription]| -> Vec<String> {
+                columns.iter().map(|column| column.name.clone()).collect()
+            };
+            if names(first_columns) != names(second_columns) {
+                return Ok(Some(DataDiff::Columns {
+                    first: names(first_columns),
+                    second: names(second_columns),
+                }));
+            }
+            if first_rows != second_rows {
+                return Ok(Some(DataDiff::NumRows {
+                    first: *first_rows,
+                    second: *second_rows,
+                }));
+            }
+            compare_cells(
+                first,
+                first_hdu,
+                second,
+                second_hdu,
+                &names(first_columns),
+                *first_rows,
+                options,
+            )
+        }
+        _ => Ok(Some(DataDiff::HduType)),
+    }
+}
+
+fn compare_pixels(
+    first: &mut FitsFile,
+    first_hdu: &FitsHdu,
+    second: &mut FitsFile,
+    second_hdu: &FitsHdu,
+    num_pixels: usize,
+    options: &DiffOptions,
+) -> Result<Option<DataDiff>> {
+    let mut count = 0;
+    let mut max_deviation = 0.0f64;
+    let mut start = 0;
+    while start < num_pixels {
+        let end = (start + CHUNK_VALUES).min(num_pixels);
+        let first_pixels: Vec<f64> = first_hdu.read_section(first, start, end)?;
+        let second_pixels: Vec<f64> = second_hdu.read_section(second, start, end)?;
+        for (a, b) in first_pixels.into_iter().zip(second_pixels) {
+            if !options.close(a, b) {
+                count += 1;
+                let deviation = (a - b).abs();
+                if deviation > max_deviation {
+                    max_deviation = deviation;
+                }
+            }
+        }
+        start = end;
+    }
+
+    if count == 0 {
+        Ok(None)
+    } else {
+        Ok(Some(DataDiff::Pixels {
+            count,
+            max_deviation,
+        }))
+    }
+}
+
+/// Running tally of differing table cells
+struct CellTally<'a> {
+    options: &'a DiffOptions,
+    count: usize,
+    max_deviation: f64,
+    cells: Vec<(String, usize)>,
+}
+
+impl<'a> CellTally<'a> {
+    fn record(&mut self, name: &str, row: usize) {
+        // Each cell of a vector column is only recorded once
+        if self.cells.last() == Some(&(name.to_string(), row)) {
+            return;
+        }
+        self.count += 1;
+        if self.cells.len() < self.options.max_reported_cells {
+            self.cells.push((name.to_string(), row));
+        }
+    }
+}
+
+fn compare_cells(
+    first: &mut FitsFile,
+    first_hdu: &FitsHdu,
+    second: &mut FitsFile,
+    second_hdu: &FitsHdu,
+    names: &[String],
+    num_rows: usize,
+    options: &DiffOptions,
+) -> Result<Option<DataDiff>> {
+    let mut tally = CellTally {
+        options,
+        count: 0,
+        max_deviation: 0.0,
+        cells: Vec::new(),
+    };
+
+    for name in names {
+        let first_type = first_hdu.column_type(first, name.as_str())?;
+        let second_type = second_hdu.column_type(second, name.as_str())?;
+        if first_type != second_type {
+            // Every cell of the column differs
+            for row in 0..num_rows {
+                tally.record(name, row);
+            }
+            continue;
+        }
+
+        let repeat = first_type.repeat.max(1);
+        let chunk_rows = (CHUNK_VALUES / repeat).max(1);
+        let mut row = 0;
+        while row < num_rows {
+            let nrows = chunk_rows.min(num_rows - row);
+            match (first_type.typ, first_type.descriptor) {
+                (
+                    ColumnDataType::Bool
+                    | ColumnDataType::SignedByte
+                    | ColumnDataType::Short
+                    | ColumnDataType::UnsignedShort
+                    | ColumnDataType::Int
+                    | ColumnDataType::UnsignedInt
+                    | ColumnDataType::Float
+                    | ColumnDataType::Double,
+                    None,
+                ) => {
+                    // Reading starts at the first value of `row`, and continues across rows for
+                    // the number of values requested, so this covers every value of vector
+                    // columns
+                    let values = row..row + nrows * repeat;
+                    let a = f64::read_col_range(first, name, &values)?;
+                    let b = f64::read_col_range(second, name, &values)?;
+                    for (i, (a, b)) in a.into_iter().zip(b).enumerate() {
+                        if !options.close(a, b) {
+                            tally.record(name, row + i / repeat);
+                            let deviation = (a - b).abs();
+                            if deviation > tally.max_deviation {
+                                tally.max_deviation = deviation;
+                            }
+                        }
+                    }
+                }
+                (ColumnDataType::String | ColumnDataType::Text, None) => {
+                    let rows = row..row + nrows;
+                    let a = String::read_col_range(first, name, &rows)?;
+                    let b = String::read_col_range(second, name, &rows)?;
+                    for (i, (a, b)) in a.into_iter().zip(b).enumerate() {
+                        if a != b {
+                            tally.record(name, row + i);
+                        }
+                    }
+                }
+                _ => {
+                    for i in row..row + nrows {
+                        let a = first_hdu.read_cell_bytes(first, name, i)?;
+                        let b = second_hdu.read_cell_bytes(second, name, i)?;
+                        if a != b {
+                            tally.record(name, i);
+                        }
+                    }
+                }
+            }
+            row += nrows;
+        }
+    }
+
+    if tally.count == 0 {
+        Ok(None)
+    } else {
+        Ok(Some(DataDiff::Cells {
+            count: tally.count,
+            max_deviation: tally.max_deviation,
+            cells: tally.cells,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::duplicate_test_file;
+
+    #[test]
+    fn test_compare_identical_files() {
+        let filename = "../testdata/full_example.fits";
+        let mut first = FitsFile::open(filename).unwrap();
+        let mut second = FitsFile::open(filename).unwrap();
+
+        let report = compare(&mut first, &mut second, DiffOptions::default()).unwrap();
+        assert!(report.is_identical(), "{}", report);
+        assert_eq!(report.num_hdus, (2, 2));
+        assert_eq!(report.to_string(), "files are identical");
+    }
+
+    #[test]
+    fn test_compare_modified_file() {
+        duplicate_test_file(|filename| {
+            {
+                let mut f = FitsFile::edit(filename).unwrap();
+                let hdu = f.primary_hdu().unwrap();
+                // Writing an existing keyword appends a second card
+                hdu.write_key(&mut f, "INTTEST", 43).unwrap();
+                hdu.write_key(&mut f, "NEWKEY", "new").unwrap();
+                hdu.write_section(&mut f, 0, 2, &[1000i32, 1000]).unwrap();
+
+                let hdu = f.hdu("TESTEXT").unwrap();
+                hdu.write_col_range(&mut f, "intcol", &[-1i32], &(3..4))
+                    .unwrap();
+                hdu.write_col_range(&mut f, "doublecol", &[1e-9], &(5..6))
+                    .unwrap();
+            }
+
+            let mut first = FitsFile::open("../testdata/full_example.fits").unwrap();
+            let mut second = FitsFile::open(filename).unwrap();
+            let report = compare(&mut first, &mut second, DiffOptions::default()).unwrap();
+            assert!(!report.is_identical());
+            assert_eq!(report.hdus.len(), 2);
+
+            let primary = &report.hdus[0];
+            assert_eq!(primary.index, 0);
+            let names: Vec<&str> = primary.keywords.iter().map(|k| k.name.as_str()).collect();
+            assert_eq!(names, ["INTTEST", "NEWKEY"]);
+            assert_eq!(primary.keywords[0].first, ["42"]);
+            assert_eq!(primary.keywords[0].second, ["42", "43"]);
+            assert!(primary.keywords[1].first.is_empty());
+            match primary.data {
+                Some(DataDiff::Pixels { count, .. }) => assert_eq!(count, 2),
+                ref other => panic!("unexpected pixel diff {:?}", other),
+            }
+
+            let table = &report.hdus[1];
+            assert!(table.keywords.is_empty());
+            match table.data {
+                Some(DataDiff::Cells {
+                    count, ref cells, ..
+                }) => {
+                    assert_eq!(count, 2);
+                    assert_eq!(
+                        cells,
+                        &[("intcol".to_string(), 3), ("doublecol".to_string(), 5)]
+                    );
+                }
+                ref other => panic!("unexpected cell diff {:?}", other),
+            }
+
+            // Ignoring the changes leaves only the modified data
+            let options = DiffOptions {
+                ignore_keywords: vec!["inttest".to_string(), "NEWKEY".to_string()],
+                compare_data: false,
+                ..Default::default()
+            };
+            let report = compare(&mut first, &mut second, options).unwrap();
+            assert!(report.is_identical(), "{}", report);
+        });
+    }
+
+    #[test]
+    fn test_compare_with_tolerance() {
+        duplicate_test_file(|filename| {
+            {
+                let mut f = FitsFile::edit(filename).unwrap();
+                let hdu = f.hdu("TESTEXT").unwrap();
+                let values: Vec<f64> = hdu.read_col(&mut f, "doublecol").unwrap();
+                let shifted: Vec<f64> = values.iter().map(|v| v * (1.0 + 1e-10)).collect();
+                hdu.write_col(&mut f, "doublecol", &shifted).unwrap();
+            }
+
+            let mut first = FitsFile::open("../testdata/full_example.fits").unwrap();
+            let mut second = FitsFile::open(filename).unwrap();
+            let options = DiffOptions {
+                rtol: 1e-8,
+                ..Default::default()
+            };
+            let report = compare(&mut first, &mut second, options).unwrap();
+            assert!(report.is_identical(), "{}", report);
+
+            let report = compare(&mut first, &mut second, DiffOptions::default()).unwrap();
+            assert!(!report.is_identical());
+        });
+    }
+}
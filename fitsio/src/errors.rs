@@ -31,6 +31,12 @@ pub enum Error {
     /// UTF-8 conversion errors
     Utf8(Utf8Error),
 
+    /// A string read from a file is not valid UTF-8
+    InvalidUtf8 {
+        /// The raw bytes of the string, e.g. to decode as Latin-1
+        bytes: Vec<u8>,
+    },
+
     /// IO errors
     Io(io::Error),
 
@@ -86,7 +92,9 @@ impl ::std::convert::From<NulError> for Error {
 
 impl ::std::convert::From<FromUtf8Error> for Error {
     fn from(error: FromUtf8Error) -> Self {
-        Error::Utf8(error.utf8_error())
+        Error::InvalidUtf8 {
+            bytes: error.into_bytes(),
+        }
     }
 }
 
@@ -134,6 +142,11 @@ impl ::std::fmt::Display for Error {
             Error::Message(ref s) => write!(f, "Error: {}", s),
             Error::Null(ref e) => e.fmt(f),
             Error::Utf8(ref e) => e.fmt(f),
+            Error::InvalidUtf8 { ref bytes } => write!(
+                f,
+                "Error: invalid UTF-8 in {:?}",
+                String::from_utf8_lossy(bytes)
+            ),
             Error::Index(ref e) => write!(f, "Error: {:?}", e),
             Error::Io(ref e) => e.fmt(f),
             Error::IntoString(ref e) => e.fmt(f),
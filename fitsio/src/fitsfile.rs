@@ -9,21 +9,70 @@
  */
 
 use crate::errors::{check_status, Error, Result};
-use crate::hdu::{DescribesHdu, FitsHdu, FitsHduIterator, HduInfo};
-use crate::images::{ImageDescription, ImageType};
+use crate::hdu::{
+    BoundHdu, DescribesHdu, FitsHdu, FitsHduIterator, HduIdentity, HduInfo, HduSummary, HduType,
+};
+use crate::headers::{read_key_optional, FloatFormat, KeyValue, ReadsKey};
+use crate::images::{set_image_scaling, CompressionOptions, ImageDescription, ImageType};
 use crate::longnam::*;
+use crate::memfile::MemoryBuffer;
 use crate::stringutils::{self, status_to_string};
 use crate::tables::{ColumnDataDescription, ConcreteColumnDescription};
+use crate::threadsafe_fitsfile::SharedFitsFile;
+use crate::verify::{verify_file, FileVerification};
 use std::ffi;
-use std::io::{self, Write};
+use std::io::{self, Read, Seek, SeekFrom, Write};
 use std::path::{Path, PathBuf};
 use std::ptr;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/**
+Options controlling the output of [`FitsFile::pretty_write_with`]
+
+The default options print one line per HDU, as [`FitsFile::pretty_write`] does.
+
+[`FitsFile::pretty_write_with`]: struct.FitsFile.html#method.pretty_write_with
+[`FitsFile::pretty_write`]: struct.FitsFile.html#method.pretty_write
+*/
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PrettyPrintOptions {
+    /// Print every header card of each HDU
+    pub show_headers: bool,
+    /// List up to this many columns of each table. No columns are listed if this is 0.
+    pub max_columns: usize,
+    /// Include the unit (`TUNITn`) of each listed column
+    pub show_units: bool,
+}
+
+/// Filename `cfitsio` uses for standard input and standard output
+const STREAM_FILENAME: &str = "-";
+
+/// Filename `cfitsio` uses for files created in memory
+const MEMORY_FILENAME: &str = "mem://";
+
+/// Number of decimal places floating point header keys are written with by default
+const DEFAULT_KEY_DECIMALS: usize = 9;
+
+/// Status which makes `fits_insert_img` insert a new primary HDU
+const PREPEND_PRIMARY: i32 = -9;
 
 /// Main entry point to the FITS file format
 pub struct FitsFile {
     filename: Option<PathBuf>,
     open_mode: FileOpenMode,
     pub(crate) fptr: ptr::NonNull<fitsfile>,
+    /// Final path of a file created with `NewFitsFile::atomic`, renamed into place on close
+    atomic_target: Option<PathBuf>,
+    /// Memory the file was opened from, which is released after the file is closed
+    memory: Option<Box<MemoryBuffer>>,
+    /// Whether reads which would lose information return errors
+    strict: bool,
+    /// Numbers of the HDUs deleted or inserted, in order, to detect out of date `FitsHdu`s
+    pub(crate) renumbered_hdus: Vec<usize>,
+    /// Number of decimal places floating point header keys are written with
+    pub(crate) key_decimals: usize,
+    /// Notation floating point header keys are written in
+    pub(crate) key_format: FloatFormat,
 }
 
 impl FitsFile {
@@ -48,8 +97,7 @@ impl FitsFile {
         let mut fptr = ptr::null_mut();
         let mut status = 0;
         let file_path = filename.as_ref();
-        let filename = file_path.to_str().expect("converting filename");
-        let c_filename = ffi::CString::new(filename)?;
+        let c_filename = path_to_c_string(file_path)?;
 
         unsafe {
             fits_open_file(
@@ -65,11 +113,114 @@ impl FitsFile {
                 fptr: p,
                 open_mode: FileOpenMode::READONLY,
                 filename: Some(file_path.to_path_buf()),
+                atomic_target: None,
+                memory: None,
+                strict: false,
+                renumbered_hdus: Vec::new(),
+                key_decimals: DEFAULT_KEY_DECIMALS,
+                key_format: FloatFormat::Exponential,
             },
             None => unimplemented!(),
         })
     }
 
+    /**
+    Open a fits file from disk, with extended filename syntax given separately from the path
+
+    [`open`](#method.open) passes the whole path to `cfitsio`, which interprets characters such
+    as `[`, `(` and a leading `!` as extended filename syntax. This method keeps the two apart:
+    with an empty `syntax` the path is opened exactly as given, so file names containing those
+    characters are not misinterpreted. Otherwise `syntax` must start with `[`, `(` or `+`, and
+    is appended to the path, which must then not contain extended syntax characters itself.
+
+    A file opened with a non-empty `syntax` may not match the file on disk, so it is not
+    associated with its path: [`into_edit`](#method.into_edit),
+    [`into_shared`](#method.into_shared) and [`write_to`](#method.write_to) return errors rather
+    than using the unfiltered file.
+
+    # Example
+
+    ```rust
+    use fitsio::FitsFile;
+    # use std::error::Error;
+
+    # fn main() -> Result<(), Box<dyn Error>> {
+    # let filename = "../testdata/full_example.fits";
+    // let filename = ...;
+    let mut fptr = FitsFile::open_with_syntax(filename, "[TESTEXT][intcol > 50]")?;
+    let hdu = fptr.hdu("TESTEXT")?;
+    assert!(hdu.num_rows(&mut fptr)? < 50);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn open_with_syntax<T: AsRef<Path>>(filename: T, syntax: &str) -> Result<Self> {
+        let mut fptr = ptr::null_mut();
+        let mut status = 0;
+        let file_path = filename.as_ref();
+        // The path alone only describes the file when no syntax is applied
+        let stored_path = if syntax.is_empty() {
+            Some(file_path.to_path_buf())
+        } else {
+            None
+        };
+
+        if syntax.is_empty() {
+            let c_filename = path_to_c_string(file_path)?;
+            unsafe {
+                fits_open_diskfile(
+                    &mut fptr as *mut *mut fitsfile,
+                    c_filename.as_ptr(),
+                    FileOpenMode::READONLY as libc::c_int,
+                    &mut status,
+                );
+            }
+        } else {
+            if !syntax.starts_with(&['[', '(', '+'][..]) {
+                return Err(Error::Message(format!(
+                    "extended filename syntax {:?} must start with '[', '(' or '+'",
+                    syntax
+                )));
+            }
+            let path = file_path.to_string_lossy();
+            if path.starts_with('!')
+                || path.contains(&['[', ']', '(', ')'][..])
+                || path.contains("://")
+            {
+                return Err(Error::Message(format!(
+                    "path {:?} contains extended filename syntax characters, so cannot be combined with {:?}",
+                    file_path, syntax
+                )));
+            }
+            let mut full_path = file_path.as_os_str().to_os_string();
+            full_path.push(syntax);
+            let c_filename = path_to_c_string(Path::new(&full_path))?;
+            unsafe {
+                fits_open_file(
+                    &mut fptr as *mut *mut fitsfile,
+                    c_filename.as_ptr(),
+                    FileOpenMode::READONLY as libc::c_int,
+                    &mut status,
+                );
+            }
+        }
+
+        check_status(status).and_then(|_| match ptr::NonNull::new(fptr) {
+            Some(p) => Ok(FitsFile {
+                fptr: p,
+                open_mode: FileOpenMode::READONLY,
+                filename: stored_path,
+                atomic_target: None,
+                memory: None,
+                strict: false,
+                renumbered_hdus: Vec::new(),
+                key_decimals: DEFAULT_KEY_DECIMALS,
+                key_format: FloatFormat::Exponential,
+            }),
+            None => Err(Error::NullPointer),
+        })
+    }
+
     /**
     Open a fits file in read/write mode
 
@@ -90,8 +241,7 @@ impl FitsFile {
         let mut fptr = ptr::null_mut();
         let mut status = 0;
         let file_path = filename.as_ref();
-        let filename = file_path.to_str().expect("converting filename");
-        let c_filename = ffi::CString::new(filename)?;
+        let c_filename = path_to_c_string(file_path)?;
 
         unsafe {
             fits_open_file(
@@ -107,11 +257,379 @@ impl FitsFile {
                 fptr: p,
                 open_mode: FileOpenMode::READWRITE,
                 filename: Some(file_path.to_path_buf()),
+                atomic_target: None,
+                memory: None,
+                strict: false,
+                renumbered_hdus: Vec::new(),
+                key_decimals: DEFAULT_KEY_DECIMALS,
+                key_format: FloatFormat::Exponential,
             },
             None => unimplemented!(),
         })
     }
 
+    /**
+    Open a fits file read only, for many concurrent readers
+
+    The file is memory mapped read only and opened with the `cfitsio` memory driver. The
+    mapping is shared through the operating system page cache, so many processes, for example
+    the workers of a web service, can open the same file while it is only held in memory once.
+    Unlike files opened with [`open`](#method.open), each handle is independent of any other
+    handles to the same file in the process, so handles can be used from different threads
+    (see [`into_send`](#method.into_send)) without sharing state.
+
+    Extended filename syntax is not supported.
+
+    # Safety
+
+    The file must not be modified or truncated, by this or any other process, while the
+    returned `FitsFile` is open. Reading from a mapping of a truncated file raises `SIGBUS`,
+    and changes to the file are visible through the mapping, breaking the guarantee that the
+    data read by `cfitsio` does not change underneath it.
+
+    # Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let filename = "../testdata/full_example.fits";
+    use fitsio::FitsFile;
+
+    // let filename = ...;
+    // Safety: nothing modifies the file while it is open
+    let mut fptr = unsafe { FitsFile::open_shared(filename)? };
+    let hdu = fptr.hdu("TESTEXT")?;
+    assert_eq!(hdu.num_rows(&mut fptr)?, 50);
+    # Ok(())
+    # }
+    ```
+    */
+    #[cfg(unix)]
+    pub unsafe fn open_shared<T: AsRef<Path>>(filename: T) -> Result<Self> {
+        let file_path = filename.as_ref();
+        let mut memory = MemoryBuffer::map(file_path)?;
+        let fptr = memory.open(&file_path.to_string_lossy())?;
+
+        match ptr::NonNull::new(fptr) {
+            Some(p) => Ok(FitsFile {
+                fptr: p,
+                open_mode: FileOpenMode::READONLY,
+                filename: Some(file_path.to_path_buf()),
+                atomic_target: None,
+                memory: Some(memory),
+                strict: false,
+                renumbered_hdus: Vec::new(),
+                key_decimals: DEFAULT_KEY_DECIMALS,
+                key_format: FloatFormat::Exponential,
+            }),
+            None => Err(Error::NullPointer),
+        }
+    }
+
+    /**
+    Read a fits file from any seekable source
+
+    Everything from the current position of `reader` to its end is read into memory, and
+    opened read only with the `cfitsio` memory driver. This allows data already held in
+    Rust, such as downloaded bytes or object store blobs, to be read without writing a
+    temporary file.
+
+    # Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    use fitsio::FitsFile;
+    use std::io::Cursor;
+
+    let bytes = std::fs::read("../testdata/full_example.fits")?;
+    let mut fptr = FitsFile::from_reader(Cursor::new(bytes))?;
+    let hdu = fptr.hdu("TESTEXT")?;
+    assert_eq!(hdu.num_rows(&mut fptr)?, 50);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn from_reader<R: Read + Seek>(mut reader: R) -> Result<Self> {
+        let start = reader.stream_position()?;
+        let end = reader.seek(SeekFrom::End(0))?;
+        reader.seek(SeekFrom::Start(start))?;
+
+        let mut bytes = Vec::with_capacity(end.saturating_sub(start) as usize);
+        reader.read_to_end(&mut bytes)?;
+        Self::from_memory(MemoryBuffer::from_bytes(bytes)?)
+    }
+
+    /// Open a fits file held in memory read only
+    pub(crate) fn from_memory(mut memory: Box<MemoryBuffer>) -> Result<Self> {
+        let fptr = memory.open("")?;
+
+        match ptr::NonNull::new(fptr) {
+            Some(p) => Ok(FitsFile {
+                fptr: p,
+                open_mode: FileOpenMode::READONLY,
+                filename: None,
+                atomic_target: None,
+                memory: Some(memory),
+                strict: false,
+                renumbered_hdus: Vec::new(),
+                key_decimals: DEFAULT_KEY_DECIMALS,
+                key_format: FloatFormat::Exponential,
+            }),
+            None => Err(Error::NullPointer),
+        }
+    }
+
+    /**
+    Read a fits file from standard input
+
+    `cfitsio` reads the whole stream into memory, so the file can be navigated freely. Together
+    with [`create_stdout`](#method.create_stdout) this allows Unix pipe friendly filters to be
+    written.
+
+    # Example
+
+    ```rust,no_run
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    use fitsio::FitsFile;
+
+    // Copy the first extension of a file: `filter < in.fits > out.fits`
+    let mut src = FitsFile::open_stdin()?;
+    let mut dest = FitsFile::create_stdout().open()?;
+    let hdu = src.hdu(1)?;
+    hdu.copy_to(&mut src, &mut dest)?;
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn open_stdin() -> Result<Self> {
+        let mut f = FitsFile::open(STREAM_FILENAME)?;
+        f.filename = None;
+        Ok(f)
+    }
+
+    /**
+    Reopen a file in read/write mode
+
+    The file is closed and opened again from the same path with [`edit`](#method.edit), and
+    the current HDU is preserved. Files which are already open in read/write mode are returned
+    unchanged. Files which were not opened from a path, such as those created with
+    [`from_raw`](#method.from_raw), cannot be reopened.
+
+    # Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+    # let tdir_path = tdir.path();
+    # let filename = tdir_path.join("test.fits");
+    # std::fs::copy("../testdata/full_example.fits", &filename)?;
+    use fitsio::FitsFile;
+
+    // let filename = ...;
+    let mut fptr = FitsFile::open(&filename)?;
+    let hdu = fptr.hdu("TESTEXT")?;
+    let num_rows = hdu.num_rows(&mut fptr)?;
+
+    let mut fptr = fptr.into_edit()?;
+    let hdu = fptr.hdu("TESTEXT")?;
+    hdu.write_key(&mut fptr, "NROWS", num_rows as i64)?;
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn into_edit(mut self) -> Result<FitsFile> {
+        if self.open_mode == FileOpenMode::READWRITE {
+            return Ok(self);
+        }
+
+        let filename = match self.filename {
+            Some(ref filename) => filename.clone(),
+            None => return Err("cannot reopen a file which was not opened from a path".into()),
+        };
+        let hdu_number = self.hdu_number();
+        let strict = self.strict;
+
+        // Close the read only handle first, so cfitsio does not share it with the new one
+        drop(self);
+        let mut f = FitsFile::edit(filename)?;
+        f.change_hdu(hdu_number)?;
+        f.strict = strict;
+        Ok(f)
+    }
+
+    /**
+    Convert a read only file into a [`SharedFitsFile`] which can be read from many threads at once
+
+    Unlike [`threadsafe`](#method.threadsafe), which serialises every access through a single
+    mutex, each thread borrows its own handle to the file, so moving between HDUs in one thread
+    does not block reads in another. This requires a `cfitsio` built with `--enable-reentrant`
+    (as the bundled `fitsio-src` build is), and a file opened read only from a path.
+
+    The file is read into memory once, and every handle is opened separately onto that copy.
+    `cfitsio` shares a single set of buffers between handles opened from the same path, so
+    opening the path again would not give handles which can be used from different threads.
+
+    # Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    use fitsio::FitsFile;
+    use std::sync::Arc;
+    use std::thread;
+
+    let shared = Arc::new(FitsFile::open("../testdata/full_example.fits")?.into_shared()?);
+    let threads: Vec<_> = (0..4)
+        .map(|_| {
+            let shared = Arc::clone(&shared);
+            thread::spawn(move || {
+                let mut f = shared.handle().unwrap();
+                let hdu = f.hdu("TESTEXT").unwrap();
+                hdu.num_rows(&mut f).unwrap()
+            })
+        })
+        .collect();
+    for thread in threads {
+        assert_eq!(thread.join().unwrap(), 50);
+    }
+    # Ok(())
+    # }
+    ```
+
+    [`SharedFitsFile`]: ../threadsafe_fitsfile/struct.SharedFitsFile.html
+    */
+    pub fn into_shared(self) -> Result<SharedFitsFile> {
+        if !crate::sys::capabilities().reentrant {
+            return Err(
+                "cfitsio was not built to be reentrant, so the file cannot be shared; use `threadsafe` instead"
+                    .into(),
+            );
+        }
+        if self.open_mode != FileOpenMode::READONLY {
+            return Err("only files opened read only can be shared".into());
+        }
+        let filename = match (&self.filename, &self.memory) {
+            (Some(filename), None) => filename.clone(),
+            _ => return Err("cannot share a file which was not opened from a path".into()),
+        };
+        SharedFitsFile::new(self, &filename)
+    }
+
+    /**
+    Enable strict mode
+
+    In strict mode, reads which would silently lose information return errors instead. This
+    covers reading images into a type which cannot hold every pixel value exactly, for example
+    a floating point (or scaled) image into `Vec<i32>`, or a 32 bit image into `Vec<i16>`, and
+    reading header keys into a type which cannot hold their value, for example a floating point
+    or out of range value into `i32`. Strict mode can be changed at any time with
+    [`set_strict`](#method.set_strict).
+
+    # Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    use fitsio::FitsFile;
+
+    let mut fptr = FitsFile::open("../testdata/full_example.fits")?.strict();
+    let hdu = fptr.primary_hdu()?;
+
+    // The primary image contains 32 bit integers
+    let pixels: Vec<i64> = hdu.read_image(&mut fptr)?;
+    assert!(hdu.read_image::<Vec<i16>>(&mut fptr).is_err());
+
+    // DBLTEST is 0.09375
+    assert!(hdu.read_key::<i32>(&mut fptr, "DBLTEST").is_err());
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn strict(mut self) -> Self {
+        self.strict = true;
+        self
+    }
+
+    /// Enable or disable strict mode, see [`strict`](#method.strict)
+    pub fn set_strict(&mut self, strict: bool) {
+        self.strict = strict;
+    }
+
+    /// Whether strict mode is enabled, see [`strict`](#method.strict)
+    pub fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    /**
+    Set the number of decimal places floating point header keys are written with
+
+    Keys are written with 9 decimal places by default. Times such as `MJD-OBS` need more to keep
+    their precision, while fewer gives more compact headers. This applies to every float key
+    written with [`write_key`], see [`write_key_with_precision`] to set the precision of a single
+    key, and [`set_key_format`](#method.set_key_format) to choose the notation.
+
+    # Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+    # let filename = tdir.path().join("test.fits");
+    let mut fptr = fitsio::FitsFile::create(filename).open()?;
+    fptr.set_key_precision(15);
+    let hdu = fptr.primary_hdu()?;
+    hdu.write_key(&mut fptr, "MJD-OBS", 60000.123456789)?;
+    assert_eq!(hdu.read_key::<f64>(&mut fptr, "MJD-OBS")?, 60000.123456789);
+    # Ok(())
+    # }
+    ```
+
+    [`write_key`]: ../hdu/struct.FitsHdu.html#method.write_key
+    [`write_key_with_precision`]: ../hdu/struct.FitsHdu.html#method.write_key_with_precision
+    */
+    pub fn set_key_precision(&mut self, decimals: usize) {
+        self.key_decimals = decimals;
+    }
+
+    /// Number of decimal places float header keys are written with, see [`set_key_precision`](#method.set_key_precision)
+    pub fn key_precision(&self) -> usize {
+        self.key_decimals
+    }
+
+    /**
+    Set the notation floating point header keys are written in
+
+    Keys are written in [`FloatFormat::Exponential`] notation by default. This applies to every
+    float key written with [`write_key`], with the number of decimal places set by
+    [`set_key_precision`](#method.set_key_precision).
+
+    # Example
+
+    ```rust
+    use fitsio::headers::FloatFormat;
+
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+    # let filename = tdir.path().join("test.fits");
+    let mut fptr = fitsio::FitsFile::create(filename).open()?;
+    fptr.set_key_format(FloatFormat::Fixed);
+    fptr.set_key_precision(2);
+    let hdu = fptr.primary_hdu()?;
+    hdu.write_key(&mut fptr, "EXPTIME", 30.0)?;
+    let cards = hdu.read_keys_matching(&mut fptr, "EXPTIME")?;
+    assert_eq!(cards[0].value, "30.00");
+    # Ok(())
+    # }
+    ```
+
+    [`FloatFormat::Exponential`]: ../headers/enum.FloatFormat.html#variant.Exponential
+    [`write_key`]: ../hdu/struct.FitsHdu.html#method.write_key
+    */
+    pub fn set_key_format(&mut self, format: FloatFormat) {
+        self.key_format = format;
+    }
+
+    /// Notation float header keys are written in, see [`set_key_format`](#method.set_key_format)
+    pub fn key_format(&self) -> FloatFormat {
+        self.key_format
+    }
+
     /**
     Create a new fits file on disk
 
@@ -156,10 +674,57 @@ impl FitsFile {
         NewFitsFile {
             path,
             image_description: None,
+            primary_keys: Vec::new(),
             overwrite: false,
+            atomic: false,
+            empty: false,
         }
     }
 
+    /**
+    Create a new fits file, written to standard output
+
+    The file is built in memory, and written to standard output when it is closed. Nothing else
+    should be written to standard output while the file is open. See
+    [`open_stdin`](#method.open_stdin) for an example.
+    */
+    pub fn create_stdout<'a>() -> NewFitsFile<'a, &'static str> {
+        FitsFile::create(STREAM_FILENAME)
+    }
+
+    /**
+    Create a new fits file in memory
+
+    The contents of the file can be fetched with [`into_bytes`](#method.into_bytes) or
+    [`write_to`](#method.write_to), for example to return a file over HTTP without writing it
+    to disk. The other options of [`NewFitsFile`] can be used as for files on disk, except for
+    [`atomic`](struct.NewFitsFile.html#method.atomic) which has no effect.
+
+    # Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    use fitsio::FitsFile;
+
+    let mut fptr = FitsFile::create_in_memory()
+        .with_primary_keys(&[("OBJECT", "M31")])
+        .open()?;
+    let hdu = fptr.primary_hdu()?;
+    hdu.write_key(&mut fptr, "EXPTIME", 30.0)?;
+
+    let bytes = fptr.into_bytes()?;
+    assert_eq!(bytes.len() % 2880, 0);
+    assert!(bytes.starts_with(b"SIMPLE  ="));
+    # Ok(())
+    # }
+    ```
+
+    [`NewFitsFile`]: struct.NewFitsFile.html
+    */
+    pub fn create_in_memory<'a>() -> NewFitsFile<'a, &'static str> {
+        FitsFile::create(MEMORY_FILENAME)
+    }
+
     /// Method to extract what open mode the file is in
     pub(crate) fn open_mode(&mut self) -> Result<FileOpenMode> {
         let mut status = 0;
@@ -233,6 +798,151 @@ impl FitsFile {
         FitsHdu::new(self, hdu_description)
     }
 
+    /**
+    Return the `n`th image or table HDU in the file, counting from 0
+
+    This is useful when extensions are unnamed, or their order varies between files. Image HDUs
+    without any data, such as an empty primary HDU, are not counted as images.
+
+    # Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let filename = "../testdata/full_example.fits";
+    use fitsio::hdu::HduType;
+
+    let mut fptr = fitsio::FitsFile::open(filename)?;
+    let table = fptr.hdu_by_type(HduType::Table, 0)?;
+    assert_eq!(table, fptr.hdu("TESTEXT")?);
+    assert!(fptr.hdu_by_type(HduType::Table, 1).is_err());
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn hdu_by_type(&mut self, hdu_type: HduType, n: usize) -> Result<FitsHdu> {
+        self.hdu((hdu_type, n))
+    }
+
+    /**
+    Delete an HDU from the file
+
+    This is equivalent to fetching the HDU and calling [`FitsHdu::delete`]. Any `FitsHdu`
+    after the deleted HDU must be fetched again, as the HDUs following it are renumbered.
+
+    # Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir()?;
+    # let filename = tdir.path().join("test.fits");
+    # std::fs::copy("../testdata/full_example.fits", &filename)?;
+    let mut fptr = fitsio::FitsFile::edit(filename)?;
+    fptr.delete_hdu("TESTEXT")?;
+    assert_eq!(fptr.num_hdus()?, 1);
+    # Ok(())
+    # }
+    ```
+
+    [`FitsHdu::delete`]: ../hdu/struct.FitsHdu.html#method.delete
+    */
+    pub fn delete_hdu<T: DescribesHdu>(&mut self, hdu_description: T) -> Result<()> {
+        let hdu = self.hdu(hdu_description)?;
+        hdu.delete(self)
+    }
+
+    /**
+    Move the data of the primary HDU into a new image extension
+
+    The primary HDU is converted into an image extension, with `SIMPLE` replaced by
+    `XTENSION = 'IMAGE'` and the `PCOUNT` and `GCOUNT` keywords added, and an empty primary HDU
+    is inserted before it. The new extension is returned. Every other HDU is renumbered, so any
+    `FitsHdu` fetched before must be fetched again.
+
+    See [`FitsHdu::promote_to_file`] to do the reverse.
+
+    # Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir()?;
+    # let filename = tdir.path().join("test.fits");
+    # std::fs::copy("../testdata/full_example.fits", &filename)?;
+    use fitsio::hdu::HduInfo;
+
+    let mut fptr = fitsio::FitsFile::edit(filename)?;
+    let hdu = fptr.demote_primary()?;
+    assert_eq!(hdu.number, 1);
+    assert_eq!(hdu.read_key::<String>(&mut fptr, "XTENSION")?, "IMAGE");
+    if let HduInfo::ImageInfo { shape, .. } = fptr.primary_hdu()?.info {
+        assert!(shape.is_empty());
+    }
+    # Ok(())
+    # }
+    ```
+
+    [`FitsHdu::promote_to_file`]: ../hdu/struct.FitsHdu.html#method.promote_to_file
+    */
+    pub fn demote_primary(&mut self) -> Result<FitsHdu> {
+        fits_check_readwrite!(self);
+        let primary = self.primary_hdu()?;
+        match primary.info {
+            HduInfo::ImageInfo { ref shape, .. } if !shape.is_empty() => {}
+            _ => return Err("the primary HDU has no data to move into an extension".into()),
+        }
+
+        // Setting the status to `PREPEND_PRIMARY` makes cfitsio insert a new primary HDU, and
+        // convert the old one into an image extension
+        let mut status = PREPEND_PRIMARY;
+        unsafe {
+            fits_insert_img(
+                self.fptr.as_mut() as *mut _,
+                8,
+                0,
+                ptr::null_mut(),
+                &mut status,
+            );
+        }
+        check_status(status)?;
+        self.renumbered_hdus.push(0);
+        self.hdu(1)
+    }
+
+    /**
+    Return a HDU bound to this file, so its methods do not need the file passed in
+
+    The returned [`BoundHdu`] mutably borrows the file until it is dropped, so the borrow checker
+    ensures it is not used with a different file. The methods taking a [`FitsHdu`] and file
+    remain available, and are needed to work with more than one HDU at a time.
+
+    # Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let filename = "../testdata/full_example.fits";
+    # use fitsio::FitsFile;
+    let mut fptr = FitsFile::open(filename)?;
+
+    let mut hdu = fptr.hdu_mut(0)?;
+    let image: Vec<i32> = hdu.read_image()?;
+    let dimensions: i64 = hdu.read_key("NAXIS")?;
+    # assert_eq!(image.len(), 100 * 100);
+    # assert_eq!(dimensions, 2);
+
+    let mut table = fptr.hdu_mut("TESTEXT")?;
+    let intcol: Vec<i32> = table.read_col("intcol")?;
+    # assert_eq!(intcol.len(), table.num_rows()?);
+    # Ok(())
+    # }
+    ```
+
+    [`BoundHdu`]: ../hdu/struct.BoundHdu.html
+    [`FitsHdu`]: ../hdu/struct.FitsHdu.html
+    */
+    pub fn hdu_mut<T: DescribesHdu>(&mut self, hdu_description: T) -> Result<BoundHdu<'_>> {
+        let hdu = FitsHdu::new(self, hdu_description)?;
+        Ok(BoundHdu::new(self, hdu))
+    }
+
     /**
     Return the primary hdu (HDU 0)
 
@@ -255,8 +965,21 @@ impl FitsFile {
         self.hdu(0)
     }
 
-    /// Return the number of HDU objects in the file
-    fn num_hdus(&mut self) -> Result<usize> {
+    /**
+    Return the number of HDU objects in the file
+
+    # Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let filename = "../testdata/full_example.fits";
+    let mut fptr = fitsio::FitsFile::open(filename)?;
+    assert_eq!(fptr.num_hdus()?, 2);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn num_hdus(&mut self) -> Result<usize> {
         let mut status = 0;
         let mut num_hdus = 0;
         unsafe {
@@ -266,19 +989,90 @@ impl FitsFile {
         check_status(status).map(|_| num_hdus as _)
     }
 
-    /// Return the list of HDU names
-    pub(crate) fn hdu_names(&mut self) -> Result<Vec<String>> {
+    /**
+    Summarise every HDU in the file
+
+    The file is visited once, collecting the name and [`HduInfo`] of each HDU in order.
+
+    # Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let filename = "../testdata/full_example.fits";
+    use fitsio::hdu::HduInfo;
+
+    let mut fptr = fitsio::FitsFile::open(filename)?;
+    for hdu in fptr.summary()? {
+        if let HduInfo::TableInfo { num_rows, .. } = hdu.info {
+            println!("{}: {} rows", hdu.name, num_rows);
+        }
+    }
+    # Ok(())
+    # }
+    ```
+
+    [`HduInfo`]: hdu/enum.HduInfo.html
+    */
+    pub fn summary(&mut self) -> Result<Vec<HduSummary>> {
         let num_hdus = self.num_hdus()?;
         let mut result = Vec::with_capacity(num_hdus);
-        for i in 0..num_hdus {
-            let hdu = self.hdu(i)?;
-            let name = hdu.name(self)?;
-            result.push(name);
+        for index in 0..num_hdus {
+            self.change_hdu(index)?;
+            let info = self.fetch_hdu_info()?;
+            let name = read_key_optional(self, "EXTNAME")?.unwrap_or_default();
+            result.push(HduSummary { index, name, info });
         }
         Ok(result)
     }
 
+    /**
+    Verify every HDU in the file
+
+    The `CHECKSUM` and `DATASUM` keywords of each HDU are checked if present, along with the
+    presence of the keywords the FITS standard requires for the type of HDU. Problems are
+    collected into the returned report rather than stopping at the first one.
+
+    # Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let filename = "../testdata/full_example.fits";
+    let mut fptr = fitsio::FitsFile::open(filename)?;
+    let report = fptr.verify()?;
+    for hdu in report.hdus.iter().filter(|hdu| !hdu.is_valid()) {
+        println!("hdu {} is missing {:?}", hdu.index, hdu.missing_keywords);
+    }
+    assert!(report.is_valid());
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn verify(&mut self) -> Result<FileVerification> {
+        let current = self.hdu_number();
+        let report = verify_file(self);
+        self.change_hdu(current)?;
+        Ok(report)
+    }
+
+    /**
+    Select `hdu`, unless it is already the current HDU
+
+    The current HDU number is tracked by `cfitsio` on the file handle, so checking it is cheap
+    and stays correct when the HDU is changed through other means (e.g. creating or deleting
+    HDUs, or through [`as_raw`](#method.as_raw)).
+    */
     pub(crate) fn make_current(&mut self, hdu: &FitsHdu) -> Result<()> {
+        // Deleting or inserting an HDU at or before this one changes its number
+        let renumbered = self.renumbered_hdus.get(hdu.renumberings..).unwrap_or(&[]);
+        if renumbered.iter().any(|&number| number <= hdu.number) {
+            return Err(Error::Message(format!(
+                "HDU {} was fetched before an earlier HDU was deleted or inserted, fetch it again",
+                hdu.number
+            )));
+        }
+        if self.hdu_number() == hdu.number {
+            return Ok(());
+        }
         self.change_hdu(hdu.number)
     }
 
@@ -335,22 +1129,18 @@ impl FitsFile {
                     fits_get_img_equivtype(self.fptr.as_mut() as *mut _, &mut bitpix, &mut status);
                 }
 
-                let image_type = match bitpix {
-                    8 => ImageType::UnsignedByte,
-                    10 => ImageType::Byte,
-                    16 => ImageType::Short,
-                    20 => ImageType::UnsignedShort,
-                    32 => ImageType::Long,
-                    40 => ImageType::UnsignedLong,
-                    64 => ImageType::LongLong,
-                    -32 => ImageType::Float,
-                    -64 => ImageType::Double,
-                    _ => unreachable!("{}", format!("Unhandled image bitpix type: {}", bitpix)),
-                };
+                let mut raw_bitpix = 0;
+                unsafe {
+                    fits_get_img_type(self.fptr.as_mut() as *mut _, &mut raw_bitpix, &mut status);
+                }
+
+                check_status(status)?;
+                let image_type = ImageType::from_bitpix(bitpix)?;
 
                 HduInfo::ImageInfo {
                     shape: shape.iter().map(|v| *v as usize).collect(),
                     image_type,
+                    raw_bitpix,
                 }
             }
             1 | 2 => {
@@ -437,7 +1227,7 @@ impl FitsFile {
         table_description: &[ConcreteColumnDescription],
     ) -> Result<FitsHdu>
     where
-        T: Into<String>,
+        T: Into<HduIdentity>,
     {
         fits_check_readwrite!(self);
 
@@ -457,7 +1247,8 @@ impl FitsFile {
             stringutils::StringList::from_slice(stringlist.as_slice())?
         };
 
-        let c_extname = ffi::CString::new(extname.into())?;
+        let identity = extname.into();
+        let c_extname = ffi::CString::new(identity.name.clone().unwrap_or_default())?;
 
         let hdu_info = HduInfo::TableInfo {
             column_descriptions: table_description.to_vec(),
@@ -478,8 +1269,14 @@ impl FitsFile {
                 &mut status,
             );
         }
+        check_status(status)?;
 
-        check_status(status).and_then(|_| self.current_hdu())
+        HduIdentity {
+            name: None,
+            ..identity
+        }
+        .write(self)?;
+        self.current_hdu()
     }
 
     /**
@@ -515,7 +1312,7 @@ impl FitsFile {
         image_description: &ImageDescription,
     ) -> Result<FitsHdu>
     where
-        T: Into<String>,
+        T: Into<HduIdentity>,
     {
         fits_check_readwrite!(self);
 
@@ -558,12 +1355,140 @@ impl FitsFile {
         }
 
         // Current HDU should be at the new HDU
-        let current_hdu = self.current_hdu()?;
-        current_hdu.write_key(self, "EXTNAME", extname.into())?;
+        extname.into().write(self)?;
 
         check_status(status).and_then(|_| self.current_hdu())
     }
 
+    /**
+    Compress an image HDU into a new HDU of another file
+
+    This is equivalent to running `fpack` on a single HDU. The compressed image is appended to
+    `dest`, and can be read like any other image. The header keywords are copied to the new HDU.
+
+    # Example
+
+    ```rust
+    use fitsio::images::{CompressionAlgorithm, CompressionOptions};
+
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+    # let filename = tdir.path().join("test.fits.fz");
+    let mut src = fitsio::FitsFile::open("../testdata/full_example.fits")?;
+    let mut dest = fitsio::FitsFile::create(filename).open()?;
+
+    let hdu = src.primary_hdu()?;
+    let options = CompressionOptions::new(CompressionAlgorithm::Rice);
+    let compressed = src.compress_hdu_to(&hdu, &mut dest, &options)?;
+
+    let original: Vec<i32> = hdu.read_image(&mut src)?;
+    let data: Vec<i32> = compressed.read_image(&mut dest)?;
+    assert_eq!(data, original);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn compress_hdu_to(
+        &mut self,
+        hdu: &FitsHdu,
+        dest: &mut FitsFile,
+        options: &CompressionOptions,
+    ) -> Result<FitsHdu> {
+        if !hdu.is_image() {
+            return Err("cannot compress a table hdu".into());
+        }
+        fits_check_readwrite!(dest);
+        self.make_current(hdu)?;
+
+        let mut status = 0;
+        let result = options.apply(dest).and_then(|_| {
+            unsafe {
+                fits_img_compress(
+                    self.fptr.as_mut() as *mut _,
+                    dest.fptr.as_mut() as *mut _,
+                    &mut status,
+                );
+            }
+            check_status(status)
+        });
+
+        if result.is_err() {
+            // Make sure later images created in `dest` are not compressed
+            let mut status = 0;
+            unsafe {
+                fits_set_compression_type(dest.fptr.as_mut() as *mut _, 0, &mut status);
+            }
+        }
+
+        self.restore_image_scaling()?;
+        result?;
+        dest.restore_image_scaling()?;
+        dest.current_hdu()
+    }
+
+    /**
+    Uncompress a compressed image HDU into a new HDU of another file
+
+    This is equivalent to running `funpack` on a single HDU. If `dest` only contains an empty
+    primary HDU and the image was compressed from a primary HDU, the image is written to the
+    primary HDU of `dest`, otherwise it is appended as a new image extension.
+
+    # Example
+
+    ```rust
+    use fitsio::images::{CompressionAlgorithm, CompressionOptions};
+
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+    # let compressed_filename = tdir.path().join("test.fits.fz");
+    # let filename = tdir.path().join("test.fits");
+    # let mut src = fitsio::FitsFile::open("../testdata/full_example.fits")?;
+    # let mut compressed = fitsio::FitsFile::create(compressed_filename).open()?;
+    # let hdu = src.primary_hdu()?;
+    # let options = CompressionOptions::new(CompressionAlgorithm::Rice);
+    let hdu = src.compress_hdu_to(&hdu, &mut compressed, &options)?;
+
+    let mut dest = fitsio::FitsFile::create(filename).open()?;
+    let uncompressed = compressed.uncompress_hdu_to(&hdu, &mut dest)?;
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn uncompress_hdu_to(&mut self, hdu: &FitsHdu, dest: &mut FitsFile) -> Result<FitsHdu> {
+        fits_check_readwrite!(dest);
+        self.make_current(hdu)?;
+
+        let mut status = 0;
+        let is_compressed =
+            unsafe { fits_is_compressed_image(self.fptr.as_mut() as *mut _, &mut status) };
+        check_status(status)?;
+        if is_compressed == 0 {
+            return Err("hdu is not a compressed image".into());
+        }
+
+        unsafe {
+            fits_img_decompress(
+                self.fptr.as_mut() as *mut _,
+                dest.fptr.as_mut() as *mut _,
+                &mut status,
+            );
+        }
+
+        self.restore_image_scaling()?;
+        check_status(status)?;
+        dest.restore_image_scaling()?;
+        dest.current_hdu()
+    }
+
+    /// Restore the pixel scaling of the current image from its header
+    ///
+    /// `cfitsio` turns off scaling of both images when compressing or uncompressing them.
+    fn restore_image_scaling(&mut self) -> Result<()> {
+        let scale = f64::read_key(self, "BSCALE").unwrap_or(1.0);
+        let offset = f64::read_key(self, "BZERO").unwrap_or(0.0);
+        set_image_scaling(self, scale, offset)
+    }
+
     /**
     Iterate over the HDUs in the file
 
@@ -645,6 +1570,40 @@ impl FitsFile {
     [`pretty_write`]: #method.pretty_write
     */
     pub fn pretty_write<W>(&mut self, w: &mut W) -> Result<()>
+    where
+        W: Write,
+    {
+        self.pretty_write_with(w, &PrettyPrintOptions::default())
+    }
+
+    /**
+    Pretty-print the fits file structure to any `Write` implementor, with extra detail
+
+    The [`PrettyPrintOptions`] control whether the columns of each table and the header cards
+    of each HDU are included.
+
+    # Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    use fitsio::{FitsFile, PrettyPrintOptions};
+
+    # let filename = "../testdata/full_example.fits";
+    # use std::io;
+    let mut fptr = FitsFile::open(filename)?;
+    let options = PrettyPrintOptions {
+        max_columns: 10,
+        show_units: true,
+        ..Default::default()
+    };
+    fptr.pretty_write_with(&mut io::stdout(), &options)?;
+    # Ok(())
+    # }
+    ```
+
+    [`PrettyPrintOptions`]: struct.PrettyPrintOptions.html
+    */
+    pub fn pretty_write_with<W>(&mut self, w: &mut W, options: &PrettyPrintOptions) -> Result<()>
     where
         W: Write,
     {
@@ -659,13 +1618,18 @@ impl FitsFile {
         /* Header line for HDUs */
         writeln!(w, "  extnum hdutype      hduname    details")?;
 
-        let hdu_names = self.hdu_names().expect("fetching hdu names");
-
-        for (i, hdu) in self.iter().enumerate() {
-            let hdu_name = &hdu_names[i];
+        for summary in self.summary()? {
+            let hdu_name = if summary.name.is_empty() {
+                "<unnamed>"
+            } else {
+                summary.name.as_str()
+            };
+            let i = summary.index;
 
-            match hdu.info {
-                HduInfo::ImageInfo { shape, image_type } => {
+            match summary.info {
+                HduInfo::ImageInfo {
+                    shape, image_type, ..
+                } => {
                     let hdu_type = "IMAGE_HDU";
                     writeln!(
                         w,
@@ -691,9 +1655,57 @@ impl FitsFile {
                         num_cols = column_descriptions.len(),
                         num_rows = num_rows,
                     )?;
+
+                    if options.max_columns > 0 {
+                        self.change_hdu(i)?;
+                        for (colno, column) in column_descriptions
+                            .iter()
+                            .take(options.max_columns)
+                            .enumerate()
+                        {
+                            let tform = String::from(column.data_type.clone());
+                            let unit = if options.show_units {
+                                String::read_key(self, &format!("TUNIT{}", colno + 1))
+                                    .unwrap_or_default()
+                            } else {
+                                String::new()
+                            };
+                            writeln!(
+                                w,
+                                "{:9}{name:20} {tform:8} {unit}",
+                                "",
+                                name = column.name,
+                                tform = tform,
+                                unit = unit,
+                            )?;
+                        }
+                        if column_descriptions.len() > options.max_columns {
+                            writeln!(
+                                w,
+                                "{:9}... {} more columns",
+                                "",
+                                column_descriptions.len() - options.max_columns
+                            )?;
+                        }
+                    }
                 }
                 HduInfo::AnyInfo => unreachable!(),
             }
+
+            if options.show_headers {
+                self.change_hdu(i)?;
+                for card in crate::headers::read_keys_matching(self, "*")? {
+                    if card.comment.is_empty() {
+                        writeln!(w, "{:9}{:8} = {}", "", card.name, card.value)?;
+                    } else {
+                        writeln!(
+                            w,
+                            "{:9}{:8} = {} / {}",
+                            "", card.name, card.value, card.comment
+                        )?;
+                    }
+                }
+            }
         }
 
         Ok(())
@@ -740,6 +1752,140 @@ impl FitsFile {
         self.fptr.as_mut() as *mut _
     }
 
+    /**
+    Write the contents of the file to `writer`
+
+    Any buffered changes are flushed first. This is supported for files in memory, created
+    with [`create_in_memory`](#method.create_in_memory) or opened with
+    [`from_reader`](#method.from_reader) or [`open_shared`](#method.open_shared), and for
+    files on disk, which are read back from their path. Other files, such as those written to
+    standard output, are not supported.
+
+    # Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    use fitsio::FitsFile;
+
+    let mut fptr = FitsFile::open("../testdata/full_example.fits")?;
+    let mut contents = Vec::new();
+    fptr.write_to(&mut contents)?;
+    assert_eq!(contents, std::fs::read("../testdata/full_example.fits")?);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn write_to<W: Write>(&mut self, writer: &mut W) -> Result<()> {
+        let size = self.content_size()?;
+        if let Some(ref memory) = self.memory {
+            writer.write_all(&memory.bytes()[..size])?;
+            return Ok(());
+        }
+
+        match self.filename {
+            Some(ref filename) => {
+                let file = ::std::fs::File::open(filename)?;
+                let copied = io::copy(&mut file.take(size as u64), writer)?;
+                if copied as usize != size {
+                    return Err("file on disk is shorter than its contents".into());
+                }
+                Ok(())
+            }
+            None => Err("the contents of this file are not available".into()),
+        }
+    }
+
+    /**
+    Close the file, returning its contents
+
+    See [`write_to`](#method.write_to) for the files which are supported, and
+    [`create_in_memory`](#method.create_in_memory) for an example.
+    */
+    pub fn into_bytes(mut self) -> Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+        self.write_to(&mut bytes)?;
+        self.close()?;
+        Ok(bytes)
+    }
+
+    /// Flush the file, and find the size of its contents from the end of the last hdu
+    fn content_size(&mut self) -> Result<usize> {
+        let mut status = 0;
+        unsafe {
+            fits_flush_file(self.fptr.as_mut() as *mut _, &mut status);
+        }
+        check_status(status)?;
+
+        let current = self.hdu_number();
+        let last = self.num_hdus()? - 1;
+        self.change_hdu(last)?;
+        let mut data_end = 0;
+        unsafe {
+            fits_get_hduaddrll(
+                self.fptr.as_mut() as *mut _,
+                ptr::null_mut(),
+                ptr::null_mut(),
+                &mut data_end,
+                &mut status,
+            );
+        }
+        check_status(status)?;
+        self.change_hdu(current)?;
+        Ok(data_end as usize)
+    }
+
+    /**
+    Close the file, returning any error
+
+    Dropping a [`FitsFile`] also closes it, but any error is ignored. Files created with
+    [`NewFitsFile::atomic`] are renamed to their final path once they are successfully closed.
+
+    # Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+    # let tdir_path = tdir.path();
+    # let filename = tdir_path.join("test.fits");
+    let fptr = fitsio::FitsFile::create(filename).open()?;
+    fptr.close()?;
+    # Ok(())
+    # }
+    ```
+
+    [`FitsFile`]: struct.FitsFile.html
+    [`NewFitsFile::atomic`]: struct.NewFitsFile.html#method.atomic
+    */
+    pub fn close(self) -> Result<()> {
+        // The file is closed here, so the destructor must not run. Every field which owns memory
+        // is released by hand instead, the memory buffer only after the file is closed.
+        let mut file = ::std::mem::ManuallyDrop::new(self);
+        let result = file.close_file(true);
+        file.filename = None;
+        file.atomic_target = None;
+        file.memory = None;
+        file.renumbered_hdus = Vec::new();
+        result
+    }
+
+    /// Close the file, moving an atomically created file into place if `keep` is set
+    fn close_file(&mut self, keep: bool) -> Result<()> {
+        let mut status = 0;
+        unsafe {
+            fits_close_file(self.fptr.as_mut() as *mut _, &mut status);
+        }
+        let result = check_status(status);
+
+        if let (Some(target), Some(temp)) = (self.atomic_target.take(), self.filename.as_ref()) {
+            if keep && result.is_ok() {
+                ::std::fs::rename(temp, target)?;
+            } else {
+                let _ = ::std::fs::remove_file(temp);
+            }
+        }
+        result
+    }
+
     /// Load a `FitsFile` from a `fitsio_sys::fitsfile` pointer.
     ///
     /// # Safety
@@ -785,6 +1931,12 @@ impl FitsFile {
             filename: None,
             open_mode: mode,
             fptr: ptr::NonNull::new(fptr).ok_or(Error::NullPointer)?,
+            atomic_target: None,
+            memory: None,
+            strict: false,
+            renumbered_hdus: Vec::new(),
+            key_decimals: DEFAULT_KEY_DECIMALS,
+            key_format: FloatFormat::Exponential,
         })
     }
 }
@@ -799,9 +1951,60 @@ impl Drop for FitsFile {
     [`FitsFile`]: struct.FitsFile.html
     */
     fn drop(&mut self) {
-        let mut status = 0;
-        unsafe {
-            fits_close_file(self.fptr.as_mut() as *mut _, &mut status);
+        let _ = self.close_file(!::std::thread::panicking());
+    }
+}
+
+/// Longest file name `cfitsio` accepts, excluding the nul terminator
+const MAX_FILENAME_LENGTH: usize = 1024;
+
+/**
+Convert a path to the file name given to `cfitsio`
+
+On unix the bytes of the path are passed through unchanged, so paths need not be valid UTF-8.
+Elsewhere paths must be valid unicode. Windows paths, including `\\?\` long paths and UNC
+paths, are passed as they are.
+*/
+fn path_to_c_string(path: &Path) -> Result<ffi::CString> {
+    #[cfg(unix)]
+    let bytes = {
+        use std::os::unix::ffi::OsStrExt;
+        path.as_os_str().as_bytes().to_vec()
+    };
+    #[cfg(not(unix))]
+    let bytes = path
+        .to_str()
+        .ok_or_else(|| Error::Message(format!("path {:?} is not valid unicode", path)))?
+        .as_bytes()
+        .to_vec();
+
+    if bytes.len() > MAX_FILENAME_LENGTH {
+        return Err(Error::Message(format!(
+            "path {:?} is longer than the {} bytes supported by cfitsio",
+            path, MAX_FILENAME_LENGTH
+        )));
+    }
+    Ok(ffi::CString::new(bytes)?)
+}
+
+/// Pick an unused hidden path in the same directory as `path`
+fn atomic_temp_path(path: &Path) -> Result<PathBuf> {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| Error::Message(format!("invalid filename {:?}", path)))?;
+    loop {
+        // Keep the original name at the end, so extensions such as `.gz` still apply
+        let mut temp_name = ffi::OsString::from(format!(
+            ".fitsio-{}-{}-",
+            ::std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed),
+        ));
+        temp_name.push(file_name);
+        let temp = path.with_file_name(temp_name);
+        if !temp.exists() {
+            return Ok(temp);
         }
     }
 }
@@ -836,6 +2039,9 @@ let fptr = FitsFile::create(filename)
     .unwrap();
 ```
 
+Keys can be added to the primary header before the file is returned with
+[`with_primary_keys`][new-fits-file-with-primary-keys].
+
 The [`open`][new-fits-file-open] method actually creates a `Result<FitsFile>` from this
 temporary representation.
 
@@ -854,6 +2060,7 @@ let fptr = FitsFile::create(filename).open().unwrap();
 [new-fits-file]: struct.NewFitsFile.html
 [new-fits-file-open]: struct.NewFitsFile.html#method.open
 [new-fits-file-with-custom-primary]: struct.NewFitsFile.html#method.with_custom_primary
+[new-fits-file-with-primary-keys]: struct.NewFitsFile.html#method.with_primary_keys
 */
 pub struct NewFitsFile<'a, T>
 where
@@ -861,7 +2068,11 @@ where
 {
     path: T,
     image_description: Option<ImageDescription<'a>>,
+    primary_keys: Vec<(String, KeyValue)>,
     overwrite: bool,
+    atomic: bool,
+    /// Create the file without any HDUs, so the first HDU written becomes the primary HDU
+    empty: bool,
 }
 
 impl<'a, T> NewFitsFile<'a, T>
@@ -877,47 +2088,104 @@ where
         let mut fptr = ptr::null_mut();
         let mut status = 0;
         let file_path = self.path.as_ref();
-        let path = file_path.to_str().expect("converting filename");
-        let c_filename = ffi::CString::new(path)?;
+        let in_memory = file_path.to_str() == Some(MEMORY_FILENAME);
+        let on_disk = !in_memory && file_path.to_str() != Some(STREAM_FILENAME);
 
         // Check if there is an existing file already with the given filename
-        if self.path.as_ref().is_file() {
+        if on_disk && self.path.as_ref().is_file() {
             // Check if the overwrite flag is set
             if !self.overwrite {
-                return Err(Error::ExistingFile(path.to_owned()));
-            } else {
+                return Err(Error::ExistingFile(
+                    file_path.to_string_lossy().into_owned(),
+                ));
+            } else if !self.atomic {
                 ::std::fs::remove_file(self.path.as_ref())?;
             }
         }
 
-        unsafe {
-            fits_create_file(
-                &mut fptr as *mut *mut fitsfile,
-                c_filename.as_ptr(),
-                &mut status,
-            );
-        }
+        // Atomic files are written next to their final path, and renamed into place on close
+        let (create_path, atomic_target) = if self.atomic && on_disk {
+            (atomic_temp_path(file_path)?, Some(file_path.to_path_buf()))
+        } else {
+            (file_path.to_path_buf(), None)
+        };
+
+        let memory = if in_memory {
+            let mut memory = MemoryBuffer::allocate()?;
+            fptr = memory.create()?;
+            Some(memory)
+        } else {
+            let c_filename = path_to_c_string(&create_path)?;
+            unsafe {
+                fits_create_file(
+                    &mut fptr as *mut *mut fitsfile,
+                    c_filename.as_ptr(),
+                    &mut status,
+                );
+            }
+            None
+        };
 
         check_status(status).and_then(|_| {
             let mut f = match ptr::NonNull::new(fptr) {
                 Some(p) => FitsFile {
                     fptr: p,
                     open_mode: FileOpenMode::READWRITE,
-                    filename: Some(file_path.to_path_buf()),
+                    filename: if on_disk {
+                        Some(create_path.clone())
+                    } else {
+                        None
+                    },
+                    atomic_target,
+                    memory,
+                    strict: false,
+                    renumbered_hdus: Vec::new(),
+                    key_decimals: DEFAULT_KEY_DECIMALS,
+                    key_format: FloatFormat::Exponential,
                 },
                 None => unimplemented!(),
             };
 
-            match self.image_description {
-                Some(ref description) => {
-                    let _ = f.create_image("_PRIMARY".to_string(), description)?;
+            let result = match self.image_description {
+                Some(ref description) => f
+                    .create_image("_PRIMARY".to_string(), description)
+                    .map(|_| ()),
+                None if self.empty => Ok(()),
+                None => f.add_empty_primary(),
+            }
+            .and_then(|_| Self::write_primary_keys(&mut f, &self.primary_keys));
+
+            if let Err(e) = result {
+                // Do not leave a file without its requested primary header behind
+                f.atomic_target = None;
+                drop(f);
+                if on_disk {
+                    let _ = ::std::fs::remove_file(&create_path);
                 }
-                None => f.add_empty_primary()?,
+                return Err(e);
             }
             Ok(f)
         })
     }
 
+    /// Create the file without a primary HDU, for the caller to add one
+    pub(crate) fn empty(mut self) -> Self {
+        self.empty = true;
+        self
+    }
+
+    fn write_primary_keys(f: &mut FitsFile, keys: &[(String, KeyValue)]) -> Result<()> {
+        if keys.is_empty() {
+            return Ok(());
+        }
+
+        let hdu = f.primary_hdu()?;
+        for (name, value) in keys {
+            hdu.write_key(f, name, value.clone())?;
+        }
+        Ok(())
+    }
+
     /**
     When creating a new file, add a custom primary HDU description before creating the
     [`FitsFile`] object.
@@ -987,6 +2255,80 @@ where
         self.overwrite = true;
         self
     }
+
+    /**
+    Write keys to the primary header when creating the file
+
+    The keys are written before [`open`] returns, so the file is never returned without them.
+    If any key cannot be written, the new file is removed and the error is returned. Keys of
+    different types can be given together by converting the values to
+    [`KeyValue`](../headers/enum.KeyValue.html).
+
+    # Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+    # let tdir_path = tdir.path();
+    # let filename = tdir_path.join("test.fits");
+    use fitsio::FitsFile;
+    use fitsio::headers::KeyValue;
+
+    let mut fptr = FitsFile::create(filename)
+        .with_primary_keys(&[("TELESCOP", "INT"), ("INSTRUME", "WFC")])
+        .with_primary_keys(&[("EXPTIME", KeyValue::from(30.0))])
+        .open()?;
+
+    let hdu = fptr.primary_hdu()?;
+    assert_eq!(hdu.read_key::<String>(&mut fptr, "TELESCOP")?, "INT");
+    # Ok(())
+    # }
+    ```
+
+    [`open`]: struct.NewFitsFile.html#method.open
+    */
+    pub fn with_primary_keys<K: Into<KeyValue> + Clone>(mut self, keys: &[(&str, K)]) -> Self {
+        self.primary_keys.extend(
+            keys.iter()
+                .map(|(name, value)| (name.to_string(), value.clone().into())),
+        );
+        self
+    }
+
+    /**
+    Create the file atomically
+
+    The file is written to a hidden temporary file in the same directory, and only renamed to
+    its final path when it is closed, either explicitly with
+    [`FitsFile::close`](struct.FitsFile.html#method.close) or when it is dropped. Partially
+    written files are therefore never visible at the final path. If the thread is panicking
+    when the file is dropped, the temporary file is removed instead.
+
+    With [`overwrite`](#method.overwrite), an existing file is replaced when the new file is
+    renamed into place, rather than when it is created.
+
+    # Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+    # let tdir_path = tdir.path();
+    # let filename = tdir_path.join("test.fits");
+    use fitsio::FitsFile;
+
+    let fptr = FitsFile::create(&filename).atomic().open()?;
+    assert!(!filename.exists());
+
+    fptr.close()?;
+    assert!(filename.exists());
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn atomic(mut self) -> Self {
+        self.atomic = true;
+        self
+    }
 }
 
 /// Enumeration of file open modes
@@ -1051,12 +2393,13 @@ casesensitivity_into_impl!(i64);
 mod test {
     use crate::errors::Error;
     use crate::fitsfile::FitsFile;
-    use crate::fitsfile::{FileOpenMode, ImageDescription};
+    use crate::fitsfile::{FileOpenMode, ImageDescription, PrettyPrintOptions};
     use crate::hdu::{FitsHdu, HduInfo};
     use crate::images::ImageType;
     use crate::tables::{ColumnDataType, ColumnDescription};
-    use crate::testhelpers::{duplicate_test_file, with_temp_file};
+    use crate::testing::{duplicate_test_file, with_temp_file};
     use std::path::Path;
+    use std::ptr;
 
     #[test]
     fn test_opening_an_existing_file() {
@@ -1083,6 +2426,62 @@ mod test {
         });
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn test_non_utf8_filenames() {
+        use std::ffi::OsStr;
+        use std::os::unix::ffi::OsStrExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(OsStr::from_bytes(b"caf\xe9.fits"));
+
+        FitsFile::create(&path).open().unwrap();
+        assert!(path.exists());
+        FitsFile::open(&path).unwrap();
+        FitsFile::edit(&path).unwrap();
+        match FitsFile::create(&path).open() {
+            Err(Error::ExistingFile(name)) => assert!(name.ends_with("caf\u{fffd}.fits")),
+            other => panic!("unexpected result {:?}", other.map(|_| ())),
+        }
+        FitsFile::create(&path).overwrite().open().unwrap();
+    }
+
+    #[test]
+    fn test_open_with_syntax() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("weird[1].fits");
+        std::fs::copy("../testdata/full_example.fits", &path).unwrap();
+
+        // The brackets are part of the file name
+        let mut f = FitsFile::open_with_syntax(&path, "").unwrap();
+        assert_eq!(f.num_hdus().unwrap(), 2);
+        assert!(FitsFile::open_with_syntax(&path, "[1]").is_err());
+
+        let mut f2 =
+            FitsFile::open_with_syntax("../testdata/full_example.fits", "[TESTEXT]").unwrap();
+        assert_eq!(f2.hdu_number(), 1);
+        assert!(FitsFile::open_with_syntax("../testdata/full_example.fits", "TESTEXT").is_err());
+
+        // A filtered file cannot be reopened or copied from the unfiltered file on disk
+        let mut filtered =
+            FitsFile::open_with_syntax("../testdata/full_example.fits", "[TESTEXT][intcol > 50]")
+                .unwrap();
+        assert!(filtered.write_to(&mut Vec::new()).is_err());
+        assert!(filtered.into_edit().is_err());
+        let filtered =
+            FitsFile::open_with_syntax("../testdata/full_example.fits", "[TESTEXT]").unwrap();
+        assert!(filtered.into_shared().is_err());
+    }
+
+    #[test]
+    fn test_overlong_filenames() {
+        let path = Path::new("/tmp").join("a".repeat(2000));
+        match FitsFile::open(&path) {
+            Err(Error::Message(msg)) => assert!(msg.contains("longer than the 1024 bytes")),
+            other => panic!("unexpected result {:?}", other.map(|_| ())),
+        }
+    }
+
     #[test]
     fn test_create_custom_primary_hdu() {
         with_temp_file(|filename| {
@@ -1099,7 +2498,9 @@ mod test {
             let mut f = FitsFile::open(filename).unwrap();
             let hdu = f.hdu(0).unwrap();
             match hdu.info {
-                HduInfo::ImageInfo { shape, image_type } => {
+                HduInfo::ImageInfo {
+                    shape, image_type, ..
+                } => {
                     assert_eq!(shape, vec![100, 103]);
                     assert_eq!(image_type, ImageType::Double);
                 }
@@ -1108,6 +2509,233 @@ mod test {
         });
     }
 
+    #[test]
+    fn test_create_with_primary_keys() {
+        use crate::headers::KeyValue;
+
+        with_temp_file(|filename| {
+            {
+                let description = ImageDescription {
+                    data_type: ImageType::Short,
+                    dimensions: &[10, 20],
+                };
+                FitsFile::create(filename)
+                    .with_custom_primary(&description)
+                    .with_primary_keys(&[("TELESCOP", "INT")])
+                    .with_primary_keys(&[("EXPTIME", KeyValue::from(30.5)), ("NCOMBINE", 3.into())])
+                    .open()
+                    .unwrap();
+            }
+            let mut f = FitsFile::open(filename).unwrap();
+            let hdu = f.primary_hdu().unwrap();
+            assert_eq!(hdu.read_key::<String>(&mut f, "TELESCOP").unwrap(), "INT");
+            assert_eq!(hdu.read_key::<f64>(&mut f, "EXPTIME").unwrap(), 30.5);
+            assert_eq!(hdu.read_key::<i64>(&mut f, "NCOMBINE").unwrap(), 3);
+            assert_eq!(hdu.shape().unwrap(), &[10, 20]);
+        });
+
+        with_temp_file(|filename| {
+            let result = FitsFile::create(filename)
+                .with_primary_keys(&[("BAD\0KEY", "value")])
+                .open();
+            assert!(result.is_err());
+            assert!(!std::path::Path::new(filename).exists());
+        });
+    }
+
+    #[test]
+    fn test_atomic_creation() {
+        use std::path::Path;
+
+        with_temp_file(|filename| {
+            let dir = Path::new(filename).parent().unwrap();
+            let num_entries = || std::fs::read_dir(dir).unwrap().count();
+
+            let mut f = FitsFile::create(filename).atomic().open().unwrap();
+            let hdu = f.primary_hdu().unwrap();
+            hdu.write_key(&mut f, "FOO", 1i64).unwrap();
+            assert!(!Path::new(filename).exists());
+            assert_eq!(num_entries(), 1);
+
+            f.close().unwrap();
+            assert!(Path::new(filename).exists());
+            assert_eq!(num_entries(), 1);
+
+            let mut f = FitsFile::open(filename).unwrap();
+            let hdu = f.primary_hdu().unwrap();
+            assert_eq!(hdu.read_key::<i64>(&mut f, "FOO").unwrap(), 1);
+        });
+
+        /* Existing files are only replaced when the new file is dropped */
+        with_temp_file(|filename| {
+            FitsFile::create(filename).open().unwrap();
+            {
+                let f = FitsFile::create(filename)
+                    .atomic()
+                    .with_primary_keys(&[("BAR", 2)])
+                    .overwrite()
+                    .open()
+                    .unwrap();
+                let mut existing = FitsFile::open(filename).unwrap();
+                let hdu = existing.primary_hdu().unwrap();
+                assert!(hdu.read_key::<i64>(&mut existing, "BAR").is_err());
+                drop(f);
+            }
+
+            let mut f = FitsFile::open(filename).unwrap();
+            let hdu = f.primary_hdu().unwrap();
+            assert_eq!(hdu.read_key::<i64>(&mut f, "BAR").unwrap(), 2);
+        });
+
+        /* Files dropped while panicking are discarded */
+        with_temp_file(|filename| {
+            let dir = Path::new(filename).parent().unwrap();
+            let result = std::panic::catch_unwind(|| {
+                let _f = FitsFile::create(filename).atomic().open().unwrap();
+                panic!("failed while writing");
+            });
+            assert!(result.is_err());
+            assert!(!Path::new(filename).exists());
+            assert_eq!(std::fs::read_dir(dir).unwrap().count(), 0);
+        });
+    }
+
+    #[test]
+    fn test_into_edit() {
+        duplicate_test_file(|filename| {
+            let mut f = FitsFile::open(filename).unwrap();
+            let _hdu = f.hdu("TESTEXT").unwrap();
+            assert!(f
+                .primary_hdu()
+                .unwrap()
+                .write_key(&mut f, "FOO", 1i64)
+                .is_err());
+            let _hdu = f.hdu("TESTEXT").unwrap();
+
+            let mut f = f.into_edit().unwrap();
+            assert_eq!(f.open_mode().unwrap(), FileOpenMode::READWRITE);
+            assert_eq!(f.hdu_number(), 1);
+            let hdu = f.current_hdu().unwrap();
+            hdu.write_key(&mut f, "FOO", 1i64).unwrap();
+
+            /* Already writable files are returned as they are */
+            let mut f = f.into_edit().unwrap();
+            assert_eq!(f.hdu_number(), 1);
+            assert_eq!(hdu.read_key::<i64>(&mut f, "FOO").unwrap(), 1);
+        });
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_open_shared() {
+        let mut f = FitsFile::open("../testdata/full_example.fits").unwrap();
+        let expected: Vec<i32> = f.primary_hdu().unwrap().read_image(&mut f).unwrap();
+
+        // Safety: the test data is never modified
+        let mut first = unsafe { FitsFile::open_shared("../testdata/full_example.fits") }.unwrap();
+        let mut second = unsafe { FitsFile::open_shared("../testdata/full_example.fits") }.unwrap();
+        assert_eq!(first.open_mode().unwrap(), FileOpenMode::READONLY);
+
+        /* Each handle has its own current hdu */
+        let table = first.hdu("TESTEXT").unwrap();
+        let image = second.primary_hdu().unwrap();
+        assert_eq!(second.hdu_number(), 0);
+        let data: Vec<i32> = image.read_image(&mut second).unwrap();
+        assert_eq!(data, expected);
+        assert_eq!(first.hdu_number(), 1);
+
+        let intcol: Vec<i32> = table.read_col(&mut first, "intcol").unwrap();
+        assert_eq!(intcol.len(), 50);
+        assert!(table.write_key(&mut first, "FOO", 1i64).is_err());
+        first.close().unwrap();
+
+        assert!(unsafe { FitsFile::open_shared("../testdata/does_not_exist.fits") }.is_err());
+    }
+
+    #[test]
+    fn test_from_reader() {
+        use std::io::{Cursor, Seek, SeekFrom};
+
+        let bytes = std::fs::read("../testdata/full_example.fits").unwrap();
+
+        /* Reading starts at the current position of the reader */
+        let mut padded = b"not a fits file".to_vec();
+        padded.extend_from_slice(&bytes);
+        let mut reader = Cursor::new(padded);
+        reader.seek(SeekFrom::Start(15)).unwrap();
+
+        let mut f = FitsFile::from_reader(reader).unwrap();
+        assert_eq!(f.num_hdus().unwrap(), 2);
+        let hdu = f.hdu("TESTEXT").unwrap();
+        let intcol: Vec<i32> = hdu.read_col(&mut f, "intcol").unwrap();
+        assert_eq!(intcol.len(), 50);
+        assert!(hdu.write_key(&mut f, "FOO", 1i64).is_err());
+        assert!(f.into_edit().is_err());
+
+        assert!(FitsFile::from_reader(Cursor::new(Vec::new())).is_err());
+        assert!(FitsFile::from_reader(Cursor::new(b"not a fits file".to_vec())).is_err());
+    }
+
+    #[test]
+    fn test_in_memory_files() {
+        use std::io::Cursor;
+
+        let mut f = FitsFile::create_in_memory().open().unwrap();
+        let description = ImageDescription {
+            data_type: ImageType::Long,
+            dimensions: &[200, 300],
+        };
+        let hdu = f.create_image("IMG".to_string(), &description).unwrap();
+        let data: Vec<i32> = (0..60_000).collect();
+        hdu.write_image(&mut f, &data).unwrap();
+        let table_description = vec![ColumnDescription::new("bar")
+            .with_type(ColumnDataType::Int)
+            .create()
+            .unwrap()];
+        let table = f
+            .create_table("TBL".to_string(), &table_description)
+            .unwrap();
+        table.write_col(&mut f, "bar", &[1i32, 2, 3]).unwrap();
+        assert!(!std::path::Path::new("mem://").exists());
+
+        /* The current hdu is not changed by writing the contents */
+        let _hdu = f.hdu("IMG").unwrap();
+        let mut contents = Vec::new();
+        f.write_to(&mut contents).unwrap();
+        assert_eq!(f.hdu_number(), 1);
+        assert_eq!(contents.len() % 2880, 0);
+
+        let bytes = f.into_bytes().unwrap();
+        assert_eq!(bytes, contents);
+
+        let mut f = FitsFile::from_reader(Cursor::new(bytes)).unwrap();
+        assert_eq!(f.num_hdus().unwrap(), 3);
+        let hdu = f.hdu("IMG").unwrap();
+        let read_back: Vec<i32> = hdu.read_image(&mut f).unwrap();
+        assert_eq!(read_back, data);
+        let table = f.hdu("TBL").unwrap();
+        let bar: Vec<i32> = table.read_col(&mut f, "bar").unwrap();
+        assert_eq!(bar, vec![1, 2, 3]);
+
+        /* Files read from memory can be written out again */
+        let mut copy = Vec::new();
+        f.write_to(&mut copy).unwrap();
+        assert_eq!(copy, contents);
+    }
+
+    #[test]
+    fn test_write_disk_file_to_bytes() {
+        with_temp_file(|filename| {
+            let mut f = FitsFile::create(filename).open().unwrap();
+            let hdu = f.primary_hdu().unwrap();
+            hdu.write_key(&mut f, "FOO", 1i64).unwrap();
+
+            /* Unflushed changes are included */
+            let bytes = f.into_bytes().unwrap();
+            assert_eq!(bytes, std::fs::read(filename).unwrap());
+        });
+    }
+
     #[test]
     fn test_overwriting() {
         use std::fs::File;
@@ -1210,10 +2838,15 @@ mod test {
     fn test_fetching_hdu_info() {
         let mut f = FitsFile::open("../testdata/full_example.fits").unwrap();
         match f.fetch_hdu_info() {
-            Ok(HduInfo::ImageInfo { shape, image_type }) => {
+            Ok(HduInfo::ImageInfo {
+                shape,
+                image_type,
+                raw_bitpix,
+            }) => {
                 assert_eq!(shape.len(), 2);
                 assert_eq!(shape, vec![100, 100]);
                 assert_eq!(image_type, ImageType::Long);
+                assert_eq!(raw_bitpix, 32);
             }
             Err(e) => panic!("Error fetching hdu info {:?}", e),
             _ => panic!("Unknown error"),
@@ -1401,6 +3034,115 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_make_current() {
+        let mut f = FitsFile::open("../testdata/full_example.fits").unwrap();
+        let primary = f.hdu(0).unwrap();
+        let table = f.hdu(1).unwrap();
+        assert_eq!(f.hdu_number(), 1);
+
+        f.make_current(&table).unwrap();
+        assert_eq!(f.hdu_number(), 1);
+        f.make_current(&primary).unwrap();
+        assert_eq!(f.hdu_number(), 0);
+
+        /* Moving through the raw pointer is picked up */
+        let mut status = 0;
+        unsafe {
+            crate::longnam::fits_movabs_hdu(f.as_raw(), 2, ptr::null_mut(), &mut status);
+        }
+        assert_eq!(status, 0);
+        f.make_current(&primary).unwrap();
+        assert_eq!(f.hdu_number(), 0);
+    }
+
+    #[test]
+    fn test_compress_hdu() {
+        use crate::images::{CompressionAlgorithm, CompressionOptions};
+
+        with_temp_file(|compressed_filename| {
+            with_temp_file(|filename| {
+                let mut src = FitsFile::open("../testdata/full_example.fits").unwrap();
+                let primary = src.primary_hdu().unwrap();
+                let original: Vec<i32> = primary.read_image(&mut src).unwrap();
+
+                let mut compressed = FitsFile::create(compressed_filename).open().unwrap();
+                let options = CompressionOptions::new(CompressionAlgorithm::Rice)
+                    .with_tile_dimensions(&[10, 100]);
+                let hdu = src
+                    .compress_hdu_to(&primary, &mut compressed, &options)
+                    .unwrap();
+                assert_eq!(hdu.number, 1);
+                assert_eq!(hdu.shape().unwrap(), &[100, 100]);
+                assert_eq!(hdu.read_key::<i64>(&mut compressed, "ZTILE2").unwrap(), 10);
+                let data: Vec<i32> = hdu.read_image(&mut compressed).unwrap();
+                assert_eq!(data, original);
+
+                /* Later images are not compressed */
+                let description = ImageDescription {
+                    data_type: ImageType::Long,
+                    dimensions: &[10, 10],
+                };
+                let plain = compressed
+                    .create_image("PLAIN".to_string(), &description)
+                    .unwrap();
+                assert!(plain
+                    .read_key::<String>(&mut compressed, "ZCMPTYPE")
+                    .is_err());
+
+                let mut dest = FitsFile::create(filename).open().unwrap();
+                let uncompressed = compressed.uncompress_hdu_to(&hdu, &mut dest).unwrap();
+                assert_eq!(uncompressed.shape().unwrap(), &[100, 100]);
+                let data: Vec<i32> = uncompressed.read_image(&mut dest).unwrap();
+                assert_eq!(data, original);
+
+                assert!(compressed.uncompress_hdu_to(&plain, &mut dest).is_err());
+                let table = src.hdu("TESTEXT").unwrap();
+                assert!(src.compress_hdu_to(&table, &mut dest, &options).is_err());
+            });
+        });
+    }
+
+    #[test]
+    fn test_compress_float_hdu() {
+        use crate::images::{CompressionAlgorithm, CompressionOptions};
+
+        with_temp_file(|src_filename| {
+            with_temp_file(|filename| {
+                let mut src = FitsFile::create(src_filename).open().unwrap();
+                let description = ImageDescription {
+                    data_type: ImageType::Float,
+                    dimensions: &[50, 50],
+                };
+                let hdu = src.create_image("FLOAT".to_string(), &description).unwrap();
+                let original: Vec<f32> = (0..2500)
+                    .map(|i| ((i * 7919) % 1000) as f32 / 7.0)
+                    .collect();
+                hdu.write_image(&mut src, &original).unwrap();
+
+                let mut dest = FitsFile::create(filename).open().unwrap();
+
+                /* Quantized compression is lossy */
+                let options = CompressionOptions::new(CompressionAlgorithm::Rice)
+                    .with_quantize_level(4.0)
+                    .with_dither_seed(42);
+                let lossy = src.compress_hdu_to(&hdu, &mut dest, &options).unwrap();
+                let data: Vec<f32> = lossy.read_image(&mut dest).unwrap();
+                assert_ne!(data, original);
+                for (value, expected) in data.iter().zip(&original) {
+                    assert!((value - expected).abs() < 50.0);
+                }
+
+                /* A level of zero is lossless */
+                let options =
+                    CompressionOptions::new(CompressionAlgorithm::Gzip2).with_quantize_level(0.0);
+                let lossless = src.compress_hdu_to(&hdu, &mut dest, &options).unwrap();
+                let data: Vec<f32> = lossless.read_image(&mut dest).unwrap();
+                assert_eq!(data, original);
+            });
+        });
+    }
+
     #[test]
     fn test_fetch_primary_hdu() {
         let mut f = FitsFile::open("../testdata/full_example.fits").unwrap();
@@ -1421,11 +3163,70 @@ mod test {
     fn test_fetch_hdu_names() {
         duplicate_test_file(|filename| {
             let mut f = FitsFile::open(filename).unwrap();
-            let hdu_names = f.hdu_names().unwrap();
+            let hdu_names: Vec<String> = f.summary().unwrap().into_iter().map(|h| h.name).collect();
             assert_eq!(hdu_names.as_slice(), &["", "TESTEXT"]);
         });
     }
 
+    #[test]
+    fn test_pretty_write_with_options() {
+        let mut f = FitsFile::open("../testdata/full_example.fits").unwrap();
+
+        let mut default_output = Vec::new();
+        f.pretty_write(&mut default_output).unwrap();
+        let default_output = String::from_utf8(default_output).unwrap();
+        assert_eq!(default_output.lines().count(), 6);
+        assert!(!default_output.contains("intcol"));
+        assert!(default_output.contains("IMAGE_HDU    <unnamed>"));
+
+        let options = PrettyPrintOptions {
+            max_columns: 2,
+            show_units: true,
+            ..Default::default()
+        };
+        let mut output = Vec::new();
+        f.pretty_write_with(&mut output, &options).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("intcol"));
+        assert!(output.contains("floatcol"));
+        assert!(!output.contains("doublecol"));
+        assert!(output.contains("... 2 more columns"));
+
+        let options = PrettyPrintOptions {
+            show_headers: true,
+            ..Default::default()
+        };
+        let mut output = Vec::new();
+        f.pretty_write_with(&mut output, &options).unwrap();
+        let output = String::from_utf8(output).unwrap();
+        assert!(output.contains("INTTEST  = 42"));
+        assert!(output.contains("EXTNAME  = TESTEXT"));
+    }
+
+    #[test]
+    fn test_summary() {
+        let mut f = FitsFile::open("../testdata/full_example.fits").unwrap();
+        let summary = f.summary().unwrap();
+        assert_eq!(summary.len(), 2);
+
+        assert_eq!(summary[0].index, 0);
+        assert_eq!(summary[0].name, "");
+        assert_eq!(summary[0].info, f.hdu(0).unwrap().info);
+
+        assert_eq!(summary[1].index, 1);
+        assert_eq!(summary[1].name, "TESTEXT");
+        match summary[1].info {
+            HduInfo::TableInfo {
+                ref column_descriptions,
+                num_rows,
+            } => {
+                assert_eq!(column_descriptions.len(), 4);
+                assert_eq!(num_rows, 50);
+            }
+            _ => panic!("expected a table"),
+        }
+    }
+
     #[test]
     fn test_creating_new_image_returns_hdu_object() {
         with_temp_file(|filename| {
@@ -1490,7 +3291,7 @@ mod test {
     fn test_read_image_region_from_table() {
         let mut f = FitsFile::open("../testdata/full_example.fits").unwrap();
         let hdu = f.hdu("TESTEXT").unwrap();
-        match hdu.read_region::<Vec<i32>>(&mut f, &[&(0..10), &(0..10)]) {
+        match hdu.read_region::<Vec<i32>, _>(&mut f, &[&(0..10), &(0..10)]) {
             Err(Error::Message(msg)) => {
                 assert!(msg.contains("cannot read image data from a table hdu"))
             }
@@ -0,0 +1,171 @@
+/*!
+Good Time Interval (GTI) tables
+
+Event lists from high-energy instruments record the times the instrument was collecting data in a
+GTI extension: a binary table with `START` and `STOP` columns, conventionally named `GTI`. This
+module reads and writes these tables, and filters event tables to the events inside the intervals.
+
+An interval includes its start time but not its stop time.
+
+# Example
+
+```rust
+use fitsio::gti;
+use fitsio::tables::{ColumnDataType, ColumnDescription};
+
+# fn main() -> Result<(), Box<dyn std::error::Error>> {
+# let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+# let filename = tdir.path().join("test.fits");
+# let mut fptr = fitsio::FitsFile::create(filename).open()?;
+let time = ColumnDescription::new("TIME")
+    .with_type(ColumnDataType::Double)
+    .create()?;
+let events = fptr.create_table("EVENTS", &[time])?;
+let events = events.write_col(&mut fptr, "TIME", &[1.0, 5.0, 12.0, 25.0])?;
+
+gti::write_gti(&mut fptr, "GTI", &[(0.0, 10.0), (20.0, 30.0)])?;
+let gti_hdu = fptr.hdu("GTI")?;
+let intervals = gti::read_gti(&mut fptr, &gti_hdu)?;
+
+let events = gti::filter_events(&mut fptr, &events, "TIME", &intervals)?;
+let times: Vec<f64> = events.read_col(&mut fptr, "TIME")?;
+assert_eq!(times, vec![1.0, 5.0, 25.0]);
+# Ok(())
+# }
+```
+*/
+use crate::errors::{check_status, Error, Result};
+use crate::fitsfile::FitsFile;
+use crate::hdu::{FitsHdu, HduIdentity};
+use crate::longnam::*;
+use crate::tables::{ColumnDataType, ColumnDescription};
+
+/// Read the intervals of a GTI table from its `START` and `STOP` columns
+pub fn read_gti(fits_file: &mut FitsFile, hdu: &FitsHdu) -> Result<Vec<(f64, f64)>> {
+    let starts: Vec<f64> = hdu.read_col(fits_file, "START")?;
+    let stops: Vec<f64> = hdu.read_col(fits_file, "STOP")?;
+    Ok(starts.into_iter().zip(stops).collect())
+}
+
+/**
+Write intervals to a new GTI table
+
+The table has `START` and `STOP` columns of double precision times. Intervals which stop before
+they start are rejected.
+*/
+pub fn write_gti<T: Into<HduIdentity>>(
+    fits_file: &mut FitsFile,
+    extname: T,
+    intervals: &[(f64, f64)],
+) -> Result<FitsHdu> {
+    let invalid = |&&(start, stop): &&(f64, f64)| start.is_nan() || stop.is_nan() || stop < start;
+    if let Some(&(start, stop)) = intervals.iter().find(invalid) {
+        return Err(Error::Message(format!(
+            "invalid good time interval from {} to {}",
+            start, stop
+        )));
+    }
+
+    let columns = [
+        ColumnDescription::new("START")
+            .with_type(ColumnDataType::Double)
+            .create()?,
+        ColumnDescription::new("STOP")
+            .with_type(ColumnDataType::Double)
+            .create()?,
+    ];
+    let hdu = fits_file.create_table(extname, &columns)?;
+    let starts: Vec<f64> = intervals.iter().map(|interval| interval.0).collect();
+    let stops: Vec<f64> = intervals.iter().map(|interval| interval.1).collect();
+    let hdu = hdu.write_col(fits_file, "START", &starts)?;
+    hdu.write_col(fits_file, "STOP", &stops)
+}
+
+/// Whether a time lies within any of the intervals
+pub fn contains(intervals: &[(f64, f64)], time: f64) -> bool {
+    intervals
+        .iter()
+        .any(|&(start, stop)| time >= start && time < stop)
+}
+
+/**
+Remove the rows of an event table whose time lies outside every interval
+
+`time_column` names the column holding the time of each event. The updated HDU is returned, as
+the number of rows changes.
+*/
+pub fn filter_events(
+    fits_file: &mut FitsFile,
+    hdu: &FitsHdu,
+    time_column: &str,
+    intervals: &[(f64, f64)],
+) -> Result<FitsHdu> {
+    let times: Vec<f64> = hdu.read_col(fits_file, time_column)?;
+    fits_check_readwrite!(fits_file);
+
+    let mut outside: Vec<libc::c_long> = times
+        .iter()
+        .enumerate()
+        .filter(|(_, time)| !contains(intervals, **time))
+        .map(|(row, _)| (row + 1) as libc::c_long)
+        .collect();
+    if !outside.is_empty() {
+        let mut status = 0;
+        unsafe {
+            fits_delete_rowlist(
+                fits_file.fptr.as_mut() as *mut _,
+                outside.as_mut_ptr(),
+                outside.len() as _,
+                &mut status,
+            );
+        }
+        check_status(status)?;
+    }
+    fits_file.current_hdu()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::with_temp_file;
+
+    #[test]
+    fn test_gti() {
+        with_temp_file(|filename| {
+            let mut f = FitsFile::create(filename).open().unwrap();
+            let columns = [
+                ColumnDescription::new("TIME")
+                    .with_type(ColumnDataType::Double)
+                    .create()
+                    .unwrap(),
+                ColumnDescription::new("PI")
+                    .with_type(ColumnDataType::Int)
+                    .create()
+                    .unwrap(),
+            ];
+            let events = f.create_table("EVENTS", &columns).unwrap();
+            let events = events
+                .write_col(&mut f, "TIME", &[-1.0, 0.0, 9.5, 10.0, 15.0, 21.0])
+                .unwrap();
+            let events = events.write_col(&mut f, "PI", &[1, 2, 3, 4, 5, 6]).unwrap();
+
+            let intervals = vec![(0.0, 10.0), (20.0, 30.0)];
+            let gti_hdu = write_gti(&mut f, "GTI", &intervals).unwrap();
+            assert_eq!(read_gti(&mut f, &gti_hdu).unwrap(), intervals);
+            assert!(write_gti(&mut f, "BAD", &[(5.0, 1.0)]).is_err());
+
+            assert!(contains(&intervals, 0.0));
+            assert!(!contains(&intervals, 10.0));
+            assert!(!contains(&[], 1.0));
+
+            let events = filter_events(&mut f, &events, "TIME", &intervals).unwrap();
+            assert_eq!(events.num_rows(&mut f).unwrap(), 3);
+            let pi: Vec<i32> = events.read_col(&mut f, "PI").unwrap();
+            assert_eq!(pi, vec![2, 3, 6]);
+
+            // Nothing to remove the second time
+            let events = filter_events(&mut f, &events, "TIME", &intervals).unwrap();
+            assert_eq!(events.num_rows(&mut f).unwrap(), 3);
+        });
+    }
+}
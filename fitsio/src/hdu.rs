@@ -1,27 +1,69 @@
 //! Fits HDU related code
 
-use crate::errors::{check_status, Result};
+use crate::errors::{check_status, Error, IndexError, Result};
 use crate::fitsfile::CaseSensitivity;
 use crate::fitsfile::FitsFile;
-use crate::headers::{ReadsKey, WritesKey};
-use crate::images::{ImageType, ReadImage, WriteImage};
+use crate::headers::{
+    insert_card, key_exists, key_index, read_key_optional, read_raw_value, read_records,
+    write_record, FloatFormat, HeaderCard, KeyClass, KeyValue, ReadsKey, ReadsNumericKey,
+    TemplateCard, WritesFloatKey, WritesKey, KEY_NO_EXIST,
+};
+use crate::images::{
+    cutout_range, set_image_scaling, CelestialWcs, Cutout, ImageAxes, ImageDescription, ImageStats,
+    ImageType, LinearConvention, LinearTransform, ReadImage, SpectralAxis, WriteImage,
+    IMAGE_CHUNK_PIXELS,
+};
 use crate::longnam::*;
+use crate::ranges::{IndexRange, RegionRanges};
 use crate::tables::{
-    ColumnIterator, ConcreteColumnDescription, DescribesColumnLocation, FitsRow, ReadsCol,
-    WritesCol,
+    column_display_width, iterator_work_fn, read_string_col_bytes, read_table_bytes,
+    write_table_bytes, ColumnDataDescription, ColumnDataType, ColumnIterator, ColumnSink,
+    ColumnStats, ConcreteColumnDescription, DescribesColumnLocation, FitsRow, IteratorChunk,
+    IteratorColumn, IteratorColumnMode, IteratorState, Order, ReadStringMode, ReadsCol, RowsChunk,
+    SortKey, TableAppender, TableCache, TableQuery, WriteStringMode, WritesCol,
 };
+use crate::types::DataType;
 use std::ffi;
-use std::ops::Range;
+use std::ops::{Deref, Range};
+use std::path::Path;
+use std::ptr;
+
+/// Number of values read at a time when computing column statistics
+const STATS_CHUNK_VALUES: usize = 1 << 16;
+
+/// Number of bytes of table rows copied at a time when appending tables
+const APPEND_CHUNK_BYTES: usize = 1 << 20;
 
-/// Struct representing a FITS HDU
-#[derive(Debug, PartialEq, Eq)]
+/**
+Struct representing a FITS HDU
+
+Deleting or inserting an HDU renumbers the HDUs after it, so a `FitsHdu` fetched before an
+earlier HDU was deleted or inserted no longer refers to the same HDU. Using it returns an error,
+and it must be fetched again.
+*/
+#[derive(Debug)]
 pub struct FitsHdu {
-    /// Information about the current HDU
+    /**
+    Information about the current HDU
+
+    This is read when the `FitsHdu` is fetched, and is not updated by later writes. Use
+    [`refresh`](#method.refresh) to re-read it.
+    */
     pub info: HduInfo,
     /// The HDU number within the fits file. Zero indexed.
     pub number: usize,
+    /// Number of times HDUs had been renumbered when this HDU was fetched
+    pub(crate) renumberings: usize,
+}
+
+impl PartialEq for FitsHdu {
+    fn eq(&self, other: &Self) -> bool {
+        self.info == other.info && self.number == other.number
+    }
 }
 
+impl Eq for FitsHdu {}
+
 impl FitsHdu {
     pub(crate) fn new<T: DescribesHdu>(
         fits_file: &mut FitsFile,
@@ -32,21 +74,64 @@ impl FitsHdu {
             Ok(hdu_info) => Ok(FitsHdu {
                 info: hdu_info,
                 number: fits_file.hdu_number(),
+                renumberings: fits_file.renumbered_hdus.len(),
             }),
             Err(e) => Err(e),
         }
     }
 
-    /// Read the HDU name
-    pub fn name(&self, fits_file: &mut FitsFile) -> Result<String> {
-        let extname = self
-            .read_key(fits_file, "EXTNAME")
-            .unwrap_or_else(|_| "".to_string());
-        Ok(extname)
+    /**
+    Re-read the HDU information from the file
+
+    [`info`](#structfield.info) is a snapshot taken when the HDU is fetched, so it becomes out of
+    date after e.g. writing past the end of a table. This returns a new `FitsHdu` describing the
+    current state of the HDU.
+
+    # Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+    # let tdir_path = tdir.path();
+    # let filename = tdir_path.join("test.fits");
+    # let mut fptr = fitsio::FitsFile::create(filename).open()?;
+    use fitsio::hdu::HduInfo;
+    use fitsio::tables::{ColumnDataType, ColumnDescription};
+
+    let column = ColumnDescription::new("A")
+        .with_type(ColumnDataType::Int)
+        .create()?;
+    let hdu = fptr.create_table("DATA", &[column])?;
+    hdu.write_col(&mut fptr, "A", &[1, 2, 3])?;
+
+    let hdu = hdu.refresh(&mut fptr)?;
+    if let HduInfo::TableInfo { num_rows, .. } = hdu.info {
+        assert_eq!(num_rows, 3);
+    }
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn refresh(&self, fits_file: &mut FitsFile) -> Result<FitsHdu> {
+        fits_file.hdu(self.number)
+    }
+
+    /// Return `true` if the HDU is an image
+    pub fn is_image(&self) -> bool {
+        matches!(self.info, HduInfo::ImageInfo { .. })
+    }
+
+    /// Return `true` if the HDU is a table
+    pub fn is_table(&self) -> bool {
+        matches!(self.info, HduInfo::TableInfo { .. })
     }
 
     /**
-    Read header key
+    Shape of an image HDU
+
+    The dimensions are in C (row-major) order, matching
+    [`ImageDescription`](../images/struct.ImageDescription.html). Returns an error for table
+    HDUs.
 
     # Example
 
@@ -54,52 +139,149 @@ impl FitsHdu {
     # fn main() -> Result<(), Box<dyn std::error::Error>> {
     # let filename = "../testdata/full_example.fits";
     # let mut fptr = fitsio::FitsFile::open(filename)?;
-    # let hdu = fptr.primary_hdu()?;
-    # {
-    let int_value: i64 = hdu.read_key(&mut fptr, "INTTEST")?;
-    # }
+    let hdu = fptr.primary_hdu()?;
+    assert!(hdu.is_image());
+    assert_eq!(hdu.shape()?, &[100, 100]);
+    assert_eq!(hdu.dimensions()?, 2);
+    assert_eq!(hdu.num_pixels()?, 10_000);
+    assert_eq!(hdu.image_type()?, fitsio::images::ImageType::Long);
     # Ok(())
     # }
+    ```
     */
-    pub fn read_key<T: ReadsKey>(&self, fits_file: &mut FitsFile, name: &str) -> Result<T> {
-        fits_file.make_current(self)?;
-        T::read_key(fits_file, name)
+    pub fn shape(&self) -> Result<&[usize]> {
+        match self.info {
+            HduInfo::ImageInfo { ref shape, .. } => Ok(shape),
+            _ => Err("hdu is not an image".into()),
+        }
+    }
+
+    /// Number of dimensions of an image HDU, or an error for table HDUs
+    pub fn dimensions(&self) -> Result<usize> {
+        self.shape().map(|shape| shape.len())
+    }
+
+    /// Total number of pixels in an image HDU, or an error for table HDUs
+    pub fn num_pixels(&self) -> Result<usize> {
+        self.shape().map(|shape| {
+            if shape.is_empty() {
+                0
+            } else {
+                shape.iter().product()
+            }
+        })
+    }
+
+    /// Data type of an image HDU, or an error for table HDUs
+    pub fn image_type(&self) -> Result<ImageType> {
+        match self.info {
+            HduInfo::ImageInfo { image_type, .. } => Ok(image_type),
+            _ => Err("hdu is not an image".into()),
+        }
     }
 
     /**
-    Write a fits key to the current header
+    `BITPIX` value stored in the header of an image HDU, or an error for table HDUs
+
+    Unlike [`image_type`](#method.image_type), this ignores any `BSCALE` and `BZERO` scaling, so
+    a floating point image stored as scaled integers has an `image_type` of `Float` and a raw
+    `BITPIX` of `16` or `32`.
+    */
+    pub fn raw_bitpix(&self) -> Result<i32> {
+        match self.info {
+            HduInfo::ImageInfo { raw_bitpix, .. } => Ok(raw_bitpix),
+            _ => Err("hdu is not an image".into()),
+        }
+    }
+
+    /**
+    Number of rows in a table HDU
+
+    Unlike the `num_rows` field of [`info`](#structfield.info), this is read from the file, so
+    reflects any rows written since the `FitsHdu` was fetched. Returns an error for image HDUs.
 
     # Example
 
     ```rust
     # fn main() -> Result<(), Box<dyn std::error::Error>> {
-    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
-    # let tdir_path = tdir.path();
-    # let filename = tdir_path.join("test.fits");
-    # {
-    # let mut fptr = fitsio::FitsFile::create(filename).open()?;
-    fptr.primary_hdu()?.write_key(&mut fptr, "foo", 1i64)?;
-    assert_eq!(fptr.hdu(0)?.read_key::<i64>(&mut fptr, "foo")?, 1i64);
+    # let filename = "../testdata/full_example.fits";
+    # let mut fptr = fitsio::FitsFile::open(filename)?;
+    let hdu = fptr.hdu("TESTEXT")?;
+    assert_eq!(hdu.num_rows(&mut fptr)?, 50);
+    assert_eq!(hdu.num_columns(&mut fptr)?, 4);
+    assert_eq!(
+        hdu.column_names(&mut fptr)?,
+        ["intcol", "floatcol", "doublecol", "strcol"]
+    );
+    assert!(!hdu.is_empty(&mut fptr)?);
     # Ok(())
     # }
-    # }
     ```
     */
-    pub fn write_key<T: WritesKey>(
-        &self,
-        fits_file: &mut FitsFile,
-        name: &str,
-        value: T,
-    ) -> Result<()> {
+    pub fn num_rows(&self, fits_file: &mut FitsFile) -> Result<usize> {
+        self.check_is_table()?;
         fits_file.make_current(self)?;
-        fits_check_readwrite!(fits_file);
-        T::write_key(fits_file, name, value)
+        let mut num_rows = 0;
+        let mut status = 0;
+        unsafe {
+            fits_get_num_rows(
+                fits_file.fptr.as_mut() as *mut _,
+                &mut num_rows,
+                &mut status,
+            );
+        }
+        check_status(status).map(|_| num_rows as usize)
+    }
+
+    /// Return `true` if a table HDU has no rows, or an error for image HDUs
+    pub fn is_empty(&self, fits_file: &mut FitsFile) -> Result<bool> {
+        self.num_rows(fits_file).map(|num_rows| num_rows == 0)
+    }
+
+    /// Number of columns in a table HDU, read from the file, or an error for image HDUs
+    pub fn num_columns(&self, fits_file: &mut FitsFile) -> Result<usize> {
+        self.check_is_table()?;
+        fits_file.make_current(self)?;
+        let mut num_cols = 0;
+        let mut status = 0;
+        unsafe {
+            fits_get_num_cols(
+                fits_file.fptr.as_mut() as *mut _,
+                &mut num_cols,
+                &mut status,
+            );
+        }
+        check_status(status).map(|_| num_cols as usize)
+    }
+
+    /// Names of the columns of a table HDU, read from the file, or an error for image HDUs
+    pub fn column_names(&self, fits_file: &mut FitsFile) -> Result<Vec<String>> {
+        self.check_is_table()?;
+        fits_file.make_current(self)?;
+        match fits_file.fetch_hdu_info()? {
+            HduInfo::TableInfo {
+                column_descriptions,
+                ..
+            } => Ok(column_descriptions
+                .into_iter()
+                .map(|description| description.name)
+                .collect()),
+            _ => Err("hdu is not a table".into()),
+        }
+    }
+
+    fn check_is_table(&self) -> Result<()> {
+        if self.is_table() {
+            Ok(())
+        } else {
+            Err("hdu is not a table".into())
+        }
     }
 
     /**
-    Read pixels from an image between a start index and end index
+    Read the HDU name from its `EXTNAME` keyword
 
-    The range is exclusive of the upper value
+    Returns `None` if the HDU has no `EXTNAME`, and an error if it cannot be read.
 
     # Example
 
@@ -107,53 +289,81 @@ impl FitsHdu {
     # fn main() -> Result<(), Box<dyn std::error::Error>> {
     # let filename = "../testdata/full_example.fits";
     # let mut fptr = fitsio::FitsFile::open(filename)?;
-    # let hdu = fptr.hdu(0)?;
-    // Read the first 100 pixels
-    let first_row: Vec<i32> = hdu.read_section(&mut fptr, 0, 100)?;
+    assert_eq!(fptr.primary_hdu()?.name(&mut fptr)?, None);
+    let hdu = fptr.hdu(1)?;
+    assert_eq!(hdu.name(&mut fptr)?, Some("TESTEXT".to_string()));
     # Ok(())
     # }
     ```
     */
-    pub fn read_section<T: ReadImage>(
+    pub fn name(&self, fits_file: &mut FitsFile) -> Result<Option<String>> {
+        fits_file.make_current(self)?;
+        read_key_optional(fits_file, "EXTNAME")
+    }
+
+    /**
+    Set the HDU name, and optionally its version
+
+    `EXTNAME` is written, replacing any existing value, as is `EXTVER` if `extver` is given.
+    Otherwise any existing `EXTVER` is left as it is.
+
+    # Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+    # let filename = tdir.path().join("test.fits");
+    # std::fs::copy("../testdata/full_example.fits", &filename)?;
+    # let mut fptr = fitsio::FitsFile::edit(filename)?;
+    let hdu = fptr.hdu("TESTEXT")?;
+    hdu.set_name(&mut fptr, "EVENTS", Some(2))?;
+    assert_eq!(hdu.name(&mut fptr)?, Some("EVENTS".to_string()));
+    assert_eq!(hdu.read_key::<i64>(&mut fptr, "EXTVER")?, 2);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn set_name(
         &self,
         fits_file: &mut FitsFile,
-        start: usize,
-        end: usize,
-    ) -> Result<T> {
+        name: &str,
+        extver: Option<i32>,
+    ) -> Result<()> {
         fits_file.make_current(self)?;
-        T::read_section(fits_file, self, start..end)
+        fits_check_readwrite!(fits_file);
+        HduIdentity {
+            name: Some(name.to_string()),
+            version: extver,
+            level: None,
+        }
+        .write(fits_file)
     }
 
     /**
+    Read the name, version and level of the HDU
+
+    See [`HduIdentity`](struct.HduIdentity.html).
+
     # Example
 
     ```rust
     # fn main() -> Result<(), Box<dyn std::error::Error>> {
     # let filename = "../testdata/full_example.fits";
     # let mut fptr = fitsio::FitsFile::open(filename)?;
-    # let hdu = fptr.hdu(0)?;
-    let start_row = 0;
-    let num_rows = 10;
-    let first_few_rows: Vec<f32> = hdu.read_rows(&mut fptr, start_row, num_rows)?;
-
-    // 10 rows of 100 columns
-    assert_eq!(first_few_rows.len(), 1000);
+    let identity = fptr.hdu(1)?.identity(&mut fptr)?;
+    assert_eq!(identity.name.as_deref(), Some("TESTEXT"));
+    assert_eq!(identity.version, None);
     # Ok(())
     # }
     ```
     */
-    pub fn read_rows<T: ReadImage>(
-        &self,
-        fits_file: &mut FitsFile,
-        start_row: usize,
-        num_rows: usize,
-    ) -> Result<T> {
+    pub fn identity(&self, fits_file: &mut FitsFile) -> Result<HduIdentity> {
         fits_file.make_current(self)?;
-        T::read_rows(fits_file, self, start_row, num_rows)
+        HduIdentity::read(fits_file)
     }
 
     /**
-    Read a single row from a fits image
+    Read header key
 
     # Example
 
@@ -161,27 +371,24 @@ impl FitsHdu {
     # fn main() -> Result<(), Box<dyn std::error::Error>> {
     # let filename = "../testdata/full_example.fits";
     # let mut fptr = fitsio::FitsFile::open(filename)?;
-    # let hdu = fptr.hdu(0)?;
-    let chosen_row = 5;
-    let row: Vec<f32> = hdu.read_row(&mut fptr, chosen_row)?;
-
-    // Should have 100 pixel values
-    assert_eq!(row.len(), 100);
+    # let hdu = fptr.primary_hdu()?;
+    # {
+    let int_value: i64 = hdu.read_key(&mut fptr, "INTTEST")?;
+    # }
     # Ok(())
     # }
-    ```
     */
-    pub fn read_row<T: ReadImage>(&self, fits_file: &mut FitsFile, row: usize) -> Result<T> {
+    pub fn read_key<T: ReadsKey>(&self, fits_file: &mut FitsFile, name: &str) -> Result<T> {
         fits_file.make_current(self)?;
-        T::read_row(fits_file, self, row)
+        T::read_key(fits_file, name)
     }
 
     /**
-    Read a square region from the chip.
+    Read a numeric header key, whether it was written as an integer or floating point number
 
-    Lower left indicates the starting point of the square, and the upper
-    right defines the pixel _beyond_ the end. The range of pixels included
-    is inclusive of the lower end, and *exclusive* of the upper end.
+    This avoids having to know whether a key was written as e.g. `EXPTIME = 10` or
+    `EXPTIME = 10.0`. String and logical keys are errors, as are values which cannot be
+    represented by `T` without truncation or overflow.
 
     # Example
 
@@ -189,28 +396,29 @@ impl FitsHdu {
     # fn main() -> Result<(), Box<dyn std::error::Error>> {
     # let filename = "../testdata/full_example.fits";
     # let mut fptr = fitsio::FitsFile::open(filename)?;
-    # let hdu = fptr.hdu(0)?;
-    // Read a square section of the image
-    let xcoord = 0..10;
-    let ycoord = 0..10;
-    let chunk: Vec<i32> = hdu.read_region(&mut fptr, &[&ycoord, &xcoord])?;
+    # let hdu = fptr.primary_hdu()?;
+    let int_value: f64 = hdu.read_key_num(&mut fptr, "INTTEST")?;
+    assert_eq!(int_value, 42.0);
+    let float_value: f64 = hdu.read_key_num(&mut fptr, "DBLTEST")?;
+    assert_eq!(float_value, 0.09375);
     # Ok(())
     # }
     ```
     */
-    pub fn read_region<T: ReadImage>(
+    pub fn read_key_num<T: ReadsNumericKey>(
         &self,
         fits_file: &mut FitsFile,
-        ranges: &[&Range<usize>],
+        name: &str,
     ) -> Result<T> {
         fits_file.make_current(self)?;
-        T::read_region(fits_file, self, ranges)
+        crate::headers::read_key_num(fits_file, name)
     }
 
     /**
-    Read a whole image into a new `Vec`
+    Read the raw bytes of a string header key
 
-    This reads an entire image into a one-dimensional vector
+    Unlike reading a `String`, the value is not required to be valid UTF-8, so keys written in
+    other encodings such as Latin-1 can be decoded by the caller.
 
     # Example
 
@@ -218,134 +426,1724 @@ impl FitsHdu {
     # fn main() -> Result<(), Box<dyn std::error::Error>> {
     # let filename = "../testdata/full_example.fits";
     # let mut fptr = fitsio::FitsFile::open(filename)?;
-    # let hdu = fptr.hdu(0)?;
-    let image_data: Vec<f32> = hdu.read_image(&mut fptr)?;
-
-    // 100 rows of 100 columns
-    assert_eq!(image_data.len(), 10_000);
+    # let hdu = fptr.primary_hdu()?;
+    let bytes = hdu.read_key_bytes(&mut fptr, "TEST")?;
+    assert_eq!(bytes, b"value");
     # Ok(())
     # }
     ```
     */
-    pub fn read_image<T: ReadImage>(&self, fits_file: &mut FitsFile) -> Result<T> {
+    pub fn read_key_bytes(&self, fits_file: &mut FitsFile, name: &str) -> Result<Vec<u8>> {
         fits_file.make_current(self)?;
-        T::read_image(fits_file, self)
+        crate::headers::read_key_bytes(fits_file, name)
     }
 
     /**
-    Write raw pixel values to a FITS image
+    Read a string header key, replacing any characters which are not valid UTF-8
 
-    If the length of the dataset exceeds the number of columns,
-    the data wraps around to the next row.
+    Invalid characters are replaced with `U+FFFD`, see
+    [`String::from_utf8_lossy`](https://doc.rust-lang.org/std/string/struct.String.html#method.from_utf8_lossy).
+    */
+    pub fn read_key_lossy(&self, fits_file: &mut FitsFile, name: &str) -> Result<String> {
+        let bytes = self.read_key_bytes(fits_file, name)?;
+        Ok(String::from_utf8_lossy(&bytes).into_owned())
+    }
 
-    The range is exclusive of the upper value.
+    /**
+    Find the type of value stored in a header key
+
+    This allows generic code to choose how to read a key, rather than trying each type in
+    turn.
 
     # Example
 
     ```rust
-    # use fitsio::images::{ImageDescription, ImageType};
-    #
+    use fitsio::headers::KeyClass;
+
     # fn main() -> Result<(), Box<dyn std::error::Error>> {
-    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
-    # let tdir_path = tdir.path();
-    # let filename = tdir_path.join("test.fits");
-    # let mut fptr = fitsio::FitsFile::create(filename).open()?;
-    # let desc = ImageDescription {
-    #    data_type: ImageType::Float,
-    #    dimensions: &[100, 100],
-    # };
-    # let hdu = fptr.create_image("".to_string(), &desc)?;
-    let data_to_write: Vec<f64> = vec![1.0, 2.0, 3.0];
-    hdu.write_section(&mut fptr, 0, data_to_write.len(), &data_to_write)?;
+    # let filename = "../testdata/full_example.fits";
+    # let mut fptr = fitsio::FitsFile::open(filename)?;
+    let hdu = fptr.primary_hdu()?;
+    match hdu.key_type(&mut fptr, "INTTEST")? {
+        KeyClass::Integer => {
+            let value: i64 = hdu.read_key(&mut fptr, "INTTEST")?;
+            assert_eq!(value, 42);
+        }
+        _ => unreachable!(),
+    }
+    assert_eq!(hdu.key_type(&mut fptr, "DBLTEST")?, KeyClass::Float);
     # Ok(())
     # }
     ```
     */
-    pub fn write_section<T: WriteImage>(
-        &self,
-        fits_file: &mut FitsFile,
-        start: usize,
-        end: usize,
-        data: &[T],
-    ) -> Result<()> {
+    pub fn key_type(&self, fits_file: &mut FitsFile, name: &str) -> Result<KeyClass> {
         fits_file.make_current(self)?;
-        fits_check_readwrite!(fits_file);
-        T::write_section(fits_file, self, start..end, data)
+        read_raw_value(fits_file, name).map(|(_, class)| class)
     }
 
     /**
-    Write a rectangular region to the fits image
-
-    The ranges must have length of 2, and they represent the limits of each axis. The limits
-    are inclusive of the lower bounds, and *exclusive* of the and upper bounds.
+    Number of keywords in the header, excluding the `END` card
 
-    For example, writing with ranges 0..10 and 0..10 wries an 10x10 sized image.
+    This can be used to preallocate buffers before reading every card of the header.
 
     # Example
 
     ```rust
-    # use fitsio::images::{ImageDescription, ImageType};
-    #
     # fn main() -> Result<(), Box<dyn std::error::Error>> {
-    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
-    # let tdir_path = tdir.path();
-    # let filename = tdir_path.join("test.fits");
-    # let mut fptr = fitsio::FitsFile::create(filename).open()?;
-    # let desc = ImageDescription {
-    #    data_type: ImageType::Float,
-    #    dimensions: &[100, 100],
-    # };
-    # let hdu = fptr.create_image("".to_string(), &desc)?;
-    let data_to_write: Vec<f64> = vec![1.0, 2.0, 3.0, 4.0];
-    let ranges = [&(0..1), &(0..1)];
-    hdu.write_region(&mut fptr, &ranges, &data_to_write)?;
+    # let filename = "../testdata/full_example.fits";
+    # let mut fptr = fitsio::FitsFile::open(filename)?;
+    let hdu = fptr.primary_hdu()?;
+    assert!(hdu.num_keys(&mut fptr)? > 0);
     # Ok(())
     # }
     ```
     */
-    pub fn write_region<T: WriteImage>(
-        &self,
-        fits_file: &mut FitsFile,
-        ranges: &[&Range<usize>],
-        data: &[T],
+    pub fn num_keys(&self, fits_file: &mut FitsFile) -> Result<usize> {
+        fits_file.make_current(self)?;
+        let mut num_keys = 0;
+        let mut status = 0;
+        unsafe {
+            fits_get_hdrspace(
+                fits_file.fptr.as_mut() as *mut _,
+                &mut num_keys,
+                ptr::null_mut(),
+                &mut status,
+            );
+        }
+        check_status(status).map(|_| num_keys as usize)
+    }
+
+    /**
+    Size of the header in bytes, including the padding to a multiple of 2880 bytes
+
+    # Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let filename = "../testdata/full_example.fits";
+    # let mut fptr = fitsio::FitsFile::open(filename)?;
+    let hdu = fptr.primary_hdu()?;
+    assert_eq!(hdu.header_size_bytes(&mut fptr)? % 2880, 0);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn header_size_bytes(&self, fits_file: &mut FitsFile) -> Result<usize> {
+        let (header_start, data_start, _) = self.byte_offsets(fits_file)?;
+        Ok(data_start - header_start)
+    }
+
+    /**
+    Size of the data unit in bytes, including the padding to a multiple of 2880 bytes
+
+    # Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let filename = "../testdata/full_example.fits";
+    # let mut fptr = fitsio::FitsFile::open(filename)?;
+    // A 100 x 100 image of 32 bit integers
+    let hdu = fptr.primary_hdu()?;
+    assert_eq!(hdu.data_size_bytes(&mut fptr)?, 14 * 2880);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn data_size_bytes(&self, fits_file: &mut FitsFile) -> Result<usize> {
+        let (_, data_start, data_end) = self.byte_offsets(fits_file)?;
+        Ok(data_end - data_start)
+    }
+
+    /**
+    Byte offsets within the file of the start of the header, the start of the data unit and
+    the end of the data unit
+
+    The end of the data unit includes its padding, so is also the start of the next HDU. This
+    allows other readers, for example memory mapped views or HTTP range requests, to locate the
+    parts of an HDU. The offsets refer to the file as stored, so are not meaningful for
+    compressed files opened through `cfitsio`.
+
+    # Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let filename = "../testdata/full_example.fits";
+    # let mut fptr = fitsio::FitsFile::open(filename)?;
+    let primary = fptr.primary_hdu()?;
+    let (header_start, data_start, data_end) = primary.byte_offsets(&mut fptr)?;
+    assert_eq!(header_start, 0);
+    assert!(data_start < data_end);
+
+    // The next HDU starts where the previous one ends
+    let table = fptr.hdu(1)?;
+    assert_eq!(table.byte_offsets(&mut fptr)?.0, data_end);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn byte_offsets(&self, fits_file: &mut FitsFile) -> Result<(usize, usize, usize)> {
+        fits_file.make_current(self)?;
+        let mut header_start = 0;
+        let mut data_start = 0;
+        let mut data_end = 0;
+        let mut status = 0;
+        unsafe {
+            fits_get_hduaddrll(
+                fits_file.fptr.as_mut() as *mut _,
+                &mut header_start,
+                &mut data_start,
+                &mut data_end,
+                &mut status,
+            );
+        }
+        check_status(status).map(|_| {
+            (
+                header_start as usize,
+                data_start as usize,
+                data_end as usize,
+            )
+        })
+    }
+
+    /**
+    Read all header cards whose names match a pattern
+
+    The pattern may contain the wildcards `?` (any single character), `*` (any sequence of
+    characters) and `#` (any sequence of digits), so indexed keyword families such as `TTYPEn`
+    can be read without knowing how many there are.
+
+    # Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let filename = "../testdata/full_example.fits";
+    # let mut fptr = fitsio::FitsFile::open(filename)?;
+    # let hdu = fptr.hdu("TESTEXT")?;
+    let cards = hdu.read_keys_matching(&mut fptr, "TTYPE#")?;
+    let column_names: Vec<String> = cards.into_iter().map(|card| card.value).collect();
+    assert_eq!(column_names, ["intcol", "floatcol", "doublecol", "strcol"]);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn read_keys_matching(
+        &self,
+        fits_file: &mut FitsFile,
+        pattern: &str,
+    ) -> Result<Vec<HeaderCard>> {
+        fits_file.make_current(self)?;
+        crate::headers::read_keys_matching(fits_file, pattern)
+    }
+
+    /**
+    Read an indexed family of header keys
+
+    Reads the keys `root1`, `root2`, ... in order, stopping at the first index which is not
+    present in the header.
+
+    # Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let filename = "../testdata/full_example.fits";
+    # let mut fptr = fitsio::FitsFile::open(filename)?;
+    # let hdu = fptr.primary_hdu()?;
+    let axes: Vec<i64> = hdu.read_keys_indexed(&mut fptr, "NAXIS")?;
+    assert_eq!(axes, [100, 100]);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn read_keys_indexed<T: ReadsKey>(
+        &self,
+        fits_file: &mut FitsFile,
+        root: &str,
+    ) -> Result<Vec<T>> {
+        fits_file.make_current(self)?;
+        crate::headers::read_keys_indexed(fits_file, root)
+    }
+
+    /**
+    Read a matrix of header keys
+
+    The value in row `i` and column `j` is read from the key `rootI_J`, numbering from 1, which
+    is the convention of e.g. the `CDi_j` and `PCi_j` world coordinate keys. Keys outside the
+    requested shape are ignored, and a missing key inside it is an error.
+
+    # Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+    # let tdir_path = tdir.path();
+    # let filename = tdir_path.join("test.fits");
+    # let mut fptr = fitsio::FitsFile::create(filename).open()?;
+    let hdu = fptr.primary_hdu()?;
+    hdu.write_key_matrix(&mut fptr, "CD", &[[-2.5e-4, 0.0], [0.0, 2.5e-4]])?;
+    let cd: Vec<Vec<f64>> = hdu.read_key_matrix(&mut fptr, "CD", 2, 2)?;
+    assert_eq!(cd[1][1], 2.5e-4);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn read_key_matrix<T: ReadsKey>(
+        &self,
+        fits_file: &mut FitsFile,
+        root: &str,
+        rows: usize,
+        cols: usize,
+    ) -> Result<Vec<Vec<T>>> {
+        fits_file.make_current(self)?;
+        crate::headers::read_key_matrix(fits_file, root, rows, cols)
+    }
+
+    /**
+    Read many header keys, continuing past keys which cannot be read
+
+    The HDU is selected once, and each key is read in turn. A missing key, or one which cannot
+    be converted to `T`, gives an error in its own entry rather than failing the whole read, so
+    the same keys can be collected from files which do not all define them.
+
+    # Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let filename = "../testdata/full_example.fits";
+    # let mut fptr = fitsio::FitsFile::open(filename)?;
+    # let hdu = fptr.primary_hdu()?;
+    let values = hdu.read_keys::<i64>(&mut fptr, &["INTTEST", "MISSING", "NAXIS"])?;
+    assert_eq!(values[0].as_ref().ok(), Some(&42));
+    assert!(values[1].is_err());
+    assert_eq!(values[2].as_ref().ok(), Some(&2));
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn read_keys<T: ReadsKey>(
+        &self,
+        fits_file: &mut FitsFile,
+        names: &[&str],
+    ) -> Result<Vec<Result<T>>> {
+        fits_file.make_current(self)?;
+        Ok(names
+            .iter()
+            .map(|name| T::read_key(fits_file, name))
+            .collect())
+    }
+
+    /**
+    Write a fits key to the current header
+
+    # Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+    # let tdir_path = tdir.path();
+    # let filename = tdir_path.join("test.fits");
+    # {
+    # let mut fptr = fitsio::FitsFile::create(filename).open()?;
+    fptr.primary_hdu()?.write_key(&mut fptr, "foo", 1i64)?;
+    assert_eq!(fptr.hdu(0)?.read_key::<i64>(&mut fptr, "foo")?, 1i64);
+    # Ok(())
+    # }
+    # }
+    ```
+    */
+    pub fn write_key<T: WritesKey>(
+        &self,
+        fits_file: &mut FitsFile,
+        name: &str,
+        value: T,
+    ) -> Result<()> {
+        fits_file.make_current(self)?;
+        fits_check_readwrite!(fits_file);
+        T::write_key(fits_file, name, value)
+    }
+
+    /**
+    Write a floating point header key with a number of decimal places
+
+    This overrides the file's default precision, see
+    [`FitsFile::set_key_precision`](../fitsfile/struct.FitsFile.html#method.set_key_precision),
+    and uses the file's notation, exponential unless changed.
+
+    # Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+    # let tdir_path = tdir.path();
+    # let filename = tdir_path.join("test.fits");
+    # let mut fptr = fitsio::FitsFile::create(filename).open()?;
+    let hdu = fptr.primary_hdu()?;
+    hdu.write_key_with_precision(&mut fptr, "MJD-OBS", 60000.123456789, 15)?;
+    hdu.write_key_with_precision(&mut fptr, "AIRMASS", 1.23456, 2)?;
+    assert_eq!(hdu.read_key::<f64>(&mut fptr, "MJD-OBS")?, 60000.123456789);
+    assert_eq!(hdu.read_key::<f64>(&mut fptr, "AIRMASS")?, 1.23);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn write_key_with_precision(
+        &self,
+        fits_file: &mut FitsFile,
+        name: &str,
+        value: f64,
+        decimals: usize,
+    ) -> Result<()> {
+        let format = fits_file.key_format;
+        self.write_key_with_format(fits_file, name, value, format, decimals)
+    }
+
+    /**
+    Write a floating point header key in a notation, with a number of decimal places
+
+    This overrides the file's default notation and precision, see
+    [`FitsFile::set_key_format`](../fitsfile/struct.FitsFile.html#method.set_key_format). For
+    [`FloatFormat::General`](../headers/enum.FloatFormat.html#variant.General), `decimals` is
+    the number of significant figures.
+
+    # Example
+
+    ```rust
+    use fitsio::headers::FloatFormat;
+
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+    # let tdir_path = tdir.path();
+    # let filename = tdir_path.join("test.fits");
+    # let mut fptr = fitsio::FitsFile::create(filename).open()?;
+    let hdu = fptr.primary_hdu()?;
+    hdu.write_key_with_format(&mut fptr, "EXPTIME", 30.0, FloatFormat::Fixed, 1)?;
+    hdu.write_key_with_format(&mut fptr, "GAIN", 2.5, FloatFormat::General, 6)?;
+    assert_eq!(hdu.read_key::<f64>(&mut fptr, "EXPTIME")?, 30.0);
+    assert_eq!(hdu.read_key::<f64>(&mut fptr, "GAIN")?, 2.5);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn write_key_with_format(
+        &self,
+        fits_file: &mut FitsFile,
+        name: &str,
+        value: f64,
+        format: FloatFormat,
+        decimals: usize,
+    ) -> Result<()> {
+        fits_file.make_current(self)?;
+        fits_check_readwrite!(fits_file);
+        f64::write_float_key(fits_file, name, value, format, decimals)
+    }
+
+    /**
+    Write many header keys at once
+
+    The HDU is selected and checked for write access once, rather than for every key, so this
+    is much faster than repeated calls to [`write_key`](#method.write_key) when writing large
+    headers. Keys of different types can be written together by converting the values to
+    [`KeyValue`](../headers/enum.KeyValue.html).
+
+    # Example
+
+    ```rust
+    use fitsio::headers::KeyValue;
+
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+    # let tdir_path = tdir.path();
+    # let filename = tdir_path.join("test.fits");
+    # let mut fptr = fitsio::FitsFile::create(filename).open()?;
+    let hdu = fptr.primary_hdu()?;
+    hdu.write_keys(
+        &mut fptr,
+        &[
+            ("EXPTIME", KeyValue::from(30.0)),
+            ("NCOMBINE", KeyValue::from(5)),
+            ("OBJECT", KeyValue::from("M31")),
+        ],
+    )?;
+    assert_eq!(hdu.read_key::<i64>(&mut fptr, "NCOMBINE")?, 5);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn write_keys<T: Into<KeyValue> + Clone>(
+        &self,
+        fits_file: &mut FitsFile,
+        keys: &[(&str, T)],
+    ) -> Result<()> {
+        fits_file.make_current(self)?;
+        fits_check_readwrite!(fits_file);
+        for (name, value) in keys {
+            KeyValue::write_key(fits_file, name, value.clone().into())?;
+        }
+        Ok(())
+    }
+
+    /**
+    Write an indexed family of header keys
+
+    The values are written to the keys `root1`, `root2`, ... in order.
+
+    # Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+    # let tdir_path = tdir.path();
+    # let filename = tdir_path.join("test.fits");
+    # let mut fptr = fitsio::FitsFile::create(filename).open()?;
+    let hdu = fptr.primary_hdu()?;
+    hdu.write_keys_indexed(&mut fptr, "CTYPE", &["RA---TAN", "DEC--TAN"])?;
+    assert_eq!(hdu.read_key::<String>(&mut fptr, "CTYPE2")?, "DEC--TAN");
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn write_keys_indexed<T: WritesKey + Clone>(
+        &self,
+        fits_file: &mut FitsFile,
+        root: &str,
+        values: &[T],
+    ) -> Result<()> {
+        fits_file.make_current(self)?;
+        fits_check_readwrite!(fits_file);
+        crate::headers::write_keys_indexed(fits_file, root, values)
+    }
+
+    /**
+    Write a matrix of header keys
+
+    Each row is a slice, array or `Vec` of values, and the value in row `i` and column `j` is
+    written to the key `rootI_J`, numbering from 1. See
+    [`read_key_matrix`](#method.read_key_matrix).
+    */
+    pub fn write_key_matrix<T: WritesKey + Clone, R: AsRef<[T]>>(
+        &self,
+        fits_file: &mut FitsFile,
+        root: &str,
+        values: &[R],
+    ) -> Result<()> {
+        fits_file.make_current(self)?;
+        fits_check_readwrite!(fits_file);
+        crate::headers::write_key_matrix(fits_file, root, values)
+    }
+
+    /**
+    Rename a header key, keeping its value, comment and position in the header
+
+    An error is returned if `old` is not in the header, or `new` already is.
+
+    # Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+    # let tdir_path = tdir.path();
+    # let filename = tdir_path.join("test.fits");
+    # let mut fptr = fitsio::FitsFile::create(filename).open()?;
+    let hdu = fptr.primary_hdu()?;
+    hdu.write_key(&mut fptr, "EXPOSURE", 30.0)?;
+    hdu.rename_key(&mut fptr, "EXPOSURE", "EXPTIME")?;
+    assert_eq!(hdu.read_key::<f64>(&mut fptr, "EXPTIME")?, 30.0);
+    assert!(hdu.read_key::<f64>(&mut fptr, "EXPOSURE").is_err());
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn rename_key(&self, fits_file: &mut FitsFile, old: &str, new: &str) -> Result<()> {
+        fits_file.make_current(self)?;
+        fits_check_readwrite!(fits_file);
+        if key_exists(fits_file, new)? {
+            return Err(Error::Message(format!(
+                "cannot rename {:?}, the header already contains {:?}",
+                old, new
+            )));
+        }
+
+        let c_old = ffi::CString::new(old)?;
+        let c_new = ffi::CString::new(new)?;
+        let mut status = 0;
+        unsafe {
+            fits_modify_name(
+                fits_file.fptr.as_mut() as *mut _,
+                c_old.as_ptr(),
+                c_new.as_ptr(),
+                &mut status,
+            );
+        }
+        check_status(status)
+    }
+
+    /**
+    Write a header key immediately before an existing key
+
+    [`write_key`](#method.write_key) adds keys at the end of the header, which does not suit
+    readers expecting keys in a particular order. An error is returned if `before` is not in the
+    header, or is `SIMPLE` or `XTENSION`, which must be the first card, or `name` already is in
+    the header.
+
+    # Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+    # let tdir_path = tdir.path();
+    # let filename = tdir_path.join("test.fits");
+    # let mut fptr = fitsio::FitsFile::create(filename).open()?;
+    let hdu = fptr.primary_hdu()?;
+    hdu.write_key(&mut fptr, "DATE-OBS", "2024-01-01")?;
+    hdu.write_key_before(&mut fptr, "DATE-OBS", "TELESCOP", "HST")?;
+    let cards = hdu.read_keys_matching(&mut fptr, "*")?;
+    let names: Vec<&str> = cards.iter().map(|card| card.name.as_str()).collect();
+    assert!(names.ends_with(&["TELESCOP", "DATE-OBS"]));
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn write_key_before<T: Into<KeyValue>>(
+        &self,
+        fits_file: &mut FitsFile,
+        before: &str,
+        name: &str,
+        value: T,
+    ) -> Result<()> {
+        fits_file.make_current(self)?;
+        fits_check_readwrite!(fits_file);
+        let index = key_index(fits_file, before)?;
+        insert_card(fits_file, index, &TemplateCard::new(name, value))
+    }
+
+    /**
+    Insert a header card at an index of the header, counting from 0
+
+    The card at `index`, and every card after it, move down by one. The index may be the number
+    of keys in the header, to add the card at the end. An error is returned if the index is 0, as
+    `SIMPLE` or `XTENSION` must be the first card, or if the header already contains the key.
+
+    # Example
+
+    ```rust
+    use fitsio::headers::TemplateCard;
+
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+    # let tdir_path = tdir.path();
+    # let filename = tdir_path.join("test.fits");
+    # let mut fptr = fitsio::FitsFile::create(filename).open()?;
+    let hdu = fptr.primary_hdu()?;
+    let card = TemplateCard::new("EXPTIME", 30.0).unit("s").comment("exposure time");
+    // After SIMPLE, BITPIX, NAXIS and EXTEND
+    hdu.insert_card_at(&mut fptr, 4, &card)?;
+    let cards = hdu.read_keys_matching(&mut fptr, "*")?;
+    assert_eq!(cards[4].name, "EXPTIME");
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn insert_card_at(
+        &self,
+        fits_file: &mut FitsFile,
+        index: usize,
+        card: &TemplateCard,
+    ) -> Result<()> {
+        let num_keys = self.num_keys(fits_file)?;
+        if index > num_keys {
+            return Err(Error::Message(format!(
+                "cannot insert a card at index {}, the header has {} keys",
+                index, num_keys
+            )));
+        }
+        fits_check_readwrite!(fits_file);
+        insert_card(fits_file, index, card)
+    }
+
+    /**
+    Read the coordinate keywords (`CTYPEi`, `CUNITi`, `CRVALi`, `CDELTi` and `CRPIXi`) of every
+    axis of an image
+
+    See [`ImageAxes`](../images/struct.ImageAxes.html) for an example.
+    */
+    pub fn read_axes(&self, fits_file: &mut FitsFile) -> Result<ImageAxes> {
+        let naxis = self.dimensions()?;
+        fits_file.make_current(self)?;
+        ImageAxes::read(fits_file, naxis)
+    }
+
+    /**
+    Write the coordinate keywords of each axis of an image
+
+    Only the keywords which are set are written, replacing any existing cards with the same name. Any
+    other keywords already in the header are left untouched.
+    */
+    pub fn write_axes(&self, fits_file: &mut FitsFile, axes: &ImageAxes) -> Result<()> {
+        let naxis = self.dimensions()?;
+        if axes.axes.len() > naxis {
+            return Err(format!(
+                "cannot write keywords for {} axes to an image with {} axes",
+                axes.axes.len(),
+                naxis
+            )
+            .as_str()
+            .into());
+        }
+        fits_file.make_current(self)?;
+        fits_check_readwrite!(fits_file);
+        axes.write(fits_file)
+    }
+
+    /**
+    Read the linear part of the WCS of an image as a `CDi_j` matrix, whether the header uses
+    `CDi_j`, `PCi_j` with `CDELTi`, or `CDELTi` with `CROTA2`
+
+    See [`LinearTransform`](../images/struct.LinearTransform.html).
+    */
+    pub fn read_linear_transform(&self, fits_file: &mut FitsFile) -> Result<LinearTransform> {
+        let naxis = self.dimensions()?;
+        fits_file.make_current(self)?;
+        LinearTransform::read(fits_file, naxis)
+    }
+
+    /**
+    Write the linear part of the WCS of an image in the given convention
+
+    Any `CDi_j`, `PCi_j`, `CDELTi` and `CROTAi` keywords already in the header are removed first,
+    so only one description of the transformation remains.
+    */
+    pub fn write_linear_transform(
+        &self,
+        fits_file: &mut FitsFile,
+        transform: &LinearTransform,
+        convention: LinearConvention,
+    ) -> Result<()> {
+        let naxis = self.dimensions()?;
+        if transform.naxis() != naxis {
+            return Err(Error::Message(format!(
+                "cannot write a transformation of {} axes to an image with {} axes",
+                transform.naxis(),
+                naxis
+            )));
+        }
+        fits_file.make_current(self)?;
+        fits_check_readwrite!(fits_file);
+        transform.write(fits_file, convention)
+    }
+
+    /**
+    Read the celestial coordinate system of the first two axes of an image
+
+    See [`CelestialWcs`](../images/struct.CelestialWcs.html).
+    */
+    pub fn read_celestial_wcs(&self, fits_file: &mut FitsFile) -> Result<CelestialWcs> {
+        if self.dimensions()? < 2 {
+            return Err("celestial coordinates need an image with at least two axes".into());
+        }
+        fits_file.make_current(self)?;
+        CelestialWcs::read(fits_file)
+    }
+
+    /// Write the celestial coordinate system of the first two axes of an image
+    pub fn write_celestial_wcs(&self, fits_file: &mut FitsFile, wcs: &CelestialWcs) -> Result<()> {
+        if self.dimensions()? < 2 {
+            return Err("celestial coordinates need an image with at least two axes".into());
+        }
+        fits_file.make_current(self)?;
+        fits_check_readwrite!(fits_file);
+        wcs.write(fits_file)
+    }
+
+    /**
+    Read the spectral coordinate system of the first axis of an image
+
+    See [`SpectralAxis`](../images/struct.SpectralAxis.html).
+    */
+    pub fn read_spectral_axis(&self, fits_file: &mut FitsFile) -> Result<SpectralAxis> {
+        if self.dimensions()? == 0 {
+            return Err("cannot read the spectral axis of an empty image".into());
+        }
+        fits_file.make_current(self)?;
+        SpectralAxis::read(fits_file)
+    }
+
+    /**
+    Read a one dimensional spectrum, with the spectral coordinate of each pixel
+
+    The coordinates come from [`read_spectral_axis`](#method.read_spectral_axis), see
+    [`SpectralAxis`](../images/struct.SpectralAxis.html) for an example.
+    */
+    pub fn read_spectrum<T>(&self, fits_file: &mut FitsFile) -> Result<(Vec<f64>, Vec<T>)>
+    where
+        Vec<T>: ReadImage,
+    {
+        let len = match *self.shape()? {
+            [len] => len,
+            ref shape => {
+                return Err(Error::Message(format!(
+                    "a spectrum must have one dimension, not {}",
+                    shape.len()
+                )))
+            }
+        };
+        let axis = self.read_spectral_axis(fits_file)?;
+        let values = self.read_image(fits_file)?;
+        Ok((axis.values(len), values))
+    }
+
+    /**
+    Compute summary statistics of the pixels of an image
+
+    The image is read in chunks, so the whole image is never held in memory. Pixels are
+    converted to `f64` after applying `BSCALE` and `BZERO`. Null pixels, which are NaN in floating
+    point images and equal to the `BLANK` keyword in integer images, are skipped.
+
+    ## Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let filename = "../testdata/full_example.fits";
+    # let mut fptr = fitsio::FitsFile::open(filename)?;
+    let hdu = fptr.primary_hdu()?;
+    let stats = hdu.image_stats(&mut fptr)?;
+    assert_eq!(stats.count, 10_000);
+    println!("{} <= pixel <= {}", stats.min, stats.max);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn image_stats(&self, fits_file: &mut FitsFile) -> Result<ImageStats> {
+        self.image_stats_chunked(fits_file, STATS_CHUNK_VALUES)
+    }
+
+    fn image_stats_chunked(
+        &self,
+        fits_file: &mut FitsFile,
+        chunk_values: usize,
+    ) -> Result<ImageStats> {
+        fits_file.make_current(self)?;
+        let num_pixels = match self.info {
+            HduInfo::ImageInfo { ref shape, .. } if shape.is_empty() => 0,
+            HduInfo::ImageInfo { ref shape, .. } => shape.iter().product::<usize>(),
+            _ => return Err("cannot compute image statistics of a table hdu".into()),
+        };
+
+        let mut range: Option<(f64, f64)> = None;
+        let mut count = 0;
+        let mut mean = 0.0;
+        let mut sum_squares = 0.0;
+        let mut start = 0;
+        while start < num_pixels {
+            let end = (start + chunk_values).min(num_pixels);
+            // Reading with a NaN null value turns BLANK pixels into NaN, so they are skipped too
+            let mut values = vec![0.0f64; end - start];
+            let mut nulval = f64::NAN;
+            let mut anynul = 0;
+            let mut status = 0;
+            unsafe {
+                fits_read_img(
+                    fits_file.fptr.as_mut() as *mut _,
+                    DataType::TDOUBLE.into(),
+                    (start + 1) as i64,
+                    values.len() as i64,
+                    &mut nulval as *mut f64 as *mut _,
+                    values.as_mut_ptr() as *mut _,
+                    &mut anynul,
+                    &mut status,
+                );
+            }
+            check_status(status)?;
+            for value in values.into_iter().filter(|value| !value.is_nan()) {
+                range = match range {
+                    Some((min, max)) => Some((min.min(value), max.max(value))),
+                    None => Some((value, value)),
+                };
+
+                // Welford's online algorithm
+                count += 1;
+                let delta = value - mean;
+                mean += delta / count as f64;
+                sum_squares += delta * (value - mean);
+            }
+            start = end;
+        }
+
+        match range {
+            Some((min, max)) => Ok(ImageStats {
+                count,
+                min,
+                max,
+                mean,
+                std: (sum_squares / count as f64).sqrt(),
+            }),
+            None => Err("image has no defined pixels".into()),
+        }
+    }
+
+    /**
+    Read a square cutout of an image centred on a sky position
+
+    The position is given in degrees in the coordinate system of the image, and `size_arcsec` is
+    the length of each side of the cutout. Pixels whose centres fall within the square are read,
+    clipped to the edges of the image, and any axes past the second are read in full. The
+    returned [`Cutout`](../images/struct.Cutout.html) carries the coordinate system of the
+    cutout, ready to be written alongside the data with
+    [`write_celestial_wcs`](#method.write_celestial_wcs).
+
+    An error is returned if the cutout does not overlap the image.
+
+    # Example
+
+    ```rust
+    use fitsio::images::{CelestialWcs, Cutout};
+
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+    # let filename = tdir.path().join("test.fits");
+    # std::fs::copy("../testdata/full_example.fits", &filename)?;
+    # let mut fptr = fitsio::FitsFile::edit(filename)?;
+    let hdu = fptr.primary_hdu()?;
+    let wcs = CelestialWcs {
+        ctype: ["RA---TAN".to_string(), "DEC--TAN".to_string()],
+        crval: [150.0, 2.0],
+        crpix: [50.5, 50.5],
+        cdelt: [-1.0 / 3600.0, 1.0 / 3600.0],
+        rotation: 0.0,
+    };
+    hdu.write_celestial_wcs(&mut fptr, &wcs)?;
+
+    // A 10 arcsecond square around the reference point
+    let cutout: Cutout<Vec<i32>> = hdu.read_cutout_world(&mut fptr, 150.0, 2.0, 10.0)?;
+    assert_eq!(cutout.shape(), vec![10, 10]);
+    assert_eq!(cutout.wcs.crpix, [5.5, 5.5]);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn read_cutout_world<T: ReadImage>(
+        &self,
+        fits_file: &mut FitsFile,
+        ra: f64,
+        dec: f64,
+        size_arcsec: f64,
+    ) -> Result<Cutout<T>> {
+        let mut wcs = self.read_celestial_wcs(fits_file)?;
+        if size_arcsec <= 0.0 || size_arcsec.is_nan() || wcs.cdelt.contains(&0.0) {
+            return Err("cutout size and pixel scale must be non-zero".into());
+        }
+        let (x, y) = wcs.world_to_pixel(ra, dec)?;
+
+        let shape = self.shape()?;
+        let size = size_arcsec / 3600.0;
+        let region: Vec<Range<usize>> = shape
+            .iter()
+            .rev()
+            .enumerate()
+            .map(|(axis, &len)| match axis {
+                0 => cutout_range(x, size / wcs.cdelt[0].abs() / 2.0, len),
+                1 => cutout_range(y, size / wcs.cdelt[1].abs() / 2.0, len),
+                _ => 0..len,
+            })
+            .collect();
+        if region.iter().any(|range| range.is_empty()) {
+            return Err(Error::Message(format!(
+                "cutout at ({}, {}) does not overlap the image",
+                ra, dec
+            )));
+        }
+
+        let data = self.read_region(fits_file, &region)?;
+        wcs.crpix[0] -= region[0].start as f64;
+        wcs.crpix[1] -= region[1].start as f64;
+        Ok(Cutout { data, region, wcs })
+    }
+
+    /**
+    Read pixels from an image between a start index and end index
+
+    The range is exclusive of the upper value
+
+    # Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let filename = "../testdata/full_example.fits";
+    # let mut fptr = fitsio::FitsFile::open(filename)?;
+    # let hdu = fptr.hdu(0)?;
+    // Read the first 100 pixels
+    let first_row: Vec<i32> = hdu.read_section(&mut fptr, 0, 100)?;
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn read_section<T: ReadImage>(
+        &self,
+        fits_file: &mut FitsFile,
+        start: usize,
+        end: usize,
+    ) -> Result<T> {
+        fits_file.make_current(self)?;
+        T::read_section(fits_file, self, start..end)
+    }
+
+    /**
+    # Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let filename = "../testdata/full_example.fits";
+    # let mut fptr = fitsio::FitsFile::open(filename)?;
+    # let hdu = fptr.hdu(0)?;
+    let start_row = 0;
+    let num_rows = 10;
+    let first_few_rows: Vec<f32> = hdu.read_rows(&mut fptr, start_row, num_rows)?;
+
+    // 10 rows of 100 columns
+    assert_eq!(first_few_rows.len(), 1000);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn read_rows<T: ReadImage>(
+        &self,
+        fits_file: &mut FitsFile,
+        start_row: usize,
+        num_rows: usize,
+    ) -> Result<T> {
+        fits_file.make_current(self)?;
+        T::read_rows(fits_file, self, start_row, num_rows)
+    }
+
+    /**
+    Read a single row from a fits image
+
+    # Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let filename = "../testdata/full_example.fits";
+    # let mut fptr = fitsio::FitsFile::open(filename)?;
+    # let hdu = fptr.hdu(0)?;
+    let chosen_row = 5;
+    let row: Vec<f32> = hdu.read_row(&mut fptr, chosen_row)?;
+
+    // Should have 100 pixel values
+    assert_eq!(row.len(), 100);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn read_row<T: ReadImage>(&self, fits_file: &mut FitsFile, row: usize) -> Result<T> {
+        fits_file.make_current(self)?;
+        T::read_row(fits_file, self, row)
+    }
+
+    /**
+    Read a square region from the chip.
+
+    Lower left indicates the starting point of the square, and the upper
+    right defines the pixel _beyond_ the end. The range of pixels included
+    is inclusive of the lower end, and *exclusive* of the upper end.
+
+    # Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let filename = "../testdata/full_example.fits";
+    # let mut fptr = fitsio::FitsFile::open(filename)?;
+    # let hdu = fptr.hdu(0)?;
+    // Read a square section of the image
+    let xcoord = 0..10;
+    let ycoord = 0..10;
+    let chunk: Vec<i32> = hdu.read_region(&mut fptr, &[&ycoord, &xcoord])?;
+
+    // Owned ranges can also be given
+    let chunk: Vec<i32> = hdu.read_region(&mut fptr, &[0..10, 0..10])?;
+
+    // Or a reusable `Region`
+    let region = fitsio::images::Region::rect(0..10, 0..10);
+    let chunk: Vec<i32> = hdu.read_region(&mut fptr, &region)?;
+    # Ok(())
+    # }
+    ```
+
+    See [`RegionRanges`](../trait.RegionRanges.html) for the supported types of `ranges`. The
+    first range applies to the first (fastest varying) axis of the image. Strides set on a
+    [`Region`](../images/struct.Region.html) are honoured.
+    */
+    pub fn read_region<T: ReadImage, R: RegionRanges>(
+        &self,
+        fits_file: &mut FitsFile,
+        ranges: R,
+    ) -> Result<T> {
+        let steps = ranges.steps();
+        self.read_region_strided(fits_file, ranges, &steps)
+    }
+
+    /**
+    Read every `n`th pixel of a region of the image
+
+    This behaves like [`read_region`](#method.read_region), but only reads every `steps[i]`th
+    pixel along axis `i`, starting from the first pixel of each range. This is useful for
+    quickly reading a decimated preview of a large image.
+
+    # Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let filename = "../testdata/full_example.fits";
+    # let mut fptr = fitsio::FitsFile::open(filename)?;
+    # let hdu = fptr.hdu(0)?;
+    // Every 10th pixel along both axes of the 100x100 image
+    let preview: Vec<i32> = hdu.read_region_strided(&mut fptr, &[0..100, 0..100], &[10, 10])?;
+    assert_eq!(preview.len(), 100);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn read_region_strided<T: ReadImage, R: RegionRanges>(
+        &self,
+        fits_file: &mut FitsFile,
+        ranges: R,
+        steps: &[usize],
+    ) -> Result<T> {
+        let ranges = ranges.ranges();
+        if steps.len() != ranges.len() {
+            return Err(format!(
+                "{} steps given for {} ranges, expected one step per range",
+                steps.len(),
+                ranges.len()
+            )
+            .as_str()
+            .into());
+        }
+        if steps.contains(&0) {
+            return Err("region steps must be greater than zero".into());
+        }
+        fits_file.make_current(self)?;
+        T::read_region(fits_file, self, &ranges, steps)
+    }
+
+    /**
+    Read a whole image into a new `Vec`
+
+    This reads an entire image into a one-dimensional vector
+
+    # Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let filename = "../testdata/full_example.fits";
+    # let mut fptr = fitsio::FitsFile::open(filename)?;
+    # let hdu = fptr.hdu(0)?;
+    let image_data: Vec<f32> = hdu.read_image(&mut fptr)?;
+
+    // 100 rows of 100 columns
+    assert_eq!(image_data.len(), 10_000);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn read_image<T: ReadImage>(&self, fits_file: &mut FitsFile) -> Result<T> {
+        fits_file.make_current(self)?;
+        T::read_image(fits_file, self)
+    }
+
+    /**
+    Read a whole image, applying an explicit linear scaling to the pixel values
+
+    Each pixel is converted as `scale * raw + offset` by cfitsio while the data are read,
+    overriding any `BSCALE` and `BZERO` values in the header. The raw values may have any
+    `BITPIX`, and are converted to the requested type in the same pass. Passing `1.0` and
+    `0.0` reads the raw stored values.
+
+    After reading, the scaling defined by the header is restored, so subsequent reads are
+    unaffected.
+
+    # Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let filename = "../testdata/full_example.fits";
+    # let mut fptr = fitsio::FitsFile::open(filename)?;
+    # let hdu = fptr.hdu(0)?;
+    let raw: Vec<f32> = hdu.read_image(&mut fptr)?;
+    let scaled: Vec<f32> = hdu.read_image_scaled(&mut fptr, 2.0, 10.0)?;
+    assert_eq!(scaled[0], 2.0 * raw[0] + 10.0);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn read_image_scaled<T: ReadImage>(
+        &self,
+        fits_file: &mut FitsFile,
+        scale: f64,
+        offset: f64,
+    ) -> Result<T> {
+        fits_file.make_current(self)?;
+        if let HduInfo::TableInfo { .. } = self.info {
+            return Err("cannot read image data from a table hdu".into());
+        }
+
+        let header_scale = f64::read_key(fits_file, "BSCALE").unwrap_or(1.0);
+        let header_offset = f64::read_key(fits_file, "BZERO").unwrap_or(0.0);
+
+        set_image_scaling(fits_file, scale, offset)?;
+        let result = T::read_image(fits_file, self);
+        set_image_scaling(fits_file, header_scale, header_offset)?;
+        result
+    }
+
+    /**
+    Write raw pixel values to a FITS image
+
+    If the length of the dataset exceeds the number of columns,
+    the data wraps around to the next row.
+
+    The range is exclusive of the upper value.
+
+    # Example
+
+    ```rust
+    # use fitsio::images::{ImageDescription, ImageType};
+    #
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+    # let tdir_path = tdir.path();
+    # let filename = tdir_path.join("test.fits");
+    # let mut fptr = fitsio::FitsFile::create(filename).open()?;
+    # let desc = ImageDescription {
+    #    data_type: ImageType::Float,
+    #    dimensions: &[100, 100],
+    # };
+    # let hdu = fptr.create_image("".to_string(), &desc)?;
+    let data_to_write: Vec<f64> = vec![1.0, 2.0, 3.0];
+    hdu.write_section(&mut fptr, 0, data_to_write.len(), &data_to_write)?;
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn write_section<T: WriteImage>(
+        &self,
+        fits_file: &mut FitsFile,
+        start: usize,
+        end: usize,
+        data: &[T],
+    ) -> Result<()> {
+        fits_file.make_current(self)?;
+        fits_check_readwrite!(fits_file);
+        T::write_section(fits_file, self, start..end, data)
+    }
+
+    /**
+    Write a rectangular region to the fits image
+
+    The ranges must have length of 2, and they represent the limits of each axis. The limits
+    are inclusive of the lower bounds, and *exclusive* of the and upper bounds.
+
+    For example, writing with ranges 0..10 and 0..10 wries an 10x10 sized image.
+
+    # Example
+
+    ```rust
+    # use fitsio::images::{ImageDescription, ImageType};
+    #
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+    # let tdir_path = tdir.path();
+    # let filename = tdir_path.join("test.fits");
+    # let mut fptr = fitsio::FitsFile::create(filename).open()?;
+    # let desc = ImageDescription {
+    #    data_type: ImageType::Float,
+    #    dimensions: &[100, 100],
+    # };
+    # let hdu = fptr.create_image("".to_string(), &desc)?;
+    let data_to_write: Vec<f64> = vec![1.0, 2.0, 3.0, 4.0];
+    let ranges = [&(0..1), &(0..1)];
+    hdu.write_region(&mut fptr, &ranges, &data_to_write)?;
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn write_region<T: WriteImage, R: RegionRanges>(
+        &self,
+        fits_file: &mut FitsFile,
+        ranges: R,
+        data: &[T],
+    ) -> Result<()> {
+        if ranges.steps().iter().any(|&step| step != 1) {
+            return Err("cannot write a strided region".into());
+        }
+        fits_file.make_current(self)?;
+        fits_check_readwrite!(fits_file);
+        T::write_region(fits_file, self, &ranges.ranges(), data)
+    }
+
+    /**
+    Set the pixels of a rectangular region of an image to the null value
+
+    The ranges are given in the same way as for [`write_region`](#method.write_region). Floating
+    point images store nulls as `NaN`, while integer images store the value of the `BLANK`
+    keyword, which must be set first with [`set_blank_value`](#method.set_blank_value).
+
+    # Example
+
+    ```rust
+    # use fitsio::images::{ImageDescription, ImageType};
+    #
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+    # let filename = tdir.path().join("test.fits");
+    # let mut fptr = fitsio::FitsFile::create(filename).open()?;
+    let desc = ImageDescription {
+        data_type: ImageType::Long,
+        dimensions: &[100, 100],
+    };
+    let hdu = fptr.create_image("SCI".to_string(), &desc)?;
+    hdu.write_image(&mut fptr, &vec![1i32; 100 * 100])?;
+
+    // Mask out a bad column
+    hdu.set_blank_value(&mut fptr, Some(-999))?;
+    hdu.write_null_region(&mut fptr, &[40..41, 0..100])?;
+
+    let data: Vec<i32> = hdu.read_region(&mut fptr, &[40..42, 0..1])?;
+    assert_eq!(data, [-999, 1]);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn write_null_region<R: RegionRanges>(
+        &self,
+        fits_file: &mut FitsFile,
+        ranges: R,
+    ) -> Result<()> {
+        if ranges.steps().iter().any(|&step| step != 1) {
+            return Err("cannot write a strided region".into());
+        }
+        fits_file.make_current(self)?;
+        fits_check_readwrite!(fits_file);
+
+        let mut shape = match fits_file.fetch_hdu_info()? {
+            HduInfo::ImageInfo { shape, .. } => shape,
+            _ => return Err("cannot write image data to a table hdu".into()),
+        };
+        // Work in FITS axis order, like the ranges
+        shape.reverse();
+
+        let ranges = ranges.ranges();
+        if ranges.len() != shape.len() {
+            return Err(format!(
+                "region has {} axes but the image has {}",
+                ranges.len(),
+                shape.len()
+            )
+            .as_str()
+            .into());
+        }
+        if ranges
+            .iter()
+            .zip(&shape)
+            .any(|(range, &len)| range.start > range.end || range.end > len)
+        {
+            return Err(format!("region {:?} does not fit within the image", ranges)
+                .as_str()
+                .into());
+        }
+        if ranges.iter().any(|range| range.start == range.end) {
+            return Ok(());
+        }
+
+        // Each run of pixels along the first axis is contiguous in the file
+        let run_length = ranges[0].end - ranges[0].start;
+        let mut position: Vec<usize> = ranges.iter().map(|range| range.start).collect();
+        loop {
+            let mut offset = 0;
+            for (&index, &len) in position.iter().zip(&shape).rev() {
+                offset = offset * len + index;
+            }
+
+            let mut status = 0;
+            unsafe {
+                fits_write_null_img(
+                    fits_file.fptr.as_mut() as *mut _,
+                    (offset + 1) as LONGLONG,
+                    run_length as LONGLONG,
+                    &mut status,
+                );
+            }
+            check_status(status)?;
+
+            // Advance to the next run, like an odometer over the remaining axes
+            let mut axis = 1;
+            loop {
+                if axis == position.len() {
+                    return Ok(());
+                }
+                position[axis] += 1;
+                if position[axis] < ranges[axis].end {
+                    break;
+                }
+                position[axis] = ranges[axis].start;
+                axis += 1;
+            }
+        }
+    }
+
+    /**
+    Set or remove the `BLANK` keyword, which gives the value of null pixels in an integer image
+
+    The new value is used immediately by [`write_null_region`](#method.write_null_region).
+    Floating point images always use `NaN` for null pixels, so this is an error for them.
+    */
+    pub fn set_blank_value(&self, fits_file: &mut FitsFile, blank: Option<i64>) -> Result<()> {
+        fits_file.make_current(self)?;
+        fits_check_readwrite!(fits_file);
+        match fits_file.fetch_hdu_info()? {
+            HduInfo::ImageInfo {
+                image_type: ImageType::Float,
+                ..
+            }
+            | HduInfo::ImageInfo {
+                image_type: ImageType::Double,
+                ..
+            } => return Err("floating point images use NaN for null pixels".into()),
+            HduInfo::ImageInfo { .. } => {}
+            _ => return Err("hdu is not an image".into()),
+        }
+
+        match blank {
+            Some(mut blank) => {
+                // Update rather than append, so cfitsio does not keep reading an earlier BLANK
+                let mut status = 0;
+                let keyname = ffi::CString::new("BLANK")?;
+                unsafe {
+                    fits_update_key(
+                        fits_file.fptr.as_mut() as *mut _,
+                        u8::from(DataType::TLONGLONG) as _,
+                        keyname.as_ptr(),
+                        &mut blank as *mut i64 as *mut _,
+                        ptr::null(),
+                        &mut status,
+                    );
+                }
+                check_status(status)?;
+            }
+            None => {
+                let mut status = 0;
+                let keyname = ffi::CString::new("BLANK")?;
+                unsafe {
+                    fits_delete_key(
+                        fits_file.fptr.as_mut() as *mut _,
+                        keyname.as_ptr(),
+                        &mut status,
+                    );
+                }
+                // Removing a keyword which is not present is not an error
+                if status != KEY_NO_EXIST {
+                    check_status(status)?;
+                }
+            }
+        }
+
+        // Re-read the header so cfitsio picks up the new null value
+        let mut status = 0;
+        unsafe {
+            fits_set_hdustruc(fits_file.fptr.as_mut() as *mut _, &mut status);
+        }
+        check_status(status)
+    }
+
+    /// Value of the `BLANK` keyword, which gives the value of null pixels in an integer image
+    pub fn blank_value(&self, fits_file: &mut FitsFile) -> Result<Option<i64>> {
+        fits_file.make_current(self)?;
+        read_key_optional(fits_file, "BLANK")
+    }
+
+    /**
+    Write an entire image to the HDU passed in
+
+    Firstly a check is performed, making sure that the amount of data will fit in the image.
+    After this, all of the data is written to the image.
+
+    ## Example
+
+    ```rust
+    # use fitsio::images::{ImageType, ImageDescription};
+    #
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+    # let tdir_path = tdir.path();
+    # let filename = tdir_path.join("test.fits");
+    # let mut fptr = fitsio::FitsFile::create(filename).open()?;
+    # let desc = ImageDescription {
+    #    data_type: ImageType::Float,
+    #    dimensions: &[3, 1],
+    # };
+    # let hdu = fptr.create_image("".to_string(), &desc)?;
+    // Image is 3x1
+    assert!(hdu.write_image(&mut fptr, &[1.0, 2.0, 3.0]).is_ok());
+    assert!(hdu.write_image(&mut fptr, &[1.0, 2.0, 3.0, 4.0]).is_err());
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn write_image<T: WriteImage>(&self, fits_file: &mut FitsFile, data: &[T]) -> Result<()> {
+        fits_file.make_current(self)?;
+        fits_check_readwrite!(fits_file);
+        T::write_image(fits_file, self, data)
+    }
+
+    /**
+    Write pre-serialized bytes directly into the data unit, starting `offset` bytes from its
+    start
+
+    The bytes are written as they are, without any conversion, scaling or byte swapping, so
+    data already encoded in the FITS format, for example by another library or a network
+    service, can be stored without decoding it first. The bytes must fit within the data unit
+    described by the header, see [`data_size_bytes`](#method.data_size_bytes).
+
+    # Safety
+
+    Nothing checks that the bytes are valid for the HDU. They must be big endian values of the
+    type given by the header, and for tables must match the row layout, including any variable
+    length array descriptors. Invalid bytes produce a corrupt file, and reading heap data
+    through invalid descriptors may read out of bounds.
+
+    # Example
+
+    ```rust
+    use fitsio::images::{ImageDescription, ImageType};
+
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+    # let filename = tdir.path().join("test.fits");
+    # let mut fptr = fitsio::FitsFile::create(filename).open()?;
+    let description = ImageDescription {
+        data_type: ImageType::Short,
+        dimensions: &[2],
+    };
+    let hdu = fptr.create_image("RAW", &description)?;
+    let bytes = [0x01, 0x00, 0xff, 0xfe];
+    unsafe { hdu.write_raw_data(&mut fptr, 0, &bytes)? };
+
+    let pixels: Vec<i16> = hdu.read_image(&mut fptr)?;
+    assert_eq!(pixels, [256, -2]);
+    # Ok(())
+    # }
+    ```
+    */
+    pub unsafe fn write_raw_data(
+        &self,
+        fits_file: &mut FitsFile,
+        offset: usize,
+        data: &[u8],
     ) -> Result<()> {
+        fits_check_readwrite!(fits_file);
+        let (_, data_start, data_end) = self.byte_offsets(fits_file)?;
+        match offset.checked_add(data.len()) {
+            Some(end) if end <= data_end - data_start => {}
+            _ => {
+                return Err(Error::Message(format!(
+                    "cannot write {} bytes at offset {} of a data unit of {} bytes",
+                    data.len(),
+                    offset,
+                    data_end - data_start
+                )))
+            }
+        }
+        if data.is_empty() {
+            return Ok(());
+        }
+
+        let mut status = 0;
+        let fptr = fits_file.fptr.as_mut() as *mut _;
+        // Ignore the end of file, as the data unit of a new HDU may not have been written yet
+        ffmbyt(fptr, (data_start + offset) as _, 1, &mut status);
+        ffpbyt(fptr, data.len() as _, data.as_ptr() as *mut _, &mut status);
+        check_status(status)
+    }
+
+    /**
+    Apply a function to every pixel of an image, in place
+
+    The image is processed in chunks, so the whole image is never held in memory. Pixel values
+    are passed to `f` as `f64` regardless of the image type, and the result is converted back
+    when written.
+
+    # Example
+
+    ```rust
+    # use std::fs::copy;
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+    # let tdir_path = tdir.path();
+    # let filename = tdir_path.join("test.fits");
+    # copy("../testdata/full_example.fits", &filename)?;
+    # let mut fptr = fitsio::FitsFile::edit(filename)?;
+    # let hdu = fptr.hdu(0)?;
+    let gain = 2.0;
+    hdu.apply(&mut fptr, |pixel| pixel * gain)?;
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn apply<F>(&self, fits_file: &mut FitsFile, mut f: F) -> Result<()>
+    where
+        F: FnMut(f64) -> f64,
+    {
         fits_file.make_current(self)?;
         fits_check_readwrite!(fits_file);
-        T::write_region(fits_file, self, ranges, data)
+        let npixels = self.num_pixels()?;
+
+        let mut start = 0;
+        while start < npixels {
+            let end = (start + IMAGE_CHUNK_PIXELS).min(npixels);
+            let mut data: Vec<f64> = self.read_section(fits_file, start, end)?;
+            for pixel in data.iter_mut() {
+                *pixel = f(*pixel);
+            }
+            self.write_section(fits_file, start, end, &data)?;
+            start = end;
+        }
+        Ok(())
     }
 
     /**
-    Write an entire image to the HDU passed in
+    Combine this image with another image of the same shape, pixel by pixel, into a new image
+    HDU
 
-    Firstly a check is performed, making sure that the amount of data will fit in the image.
-    After this, all of the data is written to the image.
+    The new image is appended to the file with the name `extname`, and has type
+    [`ImageType::Double`](../images/enum.ImageType.html#variant.Double). Each output pixel is
+    `f(a, b)` where `a` is the pixel from this image and `b` the pixel from `other`. The images
+    are processed in chunks, so neither image is held in memory in full.
 
-    ## Example
+    # Example
 
     ```rust
-    # use fitsio::images::{ImageType, ImageDescription};
-    #
+    # use std::fs::copy;
     # fn main() -> Result<(), Box<dyn std::error::Error>> {
     # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
     # let tdir_path = tdir.path();
     # let filename = tdir_path.join("test.fits");
-    # let mut fptr = fitsio::FitsFile::create(filename).open()?;
-    # let desc = ImageDescription {
-    #    data_type: ImageType::Float,
-    #    dimensions: &[3, 1],
-    # };
-    # let hdu = fptr.create_image("".to_string(), &desc)?;
-    // Image is 3x1
-    assert!(hdu.write_image(&mut fptr, &[1.0, 2.0, 3.0]).is_ok());
-    assert!(hdu.write_image(&mut fptr, &[1.0, 2.0, 3.0, 4.0]).is_err());
+    # copy("../testdata/full_example.fits", &filename)?;
+    # let mut fptr = fitsio::FitsFile::edit(filename)?;
+    # let science = fptr.hdu(0)?;
+    # let flat = fptr.hdu(0)?;
+    let flattened = science.combine_image(&mut fptr, &flat, "FLATTENED", |s, f| s / f)?;
     # Ok(())
     # }
     ```
     */
-    pub fn write_image<T: WriteImage>(&self, fits_file: &mut FitsFile, data: &[T]) -> Result<()> {
-        fits_file.make_current(self)?;
+    pub fn combine_image<F>(
+        &self,
+        fits_file: &mut FitsFile,
+        other: &FitsHdu,
+        extname: &str,
+        mut f: F,
+    ) -> Result<FitsHdu>
+    where
+        F: FnMut(f64, f64) -> f64,
+    {
         fits_check_readwrite!(fits_file);
-        T::write_image(fits_file, self, data)
+        let shape = match (&self.info, &other.info) {
+            (
+                HduInfo::ImageInfo { shape, .. },
+                HduInfo::ImageInfo {
+                    shape: other_shape, ..
+                },
+            ) => {
+                if shape != other_shape {
+                    return Err(format!(
+                        "cannot combine images of different shapes ({:?} and {:?})",
+                        shape, other_shape
+                    )
+                    .as_str()
+                    .into());
+                }
+                shape.clone()
+            }
+            _ => return Err("cannot combine non-image hdus".into()),
+        };
+        let npixels = shape.iter().product();
+
+        let description = ImageDescription {
+            data_type: ImageType::Double,
+            dimensions: &shape,
+        };
+        let output = fits_file.create_image(extname, &description)?;
+
+        let mut start = 0;
+        while start < npixels {
+            let end = (start + IMAGE_CHUNK_PIXELS).min(npixels);
+            let a: Vec<f64> = self.read_section(fits_file, start, end)?;
+            let b: Vec<f64> = other.read_section(fits_file, start, end)?;
+            let data: Vec<f64> = a.into_iter().zip(b).map(|(a, b)| f(a, b)).collect();
+            output.write_section(fits_file, start, end, &data)?;
+            start = end;
+        }
+        Ok(output)
+    }
+
+    /**
+    Add another image of the same shape to this one, writing the result into a new image HDU
+    called `extname`
+
+    See [`combine_image`](#method.combine_image) for details.
+
+    # Example
+
+    ```rust
+    # use std::fs::copy;
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+    # let tdir_path = tdir.path();
+    # let filename = tdir_path.join("test.fits");
+    # copy("../testdata/full_example.fits", &filename)?;
+    # let mut fptr = fitsio::FitsFile::edit(filename)?;
+    # let hdu = fptr.hdu(0)?;
+    let doubled = hdu.add_image(&mut fptr, &hdu, "DOUBLED")?;
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn add_image(
+        &self,
+        fits_file: &mut FitsFile,
+        other: &FitsHdu,
+        extname: &str,
+    ) -> Result<FitsHdu> {
+        self.combine_image(fits_file, other, extname, |a, b| a + b)
+    }
+
+    /**
+    Subtract another image of the same shape from this one, writing the result into a new image
+    HDU called `extname`
+
+    See [`combine_image`](#method.combine_image) for details.
+
+    # Example
+
+    ```rust
+    # use std::fs::copy;
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+    # let tdir_path = tdir.path();
+    # let filename = tdir_path.join("test.fits");
+    # copy("../testdata/full_example.fits", &filename)?;
+    # let mut fptr = fitsio::FitsFile::edit(filename)?;
+    # let science = fptr.hdu(0)?;
+    # let bias = fptr.hdu(0)?;
+    let debiased = science.subtract_image(&mut fptr, &bias, "DEBIASED")?;
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn subtract_image(
+        &self,
+        fits_file: &mut FitsFile,
+        other: &FitsHdu,
+        extname: &str,
+    ) -> Result<FitsHdu> {
+        self.combine_image(fits_file, other, extname, |a, b| a - b)
     }
 
     /**
@@ -448,6 +2246,132 @@ impl FitsHdu {
         check_status(status).map(|_| ())
     }
 
+    /**
+    Create a new fits file with this image as its primary HDU
+
+    The header and data are copied, with `XTENSION` replaced by `SIMPLE = T` and the `PCOUNT`
+    and `GCOUNT` keywords removed. An error is returned if the file already exists, or this HDU
+    is a table, which cannot be a primary HDU.
+
+    See [`FitsFile::demote_primary`] to do the reverse.
+
+    ## Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir()?;
+    # let src_filename = tdir.path().join("src.fits");
+    # std::fs::copy("../testdata/full_example.fits", &src_filename)?;
+    # let mut fptr = fitsio::FitsFile::edit(src_filename)?;
+    # let sci = fptr.demote_primary()?;
+    let filename = tdir.path().join("sci.fits");
+    let mut new_fptr = sci.promote_to_file(&mut fptr, &filename)?;
+    let primary = new_fptr.primary_hdu()?;
+    assert!(primary.read_key::<bool>(&mut new_fptr, "SIMPLE")?);
+    # Ok(())
+    # }
+    ```
+
+    [`FitsFile::demote_primary`]: ../fitsfile/struct.FitsFile.html#method.demote_primary
+    */
+    pub fn promote_to_file<P: AsRef<Path>>(
+        &self,
+        fits_file: &mut FitsFile,
+        path: P,
+    ) -> Result<FitsFile> {
+        if let HduInfo::TableInfo { .. } = self.info {
+            return Err("a table cannot be the primary HDU of a file".into());
+        }
+        fits_file.make_current(self)?;
+
+        let path = path.as_ref();
+        let mut dest = FitsFile::create(path).empty().open()?;
+        // cfitsio converts the header of an image extension copied to the start of a file
+        let mut status = 0;
+        unsafe {
+            fits_copy_hdu(
+                fits_file.fptr.as_mut() as *mut _,
+                dest.fptr.as_mut() as *mut _,
+                0,
+                &mut status,
+            );
+        }
+        if let Err(e) = check_status(status) {
+            // Do not leave a file without any HDUs behind
+            drop(dest);
+            let _ = std::fs::remove_file(path);
+            return Err(e);
+        }
+        Ok(dest)
+    }
+
+    /**
+    Copy selected header cards to another HDU
+
+    Every card in this HDU's header whose keyword name passes `filter` is appended to the
+    header of `dest_hdu`, which may be in a different file. Structural keywords must be
+    excluded, which [`is_structural_key`](../headers/fn.is_structural_key.html) can be
+    used for.
+
+    ## Example
+
+    ```rust
+    use fitsio::headers::is_structural_key;
+    # use fitsio::images::{ImageDescription, ImageType};
+
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let filename = "../testdata/full_example.fits";
+    # let mut src_fptr = fitsio::FitsFile::open(filename)?;
+    #
+    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+    # let tdir_path = tdir.path();
+    # let filename = tdir_path.join("test.fits");
+    # let mut dest_fptr = fitsio::FitsFile::create(filename).open()?;
+    # let description = ImageDescription {
+    #     data_type: ImageType::Float,
+    #     dimensions: &[100, 100],
+    # };
+    # let dest_hdu = dest_fptr.create_image("SCI".to_string(), &description)?;
+    let src_hdu = src_fptr.primary_hdu()?;
+    src_hdu.copy_header_filtered(&mut src_fptr, &dest_hdu, &mut dest_fptr, |name| {
+        !is_structural_key(name)
+    })?;
+
+    let value: i64 = dest_hdu.read_key(&mut dest_fptr, "INTTEST")?;
+    assert_eq!(value, 42);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn copy_header_filtered<F>(
+        &self,
+        src_fits_file: &mut FitsFile,
+        dest_hdu: &FitsHdu,
+        dest_fits_file: &mut FitsFile,
+        mut filter: F,
+    ) -> Result<()>
+    where
+        F: FnMut(&str) -> bool,
+    {
+        src_fits_file.make_current(self)?;
+        let records = read_records(src_fits_file)?;
+
+        fits_check_readwrite!(dest_fits_file);
+        dest_fits_file.make_current(dest_hdu)?;
+        for (name, card) in records {
+            if filter(&name) {
+                write_record(dest_fits_file, &card)?;
+            }
+        }
+
+        // Copied cards such as BSCALE and BLANK change how the data are read
+        let mut status = 0;
+        unsafe {
+            fits_set_hdustruc(dest_fits_file.fptr.as_mut() as *mut _, &mut status);
+        }
+        check_status(status)
+    }
+
     /**
     Insert a column into a fits table
 
@@ -624,6 +2548,95 @@ impl FitsHdu {
         check_status(status).and_then(|_| fits_file.current_hdu())
     }
 
+    /**
+    Get the data type, repeat count and width of a column
+
+    The column can be identified by id or name. The data type is the one values are
+    converted to on reading once `TSCALn` and `TZEROn` are applied, so for example a column
+    of unsigned 16 bit integers, stored as signed integers with an offset, is reported as
+    [`UnsignedShort`](../tables/enum.ColumnDataType.html#variant.UnsignedShort).
+
+    ## Example
+
+    ```rust
+    use fitsio::tables::ColumnDataType;
+
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let filename = "../testdata/full_example.fits";
+    # let mut fptr = fitsio::FitsFile::open(filename)?;
+    let hdu = fptr.hdu("TESTEXT")?;
+    let description = hdu.column_type(&mut fptr, "intcol")?;
+    assert_eq!(description.typ, ColumnDataType::Int);
+    assert_eq!(description.repeat, 1);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn column_type<T: DescribesColumnLocation>(
+        &self,
+        fits_file: &mut FitsFile,
+        col_identifier: T,
+    ) -> Result<ColumnDataDescription> {
+        fits_file.make_current(self)?;
+
+        let colno = T::get_column_no(&col_identifier, self, fits_file)?;
+        let mut typecode = 0;
+        let mut repeat = 0;
+        let mut width = 0;
+        let mut status = 0;
+
+        unsafe {
+            fits_get_eqcoltypell(
+                fits_file.fptr.as_mut() as *mut _,
+                (colno + 1) as _,
+                &mut typecode,
+                &mut repeat,
+                &mut width,
+                &mut status,
+            );
+        }
+        check_status(status)?;
+
+        let typ = ColumnDataType::from_typecode(typecode)?;
+        let mut description = ColumnDataDescription::new(typ, repeat as _, width as _);
+        if typecode < 0 {
+            let tform: String = self.read_key(fits_file, &format!("TFORM{}", colno + 1))?;
+            description.descriptor = tform.parse::<ColumnDataDescription>()?.descriptor;
+        }
+        Ok(description)
+    }
+
+    /**
+    Width in characters that values of a column are displayed with
+
+    This comes from the `TDISPn` keyword if present, and otherwise from the column type. For
+    string columns it may differ from the width each value is stored with, which is the
+    [`width`](../tables/struct.ColumnDataDescription.html#structfield.width) returned by
+    [`column_type`](#method.column_type). Strings are always read at their stored width.
+
+    ## Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let filename = "../testdata/full_example.fits";
+    # let mut fptr = fitsio::FitsFile::open(filename)?;
+    let hdu = fptr.hdu("TESTEXT")?;
+    assert_eq!(hdu.column_display_width(&mut fptr, "strcol")?, 7);
+    assert_eq!(hdu.column_type(&mut fptr, "strcol")?.width, 7);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn column_display_width<T: DescribesColumnLocation>(
+        &self,
+        fits_file: &mut FitsFile,
+        col_identifier: T,
+    ) -> Result<usize> {
+        fits_file.make_current(self)?;
+        let colno = T::get_column_no(&col_identifier, self, fits_file)?;
+        column_display_width(fits_file, colno as _)
+    }
+
     /**
     Return the index for a given column.
 
@@ -657,9 +2670,10 @@ impl FitsHdu {
     }
 
     /**
-    Read a subset of a fits column
+    Read a whole fits column
 
-    The range is exclusive of the upper value
+    The column can be read into any [`ColumnSink`](../tables/trait.ColumnSink.html), for
+    example a `Vec`, or an `ndarray::Array1` with the `array` feature.
 
     ## Example
 
@@ -678,17 +2692,170 @@ impl FitsHdu {
     #         .create()?,
     # ];
     # let hdu = fptr.create_table("foo".to_string(), &table_description)?;
-    let data_to_write: Vec<i32> = vec![10101; 10];
-    hdu.write_col_range(&mut fptr, "bar", &data_to_write, &(0..5))?;
+    let data_to_write: Vec<i32> = vec![10101; 5];
+    hdu.write_col_range(&mut fptr, "bar", &data_to_write, 0..5)?;
     let data: Vec<i32> = hdu.read_col(&mut fptr, "bar")?;
     assert_eq!(data, vec![10101, 10101, 10101, 10101, 10101]);
     # Ok(())
     # }
     ```
     */
-    pub fn read_col<T: ReadsCol>(&self, fits_file: &mut FitsFile, name: &str) -> Result<Vec<T>> {
+    pub fn read_col<S: ColumnSink>(&self, fits_file: &mut FitsFile, name: &str) -> Result<S> {
         fits_file.make_current(self)?;
-        T::read_col(fits_file, name)
+        S::read_col(fits_file, name)
+    }
+
+    /**
+    Read a whole string column, replacing any characters which are not valid UTF-8
+
+    Reading a column as `String`s returns an
+    [`Error::InvalidUtf8`](../errors/enum.Error.html#variant.InvalidUtf8) holding the raw bytes
+    of the first invalid value. This instead replaces invalid characters with `U+FFFD`.
+
+    # Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let filename = "../testdata/full_example.fits";
+    # let mut fptr = fitsio::FitsFile::open(filename)?;
+    # let hdu = fptr.hdu("TESTEXT")?;
+    let values = hdu.read_col_lossy(&mut fptr, "strcol")?;
+    assert_eq!(values[0], "value0");
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn read_col_lossy(&self, fits_file: &mut FitsFile, name: &str) -> Result<Vec<String>> {
+        let num_rows = self.num_rows(fits_file)?;
+        Ok(read_string_col_bytes(fits_file, name, &(0..num_rows))?
+            .iter()
+            .map(|bytes| String::from_utf8_lossy(bytes).into_owned())
+            .collect())
+    }
+
+    /**
+    Read a whole string column, choosing how trailing blanks are handled
+
+    FITS pads string cells with blanks, which `cfitsio` (and so
+    [`read_col`](#method.read_col)) removes. With
+    [`ReadStringMode::Raw`](../tables/enum.ReadStringMode.html#variant.Raw) each value is
+    returned as stored instead, up to the first nul byte or the width of the column.
+
+    # Example
+
+    ```rust
+    use fitsio::tables::{ColumnDataType, ColumnDescription, ReadStringMode, WriteStringMode};
+
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+    # let filename = tdir.path().join("test.fits");
+    # let mut fptr = fitsio::FitsFile::create(filename).open()?;
+    let column = ColumnDescription::new("NAME")
+        .with_type(ColumnDataType::String)
+        .that_repeats(8)
+        .create()?;
+    let hdu = fptr.create_table("DATA", &[column])?;
+    let values = ["ab  ", "cd"];
+    let hdu = hdu.write_string_col(&mut fptr, "NAME", &values, WriteStringMode::NulTerminated)?;
+
+    let trimmed = hdu.read_string_col(&mut fptr, "NAME", ReadStringMode::Trimmed)?;
+    assert_eq!(trimmed, vec!["ab", "cd"]);
+    let raw = hdu.read_string_col(&mut fptr, "NAME", ReadStringMode::Raw)?;
+    assert_eq!(raw, vec!["ab  ", "cd"]);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn read_string_col(
+        &self,
+        fits_file: &mut FitsFile,
+        name: &str,
+        mode: ReadStringMode,
+    ) -> Result<Vec<String>> {
+        let bytes = self.string_column_bytes(fits_file, name)?;
+        match mode {
+            ReadStringMode::Trimmed => {
+                // `cfitsio` leaves a single blank when a value is entirely blank
+                let values: Vec<String> = self.read_col(fits_file, name)?;
+                Ok(values
+                    .into_iter()
+                    .map(|value| value.trim_end_matches(' ').to_string())
+                    .collect())
+            }
+            ReadStringMode::Raw => {
+                let num_rows = self.num_rows(fits_file)?;
+                let mut values = Vec::with_capacity(num_rows);
+                for idx in 0..num_rows {
+                    let mut cell = read_table_bytes(fits_file, idx, &bytes)?;
+                    if let Some(end) = cell.iter().position(|b| *b == 0) {
+                        cell.truncate(end);
+                    }
+                    values.push(String::from_utf8(cell)?);
+                }
+                Ok(values)
+            }
+        }
+    }
+
+    /**
+    Write a whole string column, choosing how values shorter than the column are filled
+
+    As with [`write_col`](#method.write_col), an error is returned if any value is longer than
+    the column width. See [`read_string_col`](#method.read_string_col) for an example.
+    */
+    pub fn write_string_col<S: AsRef<str>>(
+        &self,
+        fits_file: &mut FitsFile,
+        name: &str,
+        values: &[S],
+        mode: WriteStringMode,
+    ) -> Result<FitsHdu> {
+        let bytes = self.string_column_bytes(fits_file, name)?;
+        let width = bytes.len();
+        if let Some(value) = values.iter().find(|value| value.as_ref().len() > width) {
+            return Err(Error::Message(format!(
+                "value {:?} is longer than the width {} of column {:?}",
+                value.as_ref(),
+                width,
+                name
+            )));
+        }
+
+        let strings: Vec<String> = values.iter().map(|v| v.as_ref().to_string()).collect();
+        let hdu = self.write_col(fits_file, name, &strings)?;
+        if mode == WriteStringMode::NulTerminated {
+            for (idx, value) in strings.iter().enumerate() {
+                if value.len() < width {
+                    let mut cell = value.as_bytes().to_vec();
+                    cell.resize(width, 0);
+                    write_table_bytes(fits_file, idx, bytes.start, &cell)?;
+                }
+            }
+        }
+        Ok(hdu)
+    }
+
+    /// The bytes of each row holding a scalar string column
+    fn string_column_bytes(&self, fits_file: &mut FitsFile, name: &str) -> Result<Range<usize>> {
+        let description = self.column_type(fits_file, name)?;
+        let is_string = matches!(
+            description.typ,
+            ColumnDataType::String | ColumnDataType::Text
+        );
+        if !is_string || description.descriptor.is_some() {
+            return Err(Error::Message(format!(
+                "column {:?} is not a fixed width string column",
+                name
+            )));
+        }
+        let bytes = self.column_bytes(fits_file, name)?;
+        if description.width != bytes.len() {
+            return Err(Error::Message(format!(
+                "column {:?} holds more than one string per row",
+                name
+            )));
+        }
+        Ok(bytes)
     }
 
     /**
@@ -713,28 +2880,38 @@ impl FitsHdu {
     #         .create()?,
     # ];
     # let hdu = fptr.create_table("foo".to_string(), &table_description)?;
-    # let data_to_write: Vec<i32> = vec![10101; 10];
-    # hdu.write_col_range(&mut fptr, "bar", &data_to_write, &(0..5))?;
-    let data: Vec<i32> = hdu.read_col_range(&mut fptr, "bar", &(0..5))?;
+    # let data_to_write: Vec<i32> = vec![10101; 5];
+    # hdu.write_col_range(&mut fptr, "bar", &data_to_write, 0..5)?;
+    let data: Vec<i32> = hdu.read_col_range(&mut fptr, "bar", 0..5)?;
     assert_eq!(data, vec![10101, 10101, 10101, 10101, 10101]);
+    let data: Vec<i32> = hdu.read_col_range(&mut fptr, "bar", 3..=4)?;
+    assert_eq!(data, vec![10101, 10101]);
     # Ok(())
     # }
     ```
+
+    [`IndexRange`]: ../trait.IndexRange.html
     */
-    pub fn read_col_range<T: ReadsCol>(
+    pub fn read_col_range<T: ReadsCol, R: IndexRange>(
         &self,
         fits_file: &mut FitsFile,
         name: &str,
-        range: &Range<usize>,
+        range: R,
     ) -> Result<Vec<T>> {
-        fits_file.make_current(self)?;
-        T::read_col_range(fits_file, name, range)
+        let range = range.to_range(self.num_rows(fits_file)?);
+        T::read_col_range(fits_file, name, &range)
     }
 
     /**
     Write data to part of a column
 
-    The range is exclusive of the upper value
+    Any of the standard range types can be given (see [`IndexRange`]), for example `0..5`
+    (exclusive of the upper value) or `0..=4` (inclusive). A range without an upper bound, such
+    as `10..`, covers as many rows as there are elements in `col_data`. The table is extended if
+    the range runs past its end.
+
+    The length of `col_data` must match the number of rows in the range, otherwise an
+    [`IndexError`] is returned and nothing is written.
 
     ## Example
 
@@ -753,24 +2930,127 @@ impl FitsHdu {
     #         .create()?,
     # ];
     # let hdu = fptr.create_table("foo".to_string(), &table_description)?;
-    let data_to_write: Vec<i32> = vec![10101; 10];
-    hdu.write_col_range(&mut fptr, "bar", &data_to_write, &(0..5))?;
+    let data_to_write: Vec<i32> = vec![10101; 5];
+    hdu.write_col_range(&mut fptr, "bar", &data_to_write, 0..5)?;
     # let data: Vec<i32> = hdu.read_col(&mut fptr, "bar")?;
     # assert_eq!(data, vec![10101, 10101, 10101, 10101, 10101]);
+
+    // Too much data for the range
+    assert!(hdu.write_col_range(&mut fptr, "bar", &[1, 2, 3], 0..=1).is_err());
     # Ok(())
     # }
     ```
+
+    [`IndexRange`]: ../trait.IndexRange.html
+    [`IndexError`]: ../errors/struct.IndexError.html
     */
-    pub fn write_col_range<T: WritesCol, N: Into<String>>(
+    pub fn write_col_range<T: WritesCol, N: Into<String>, R: IndexRange>(
         &self,
         fits_file: &mut FitsFile,
         name: N,
         col_data: &[T],
-        rows: &Range<usize>,
+        rows: R,
+    ) -> Result<FitsHdu> {
+        fits_file.make_current(self)?;
+        fits_check_readwrite!(fits_file);
+
+        // An open-ended range covers all of the data, from its first row
+        let start = rows.to_range(0).start;
+        let rows = rows.to_range(start + col_data.len());
+        if rows.end < rows.start || rows.end - rows.start != col_data.len() {
+            return Err(IndexError {
+                message: format!(
+                    "{} values given to write to {} rows",
+                    col_data.len(),
+                    rows.end.saturating_sub(rows.start)
+                ),
+                given: rows,
+            }
+            .into());
+        }
+        T::write_col_range(fits_file, self, name, col_data, &rows)
+    }
+
+    /**
+    Write the same rows of several columns in one call
+
+    The slices in `columns` are written to the first columns of the table, in order, starting at
+    the zero-indexed row `start_row`. Every slice must have the same length, and must not be
+    empty. As with [`write_col_range`](#method.write_col_range), the table is extended if the
+    rows run past its end, so this can append a chunk of rows to a growing table.
+
+    ## Example
+
+    ```rust
+    use fitsio::tables::{ColumnDataType, ColumnDescription};
+
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+    # let filename = tdir.path().join("test.fits");
+    # let mut fptr = fitsio::FitsFile::create(filename).open()?;
+    let columns = &[
+        ColumnDescription::new("TIME").with_type(ColumnDataType::Double).create()?,
+        ColumnDescription::new("COUNTS").with_type(ColumnDataType::Int).create()?,
+        ColumnDescription::new("STATE")
+            .with_type(ColumnDataType::String)
+            .that_repeats(8)
+            .create()?,
+    ];
+    let hdu = fptr.create_table("TELEMETRY", columns)?;
+
+    let times = [0.0, 0.5, 1.0];
+    let counts = [10, 12, 9];
+    let states = vec!["ok".to_string(), "ok".to_string(), "slew".to_string()];
+    let hdu = hdu.write_rows_chunk(&mut fptr, 0, (&times[..], &counts[..], &states[..]))?;
+    let hdu = hdu.write_rows_chunk(&mut fptr, 3, (&times[..1], &counts[..1], &states[..1]))?;
+    assert_eq!(hdu.num_rows(&mut fptr)?, 4);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn write_rows_chunk<C: RowsChunk>(
+        &self,
+        fits_file: &mut FitsFile,
+        start_row: usize,
+        columns: C,
     ) -> Result<FitsHdu> {
         fits_file.make_current(self)?;
         fits_check_readwrite!(fits_file);
-        T::write_col_range(fits_file, self, name, col_data, rows)
+
+        let names: Vec<String> = match self.info {
+            HduInfo::TableInfo {
+                ref column_descriptions,
+                ..
+            } => column_descriptions.iter().map(|c| c.name.clone()).collect(),
+            _ => return Err("cannot write rows to a non-table hdu".into()),
+        };
+        let lengths = columns.column_lengths();
+        if lengths.len() > names.len() {
+            return Err(Error::Message(format!(
+                "{} columns given, but the table only has {}",
+                lengths.len(),
+                names.len()
+            )));
+        }
+        let num_rows = lengths[0];
+        let rows = start_row..start_row + num_rows;
+        if lengths.iter().any(|&len| len != num_rows) {
+            return Err(IndexError {
+                message: format!("columns given with different lengths {:?}", lengths),
+                given: rows,
+            }
+            .into());
+        }
+        if num_rows == 0 {
+            return Err(IndexError {
+                message: "no rows given to write".to_string(),
+                given: rows,
+            }
+            .into());
+        }
+
+        columns.write_chunk(fits_file, self, &names, &rows)?;
+        fits_file.current_hdu()
     }
 
     /**
@@ -818,6 +3098,301 @@ impl FitsHdu {
         T::write_col(fits_file, self, name, col_data)
     }
 
+    /**
+    Sort the rows of a table by the values of one or more columns
+
+    Rows are ordered by the first column, then rows with equal values in the first column by
+    the second column and so on. The sort is stable, so rows with equal values in every column
+    keep their original order. NaN values sort after every other value.
+
+    The whole table is read into memory and rewritten in the new order. The key columns must
+    be scalar, or string, columns.
+
+    ## Example
+
+    ```rust
+    use fitsio::tables::{ColumnDataType, ColumnDescription, Order};
+
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+    # let tdir_path = tdir.path();
+    # let filename = tdir_path.join("test.fits");
+    # let mut fptr = fitsio::FitsFile::create(filename).open()?;
+    # let table_description = vec![
+    #     ColumnDescription::new("MJD")
+    #         .with_type(ColumnDataType::Double)
+    #         .create()?,
+    #     ColumnDescription::new("ID")
+    #         .with_type(ColumnDataType::Int)
+    #         .create()?,
+    # ];
+    # let hdu = fptr.create_table("DATA", &table_description)?;
+    hdu.write_col(&mut fptr, "MJD", &[59001.5, 59000.5, 59001.5])?;
+    hdu.write_col(&mut fptr, "ID", &[2, 3, 1])?;
+
+    hdu.sort_rows(&mut fptr, &["MJD", "ID"], Order::Ascending)?;
+    let ids: Vec<i32> = hdu.read_col(&mut fptr, "ID")?;
+    assert_eq!(ids, vec![3, 1, 2]);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn sort_rows(
+        &self,
+        fits_file: &mut FitsFile,
+        columns: &[&str],
+        order: Order,
+    ) -> Result<()> {
+        fits_file.make_current(self)?;
+        fits_check_readwrite!(fits_file);
+
+        if columns.is_empty() {
+            return Err("no columns given to sort rows by".into());
+        }
+
+        let keys = columns
+            .iter()
+            .map(|name| SortKey::read(self, fits_file, name))
+            .collect::<Result<Vec<_>>>()?;
+
+        let num_rows = self.num_rows(fits_file)?;
+        let mut indices: Vec<usize> = (0..num_rows).collect();
+        indices.sort_by(|&a, &b| {
+            let ordering = keys
+                .iter()
+                .map(|key| key.compare(a, b))
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or(std::cmp::Ordering::Equal);
+            match order {
+                Order::Ascending => ordering,
+                Order::Descending => ordering.reverse(),
+            }
+        });
+        if indices.iter().enumerate().all(|(i, &idx)| i == idx) {
+            return Ok(());
+        }
+
+        let row_width: i64 = self.read_key(fits_file, "NAXIS1")?;
+        let row_width = row_width as usize;
+        let table = read_table_bytes(fits_file, 0, &(0..num_rows * row_width))?;
+        let mut sorted = Vec::with_capacity(table.len());
+        for idx in indices {
+            sorted.extend_from_slice(&table[idx * row_width..(idx + 1) * row_width]);
+        }
+        write_table_bytes(fits_file, 0, 0, &sorted)
+    }
+
+    /**
+    Append the rows of another table to this table
+
+    The rows of `other_hdu`, in `other_fits_file`, are copied in chunks onto the end of this
+    table without being converted, so both tables must be binary tables with the same columns:
+    matching names, `TFORMn`, `TSCALn` and `TZEROn` values, in the same order. Tables with
+    variable length array columns are not supported, as their data lives in a separate heap.
+
+    ## Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+    # let tdir_path = tdir.path();
+    # let filename = tdir_path.join("test.fits");
+    # std::fs::copy("../testdata/full_example.fits", &filename)?;
+    # let mut fptr = fitsio::FitsFile::edit(&filename)?;
+    # let mut other_fptr = fitsio::FitsFile::open("../testdata/full_example.fits")?;
+    let hdu = fptr.hdu("TESTEXT")?;
+    let other_hdu = other_fptr.hdu("TESTEXT")?;
+    let hdu = hdu.append_table(&mut fptr, &mut other_fptr, &other_hdu)?;
+    assert_eq!(hdu.num_rows(&mut fptr)?, 100);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn append_table(
+        &self,
+        fits_file: &mut FitsFile,
+        other_fits_file: &mut FitsFile,
+        other_hdu: &FitsHdu,
+    ) -> Result<FitsHdu> {
+        fits_file.make_current(self)?;
+        fits_check_readwrite!(fits_file);
+
+        let schema = self.table_schema(fits_file)?;
+        let other_schema = other_hdu.table_schema(other_fits_file)?;
+        if schema.len() != other_schema.len() {
+            return Err(format!(
+                "cannot append a table with {} columns to a table with {} columns",
+                other_schema.len(),
+                schema.len()
+            )
+            .as_str()
+            .into());
+        }
+        for (column, other_column) in schema.iter().zip(&other_schema) {
+            if column != other_column {
+                return Err(format!(
+                    "cannot append table, column {:?} does not match column {:?}",
+                    other_column.0, column.0
+                )
+                .as_str()
+                .into());
+            }
+        }
+
+        let row_width: i64 = self.read_key(fits_file, "NAXIS1")?;
+        let row_width = row_width as usize;
+        let num_rows = self.num_rows(fits_file)?;
+        let num_other_rows = other_hdu.num_rows(other_fits_file)?;
+
+        let mut status = 0;
+        unsafe {
+            fits_insert_rows(
+                fits_file.fptr.as_mut() as *mut _,
+                num_rows as _,
+                num_other_rows as _,
+                &mut status,
+            );
+        }
+        check_status(status)?;
+
+        let chunk_rows = (APPEND_CHUNK_BYTES / row_width.max(1)).max(1);
+        let mut row = 0;
+        while row < num_other_rows {
+            let nrows = chunk_rows.min(num_other_rows - row);
+            other_fits_file.make_current(other_hdu)?;
+            let bytes = read_table_bytes(other_fits_file, row, &(0..nrows * row_width))?;
+            fits_file.make_current(self)?;
+            write_table_bytes(fits_file, num_rows + row, 0, &bytes)?;
+            row += nrows;
+        }
+
+        fits_file.current_hdu()
+    }
+
+    /// The name, format, scale and offset of every column, for checking tables match
+    #[allow(clippy::type_complexity)]
+    fn table_schema(
+        &self,
+        fits_file: &mut FitsFile,
+    ) -> Result<Vec<(String, ColumnDataDescription, Option<f64>, Option<f64>)>> {
+        fits_file.make_current(self)?;
+        let xtension: String = self.read_key(fits_file, "XTENSION")?;
+        if xtension.trim() != "BINTABLE" {
+            return Err("can only append binary tables".into());
+        }
+
+        let num_columns: i64 = self.read_key(fits_file, "TFIELDS")?;
+        let mut schema = Vec::with_capacity(num_columns as usize);
+        for i in 1..=num_columns {
+            let name: String = self.read_key(fits_file, &format!("TTYPE{}", i))?;
+            let tform: String = self.read_key(fits_file, &format!("TFORM{}", i))?;
+            let description = tform.parse::<ColumnDataDescription>()?;
+            if description.descriptor.is_some() {
+                return Err(format!(
+                    "cannot append tables with variable length array column {:?}",
+                    name
+                )
+                .as_str()
+                .into());
+            }
+            let scale = read_key_optional(fits_file, &format!("TSCAL{}", i))?;
+            let zero = read_key_optional(fits_file, &format!("TZERO{}", i))?;
+            schema.push((name.trim().to_uppercase(), description, scale, zero));
+        }
+        Ok(schema)
+    }
+
+    /**
+    Compute summary statistics of a numeric column
+
+    The column is read in chunks, so the whole column is never held in memory. Undefined values,
+    equal to the column's `TNULLn` value or NaN, are skipped, and every value of vector columns
+    is included. To compute the statistics of a subset of rows, open the file with a `cfitsio`
+    row filter, e.g. `"catalogue.fits[EVENTS][FLUX > 0]"`.
+
+    ## Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let filename = "../testdata/full_example.fits";
+    # let mut fptr = fitsio::FitsFile::open(filename)?;
+    let hdu = fptr.hdu("TESTEXT")?;
+    let stats = hdu.column_stats::<f64>(&mut fptr, "intcol")?;
+    assert_eq!(stats.count, 50);
+    println!("{} <= intcol <= {}", stats.min, stats.max);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn column_stats<T>(&self, fits_file: &mut FitsFile, name: &str) -> Result<ColumnStats<T>>
+    where
+        T: Copy + PartialOrd,
+        Option<T>: ReadsCol,
+        f64: From<T>,
+    {
+        self.column_stats_chunked(fits_file, name, STATS_CHUNK_VALUES)
+    }
+
+    fn column_stats_chunked<T>(
+        &self,
+        fits_file: &mut FitsFile,
+        name: &str,
+        chunk_values: usize,
+    ) -> Result<ColumnStats<T>>
+    where
+        T: Copy + PartialOrd,
+        Option<T>: ReadsCol,
+        f64: From<T>,
+    {
+        fits_file.make_current(self)?;
+        let repeat = self.column_type(fits_file, name)?.repeat.max(1);
+        let num_rows = self.num_rows(fits_file)?;
+        let chunk_rows = (chunk_values / repeat).max(1);
+
+        let mut range: Option<(T, T)> = None;
+        let mut count = 0;
+        let mut mean = 0.0;
+        let mut sum_squares = 0.0;
+        let mut row = 0;
+        while row < num_rows {
+            let nrows = chunk_rows.min(num_rows - row);
+            // Reading starts at the first value of `row`, and continues across rows for the
+            // number of values requested, so this covers every value of vector columns
+            let values =
+                Option::<T>::read_col_range(fits_file, name, &(row..row + nrows * repeat))?;
+            for value in values.into_iter().flatten() {
+                range = match range {
+                    Some((min, max)) => Some((
+                        if value < min { value } else { min },
+                        if value > max { value } else { max },
+                    )),
+                    None => Some((value, value)),
+                };
+
+                // Welford's online algorithm
+                count += 1;
+                let value = f64::from(value);
+                let delta = value - mean;
+                mean += delta / count as f64;
+                sum_squares += delta * (value - mean);
+            }
+            row += nrows;
+        }
+
+        match range {
+            Some((min, max)) => Ok(ColumnStats {
+                count,
+                min,
+                max,
+                mean,
+                std: (sum_squares / count as f64).sqrt(),
+            }),
+            None => Err(format!("column {:?} has no defined values", name)
+                .as_str()
+                .into()),
+        }
+    }
+
     /**
     Iterate over the columns in a fits file
 
@@ -828,20 +3403,236 @@ impl FitsHdu {
     # let filename = "../testdata/full_example.fits";
     # let mut fptr = fitsio::FitsFile::open(filename)?;
     # let hdu = fptr.hdu("TESTEXT")?;
-    for column in hdu.columns(&mut fptr) {
+    for column in hdu.columns(&mut fptr)? {
+        let column = column?;
         // Do something with column
     }
     # Ok(())
     # }
     ```
     */
-    pub fn columns<'a>(&self, fits_file: &'a mut FitsFile) -> ColumnIterator<'a> {
-        fits_file
-            .make_current(self)
-            .expect("Cannot make hdu current");
+    pub fn columns<'a>(&self, fits_file: &'a mut FitsFile) -> Result<ColumnIterator<'a>> {
+        fits_file.make_current(self)?;
         ColumnIterator::new(fits_file)
     }
 
+    /**
+    Read a window of rows from several columns at once
+
+    The returned [`TableQuery`] selects the columns and rows to read, and reads them with a
+    single move to this HDU. Each column is returned with the type given by its header.
+
+    ## Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let filename = "../testdata/full_example.fits";
+    # let mut fptr = fitsio::FitsFile::open(filename)?;
+    use fitsio::tables::Column;
+
+    let hdu = fptr.hdu("TESTEXT")?;
+    let page = hdu
+        .table_query(&mut fptr)
+        .columns(&["intcol", "floatcol"])
+        .rows(40..60)
+        .read()?;
+
+    // Only 50 rows in the table
+    assert_eq!(page.num_rows(), 10);
+    match page.column("intcol") {
+        Some(Column::Int32 { data, .. }) => assert_eq!(data.len(), 10),
+        _ => unreachable!(),
+    }
+    # Ok(())
+    # }
+    ```
+
+    [`TableQuery`]: ../tables/struct.TableQuery.html
+    */
+    pub fn table_query<'a>(&'a self, fits_file: &'a mut FitsFile) -> TableQuery<'a> {
+        TableQuery::new(self, fits_file)
+    }
+
+    /**
+    Append rows to the end of a table, buffering them in memory and writing them in chunks
+
+    Each row is a [`FitsRow`](../tables/trait.FitsRow.html), usually derived with
+    `fitsio-derive`, whose fields are written to the columns of the same name. See
+    [`TableAppender`] for details.
+
+    ## Example
+
+    ```rust
+    use fitsio::tables::{ColumnDataType, ColumnDescription, FitsRow};
+    use fitsio_derive::FitsRow;
+
+    #[derive(Default, FitsRow)]
+    #[fitsio(writable)]
+    struct Event {
+        time: f64,
+        energy: i32,
+        detector: String,
+    }
+
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+    # let filename = tdir.path().join("test.fits");
+    # let mut fptr = fitsio::FitsFile::create(filename).open()?;
+    # let columns = &[
+    #     ColumnDescription::new("time").with_type(ColumnDataType::Double).create()?,
+    #     ColumnDescription::new("energy").with_type(ColumnDataType::Int).create()?,
+    #     ColumnDescription::new("detector")
+    #         .with_type(ColumnDataType::String)
+    #         .that_repeats(8)
+    #         .create()?,
+    # ];
+    let hdu = fptr.create_table("EVENTS", columns)?;
+
+    let mut appender = hdu.table_appender(&mut fptr)?;
+    for i in 0..1000 {
+        appender.append_row(&Event {
+            time: i as f64 * 0.1,
+            energy: 100 + i,
+            detector: format!("det{}", i % 4),
+        })?;
+    }
+    let hdu = appender.finish()?;
+    assert_eq!(hdu.num_rows(&mut fptr)?, 1000);
+    # Ok(())
+    # }
+    ```
+
+    [`TableAppender`]: ../tables/struct.TableAppender.html
+    */
+    pub fn table_appender<'a>(&self, fits_file: &'a mut FitsFile) -> Result<TableAppender<'a>> {
+        fits_file.make_current(self)?;
+        fits_check_readwrite!(fits_file);
+        let hdu = self.refresh(fits_file)?;
+        TableAppender::new(fits_file, hdu)
+    }
+
+    /**
+    Cache chunks of the table's columns for repeated reads of nearby cells
+
+    This is much faster than [`read_cell_value`](#method.read_cell_value) and
+    [`row`](#method.row) for random access to a table. See [`TableCache`] for details.
+
+    ## Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let filename = "../testdata/full_example.fits";
+    # let mut fptr = fitsio::FitsFile::open(filename)?;
+    let hdu = fptr.hdu("TESTEXT")?;
+    let mut cache = hdu.table_cache(&mut fptr)?;
+    let mut total = 0;
+    for idx in [40, 4, 41] {
+        total += cache.read_cell_value::<i32>("intcol", idx)?;
+    }
+    let row: (i32, f32) = cache.row(4)?;
+    assert_eq!(row.0, 16);
+    # Ok(())
+    # }
+    ```
+
+    [`TableCache`]: ../tables/struct.TableCache.html
+    */
+    pub fn table_cache<'a>(&self, fits_file: &'a mut FitsFile) -> Result<TableCache<'a>> {
+        fits_file.make_current(self)?;
+        let hdu = self.refresh(fits_file)?;
+        TableCache::new(fits_file, hdu)
+    }
+
+    /**
+    Process table columns in chunks using the `cfitsio` iterator engine
+
+    `cfitsio` reads the requested columns in chunks of `chunk_size` rows (or an optimal number of
+    rows if `chunk_size` is 0), converting all values to `f64`, and calls `f` with each chunk.
+    Columns marked as written are saved to the file after each call. Returning an error from
+    `f` stops the iteration, and the error is returned.
+
+    # Example
+
+    ```rust
+    use fitsio::tables::IteratorColumn;
+
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let filename = "../testdata/full_example.fits";
+    # let mut fptr = fitsio::FitsFile::open(filename)?;
+    # let hdu = fptr.hdu("TESTEXT")?;
+    let columns = [IteratorColumn::read("intcol"), IteratorColumn::read("floatcol")];
+    let mut total = 0.0;
+    hdu.iterate_columns(&mut fptr, &columns, 10, |chunk| {
+        for (a, b) in chunk.column(0).iter().zip(chunk.column(1)) {
+            total += a * b;
+        }
+        Ok(())
+    })?;
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn iterate_columns<F>(
+        &self,
+        fits_file: &mut FitsFile,
+        columns: &[IteratorColumn],
+        chunk_size: usize,
+        mut f: F,
+    ) -> Result<()>
+    where
+        F: FnMut(&mut IteratorChunk) -> Result<()>,
+    {
+        fits_file.make_current(self)?;
+        if let HduInfo::ImageInfo { .. } = self.info {
+            return Err("cannot iterate over columns of an image hdu".into());
+        }
+        if columns.iter().any(|c| c.mode != IteratorColumnMode::Read) {
+            fits_check_readwrite!(fits_file);
+        }
+
+        let mut iterator_columns = Vec::with_capacity(columns.len());
+        for column in columns {
+            let colno = self.get_column_no(fits_file, column.name)?;
+            let iotype = match column.mode {
+                IteratorColumnMode::Read => crate::sys::InputCol,
+                IteratorColumnMode::ReadWrite => crate::sys::InputOutputCol,
+                IteratorColumnMode::Write => crate::sys::OutputCol,
+            };
+            let mut iterator_column: crate::sys::iteratorCol = unsafe { std::mem::zeroed() };
+            iterator_column.fptr = fits_file.fptr.as_ptr();
+            iterator_column.colnum = (colno + 1) as _;
+            iterator_column.datatype = DataType::TDOUBLE.into();
+            iterator_column.iotype = iotype as _;
+            iterator_columns.push(iterator_column);
+        }
+
+        let mut state = IteratorState {
+            callback: &mut f,
+            error: None,
+            panic: None,
+        };
+        let mut status = 0;
+        unsafe {
+            fits_iterate_data(
+                iterator_columns.len() as _,
+                iterator_columns.as_mut_ptr(),
+                0,
+                chunk_size as _,
+                Some(iterator_work_fn),
+                &mut state as *mut IteratorState as *mut _,
+                &mut status,
+            );
+        }
+
+        if let Some(payload) = state.panic {
+            std::panic::resume_unwind(payload);
+        }
+        if let Some(e) = state.error {
+            return Err(e);
+        }
+        check_status(status)
+    }
+
     /**
     Delete the current HDU from the fits file.
 
@@ -878,7 +3669,9 @@ impl FitsHdu {
         unsafe {
             fits_delete_hdu(fits_file.fptr.as_mut() as *mut _, &mut curhdu, &mut status);
         }
-        check_status(status).map(|_| ())
+        check_status(status)?;
+        fits_file.renumbered_hdus.push(self.number);
+        Ok(())
     }
 
     /**
@@ -910,6 +3703,103 @@ impl FitsHdu {
         T::read_cell_value(fits_file, name, idx)
     }
 
+    /**
+    Read the raw bytes of a single table cell
+
+    The bytes are returned exactly as stored in the file, without any conversion, byte
+    swapping or scaling, so columns of types `fitsio` does not support can still be read. The
+    bytes of a variable length array cell are its descriptor, not the array values.
+
+    ## Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let filename = "../testdata/full_example.fits";
+    # let mut f = fitsio::FitsFile::open(filename)?;
+    # let tbl_hdu = f.hdu("TESTEXT")?;
+    let bytes = tbl_hdu.read_cell_bytes(&mut f, "intcol", 4)?;
+    assert_eq!(bytes, 16i32.to_be_bytes());
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn read_cell_bytes(
+        &self,
+        fits_file: &mut FitsFile,
+        name: &str,
+        idx: usize,
+    ) -> Result<Vec<u8>> {
+        fits_file.make_current(self)?;
+        let bytes = self.column_bytes(fits_file, name)?;
+        read_table_bytes(fits_file, idx, &bytes)
+    }
+
+    /**
+    Read the raw bytes of every cell in a column
+
+    The bytes of each cell are concatenated in row order, see
+    [`read_cell_bytes`](#method.read_cell_bytes).
+
+    ## Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let filename = "../testdata/full_example.fits";
+    # let mut f = fitsio::FitsFile::open(filename)?;
+    # let tbl_hdu = f.hdu("TESTEXT")?;
+    let bytes = tbl_hdu.read_col_bytes(&mut f, "intcol")?;
+    let values: Vec<i32> = bytes
+        .chunks(4)
+        .map(|chunk| i32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
+        .collect();
+    assert_eq!(values, tbl_hdu.read_col::<Vec<i32>>(&mut f, "intcol")?);
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn read_col_bytes(&self, fits_file: &mut FitsFile, name: &str) -> Result<Vec<u8>> {
+        fits_file.make_current(self)?;
+        let bytes = self.column_bytes(fits_file, name)?;
+        let num_rows = match fits_file.fetch_hdu_info()? {
+            HduInfo::TableInfo { num_rows, .. } => num_rows,
+            _ => return Err("cannot read column bytes from a non-table hdu".into()),
+        };
+
+        let mut out = Vec::with_capacity(num_rows * bytes.len());
+        for idx in 0..num_rows {
+            out.extend(read_table_bytes(fits_file, idx, &bytes)?);
+        }
+        Ok(out)
+    }
+
+    /// The range of bytes a column occupies within each table row
+    fn column_bytes(&self, fits_file: &mut FitsFile, name: &str) -> Result<Range<usize>> {
+        let colno = self.get_column_no(fits_file, name)? + 1;
+        let xtension: String = self.read_key(fits_file, "XTENSION")?;
+
+        if xtension.trim() == "TABLE" {
+            let tbcol: i64 = self.read_key(fits_file, &format!("TBCOL{}", colno))?;
+            let tform: String = self.read_key(fits_file, &format!("TFORM{}", colno))?;
+            let width = tform
+                .trim()
+                .get(1..)
+                .and_then(|rest| rest.split('.').next())
+                .and_then(|width| width.parse::<usize>().ok())
+                .ok_or_else(|| Error::Message(format!("invalid TFORM value {:?}", tform)))?;
+            let start = (tbcol - 1) as usize;
+            return Ok(start..start + width);
+        }
+
+        let mut start = 0;
+        for i in 1..colno {
+            let tform: String = self.read_key(fits_file, &format!("TFORM{}", i))?;
+            start += tform.parse::<ColumnDataDescription>()?.num_bytes();
+        }
+        let tform: String = self.read_key(fits_file, &format!("TFORM{}", colno))?;
+        let width = tform.parse::<ColumnDataDescription>()?.num_bytes();
+        Ok(start..start + width)
+    }
+
     /**
     Extract a single row from the file
 
@@ -936,20 +3826,517 @@ impl FitsHdu {
     # let mut f = fitsio::FitsFile::open(filename)?;
     # let hdu = f.hdu("TESTEXT")?;
 
-    // Pick the 4th row
-    let row: Row = hdu.row(&mut f, 4)?;
-    assert_eq!(row.intfoo, 16);
-    assert_eq!(row.foobar, "value4");
-    # Ok(())
-    # }
-    ```
-    */
-    pub fn row<F>(&self, fits_file: &mut FitsFile, idx: usize) -> Result<F>
-    where
-        F: FitsRow,
-    {
-        fits_file.make_current(self)?;
-        F::from_table(self, fits_file, idx)
+    // Pick the 4th row
+    let row: Row = hdu.row(&mut f, 4)?;
+    assert_eq!(row.intfoo, 16);
+    assert_eq!(row.foobar, "value4");
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn row<F>(&self, fits_file: &mut FitsFile, idx: usize) -> Result<F>
+    where
+        F: FitsRow,
+    {
+        fits_file.make_current(self)?;
+        F::from_table(self, fits_file, idx)
+    }
+
+    /**
+    Extract a range of rows from the file
+
+    Any of the standard range types can be given (see
+    [`IndexRange`](../ranges/trait.IndexRange.html)). Rows derived with
+    [`fitsio-derive`](https://docs.rs/fitsio-derive), and tuples, read each column once for the
+    whole range rather than one cell at a time, so this is much faster than calling
+    [`row`](#method.row) for each row. An [`IndexError`](../errors/struct.IndexError.html) is
+    returned, before anything is read, if the range is reversed or runs past the end of the
+    table.
+
+    # Example
+
+    ```rust
+    use fitsio::tables::FitsRow;
+    use fitsio_derive::FitsRow;
+
+    #[derive(Default, FitsRow)]
+    struct Row {
+        #[fitsio(colname = "intcol")]
+        intfoo: i32,
+        #[fitsio(colname = "strcol")]
+        foobar: String,
+    }
+    #
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let filename = "../testdata/full_example.fits";
+    # let mut f = fitsio::FitsFile::open(filename)?;
+    # let hdu = f.hdu("TESTEXT")?;
+
+    let rows: Vec<Row> = hdu.rows_range(&mut f, 2..5)?;
+    assert_eq!(rows.len(), 3);
+    assert_eq!(rows[2].intfoo, 16);
+    assert_eq!(rows[2].foobar, "value4");
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn rows_range<F, R>(&self, fits_file: &mut FitsFile, range: R) -> Result<Vec<F>>
+    where
+        F: FitsRow,
+        R: IndexRange,
+    {
+        fits_file.make_current(self)?;
+        let num_rows = self.num_rows(fits_file)?;
+        let range = range.to_range(num_rows);
+        if range.start > range.end || range.end > num_rows {
+            return Err(IndexError {
+                message: format!(
+                    "rows {:?} out of range for a table of {} rows",
+                    range, num_rows
+                ),
+                given: range,
+            }
+            .into());
+        }
+        F::from_table_range(self, fits_file, &range)
+    }
+}
+
+/**
+A [`FitsHdu`][fits-hdu] bound to the file it came from, returned by
+[`FitsFile::hdu_mut`][fits-file-hdu-mut]
+
+The methods mirror those of [`FitsHdu`][fits-hdu] without the file argument. Methods which do
+not need the file, such as [`shape`][fits-hdu-shape], are available through `Deref`. Writes which
+change the HDU structure update the bound HDU, so there is no need to call
+[`refresh`][fits-hdu-refresh].
+
+[fits-hdu]: struct.FitsHdu.html
+[fits-hdu-shape]: struct.FitsHdu.html#method.shape
+[fits-hdu-refresh]: struct.FitsHdu.html#method.refresh
+[fits-file-hdu-mut]: ../fitsfile/struct.FitsFile.html#method.hdu_mut
+*/
+pub struct BoundHdu<'a> {
+    fits_file: &'a mut FitsFile,
+    hdu: FitsHdu,
+}
+
+impl<'a> BoundHdu<'a> {
+    pub(crate) fn new(fits_file: &'a mut FitsFile, hdu: FitsHdu) -> Self {
+        BoundHdu { fits_file, hdu }
+    }
+
+    /// The file the HDU is bound to, for calls which this type does not wrap
+    pub fn file(&mut self) -> &mut FitsFile {
+        self.fits_file
+    }
+
+    /// Release the file, returning the unbound HDU
+    pub fn into_hdu(self) -> FitsHdu {
+        self.hdu
+    }
+
+    /// Re-read the HDU information from the file, see [`FitsHdu::refresh`](struct.FitsHdu.html#method.refresh)
+    pub fn refresh(&mut self) -> Result<()> {
+        self.hdu = self.hdu.refresh(self.fits_file)?;
+        Ok(())
+    }
+
+    /// Get the number of rows of a table, see [`FitsHdu::num_rows`](struct.FitsHdu.html#method.num_rows)
+    pub fn num_rows(&mut self) -> Result<usize> {
+        self.hdu.num_rows(self.fits_file)
+    }
+
+    /// Get the number of columns of a table, see [`FitsHdu::num_columns`](struct.FitsHdu.html#method.num_columns)
+    pub fn num_columns(&mut self) -> Result<usize> {
+        self.hdu.num_columns(self.fits_file)
+    }
+
+    /// Get the column names of a table, see [`FitsHdu::column_names`](struct.FitsHdu.html#method.column_names)
+    pub fn column_names(&mut self) -> Result<Vec<String>> {
+        self.hdu.column_names(self.fits_file)
+    }
+
+    /// Get the `EXTNAME` of the HDU, see [`FitsHdu::name`](struct.FitsHdu.html#method.name)
+    pub fn name(&mut self) -> Result<Option<String>> {
+        self.hdu.name(self.fits_file)
+    }
+
+    /// Set the `EXTNAME` of the HDU, see [`FitsHdu::set_name`](struct.FitsHdu.html#method.set_name)
+    pub fn set_name(&mut self, name: &str, extver: Option<i32>) -> Result<()> {
+        self.hdu.set_name(self.fits_file, name, extver)
+    }
+
+    /// Get the name, version and level of the HDU, see [`FitsHdu::identity`](struct.FitsHdu.html#method.identity)
+    pub fn identity(&mut self) -> Result<HduIdentity> {
+        self.hdu.identity(self.fits_file)
+    }
+
+    /// Read a header key, see [`FitsHdu::read_key`](struct.FitsHdu.html#method.read_key)
+    pub fn read_key<T: ReadsKey>(&mut self, name: &str) -> Result<T> {
+        self.hdu.read_key(self.fits_file, name)
+    }
+
+    /// Read a numeric header key, see [`FitsHdu::read_key_num`](struct.FitsHdu.html#method.read_key_num)
+    pub fn read_key_num<T: ReadsNumericKey>(&mut self, name: &str) -> Result<T> {
+        self.hdu.read_key_num(self.fits_file, name)
+    }
+
+    /// Read a matrix of header keys, see [`FitsHdu::read_key_matrix`](struct.FitsHdu.html#method.read_key_matrix)
+    pub fn read_key_matrix<T: ReadsKey>(
+        &mut self,
+        root: &str,
+        rows: usize,
+        cols: usize,
+    ) -> Result<Vec<Vec<T>>> {
+        self.hdu.read_key_matrix(self.fits_file, root, rows, cols)
+    }
+
+    /// Write a matrix of header keys, see [`FitsHdu::write_key_matrix`](struct.FitsHdu.html#method.write_key_matrix)
+    pub fn write_key_matrix<T: WritesKey + Clone, R: AsRef<[T]>>(
+        &mut self,
+        root: &str,
+        values: &[R],
+    ) -> Result<()> {
+        self.hdu.write_key_matrix(self.fits_file, root, values)
+    }
+
+    /// Read many header keys, see [`FitsHdu::read_keys`](struct.FitsHdu.html#method.read_keys)
+    pub fn read_keys<T: ReadsKey>(&mut self, names: &[&str]) -> Result<Vec<Result<T>>> {
+        self.hdu.read_keys(self.fits_file, names)
+    }
+
+    /// Write a header key, see [`FitsHdu::write_key`](struct.FitsHdu.html#method.write_key)
+    pub fn write_key<T: WritesKey>(&mut self, name: &str, value: T) -> Result<()> {
+        self.hdu.write_key(self.fits_file, name, value)
+    }
+
+    /// Rename a header key, see [`FitsHdu::rename_key`](struct.FitsHdu.html#method.rename_key)
+    pub fn rename_key(&mut self, old: &str, new: &str) -> Result<()> {
+        self.hdu.rename_key(self.fits_file, old, new)
+    }
+
+    /// Write a float header key with a number of decimal places, see [`FitsHdu::write_key_with_precision`](struct.FitsHdu.html#method.write_key_with_precision)
+    pub fn write_key_with_precision(
+        &mut self,
+        name: &str,
+        value: f64,
+        decimals: usize,
+    ) -> Result<()> {
+        self.hdu
+            .write_key_with_precision(self.fits_file, name, value, decimals)
+    }
+
+    /// Write a float header key in a notation, see [`FitsHdu::write_key_with_format`](struct.FitsHdu.html#method.write_key_with_format)
+    pub fn write_key_with_format(
+        &mut self,
+        name: &str,
+        value: f64,
+        format: FloatFormat,
+        decimals: usize,
+    ) -> Result<()> {
+        self.hdu
+            .write_key_with_format(self.fits_file, name, value, format, decimals)
+    }
+
+    /// Write a header key before another, see [`FitsHdu::write_key_before`](struct.FitsHdu.html#method.write_key_before)
+    pub fn write_key_before<T: Into<KeyValue>>(
+        &mut self,
+        before: &str,
+        name: &str,
+        value: T,
+    ) -> Result<()> {
+        self.hdu
+            .write_key_before(self.fits_file, before, name, value)
+    }
+
+    /// Insert a header card, see [`FitsHdu::insert_card_at`](struct.FitsHdu.html#method.insert_card_at)
+    pub fn insert_card_at(&mut self, index: usize, card: &TemplateCard) -> Result<()> {
+        self.hdu.insert_card_at(self.fits_file, index, card)
+    }
+
+    /// Read a whole image, see [`FitsHdu::read_image`](struct.FitsHdu.html#method.read_image)
+    pub fn read_image<T: ReadImage>(&mut self) -> Result<T> {
+        self.hdu.read_image(self.fits_file)
+    }
+
+    /// Read a range of pixels, see [`FitsHdu::read_section`](struct.FitsHdu.html#method.read_section)
+    pub fn read_section<T: ReadImage>(&mut self, start: usize, end: usize) -> Result<T> {
+        self.hdu.read_section(self.fits_file, start, end)
+    }
+
+    /// Read image rows, see [`FitsHdu::read_rows`](struct.FitsHdu.html#method.read_rows)
+    pub fn read_rows<T: ReadImage>(&mut self, start_row: usize, num_rows: usize) -> Result<T> {
+        self.hdu.read_rows(self.fits_file, start_row, num_rows)
+    }
+
+    /// Read a single image row, see [`FitsHdu::read_row`](struct.FitsHdu.html#method.read_row)
+    pub fn read_row<T: ReadImage>(&mut self, row: usize) -> Result<T> {
+        self.hdu.read_row(self.fits_file, row)
+    }
+
+    /// Read a region of an image, see [`FitsHdu::read_region`](struct.FitsHdu.html#method.read_region)
+    pub fn read_region<T: ReadImage, R: RegionRanges>(&mut self, ranges: R) -> Result<T> {
+        self.hdu.read_region(self.fits_file, ranges)
+    }
+
+    /// Write a whole image, see [`FitsHdu::write_image`](struct.FitsHdu.html#method.write_image)
+    pub fn write_image<T: WriteImage>(&mut self, data: &[T]) -> Result<()> {
+        self.hdu.write_image(self.fits_file, data)
+    }
+
+    /// Write a range of pixels, see [`FitsHdu::write_section`](struct.FitsHdu.html#method.write_section)
+    pub fn write_section<T: WriteImage>(
+        &mut self,
+        start: usize,
+        end: usize,
+        data: &[T],
+    ) -> Result<()> {
+        self.hdu.write_section(self.fits_file, start, end, data)
+    }
+
+    /// Write a region of an image, see [`FitsHdu::write_region`](struct.FitsHdu.html#method.write_region)
+    pub fn write_region<T: WriteImage, R: RegionRanges>(
+        &mut self,
+        ranges: R,
+        data: &[T],
+    ) -> Result<()> {
+        self.hdu.write_region(self.fits_file, ranges, data)
+    }
+
+    /// Resize an image, see [`FitsHdu::resize`](struct.FitsHdu.html#method.resize)
+    pub fn resize(&mut self, new_size: &[usize]) -> Result<()> {
+        let hdu = FitsHdu {
+            info: self.hdu.info.clone(),
+            number: self.hdu.number,
+            renumberings: self.hdu.renumberings,
+        };
+        self.hdu = hdu.resize(self.fits_file, new_size)?;
+        Ok(())
+    }
+
+    /// Read a whole column, see [`FitsHdu::read_col`](struct.FitsHdu.html#method.read_col)
+    pub fn read_col<S: ColumnSink>(&mut self, name: &str) -> Result<S> {
+        self.hdu.read_col(self.fits_file, name)
+    }
+
+    /// Read a range of rows of a column, see [`FitsHdu::read_col_range`](struct.FitsHdu.html#method.read_col_range)
+    pub fn read_col_range<T: ReadsCol, R: IndexRange>(
+        &mut self,
+        name: &str,
+        range: R,
+    ) -> Result<Vec<T>> {
+        self.hdu.read_col_range(self.fits_file, name, range)
+    }
+
+    /// Read a single cell, see [`FitsHdu::read_cell_value`](struct.FitsHdu.html#method.read_cell_value)
+    pub fn read_cell_value<T: ReadsCol>(&mut self, name: &str, idx: usize) -> Result<T> {
+        self.hdu.read_cell_value(self.fits_file, name, idx)
+    }
+
+    /// Read a single row into a struct, see [`FitsHdu::row`](struct.FitsHdu.html#method.row)
+    pub fn row<F: FitsRow>(&mut self, idx: usize) -> Result<F> {
+        self.hdu.row(self.fits_file, idx)
+    }
+
+    /// Read a range of rows into structs, see [`FitsHdu::rows_range`](struct.FitsHdu.html#method.rows_range)
+    pub fn rows_range<F: FitsRow, R: IndexRange>(&mut self, range: R) -> Result<Vec<F>> {
+        self.hdu.rows_range(self.fits_file, range)
+    }
+
+    /// Write a whole column, see [`FitsHdu::write_col`](struct.FitsHdu.html#method.write_col)
+    pub fn write_col<T: WritesCol, N: Into<String>>(
+        &mut self,
+        name: N,
+        col_data: &[T],
+    ) -> Result<()> {
+        self.hdu = self.hdu.write_col(self.fits_file, name, col_data)?;
+        Ok(())
+    }
+
+    /// Write a range of rows of a column, see [`FitsHdu::write_col_range`](struct.FitsHdu.html#method.write_col_range)
+    pub fn write_col_range<T: WritesCol, N: Into<String>, R: IndexRange>(
+        &mut self,
+        name: N,
+        col_data: &[T],
+        rows: R,
+    ) -> Result<()> {
+        self.hdu = self
+            .hdu
+            .write_col_range(self.fits_file, name, col_data, rows)?;
+        Ok(())
+    }
+
+    /// Delete the HDU from the file, see [`FitsHdu::delete`](struct.FitsHdu.html#method.delete)
+    pub fn delete(self) -> Result<()> {
+        self.hdu.delete(self.fits_file)
+    }
+}
+
+impl Deref for BoundHdu<'_> {
+    type Target = FitsHdu;
+
+    fn deref(&self) -> &FitsHdu {
+        &self.hdu
+    }
+}
+
+/**
+Summary of a single HDU, as returned by [`FitsFile::summary`][fits-file-summary]
+
+[fits-file-summary]: ../fitsfile/struct.FitsFile.html#method.summary
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HduSummary {
+    /// The HDU number within the fits file. Zero indexed.
+    pub index: usize,
+    /// The `EXTNAME` of the HDU, or an empty string if it is not set
+    pub name: String,
+    /// Type of the HDU, and either its image shape or its columns and number of rows
+    pub info: HduInfo,
+}
+
+/**
+The name, version and level of an HDU, from its `EXTNAME`, `EXTVER` and `EXTLEVEL` keywords
+
+Multi-extension formats often hold several HDUs with the same name, told apart by their
+version. An `HduIdentity` is returned by [`FitsHdu::identity`](struct.FitsHdu.html#method.identity),
+sets these keywords when given to [`FitsFile::create_image`][create-image] or
+[`FitsFile::create_table`][create-table], and selects an HDU by name and version when given to
+[`FitsFile::hdu`][fits-file-hdu].
+
+# Example
+
+```rust
+use fitsio::hdu::HduIdentity;
+use fitsio::images::{ImageDescription, ImageType};
+
+# fn main() -> Result<(), Box<dyn std::error::Error>> {
+# let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+# let filename = tdir.path().join("test.fits");
+# let mut fptr = fitsio::FitsFile::create(filename).open()?;
+let description = ImageDescription {
+    data_type: ImageType::Float,
+    dimensions: &[10, 10],
+};
+for version in 1..=3 {
+    fptr.create_image(HduIdentity::new("SCI").with_version(version), &description)?;
+}
+
+let hdu = fptr.hdu(HduIdentity::new("SCI").with_version(2))?;
+assert_eq!(hdu.number, 2);
+assert_eq!(hdu.identity(&mut fptr)?.version, Some(2));
+# Ok(())
+# }
+```
+
+[create-image]: ../fitsfile/struct.FitsFile.html#method.create_image
+[create-table]: ../fitsfile/struct.FitsFile.html#method.create_table
+[fits-file-hdu]: ../fitsfile/struct.FitsFile.html#method.hdu
+*/
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HduIdentity {
+    /// `EXTNAME`, if set
+    pub name: Option<String>,
+    /// `EXTVER`, if set. HDUs without one are version 1
+    pub version: Option<i32>,
+    /// `EXTLEVEL`, the level in a hierarchy of HDUs, if set. HDUs without one are level 1
+    pub level: Option<i32>,
+}
+
+impl HduIdentity {
+    /// Create an identity with a name and no version or level
+    pub fn new<T: Into<String>>(name: T) -> Self {
+        HduIdentity {
+            name: Some(name.into()),
+            ..Default::default()
+        }
+    }
+
+    /// Set the version
+    pub fn with_version(mut self, version: i32) -> Self {
+        self.version = Some(version);
+        self
+    }
+
+    /// Set the level
+    pub fn with_level(mut self, level: i32) -> Self {
+        self.level = Some(level);
+        self
+    }
+
+    /// Read the identity of the current HDU
+    pub(crate) fn read(fits_file: &mut FitsFile) -> Result<Self> {
+        let read_int = |fits_file: &mut FitsFile, name: &str| -> Result<Option<i32>> {
+            match read_key_optional::<i64>(fits_file, name)? {
+                Some(value) if value < i32::MIN as i64 || value > i32::MAX as i64 => Err(
+                    Error::Message(format!("{} value {} is out of range", name, value)),
+                ),
+                value => Ok(value.map(|value| value as i32)),
+            }
+        };
+        Ok(HduIdentity {
+            name: read_key_optional(fits_file, "EXTNAME")?,
+            version: read_int(fits_file, "EXTVER")?,
+            level: read_int(fits_file, "EXTLEVEL")?,
+        })
+    }
+
+    /// Write the keywords which are set to the current HDU, replacing any existing values
+    pub(crate) fn write(&self, fits_file: &mut FitsFile) -> Result<()> {
+        if let Some(ref name) = self.name {
+            let c_key = ffi::CString::new("EXTNAME")?;
+            let c_name = ffi::CString::new(name.as_str())?;
+            let mut status = 0;
+            unsafe {
+                fits_update_key(
+                    fits_file.fptr.as_mut() as *mut _,
+                    u8::from(DataType::TSTRING) as _,
+                    c_key.as_ptr(),
+                    c_name.as_ptr() as *mut _,
+                    ptr::null(),
+                    &mut status,
+                );
+            }
+            check_status(status)?;
+        }
+
+        for (key, value) in &[("EXTVER", self.version), ("EXTLEVEL", self.level)] {
+            if let Some(mut value) = *value {
+                let c_key = ffi::CString::new(*key)?;
+                let mut status = 0;
+                unsafe {
+                    fits_update_key(
+                        fits_file.fptr.as_mut() as *mut _,
+                        u8::from(DataType::TINT) as _,
+                        c_key.as_ptr(),
+                        &mut value as *mut i32 as *mut _,
+                        ptr::null(),
+                        &mut status,
+                    );
+                }
+                check_status(status)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'a> From<&'a str> for HduIdentity {
+    fn from(name: &'a str) -> Self {
+        HduIdentity::new(name)
+    }
+}
+
+impl<'a> From<&'a String> for HduIdentity {
+    fn from(name: &'a String) -> Self {
+        HduIdentity::new(name.as_str())
+    }
+}
+
+impl From<String> for HduIdentity {
+    fn from(name: String) -> Self {
+        HduIdentity::new(name)
     }
 }
 
@@ -1002,6 +4389,25 @@ impl DescribesHdu for usize {
     }
 }
 
+impl DescribesHdu for u32 {
+    fn change_hdu(&self, f: &mut FitsFile) -> Result<()> {
+        (*self as usize).change_hdu(f)
+    }
+}
+
+/// Negative indices are rejected
+impl DescribesHdu for i32 {
+    fn change_hdu(&self, f: &mut FitsFile) -> Result<()> {
+        if *self < 0 {
+            return Err(Error::Message(format!(
+                "HDU index must not be negative, got {}",
+                self
+            )));
+        }
+        (*self as usize).change_hdu(f)
+    }
+}
+
 impl<'a> DescribesHdu for &'a str {
     fn change_hdu(&self, f: &mut FitsFile) -> Result<()> {
         let mut status = 0;
@@ -1021,6 +4427,75 @@ impl<'a> DescribesHdu for &'a str {
     }
 }
 
+/// Selects the HDU with the given name and version, or the first with the name if no version
+/// is set
+impl DescribesHdu for HduIdentity {
+    fn change_hdu(&self, f: &mut FitsFile) -> Result<()> {
+        let name = self
+            .name
+            .as_deref()
+            .ok_or_else(|| Error::Message("cannot select an HDU without a name".to_string()))?;
+        let mut status = 0;
+        let c_hdu_name = ffi::CString::new(name)?;
+
+        unsafe {
+            fits_movnam_hdu(
+                f.fptr.as_mut() as *mut _,
+                HduInfo::AnyInfo.into(),
+                c_hdu_name.as_ptr() as *mut _,
+                self.version.unwrap_or(0),
+                &mut status,
+            );
+        }
+
+        check_status(status)
+    }
+}
+
+/**
+Kind of HDU, to select HDUs by their position among HDUs of the same kind
+
+See [`FitsFile::hdu_by_type`](../fitsfile/struct.FitsFile.html#method.hdu_by_type).
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HduType {
+    /// Image HDUs containing data, including compressed images
+    Image,
+    /// ASCII or binary table HDUs
+    Table,
+}
+
+impl HduType {
+    fn matches(self, info: &HduInfo) -> bool {
+        match (self, info) {
+            (HduType::Image, HduInfo::ImageInfo { shape, .. }) => !shape.is_empty(),
+            (HduType::Table, HduInfo::TableInfo { .. }) => true,
+            _ => false,
+        }
+    }
+}
+
+/// Selects the `n`th HDU of the given type, counting from 0
+impl DescribesHdu for (HduType, usize) {
+    fn change_hdu(&self, f: &mut FitsFile) -> Result<()> {
+        let (hdu_type, n) = *self;
+        let mut count = 0;
+        for index in 0..f.num_hdus()? {
+            index.change_hdu(f)?;
+            if hdu_type.matches(&f.fetch_hdu_info()?) {
+                if count == n {
+                    return Ok(());
+                }
+                count += 1;
+            }
+        }
+        Err(Error::Message(format!(
+            "cannot find {:?} HDU {}, the file contains {}",
+            hdu_type, n, count
+        )))
+    }
+}
+
 /**
 Description of the current HDU
 
@@ -1031,11 +4506,14 @@ Otherwise the variant is `HduInfo::TableInfo`.
 [fetch-hdu-info]: ../fitsfile/struct.FitsFile.html#method.fetch_hdu_info
 */
 #[allow(missing_docs)]
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum HduInfo {
     ImageInfo {
         shape: Vec<usize>,
+        /// Type of the pixel values after applying `BSCALE` and `BZERO`
         image_type: ImageType,
+        /// `BITPIX` value stored in the header, the type of the pixel values on disk
+        raw_bitpix: i32,
     },
     TableInfo {
         column_descriptions: Vec<ConcreteColumnDescription>,
@@ -1064,9 +4542,351 @@ hduinfo_into_impl!(i64);
 
 #[cfg(test)]
 mod tests {
-    use super::FitsFile;
-    use crate::hdu::{FitsHdu, HduInfo};
-    use crate::testhelpers::duplicate_test_file;
+    use super::{FitsFile, STATS_CHUNK_VALUES};
+    use crate::hdu::{FitsHdu, HduIdentity, HduInfo, HduType};
+    use crate::images::{ImageDescription, ImageType};
+    use crate::tables::{
+        ArrayDescriptor, ColumnDataDescription, ColumnDataType, ColumnDescription,
+        ConcreteColumnDescription,
+    };
+    use crate::testing::{duplicate_test_file, with_temp_file};
+
+    #[test]
+    fn test_image_accessors() {
+        let mut f = FitsFile::open("../testdata/full_example.fits").unwrap();
+        let hdu = f.primary_hdu().unwrap();
+        assert!(hdu.is_image());
+        assert!(!hdu.is_table());
+        assert_eq!(hdu.shape().unwrap(), &[100, 100]);
+        assert_eq!(hdu.dimensions().unwrap(), 2);
+        assert_eq!(hdu.num_pixels().unwrap(), 10_000);
+        assert_eq!(hdu.image_type().unwrap(), ImageType::Long);
+
+        let hdu = f.hdu("TESTEXT").unwrap();
+        assert!(!hdu.is_image());
+        assert!(hdu.is_table());
+        assert!(hdu.shape().is_err());
+        assert!(hdu.dimensions().is_err());
+        assert!(hdu.num_pixels().is_err());
+        assert!(hdu.image_type().is_err());
+    }
+
+    #[test]
+    fn test_table_accessors() {
+        duplicate_test_file(|filename| {
+            let mut f = FitsFile::edit(filename).unwrap();
+            let hdu = f.hdu("TESTEXT").unwrap();
+            assert_eq!(hdu.num_rows(&mut f).unwrap(), 50);
+            assert_eq!(hdu.num_columns(&mut f).unwrap(), 4);
+            assert!(!hdu.is_empty(&mut f).unwrap());
+
+            /* Sizes reflect writes made after fetching the HDU */
+            let hdu = hdu
+                .append_column(
+                    &mut f,
+                    &ColumnDescription::new("abcdefg")
+                        .with_type(ColumnDataType::Int)
+                        .create()
+                        .unwrap(),
+                )
+                .unwrap();
+            let data: Vec<i32> = (0..60).collect();
+            hdu.write_col(&mut f, "abcdefg", &data).unwrap();
+            assert_eq!(hdu.num_rows(&mut f).unwrap(), 60);
+            assert_eq!(hdu.num_columns(&mut f).unwrap(), 5);
+            assert_eq!(
+                hdu.column_names(&mut f).unwrap(),
+                vec!["intcol", "floatcol", "doublecol", "strcol", "abcdefg"]
+            );
+
+            let hdu = f.primary_hdu().unwrap();
+            assert!(hdu.num_rows(&mut f).is_err());
+            assert!(hdu.num_columns(&mut f).is_err());
+            assert!(hdu.column_names(&mut f).is_err());
+        });
+    }
+
+    #[test]
+    fn test_refresh() {
+        duplicate_test_file(|filename| {
+            let mut f = FitsFile::edit(filename).unwrap();
+            let hdu = f.hdu("TESTEXT").unwrap();
+            let data: Vec<i32> = (0..60).collect();
+            hdu.write_col(&mut f, "intcol", &data).unwrap();
+
+            /* The original HDU still describes the old table */
+            match hdu.info {
+                HduInfo::TableInfo { num_rows, .. } => assert_eq!(num_rows, 50),
+                _ => panic!("Incorrect HDU type found"),
+            }
+
+            f.primary_hdu().unwrap();
+            let refreshed = hdu.refresh(&mut f).unwrap();
+            assert_eq!(refreshed.number, hdu.number);
+            match refreshed.info {
+                HduInfo::TableInfo { num_rows, .. } => assert_eq!(num_rows, 60),
+                _ => panic!("Incorrect HDU type found"),
+            }
+        });
+    }
+
+    #[test]
+    fn test_linear_transform() {
+        use crate::images::{LinearConvention, LinearTransform};
+
+        let close = |a: &[f64], b: &[f64]| a.iter().zip(b).all(|(x, y)| (x - y).abs() < 1e-12);
+
+        duplicate_test_file(|filename| {
+            let mut f = FitsFile::edit(filename).unwrap();
+            let hdu = f.primary_hdu().unwrap();
+            assert_eq!(
+                hdu.read_linear_transform(&mut f).unwrap(),
+                LinearTransform::identity(2)
+            );
+
+            // CDELT with the legacy CROTA2 rotation
+            hdu.write_keys_indexed(&mut f, "CDELT", &[-0.002, 0.001])
+                .unwrap();
+            hdu.write_key(&mut f, "CROTA2", 30.0).unwrap();
+            let transform = hdu.read_linear_transform(&mut f).unwrap();
+            let (sin, cos) = 30f64.to_radians().sin_cos();
+            let expected = [-0.002 * cos, -0.001 * sin, -0.002 * sin, 0.001 * cos];
+            assert!(close(transform.cd(), &expected), "{:?}", transform);
+
+            // PC and CDELT round trip, replacing CROTA2
+            hdu.write_linear_transform(&mut f, &transform, LinearConvention::PcCdelt)
+                .unwrap();
+            assert!(hdu.read_key::<f64>(&mut f, "CROTA2").is_err());
+            assert!(hdu.read_key::<f64>(&mut f, "PC1_1").unwrap() > 0.0);
+            let (pc, cdelt) = transform.to_pc_cdelt();
+            assert!(cdelt[0] < 0.0 && cdelt[1] > 0.0, "{:?}", cdelt);
+            let rebuilt = LinearTransform::from_pc_cdelt(&pc, &cdelt).unwrap();
+            assert!(close(rebuilt.cd(), transform.cd()));
+            let read = hdu.read_linear_transform(&mut f).unwrap();
+            assert!(close(read.cd(), transform.cd()));
+
+            // CD matrix round trip, replacing PC and CDELT
+            hdu.write_linear_transform(&mut f, &transform, LinearConvention::Cd)
+                .unwrap();
+            assert!(hdu.read_key::<f64>(&mut f, "PC1_1").is_err());
+            assert!(hdu.read_key::<f64>(&mut f, "CDELT1").is_err());
+            let read = hdu.read_linear_transform(&mut f).unwrap();
+            assert!(close(read.cd(), transform.cd()));
+
+            assert!(hdu
+                .write_linear_transform(&mut f, &LinearTransform::identity(3), LinearConvention::Cd)
+                .is_err());
+            assert!(LinearTransform::from_cd(2, vec![1.0]).is_err());
+        });
+    }
+
+    #[test]
+    fn test_read_spectrum() {
+        use crate::images::SpectralScale;
+
+        with_temp_file(|filename| {
+            let mut f = FitsFile::create(filename).open().unwrap();
+            let description = ImageDescription {
+                data_type: ImageType::Double,
+                dimensions: &[3],
+            };
+            let hdu = f.create_image("SPEC", &description).unwrap();
+            hdu.write_image(&mut f, &[1.0, 2.0, 3.0]).unwrap();
+
+            // No keywords gives pixel numbers
+            let (x, y): (Vec<f64>, Vec<f64>) = hdu.read_spectrum(&mut f).unwrap();
+            assert_eq!(x, vec![1.0, 2.0, 3.0]);
+            assert_eq!(y, vec![1.0, 2.0, 3.0]);
+
+            // Linear, from CD1_1, with the reference pixel in the middle
+            hdu.write_key(&mut f, "CRVAL1", 100.0).unwrap();
+            hdu.write_key(&mut f, "CRPIX1", 2.0).unwrap();
+            hdu.write_key(&mut f, "CD1_1", 10.0).unwrap();
+            let (x, _): (Vec<f64>, Vec<f64>) = hdu.read_spectrum(&mut f).unwrap();
+            assert_eq!(x, vec![90.0, 100.0, 110.0]);
+
+            // FITS WCS logarithmic sampling
+            hdu.write_key(&mut f, "CTYPE1", "WAVE-LOG").unwrap();
+            let axis = hdu.read_spectral_axis(&mut f).unwrap();
+            assert_eq!(axis.scale, SpectralScale::Log);
+            let x = axis.values(3);
+            assert!((x[0] - 100.0 * (-0.1f64).exp()).abs() < 1e-9);
+            assert!((x[2] - 100.0 * 0.1f64.exp()).abs() < 1e-9);
+
+            // IRAF base 10 logarithmic sampling takes precedence, and CD1_1 over CDELT1
+            hdu.write_key(&mut f, "DC-FLAG", 1i64).unwrap();
+            hdu.write_key(&mut f, "CDELT1", 0.5).unwrap();
+            let axis = hdu.read_spectral_axis(&mut f).unwrap();
+            assert_eq!(axis.scale, SpectralScale::Log10);
+            assert_eq!(axis.cdelt, 10.0);
+            assert!((axis.value(3.0) - 10f64.powf(110.0)).abs() / axis.value(3.0) < 1e-12);
+
+            // Without CD1_1, CDELT1 is scaled by PC1_1
+            let hdu = f.create_image("SPEC2", &description).unwrap();
+            hdu.write_key(&mut f, "CDELT1", 0.5).unwrap();
+            hdu.write_key(&mut f, "PC1_1", 4.0).unwrap();
+            assert_eq!(hdu.read_spectral_axis(&mut f).unwrap().cdelt, 2.0);
+            hdu.write_key(&mut f, "CD1_1", 0.25).unwrap();
+            assert_eq!(hdu.read_spectral_axis(&mut f).unwrap().cdelt, 0.25);
+
+            let primary = f.primary_hdu().unwrap();
+            assert!(primary.read_spectrum::<f64>(&mut f).is_err());
+        });
+    }
+
+    #[test]
+    fn test_read_cutout_world() {
+        use crate::images::{CelestialWcs, Cutout};
+
+        duplicate_test_file(|filename| {
+            let mut f = FitsFile::edit(filename).unwrap();
+            let hdu = f.primary_hdu().unwrap();
+            hdu.write_key(&mut f, "CRPIX1", 1.0).unwrap();
+            let wcs = CelestialWcs {
+                ctype: ["RA---TAN".to_string(), "DEC--TAN".to_string()],
+                crval: [150.0, 2.0],
+                crpix: [20.0, 30.0],
+                cdelt: [-2.0 / 3600.0, 2.0 / 3600.0],
+                rotation: 0.0,
+            };
+            hdu.write_celestial_wcs(&mut f, &wcs).unwrap();
+            // Writing again replaces the keywords rather than adding duplicates
+            hdu.write_celestial_wcs(&mut f, &wcs).unwrap();
+            let read = hdu.read_celestial_wcs(&mut f).unwrap();
+            assert_eq!(read.projection(), "-TAN");
+            assert!((read.cdelt[0] - wcs.cdelt[0]).abs() < 1e-12);
+            assert!((read.crpix[1] - 30.0).abs() < 1e-12);
+
+            // 20 arcseconds is 10 pixels, centred on pixel (20, 30)
+            let cutout: Cutout<Vec<i32>> = hdu.read_cutout_world(&mut f, 150.0, 2.0, 20.0).unwrap();
+            assert_eq!(cutout.region, vec![14..24, 24..34]);
+            let expected: Vec<i32> = hdu.read_region(&mut f, &[14..24, 24..34]).unwrap();
+            assert_eq!(cutout.data, expected);
+            assert!((cutout.wcs.crpix[0] - 6.0).abs() < 1e-12);
+            let (ra, dec) = cutout.wcs.pixel_to_world(6.0, 6.0).unwrap();
+            assert!((ra - 150.0).abs() < 1e-9 && (dec - 2.0).abs() < 1e-9);
+
+            // Clipped at the edge of the 100x100 image
+            let (ra, dec) = wcs.pixel_to_world(1.0, 1.0).unwrap();
+            let cutout: Cutout<Vec<i32>> = hdu.read_cutout_world(&mut f, ra, dec, 20.0).unwrap();
+            assert_eq!(cutout.region, vec![0..5, 0..5]);
+            assert_eq!(cutout.shape(), vec![5, 5]);
+
+            // Entirely off the image
+            let (ra, dec) = wcs.pixel_to_world(-50.0, -50.0).unwrap();
+            assert!(hdu
+                .read_cutout_world::<Vec<i32>>(&mut f, ra, dec, 20.0)
+                .is_err());
+            assert!(hdu
+                .read_cutout_world::<Vec<i32>>(&mut f, 150.0, 2.0, 0.0)
+                .is_err());
+        });
+    }
+
+    #[test]
+    fn test_image_axes() {
+        use crate::images::{ImageAxes, ImageAxis};
+
+        duplicate_test_file(|filename| {
+            let mut f = FitsFile::edit(filename).unwrap();
+            let hdu = f.primary_hdu().unwrap();
+            let axes = hdu.read_axes(&mut f).unwrap();
+            assert_eq!(axes.axes, vec![ImageAxis::default(), ImageAxis::default()]);
+
+            let axes = ImageAxes {
+                axes: vec![
+                    ImageAxis {
+                        ctype: Some("RA---TAN".to_string()),
+                        cunit: Some("deg".to_string()),
+                        crval: Some(150.25),
+                        cdelt: Some(-0.001),
+                        crpix: Some(50.5),
+                    },
+                    ImageAxis {
+                        ctype: Some("DEC--TAN".to_string()),
+                        crval: Some(2.5),
+                        ..Default::default()
+                    },
+                ],
+            };
+            hdu.write_axes(&mut f, &axes).unwrap();
+            assert_eq!(hdu.read_axes(&mut f).unwrap(), axes);
+            assert_eq!(hdu.read_key::<f64>(&mut f, "CRPIX1").unwrap(), 50.5);
+
+            let mut updated = axes.clone();
+            updated.axes[0].crval = Some(99.0);
+            updated.axes[1].ctype = Some("DEC--SIN".to_string());
+            hdu.write_axes(&mut f, &updated).unwrap();
+            assert_eq!(hdu.read_axes(&mut f).unwrap(), updated);
+            assert_eq!(hdu.read_keys_matching(&mut f, "CRVAL1").unwrap().len(), 1);
+            assert_eq!(hdu.read_keys_matching(&mut f, "CTYPE2").unwrap().len(), 1);
+
+            let too_many = ImageAxes {
+                axes: vec![ImageAxis::default(); 3],
+            };
+            assert!(hdu.write_axes(&mut f, &too_many).is_err());
+
+            let table = f.hdu("TESTEXT").unwrap();
+            assert!(table.read_axes(&mut f).is_err());
+        });
+    }
+
+    #[test]
+    fn test_write_null_region() {
+        with_temp_file(|filename| {
+            let mut f = FitsFile::create(filename).open().unwrap();
+            let description = ImageDescription {
+                data_type: ImageType::Long,
+                dimensions: &[3, 5],
+            };
+            let hdu = f.create_image("INT".to_string(), &description).unwrap();
+            hdu.write_image(&mut f, &(0..15).collect::<Vec<i32>>())
+                .unwrap();
+
+            /* Integer images need a BLANK value */
+            assert_eq!(hdu.blank_value(&mut f).unwrap(), None);
+            assert!(hdu.write_null_region(&mut f, &[1..3, 1..3]).is_err());
+
+            hdu.set_blank_value(&mut f, Some(-1)).unwrap();
+            assert_eq!(hdu.blank_value(&mut f).unwrap(), Some(-1));
+            hdu.write_null_region(&mut f, &[1..3, 1..3]).unwrap();
+            let data: Vec<i32> = hdu.read_image(&mut f).unwrap();
+            let expected: Vec<i32> = (0..15)
+                .map(|i| if [6, 7, 11, 12].contains(&i) { -1 } else { i })
+                .collect();
+            assert_eq!(data, expected);
+
+            assert!(hdu.write_null_region(&mut f, &[0..6, 0..1]).is_err());
+            assert!(hdu.write_null_region(&mut f, &[&(0..1)]).is_err());
+
+            /* Changing BLANK replaces the existing card */
+            hdu.set_blank_value(&mut f, Some(-99)).unwrap();
+            assert_eq!(hdu.blank_value(&mut f).unwrap(), Some(-99));
+            assert_eq!(hdu.read_keys_matching(&mut f, "BLANK").unwrap().len(), 1);
+            hdu.write_null_region(&mut f, &[0..1, 0..1]).unwrap();
+            let data: Vec<i32> = hdu.read_image(&mut f).unwrap();
+            assert_eq!(data[0], -99);
+
+            hdu.set_blank_value(&mut f, None).unwrap();
+            assert_eq!(hdu.blank_value(&mut f).unwrap(), None);
+            hdu.set_blank_value(&mut f, None).unwrap();
+
+            /* Floating point images use NaN */
+            let description = ImageDescription {
+                data_type: ImageType::Double,
+                dimensions: &[2, 3, 4],
+            };
+            let hdu = f.create_image("FLOAT".to_string(), &description).unwrap();
+            hdu.write_image(&mut f, &vec![1.0f64; 24]).unwrap();
+            assert!(hdu.set_blank_value(&mut f, Some(-1)).is_err());
+            hdu.write_null_region(&mut f, &[2..4, 0..3, 1..2]).unwrap();
+            let data: Vec<f64> = hdu.read_image(&mut f).unwrap();
+            for (i, value) in data.iter().enumerate() {
+                let (x, z) = (i % 4, i / 12);
+                assert_eq!(value.is_nan(), x >= 2 && z == 1, "pixel {}", i);
+            }
+        });
+    }
 
     #[test]
     fn test_manually_creating_a_fits_hdu() {
@@ -1103,7 +4923,194 @@ mod tests {
         duplicate_test_file(|filename| {
             let mut f = FitsFile::open(filename).unwrap();
             let hdu = f.hdu("TESTEXT").unwrap();
-            assert_eq!(hdu.name(&mut f).unwrap(), "TESTEXT".to_string());
+            assert_eq!(hdu.name(&mut f).unwrap(), Some("TESTEXT".to_string()));
+            assert_eq!(f.primary_hdu().unwrap().name(&mut f).unwrap(), None);
+        });
+    }
+
+    #[test]
+    fn test_delete_hdu_by_description() {
+        with_temp_file(|filename| {
+            let mut f = FitsFile::create(filename).open().unwrap();
+            let description = ImageDescription {
+                data_type: ImageType::Float,
+                dimensions: &[2, 2],
+            };
+            for name in &["A", "B", "C"] {
+                f.create_image(*name, &description).unwrap();
+            }
+            let first = f.hdu("A").unwrap();
+            let last = f.hdu("C").unwrap();
+
+            f.delete_hdu("B").unwrap();
+            assert_eq!(f.num_hdus().unwrap(), 3);
+            assert!(f.hdu("B").is_err());
+
+            // HDUs before the deleted HDU keep their numbers, later ones are out of date
+            assert!(first.read_key::<String>(&mut f, "EXTNAME").is_ok());
+            assert!(last.read_key::<String>(&mut f, "EXTNAME").is_err());
+            let last = f.hdu("C").unwrap();
+            assert_eq!(last.number, 2);
+            assert_eq!(last.read_key::<String>(&mut f, "EXTNAME").unwrap(), "C");
+
+            f.delete_hdu(2).unwrap();
+            assert_eq!(f.num_hdus().unwrap(), 2);
+            assert!(last.delete(&mut f).is_err());
+        });
+    }
+
+    #[test]
+    fn test_demote_and_promote_primary() {
+        with_temp_file(|filename| {
+            let description = ImageDescription {
+                data_type: ImageType::Long,
+                dimensions: &[3, 2],
+            };
+            let mut f = FitsFile::create(filename)
+                .with_custom_primary(&description)
+                .open()
+                .unwrap();
+            let primary = f.primary_hdu().unwrap();
+            let data: Vec<i32> = (0..6).collect();
+            primary.write_image(&mut f, &data).unwrap();
+            primary.write_key(&mut f, "OBSERVER", "me").unwrap();
+            let columns = vec![ColumnDescription::new("x")
+                .with_type(ColumnDataType::Int)
+                .create()
+                .unwrap()];
+            let table = f.create_table("TABLE", &columns).unwrap();
+
+            let sci = f.demote_primary().unwrap();
+            assert_eq!(sci.number, 1);
+            assert_eq!(f.num_hdus().unwrap(), 3);
+            assert_eq!(sci.read_key::<String>(&mut f, "XTENSION").unwrap(), "IMAGE");
+            assert_eq!(sci.read_key::<i64>(&mut f, "PCOUNT").unwrap(), 0);
+            assert_eq!(sci.read_key::<i64>(&mut f, "GCOUNT").unwrap(), 1);
+            assert_eq!(sci.read_key::<String>(&mut f, "OBSERVER").unwrap(), "me");
+            assert_eq!(sci.read_image::<Vec<i32>>(&mut f).unwrap(), data);
+            match f.primary_hdu().unwrap().info {
+                HduInfo::ImageInfo { shape, .. } => assert!(shape.is_empty()),
+                _ => panic!("primary HDU is not an image"),
+            }
+            assert!(f.demote_primary().is_err());
+
+            // Every HDU moved along by one
+            assert!(table.num_rows(&mut f).is_err());
+            let table = f.hdu("TABLE").unwrap();
+            assert_eq!(table.number, 2);
+
+            let promoted = format!("{}.promoted.fits", filename);
+            let mut promoted_file = sci.promote_to_file(&mut f, &promoted).unwrap();
+            assert_eq!(promoted_file.num_hdus().unwrap(), 1);
+            let primary = promoted_file.primary_hdu().unwrap();
+            assert!(primary
+                .read_key::<bool>(&mut promoted_file, "SIMPLE")
+                .unwrap());
+            assert!(primary
+                .read_key::<String>(&mut promoted_file, "XTENSION")
+                .is_err());
+            assert!(primary
+                .read_key::<i64>(&mut promoted_file, "PCOUNT")
+                .is_err());
+            assert_eq!(
+                primary.read_image::<Vec<i32>>(&mut promoted_file).unwrap(),
+                data
+            );
+
+            let table_file = format!("{}.table.fits", filename);
+            assert!(table.promote_to_file(&mut f, &table_file).is_err());
+            assert!(sci.promote_to_file(&mut f, &promoted).is_err());
+        });
+    }
+
+    #[test]
+    fn test_hdu_by_number_and_type() {
+        with_temp_file(|filename| {
+            let mut f = FitsFile::create(filename).open().unwrap();
+            let description = ImageDescription {
+                data_type: ImageType::Float,
+                dimensions: &[2, 2],
+            };
+            let columns = vec![ColumnDescription::new("x")
+                .with_type(ColumnDataType::Int)
+                .create()
+                .unwrap()];
+            f.create_table("FIRST", &columns).unwrap();
+            f.create_image("SCI", &description).unwrap();
+            f.create_table("SECOND", &columns).unwrap();
+            f.create_image("ERR", &description).unwrap();
+
+            assert_eq!(f.hdu(2u32).unwrap(), f.hdu(2usize).unwrap());
+            assert_eq!(f.hdu(2i32).unwrap(), f.hdu("SCI").unwrap());
+            assert!(f.hdu(-1i32).is_err());
+
+            // The empty primary HDU is not counted as an image
+            let hdu = f.hdu_by_type(HduType::Image, 0).unwrap();
+            assert_eq!(hdu, f.hdu("SCI").unwrap());
+            let hdu = f.hdu_by_type(HduType::Image, 1).unwrap();
+            assert_eq!(hdu, f.hdu("ERR").unwrap());
+            let hdu = f.hdu_by_type(HduType::Table, 1).unwrap();
+            assert_eq!(hdu, f.hdu("SECOND").unwrap());
+            assert!(f.hdu_by_type(HduType::Image, 2).is_err());
+        });
+    }
+
+    #[test]
+    fn test_hdu_identity() {
+        with_temp_file(|filename| {
+            let mut f = FitsFile::create(filename).open().unwrap();
+            let columns = vec![ColumnDescription::new("A")
+                .with_type(ColumnDataType::Int)
+                .create()
+                .unwrap()];
+            for version in 1..=2 {
+                let identity = HduIdentity::new("EVENTS")
+                    .with_version(version)
+                    .with_level(2);
+                f.create_table(identity, &columns).unwrap();
+            }
+            f.create_table("OTHER", &columns).unwrap();
+
+            let hdu = f.hdu(HduIdentity::new("EVENTS").with_version(2)).unwrap();
+            assert_eq!(hdu.number, 2);
+            assert_eq!(
+                hdu.identity(&mut f).unwrap(),
+                HduIdentity::new("EVENTS").with_version(2).with_level(2)
+            );
+            let cards = hdu.read_keys_matching(&mut f, "EXTNAME").unwrap();
+            assert_eq!(cards.len(), 1);
+
+            // Without a version the first HDU with the name is selected
+            assert_eq!(f.hdu(HduIdentity::new("EVENTS")).unwrap().number, 1);
+            assert!(f.hdu(HduIdentity::new("EVENTS").with_version(3)).is_err());
+            assert!(f.hdu(HduIdentity::default()).is_err());
+
+            let other = f.hdu("OTHER").unwrap();
+            assert_eq!(other.identity(&mut f).unwrap(), HduIdentity::new("OTHER"));
+            assert_eq!(
+                f.primary_hdu().unwrap().identity(&mut f).unwrap(),
+                HduIdentity::default()
+            );
+        });
+    }
+
+    #[test]
+    fn test_set_hdu_name() {
+        duplicate_test_file(|filename| {
+            let mut f = FitsFile::edit(filename).unwrap();
+            let primary = f.primary_hdu().unwrap();
+            primary.set_name(&mut f, "PRIMARY", None).unwrap();
+            assert_eq!(primary.name(&mut f).unwrap(), Some("PRIMARY".to_string()));
+            assert!(primary.read_key::<i64>(&mut f, "EXTVER").is_err());
+
+            let hdu = f.hdu("TESTEXT").unwrap();
+            hdu.set_name(&mut f, "EVENTS", Some(2)).unwrap();
+            hdu.set_name(&mut f, "EVENTS", Some(3)).unwrap();
+            let cards = hdu.read_keys_matching(&mut f, "EXT*").unwrap();
+            assert_eq!(cards.len(), 2);
+            assert_eq!(hdu.read_key::<i64>(&mut f, "EXTVER").unwrap(), 3);
+            assert!(f.hdu("TESTEXT").is_err());
+            assert_eq!(f.hdu("EVENTS").unwrap().number, 1);
         });
     }
     #[test]
@@ -1116,8 +5123,606 @@ mod tests {
             }
 
             let mut f = FitsFile::open(filename).unwrap();
-            let hdu_names = f.hdu_names().unwrap();
-            assert!(!hdu_names.contains(&"TESTEXT".to_string()));
+            let summary = f.summary().unwrap();
+            assert!(!summary.iter().any(|hdu| hdu.name == "TESTEXT"));
+        });
+    }
+
+    #[test]
+    fn test_copy_header_filtered() {
+        use crate::headers::is_structural_key;
+
+        with_temp_file(|filename| {
+            let mut src = FitsFile::open("../testdata/full_example.fits").unwrap();
+            let src_hdu = src.primary_hdu().unwrap();
+
+            let mut dest = FitsFile::create(filename).open().unwrap();
+            let description = ImageDescription {
+                data_type: ImageType::Short,
+                dimensions: &[5, 10],
+            };
+            let dest_hdu = dest.create_image("DEST".to_string(), &description).unwrap();
+            src_hdu
+                .copy_header_filtered(&mut src, &dest_hdu, &mut dest, |name| {
+                    !is_structural_key(name) && name != "DBLTEST"
+                })
+                .unwrap();
+
+            let value: String = dest_hdu.read_key(&mut dest, "TEST").unwrap();
+            assert_eq!(value, "value");
+            let value: i64 = dest_hdu.read_key(&mut dest, "INTTEST").unwrap();
+            assert_eq!(value, 42);
+            assert!(dest_hdu.read_key::<f64>(&mut dest, "DBLTEST").is_err());
+            let comments = dest_hdu.read_keys_matching(&mut dest, "COMMENT").unwrap();
+            assert_eq!(comments.len(), 2);
+
+            /* The structure of the destination is unchanged */
+            let dest_hdu = dest_hdu.refresh(&mut dest).unwrap();
+            assert_eq!(dest_hdu.shape().unwrap(), &[5, 10]);
+            assert_eq!(dest_hdu.image_type().unwrap(), ImageType::Short);
+        });
+    }
+
+    #[test]
+    fn test_column_type() {
+        with_temp_file(|filename| {
+            let mut f = FitsFile::create(filename).open().unwrap();
+            let table_description = vec![
+                ColumnDescription::new("ushort")
+                    .with_type(ColumnDataType::UnsignedShort)
+                    .create()
+                    .unwrap(),
+                ColumnDescription::new("vector")
+                    .with_type(ColumnDataType::Double)
+                    .that_repeats(3)
+                    .create()
+                    .unwrap(),
+                ColumnDescription::new("text")
+                    .with_type(ColumnDataType::String)
+                    .that_repeats(12)
+                    .create()
+                    .unwrap(),
+            ];
+            let hdu = f.create_table("DATA", &table_description).unwrap();
+
+            let description = hdu.column_type(&mut f, "ushort").unwrap();
+            assert_eq!(
+                description,
+                ColumnDataDescription::new(ColumnDataType::UnsignedShort, 1, 2)
+            );
+            let description = hdu.column_type(&mut f, 1).unwrap();
+            assert_eq!(
+                description,
+                ColumnDataDescription::new(ColumnDataType::Double, 3, 8)
+            );
+            let description = hdu.column_type(&mut f, "text").unwrap();
+            assert_eq!(
+                description,
+                ColumnDataDescription::new(ColumnDataType::String, 12, 12)
+            );
+            assert!(hdu.column_type(&mut f, "missing").is_err());
+
+            let table_description = vec![ConcreteColumnDescription {
+                name: "varcol".to_string(),
+                data_type: ColumnDataDescription::variable_length(
+                    ColumnDataType::Float,
+                    ArrayDescriptor::Q,
+                ),
+            }];
+            let hdu = f.create_table("VARDATA", &table_description).unwrap();
+            let description = hdu.column_type(&mut f, "varcol").unwrap();
+            assert_eq!(description.typ, ColumnDataType::Float);
+            assert_eq!(description.descriptor, Some(ArrayDescriptor::Q));
+        });
+    }
+
+    #[test]
+    fn test_read_raw_bytes() {
+        let mut f = FitsFile::open("../testdata/full_example.fits").unwrap();
+        let hdu = f.hdu("TESTEXT").unwrap();
+
+        let bytes = hdu.read_col_bytes(&mut f, "doublecol").unwrap();
+        let values: Vec<f64> = hdu.read_col(&mut f, "doublecol").unwrap();
+        assert_eq!(bytes.len(), values.len() * 8);
+        for (chunk, value) in bytes.chunks(8).zip(&values) {
+            assert_eq!(chunk, value.to_be_bytes());
+        }
+
+        let bytes = hdu.read_cell_bytes(&mut f, "strcol", 4).unwrap();
+        let value: String = hdu.read_cell_value(&mut f, "strcol", 4).unwrap();
+        assert_eq!(String::from_utf8(bytes).unwrap().trim_end(), value);
+
+        assert!(hdu.read_cell_bytes(&mut f, "intcol", 1000).is_err());
+        assert!(hdu.read_col_bytes(&mut f, "missing").is_err());
+    }
+
+    #[test]
+    fn test_read_raw_bytes_from_ascii_table() {
+        use crate::errors::check_status;
+        use crate::longnam::fits_create_tbl;
+        use std::{ffi, ptr};
+
+        with_temp_file(|filename| {
+            let mut f = FitsFile::create(filename).open().unwrap();
+            let mut status = 0;
+            let ttype = [
+                ffi::CString::new("A").unwrap(),
+                ffi::CString::new("B").unwrap(),
+            ];
+            let tform = [
+                ffi::CString::new("I4").unwrap(),
+                ffi::CString::new("F8.2").unwrap(),
+            ];
+            let mut ttype_ptrs: Vec<_> = ttype.iter().map(|s| s.as_ptr() as *mut _).collect();
+            let mut tform_ptrs: Vec<_> = tform.iter().map(|s| s.as_ptr() as *mut _).collect();
+            unsafe {
+                fits_create_tbl(
+                    f.fptr.as_mut() as *mut _,
+                    1,
+                    2,
+                    2,
+                    ttype_ptrs.as_mut_ptr(),
+                    tform_ptrs.as_mut_ptr(),
+                    ptr::null_mut(),
+                    ptr::null_mut(),
+                    &mut status,
+                );
+            }
+            check_status(status).unwrap();
+            let hdu = f.hdu(1).unwrap();
+            hdu.write_col(&mut f, "A", &[12, 34]).unwrap();
+            hdu.write_col(&mut f, "B", &[1.5, -2.25]).unwrap();
+
+            assert_eq!(hdu.read_cell_bytes(&mut f, "A", 1).unwrap(), b"  34");
+            assert_eq!(
+                hdu.read_col_bytes(&mut f, "B").unwrap(),
+                b"    1.50   -2.25"
+            );
+        });
+    }
+
+    #[test]
+    fn test_sort_rows() {
+        use crate::tables::Order;
+
+        with_temp_file(|filename| {
+            let mut f = FitsFile::create(filename).open().unwrap();
+            let table_description = vec![
+                ColumnDescription::new("mjd")
+                    .with_type(ColumnDataType::Double)
+                    .create()
+                    .unwrap(),
+                ColumnDescription::new("id")
+                    .with_type(ColumnDataType::Int)
+                    .create()
+                    .unwrap(),
+                ColumnDescription::new("name")
+                    .with_type(ColumnDataType::String)
+                    .that_repeats(8)
+                    .create()
+                    .unwrap(),
+                ColumnDescription::new("vector")
+                    .with_type(ColumnDataType::Int)
+                    .that_repeats(2)
+                    .create()
+                    .unwrap(),
+            ];
+            let hdu = f.create_table("DATA", &table_description).unwrap();
+            hdu.write_col(&mut f, "mjd", &[2.0, f64::NAN, 1.0, 2.0])
+                .unwrap();
+            hdu.write_col(&mut f, "id", &[4, 3, 2, 1]).unwrap();
+            let names: Vec<String> = ["d", "c", "b", "a"].iter().map(|s| s.to_string()).collect();
+            hdu.write_col(&mut f, "name", &names).unwrap();
+
+            hdu.sort_rows(&mut f, &["mjd", "id"], Order::Ascending)
+                .unwrap();
+            let ids: Vec<i32> = hdu.read_col(&mut f, "id").unwrap();
+            assert_eq!(ids, vec![2, 1, 4, 3]);
+            let names: Vec<String> = hdu.read_col(&mut f, "name").unwrap();
+            assert_eq!(names, vec!["b", "a", "d", "c"]);
+            let mjds: Vec<f64> = hdu.read_col(&mut f, "mjd").unwrap();
+            assert_eq!(&mjds[..3], &[1.0, 2.0, 2.0]);
+            assert!(mjds[3].is_nan());
+
+            hdu.sort_rows(&mut f, &["name"], Order::Descending).unwrap();
+            let ids: Vec<i32> = hdu.read_col(&mut f, "id").unwrap();
+            assert_eq!(ids, vec![4, 3, 2, 1]);
+
+            assert!(hdu
+                .sort_rows(&mut f, &["vector"], Order::Ascending)
+                .is_err());
+            assert!(hdu
+                .sort_rows(&mut f, &["missing"], Order::Ascending)
+                .is_err());
+            assert!(hdu.sort_rows(&mut f, &[], Order::Ascending).is_err());
+        });
+    }
+
+    #[test]
+    fn test_append_table() {
+        duplicate_test_file(|filename| {
+            let mut f = FitsFile::edit(filename).unwrap();
+            let mut other_f = FitsFile::open("../testdata/full_example.fits").unwrap();
+            let hdu = f.hdu("TESTEXT").unwrap();
+            let other_hdu = other_f.hdu("TESTEXT").unwrap();
+
+            let hdu = hdu.append_table(&mut f, &mut other_f, &other_hdu).unwrap();
+            assert_eq!(hdu.num_rows(&mut f).unwrap(), 100);
+            let original: Vec<String> = other_hdu.read_col(&mut other_f, "strcol").unwrap();
+            let appended: Vec<String> = hdu.read_col(&mut f, "strcol").unwrap();
+            assert_eq!(&appended[..50], &original[..]);
+            assert_eq!(&appended[50..], &original[..]);
+        });
+    }
+
+    #[test]
+    fn test_append_mismatched_table() {
+        with_temp_file(|filename| {
+            let mut f = FitsFile::create(filename).open().unwrap();
+            let table_description = vec![ColumnDescription::new("intcol")
+                .with_type(ColumnDataType::Long)
+                .create()
+                .unwrap()];
+            let hdu = f.create_table("DATA", &table_description).unwrap();
+
+            let mut other_f = FitsFile::open("../testdata/full_example.fits").unwrap();
+            let other_hdu = other_f.hdu("TESTEXT").unwrap();
+            assert!(hdu.append_table(&mut f, &mut other_f, &other_hdu).is_err());
+
+            let other_hdu = other_f.primary_hdu().unwrap();
+            assert!(hdu.append_table(&mut f, &mut other_f, &other_hdu).is_err());
+        });
+    }
+
+    #[test]
+    fn test_column_stats() {
+        let mut f = FitsFile::open("../testdata/full_example.fits").unwrap();
+        let hdu = f.hdu("TESTEXT").unwrap();
+
+        let values: Vec<f64> = hdu.read_col(&mut f, "intcol").unwrap();
+        let mean = values.iter().sum::<f64>() / values.len() as f64;
+        let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / values.len() as f64;
+
+        // Small chunks, to check the statistics are combined across chunks
+        for &chunk_values in &[7, STATS_CHUNK_VALUES] {
+            let stats = hdu
+                .column_stats_chunked::<i32>(&mut f, "intcol", chunk_values)
+                .unwrap();
+            assert_eq!(stats.count, 50);
+            assert_eq!(
+                stats.min as f64,
+                values.iter().cloned().fold(f64::MAX, f64::min)
+            );
+            assert_eq!(
+                stats.max as f64,
+                values.iter().cloned().fold(f64::MIN, f64::max)
+            );
+            assert!((stats.mean - mean).abs() < 1e-9);
+            assert!((stats.std - variance.sqrt()).abs() < 1e-9);
+        }
+
+        assert!(hdu.column_stats::<f64>(&mut f, "missing").is_err());
+    }
+
+    #[test]
+    fn test_image_stats() {
+        with_temp_file(|filename| {
+            let mut f = FitsFile::create(filename).open().unwrap();
+            let description = ImageDescription {
+                data_type: ImageType::Double,
+                dimensions: &[4, 5],
+            };
+            let hdu = f.create_image("IMG", &description).unwrap();
+            let mut data: Vec<f64> = (0..20).map(|i| i as f64).collect();
+            data[3] = f64::NAN;
+            hdu.write_image(&mut f, &data).unwrap();
+
+            let values: Vec<f64> = data.iter().cloned().filter(|v| !v.is_nan()).collect();
+            let mean = values.iter().sum::<f64>() / values.len() as f64;
+            let variance =
+                values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / values.len() as f64;
+
+            for &chunk_values in &[7, STATS_CHUNK_VALUES] {
+                let stats = hdu.image_stats_chunked(&mut f, chunk_values).unwrap();
+                assert_eq!(stats.count, 19);
+                assert_eq!(stats.min, 0.0);
+                assert_eq!(stats.max, 19.0);
+                assert!((stats.mean - mean).abs() < 1e-9);
+                assert!((stats.std - variance.sqrt()).abs() < 1e-9);
+            }
+        });
+
+        let mut f = FitsFile::open("../testdata/full_example.fits").unwrap();
+        let table = f.hdu("TESTEXT").unwrap();
+        assert!(table.image_stats(&mut f).is_err());
+    }
+
+    #[test]
+    fn test_image_stats_skips_blank_pixels() {
+        with_temp_file(|filename| {
+            let mut f = FitsFile::create(filename).open().unwrap();
+            let description = ImageDescription {
+                data_type: ImageType::Short,
+                dimensions: &[2, 3],
+            };
+            let hdu = f.create_image("IMG", &description).unwrap();
+            hdu.write_image(&mut f, &[1i16, 2, -32768, 3, 4, -32768])
+                .unwrap();
+            hdu.set_blank_value(&mut f, Some(-32768)).unwrap();
+
+            for &chunk_values in &[4, STATS_CHUNK_VALUES] {
+                let stats = hdu.image_stats_chunked(&mut f, chunk_values).unwrap();
+                assert_eq!(stats.count, 4);
+                assert_eq!(stats.min, 1.0);
+                assert_eq!(stats.max, 4.0);
+                assert_eq!(stats.mean, 2.5);
+            }
+
+            // Without BLANK every pixel is defined
+            hdu.set_blank_value(&mut f, None).unwrap();
+            let stats = hdu.image_stats(&mut f).unwrap();
+            assert_eq!(stats.count, 6);
+            assert_eq!(stats.min, -32768.0);
+
+            hdu.set_blank_value(&mut f, Some(-32768)).unwrap();
+            hdu.write_image(&mut f, &[-32768i16; 6]).unwrap();
+            assert!(hdu.image_stats(&mut f).is_err());
+        });
+    }
+
+    #[test]
+    fn test_column_stats_skips_undefined_values() {
+        with_temp_file(|filename| {
+            let mut f = FitsFile::create(filename).open().unwrap();
+            let table_description = vec![
+                ColumnDescription::new("flux")
+                    .with_type(ColumnDataType::Double)
+                    .that_repeats(2)
+                    .create()
+                    .unwrap(),
+                ColumnDescription::new("empty")
+                    .with_type(ColumnDataType::Double)
+                    .create()
+                    .unwrap(),
+            ];
+            let hdu = f.create_table("DATA", &table_description).unwrap();
+            hdu.write_col(&mut f, "flux", &[1.0, f64::NAN, 3.0, 4.0, 7.0, 9.0])
+                .unwrap();
+            hdu.write_col(&mut f, "empty", &[f64::NAN; 3]).unwrap();
+
+            let stats = hdu.column_stats_chunked::<f64>(&mut f, "flux", 2).unwrap();
+            assert_eq!(stats.count, 5);
+            assert_eq!(stats.min, 1.0);
+            assert_eq!(stats.max, 9.0);
+            assert_eq!(stats.mean, 4.8);
+
+            assert!(hdu.column_stats::<f64>(&mut f, "empty").is_err());
+        });
+    }
+
+    #[test]
+    fn test_string_col_modes() {
+        use crate::tables::{ColumnDataType, ColumnDescription, ReadStringMode, WriteStringMode};
+
+        with_temp_file(|filename| {
+            let mut f = FitsFile::create(filename).open().unwrap();
+            let columns = vec![
+                ColumnDescription::new("NAME")
+                    .with_type(ColumnDataType::String)
+                    .that_repeats(6)
+                    .create()
+                    .unwrap(),
+                ColumnDescription::new("COUNT")
+                    .with_type(ColumnDataType::Int)
+                    .create()
+                    .unwrap(),
+            ];
+            let hdu = f.create_table("DATA", &columns).unwrap();
+            let values = ["a b ", "cdefgh", ""];
+
+            let hdu = hdu
+                .write_string_col(&mut f, "NAME", &values, WriteStringMode::Padded)
+                .unwrap();
+            assert_eq!(hdu.num_rows(&mut f).unwrap(), 3);
+            assert_eq!(
+                hdu.read_string_col(&mut f, "NAME", ReadStringMode::Raw)
+                    .unwrap(),
+                vec!["a b   ", "cdefgh", "      "]
+            );
+            assert_eq!(
+                hdu.read_string_col(&mut f, "NAME", ReadStringMode::Trimmed)
+                    .unwrap(),
+                vec!["a b", "cdefgh", ""]
+            );
+
+            let hdu = hdu
+                .write_string_col(&mut f, "NAME", &values, WriteStringMode::NulTerminated)
+                .unwrap();
+            assert_eq!(
+                hdu.read_string_col(&mut f, "NAME", ReadStringMode::Raw)
+                    .unwrap(),
+                values
+            );
+            assert_eq!(
+                hdu.read_string_col(&mut f, "NAME", ReadStringMode::Trimmed)
+                    .unwrap(),
+                vec!["a b", "cdefgh", ""]
+            );
+
+            assert!(hdu
+                .write_string_col(&mut f, "NAME", &["too long"], WriteStringMode::Padded)
+                .is_err());
+            assert!(hdu
+                .read_string_col(&mut f, "COUNT", ReadStringMode::Raw)
+                .is_err());
+        });
+    }
+
+    #[test]
+    fn test_read_invalid_utf8() {
+        use crate::errors::Error;
+        use crate::tables::write_table_bytes;
+        use std::fs;
+
+        duplicate_test_file(|filename| {
+            // cfitsio replaces non-ASCII characters when writing cards, so edit the file directly
+            // to add Latin-1 encoded text
+            let mut data = fs::read(filename).unwrap();
+            let replace = |data: &mut Vec<u8>, from: &[u8], to: &[u8]| {
+                let start = data.windows(from.len()).position(|w| w == from).unwrap();
+                data[start..start + to.len()].copy_from_slice(to);
+            };
+            replace(&mut data, b"'value   '", b"'caf\xe9    '");
+            replace(&mut data, b"Double value", b"Double valu\xe9");
+            fs::write(filename, data).unwrap();
+
+            let mut f = FitsFile::edit(filename).unwrap();
+            let hdu = f.primary_hdu().unwrap();
+            match hdu.read_key::<String>(&mut f, "TEST") {
+                Err(Error::InvalidUtf8 { bytes }) => assert_eq!(bytes, b"caf\xe9"),
+                other => panic!("unexpected result {:?}", other),
+            }
+            assert_eq!(hdu.read_key_bytes(&mut f, "TEST").unwrap(), b"caf\xe9");
+            assert_eq!(hdu.read_key_lossy(&mut f, "TEST").unwrap(), "caf\u{fffd}");
+            let cards = hdu.read_keys_matching(&mut f, "DBLTEST").unwrap();
+            assert_eq!(cards[0].comment, "Double valu\u{fffd}");
+
+            let hdu = f.hdu("TESTEXT").unwrap();
+            let start = hdu.column_bytes(&mut f, "strcol").unwrap().start;
+            write_table_bytes(&mut f, 2, start, b"\xe9t\xe9").unwrap();
+            match hdu.read_col::<Vec<String>>(&mut f, "strcol") {
+                Err(Error::InvalidUtf8 { bytes }) => assert_eq!(bytes, b"\xe9t\xe9ue2"),
+                other => panic!("unexpected result {:?}", other),
+            }
+            let values = hdu.read_col_lossy(&mut f, "strcol").unwrap();
+            assert_eq!(values.len(), 50);
+            assert_eq!(values[1], "value1");
+            assert_eq!(values[2], "\u{fffd}t\u{fffd}ue2");
+        });
+    }
+
+    #[test]
+    fn test_raw_bitpix() {
+        with_temp_file(|filename| {
+            {
+                let description = ImageDescription {
+                    data_type: ImageType::Short,
+                    dimensions: &[10, 10],
+                };
+                let mut f = FitsFile::create(filename)
+                    .with_custom_primary(&description)
+                    .open()
+                    .unwrap();
+                let hdu = f.primary_hdu().unwrap();
+                assert_eq!(hdu.image_type().unwrap(), ImageType::Short);
+                assert_eq!(hdu.raw_bitpix().unwrap(), 16);
+                hdu.write_key(&mut f, "BSCALE", 0.5).unwrap();
+            }
+
+            let mut f = FitsFile::open(filename).unwrap();
+            let hdu = f.primary_hdu().unwrap();
+            assert_eq!(hdu.image_type().unwrap(), ImageType::Float);
+            assert_eq!(hdu.raw_bitpix().unwrap(), 16);
+        });
+
+        let mut f = FitsFile::open("../testdata/full_example.fits").unwrap();
+        assert!(f.hdu("TESTEXT").unwrap().raw_bitpix().is_err());
+    }
+
+    #[test]
+    fn test_hdu_sizes() {
+        let mut f = FitsFile::open("../testdata/full_example.fits").unwrap();
+        let primary = f.primary_hdu().unwrap();
+        let num_keys = primary.num_keys(&mut f).unwrap();
+        assert_eq!(
+            num_keys,
+            crate::headers::read_records(&mut f).unwrap().len()
+        );
+        assert_eq!(primary.header_size_bytes(&mut f).unwrap(), 2880);
+        assert_eq!(primary.data_size_bytes(&mut f).unwrap(), 40320);
+
+        // 50 rows of a 4 byte integer, 4 byte float, 8 byte double and 7 character string
+        let table = f.hdu("TESTEXT").unwrap();
+        assert_eq!(table.data_size_bytes(&mut f).unwrap(), 2880);
+        assert_eq!(table.header_size_bytes(&mut f).unwrap() % 2880, 0);
+        assert!(table.num_keys(&mut f).unwrap() > num_keys);
+    }
+
+    #[test]
+    fn test_byte_offsets() {
+        let mut f = FitsFile::open("../testdata/full_example.fits").unwrap();
+        let primary = f.primary_hdu().unwrap();
+        assert_eq!(primary.byte_offsets(&mut f).unwrap(), (0, 2880, 43200));
+
+        // The data unit holds the raw big endian pixel values
+        let table = f.hdu("TESTEXT").unwrap();
+        let (header_start, data_start, data_end) = table.byte_offsets(&mut f).unwrap();
+        assert_eq!(header_start, 43200);
+        let bytes = std::fs::read("../testdata/full_example.fits").unwrap();
+        assert_eq!(bytes.len(), data_end);
+        let first_row: Vec<i32> = primary.read_section(&mut f, 0, 2).unwrap();
+        let raw = i32::from_be_bytes([bytes[2880], bytes[2881], bytes[2882], bytes[2883]]);
+        assert_eq!(raw, first_row[0]);
+        assert!(data_start > header_start);
+    }
+
+    #[test]
+    fn test_write_raw_data() {
+        with_temp_file(|filename| {
+            let mut f = FitsFile::create(filename).open().unwrap();
+            let description = ImageDescription {
+                data_type: ImageType::Long,
+                dimensions: &[2, 3],
+            };
+            let hdu = f.create_image("RAW", &description).unwrap();
+            let values = [1i32, -2, 3, 70000, 5, 6];
+            let bytes: Vec<u8> = values.iter().flat_map(|v| v.to_be_bytes()).collect();
+            unsafe {
+                hdu.write_raw_data(&mut f, 0, &bytes[..8]).unwrap();
+                hdu.write_raw_data(&mut f, 8, &bytes[8..]).unwrap();
+            }
+            let pixels: Vec<i32> = hdu.read_image(&mut f).unwrap();
+            assert_eq!(pixels, values);
+
+            // Writes may extend into the padding, but not beyond the data unit
+            let data_size = hdu.data_size_bytes(&mut f).unwrap();
+            assert_eq!(data_size, 2880);
+            unsafe {
+                assert!(hdu.write_raw_data(&mut f, data_size - 4, &[0; 4]).is_ok());
+                assert!(hdu.write_raw_data(&mut f, data_size - 3, &[0; 4]).is_err());
+            }
+        });
+    }
+
+    #[test]
+    fn test_bound_hdu() {
+        duplicate_test_file(|filename| {
+            let mut f = FitsFile::edit(filename).unwrap();
+
+            let mut hdu = f.hdu_mut(0).unwrap();
+            let image: Vec<i32> = hdu.read_image().unwrap();
+            assert_eq!(hdu.shape().unwrap(), &[100, 100]);
+            hdu.write_section(0, 2, &[-1i32, -2]).unwrap();
+            hdu.write_key("BOUND", 42i64).unwrap();
+            let section: Vec<i32> = hdu.read_section(0, 3).unwrap();
+            assert_eq!(section, vec![-1, -2, image[2]]);
+            assert_eq!(hdu.read_key::<i64>("BOUND").unwrap(), 42);
+
+            let mut table = f.hdu_mut("TESTEXT").unwrap();
+            let num_columns = table.num_columns().unwrap();
+            let num_rows = table.num_rows().unwrap();
+            table.write_col("intcol", &vec![7i32; num_rows]).unwrap();
+            assert_eq!(table.read_cell_value::<i32>("intcol", 3).unwrap(), 7);
+            assert_eq!(table.column_names().unwrap().len(), num_columns);
+            match table.info {
+                HduInfo::TableInfo { num_rows: n, .. } => assert_eq!(n, num_rows),
+                _ => panic!("expected a table"),
+            }
+
+            // The original API still works once the binding is released
+            let hdu = table.into_hdu();
+            let col: Vec<i32> = hdu.read_col(&mut f, "intcol").unwrap();
+            assert_eq!(col, vec![7; num_rows]);
         });
     }
 
@@ -1,12 +1,157 @@
 //! Header-related code
-use crate::errors::{check_status, Result};
+use crate::errors::{check_status, Error, FitsError, Result};
 use crate::fitsfile::FitsFile;
+use crate::hdu::FitsHdu;
 use crate::longnam::*;
+use crate::stringutils::{buf_to_string, buf_to_string_lossy, StringList};
 use crate::types::DataType;
+use std::convert::TryFrom;
 use std::ffi;
 use std::ptr;
 
-const MAX_VALUE_LENGTH: usize = 71;
+pub(crate) const MAX_VALUE_LENGTH: usize = 71;
+const MAX_CARD_LENGTH: usize = 81;
+const MAX_KEYNAME_LENGTH: usize = 75;
+const MAX_COMMENT_LENGTH: usize = 73;
+
+/// Status returned by cfitsio when no more keywords match
+pub(crate) const KEY_NO_EXIST: i32 = 202;
+
+/// A single header card (keyword record)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeaderCard {
+    /// Name of the keyword
+    pub name: String,
+    /**
+    Value of the keyword
+
+    String values have their enclosing quotes and trailing spaces removed. Other values are
+    given as written in the header.
+    */
+    pub value: String,
+    /// Comment of the keyword, or an empty string if there is none
+    ///
+    /// Any characters which are not valid UTF-8 are replaced with `U+FFFD`.
+    pub comment: String,
+}
+
+impl HeaderCard {
+    /// Split a raw 80 character header card into its parts
+    pub(crate) fn parse(card: &mut [c_char]) -> Result<Self> {
+        let mut name: Vec<c_char> = vec![0; MAX_KEYNAME_LENGTH];
+        let mut value: Vec<c_char> = vec![0; MAX_VALUE_LENGTH];
+        let mut comment: Vec<c_char> = vec![0; MAX_COMMENT_LENGTH];
+        let mut length = 0;
+        let mut status = 0;
+
+        unsafe {
+            fits_get_keyname(
+                card.as_mut_ptr(),
+                name.as_mut_ptr(),
+                &mut length,
+                &mut status,
+            );
+            fits_parse_value(
+                card.as_mut_ptr(),
+                value.as_mut_ptr(),
+                comment.as_mut_ptr(),
+                &mut status,
+            );
+        }
+
+        check_status(status)?;
+        Ok(HeaderCard {
+            name: buf_to_string(&name)?,
+            value: unquote(&buf_to_string(&value)?),
+            // Comments of archive files often contain Latin-1 characters
+            comment: buf_to_string_lossy(&comment)?,
+        })
+    }
+}
+
+/**
+Parse a single header card, of at most 80 characters
+
+Cards may only contain printable ASCII characters, so anything else (including nul bytes) is an
+error rather than being passed on to `cfitsio`.
+
+# Example
+
+```rust
+use fitsio::headers::HeaderCard;
+
+let card: HeaderCard = "EXPTIME =                 30.0 / exposure time".parse().unwrap();
+assert_eq!(card.name, "EXPTIME");
+assert_eq!(card.value, "30.0");
+assert_eq!(card.comment, "exposure time");
+```
+*/
+impl std::str::FromStr for HeaderCard {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if s.len() >= MAX_CARD_LENGTH {
+            return Err(Error::Message(format!(
+                "header card is {} characters long, the maximum is {}",
+                s.len(),
+                MAX_CARD_LENGTH - 1
+            )));
+        }
+        if !s.bytes().all(|c| (b' '..=b'~').contains(&c)) {
+            return Err("header card contains non-printable ASCII characters".into());
+        }
+
+        let mut card: Vec<c_char> = s.bytes().map(|c| c as c_char).collect();
+        card.resize(MAX_CARD_LENGTH, 0);
+        HeaderCard::parse(&mut card)
+    }
+}
+
+/// Remove the quotes around a FITS string value
+pub(crate) fn unquote(value: &str) -> String {
+    if value.len() >= 2 && value.starts_with('\'') && value.ends_with('\'') {
+        value[1..value.len() - 1]
+            .replace("''", "'")
+            .trim_end()
+            .to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+/// Read all cards in the current header whose names match `pattern`
+pub(crate) fn read_keys_matching(f: &mut FitsFile, pattern: &str) -> Result<Vec<HeaderCard>> {
+    let patterns = StringList::from_slice(&[pattern.to_string()])?;
+    let mut card: Vec<c_char> = vec![0; MAX_CARD_LENGTH];
+    let mut status = 0;
+
+    // Searching starts after the last record read, so move back to the start of the header
+    unsafe {
+        fits_read_record(f.fptr.as_mut() as *mut _, 0, card.as_mut_ptr(), &mut status);
+    }
+    check_status(status)?;
+
+    let mut cards = Vec::new();
+    loop {
+        unsafe {
+            fits_find_nextkey(
+                f.fptr.as_mut() as *mut _,
+                patterns.as_ptr(),
+                patterns.len as _,
+                ptr::null_mut(),
+                0,
+                card.as_mut_ptr(),
+                &mut status,
+            );
+        }
+
+        match status {
+            0 => cards.push(HeaderCard::parse(&mut card)?),
+            KEY_NO_EXIST => return Ok(cards),
+            _ => return check_status(status).map(|_| cards),
+        }
+    }
+}
 
 /**
 Trait applied to types which can be read from a FITS header
@@ -15,6 +160,7 @@ This is currently:
 
 * i32
 * i64
+* u64
 * f32
 * f64
 * String
@@ -26,10 +172,128 @@ pub trait ReadsKey {
         Self: Sized;
 }
 
+/// The values a key is read as, used to detect lossy reads in strict mode
+#[derive(Debug, Clone, Copy)]
+enum StrictKind {
+    /// Integers in the inclusive range, wide enough for both `i64` and `u64`
+    Integer(i128, i128),
+    /// Integer or floating point numbers up to the given magnitude
+    Float(f64),
+    /// Logical values
+    Logical,
+}
+
+/**
+The type of value stored in a header key
+
+See [`key_type`](../hdu/struct.FitsHdu.html#method.key_type).
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyClass {
+    /// A quoted string
+    String,
+    /// `T` or `F`
+    Logical,
+    /// An integer
+    Integer,
+    /// A floating point number
+    Float,
+    /// A complex number, e.g. `(1.5, -2.0)`
+    Complex,
+    /// The key is present but has no value
+    Undefined,
+}
+
+/// Read the unparsed value of a key, and the type of value it contains
+pub(crate) fn read_raw_value(f: &mut FitsFile, name: &str) -> Result<(String, KeyClass)> {
+    let c_name = ffi::CString::new(name)?;
+    let mut value: Vec<c_char> = vec![0; MAX_VALUE_LENGTH];
+    let mut status = 0;
+
+    unsafe {
+        fits_read_keyword(
+            f.fptr.as_mut() as *mut _,
+            c_name.as_ptr(),
+            value.as_mut_ptr(),
+            ptr::null_mut(),
+            &mut status,
+        );
+    }
+    check_status(status)?;
+
+    let value_string = buf_to_string(&value)?;
+    if value_string.trim().is_empty() {
+        return Ok((value_string, KeyClass::Undefined));
+    }
+
+    let mut key_type: c_char = 0;
+    unsafe {
+        fits_get_keytype(value.as_ptr(), &mut key_type, &mut status);
+    }
+    check_status(status)?;
+
+    let class = match key_type as u8 {
+        b'C' => KeyClass::String,
+        b'L' => KeyClass::Logical,
+        b'I' => KeyClass::Integer,
+        b'F' => KeyClass::Float,
+        b'X' => KeyClass::Complex,
+        other => {
+            return Err(Error::Message(format!(
+                "unknown type {:?} of key {:?}",
+                other as char, name
+            )))
+        }
+    };
+    Ok((value_string, class))
+}
+
+/// In strict mode, refuse to read a key as a type which cannot hold its value
+fn check_strict_read(
+    f: &mut FitsFile,
+    name: &str,
+    kind: StrictKind,
+    type_name: &str,
+) -> Result<()> {
+    if !f.is_strict() {
+        return Ok(());
+    }
+
+    let (value, class) = read_raw_value(f, name)?;
+    let value = value.trim();
+    let lossless = match (kind, class) {
+        (StrictKind::Integer(min, max), KeyClass::Integer) => value
+            .parse::<i128>()
+            .map(|v| v >= min && v <= max)
+            .unwrap_or(false),
+        (StrictKind::Float(max), KeyClass::Integer) | (StrictKind::Float(max), KeyClass::Float) => {
+            value
+                .replace('D', "E")
+                .parse::<f64>()
+                .map(|v| v.abs() <= max)
+                .unwrap_or(false)
+        }
+        (StrictKind::Logical, KeyClass::Logical) => true,
+        _ => false,
+    };
+
+    if lossless {
+        Ok(())
+    } else {
+        Err(format!(
+            "strict mode: cannot read key {} with value {} without loss as {}",
+            name, value, type_name
+        )
+        .as_str()
+        .into())
+    }
+}
+
 macro_rules! reads_key_impl {
-    ($t:ty, $func:ident) => {
+    ($t:ty, $func:ident, $strict_kind:expr) => {
         impl ReadsKey for $t {
             fn read_key(f: &mut FitsFile, name: &str) -> Result<Self> {
+                check_strict_read(f, name, $strict_kind, stringify!($t))?;
                 let c_name = ffi::CString::new(name)?;
                 let mut status = 0;
                 let mut value: Self = Self::default();
@@ -50,47 +314,190 @@ macro_rules! reads_key_impl {
     };
 }
 
-reads_key_impl!(i32, fits_read_key_log);
-#[cfg(all(target_pointer_width = "64", not(target_os = "windows")))]
-reads_key_impl!(i64, fits_read_key_lng);
-#[cfg(any(target_pointer_width = "32", target_os = "windows"))]
-reads_key_impl!(i64, fits_read_key_lnglng);
-reads_key_impl!(f32, fits_read_key_flt);
-reads_key_impl!(f64, fits_read_key_dbl);
+const I32_RANGE: StrictKind = StrictKind::Integer(i32::MIN as i128, i32::MAX as i128);
+const I64_RANGE: StrictKind = StrictKind::Integer(i64::MIN as i128, i64::MAX as i128);
+const U64_RANGE: StrictKind = StrictKind::Integer(0, u64::MAX as i128);
+
+reads_key_impl!(i32, fits_read_key_log, I32_RANGE);
+// `long` is 32 bits on some platforms, so always read through `LONGLONG`
+reads_key_impl!(i64, fits_read_key_lnglng, I64_RANGE);
+
+impl ReadsKey for u64 {
+    fn read_key(f: &mut FitsFile, name: &str) -> Result<Self> {
+        check_strict_read(f, name, U64_RANGE, "u64")?;
+        // cfitsio wraps negative values around rather than reporting an overflow
+        let (raw_value, _) = read_raw_value(f, name)?;
+        if raw_value.trim().starts_with('-') {
+            return Err(Error::Message(format!(
+                "cannot read key {} with negative value {} as u64",
+                name,
+                raw_value.trim()
+            )));
+        }
+
+        let c_name = ffi::CString::new(name)?;
+        let mut status = 0;
+        let mut value = 0;
+
+        unsafe {
+            fits_read_key_ulnglng(
+                f.fptr.as_mut() as *mut _,
+                c_name.as_ptr(),
+                &mut value,
+                ptr::null_mut(),
+                &mut status,
+            );
+        }
+
+        check_status(status).map(|_| value)
+    }
+}
+reads_key_impl!(f32, fits_read_key_flt, StrictKind::Float(f32::MAX as f64));
+reads_key_impl!(f64, fits_read_key_dbl, StrictKind::Float(f64::MAX));
 
 impl ReadsKey for bool {
     fn read_key(f: &mut FitsFile, name: &str) -> Result<Self>
     where
         Self: Sized,
     {
-        let int_value = i32::read_key(f, name)?;
-        Ok(int_value > 0)
-    }
-}
-
-impl ReadsKey for String {
-    fn read_key(f: &mut FitsFile, name: &str) -> Result<Self> {
+        check_strict_read(f, name, StrictKind::Logical, "bool")?;
         let c_name = ffi::CString::new(name)?;
         let mut status = 0;
-        let mut value: Vec<c_char> = vec![0; MAX_VALUE_LENGTH];
+        let mut value = 0;
 
         unsafe {
-            fits_read_key_str(
+            fits_read_key_log(
                 f.fptr.as_mut() as *mut _,
                 c_name.as_ptr(),
-                value.as_mut_ptr(),
+                &mut value,
                 ptr::null_mut(),
                 &mut status,
             );
         }
 
-        check_status(status).and_then(|_| {
-            let value: Vec<u8> = value.iter().map(|&x| x as u8).filter(|&x| x != 0).collect();
-            Ok(String::from_utf8(value)?)
-        })
+        check_status(status).map(|_| value > 0)
+    }
+}
+
+impl ReadsKey for String {
+    fn read_key(f: &mut FitsFile, name: &str) -> Result<Self> {
+        Ok(String::from_utf8(read_key_bytes(f, name)?)?)
     }
 }
 
+/**
+Numeric types any integer or floating point key can be read as
+
+See [`read_key_num`](../hdu/struct.FitsHdu.html#method.read_key_num).
+*/
+pub trait ReadsNumericKey: Sized {
+    #[doc(hidden)]
+    fn from_int(value: i64) -> Option<Self>;
+    #[doc(hidden)]
+    fn from_float(value: f64) -> Option<Self>;
+}
+
+macro_rules! reads_numeric_key_impl_int {
+    ($t:ty) => {
+        impl ReadsNumericKey for $t {
+            fn from_int(value: i64) -> Option<Self> {
+                <$t>::try_from(value).ok()
+            }
+
+            fn from_float(value: f64) -> Option<Self> {
+                // `MAX as f64 + 1.0` is exact for every integer type, unlike `MAX as f64`
+                let in_range = value >= <$t>::MIN as f64 && value < <$t>::MAX as f64 + 1.0;
+                if value.fract() == 0.0 && in_range {
+                    Some(value as $t)
+                } else {
+                    None
+                }
+            }
+        }
+    };
+}
+
+reads_numeric_key_impl_int!(i8);
+reads_numeric_key_impl_int!(i16);
+reads_numeric_key_impl_int!(i32);
+reads_numeric_key_impl_int!(i64);
+reads_numeric_key_impl_int!(u8);
+reads_numeric_key_impl_int!(u16);
+reads_numeric_key_impl_int!(u32);
+reads_numeric_key_impl_int!(u64);
+
+impl ReadsNumericKey for f32 {
+    fn from_int(value: i64) -> Option<Self> {
+        Some(value as f32)
+    }
+
+    fn from_float(value: f64) -> Option<Self> {
+        if value.abs() <= f32::MAX as f64 {
+            Some(value as f32)
+        } else {
+            None
+        }
+    }
+}
+
+impl ReadsNumericKey for f64 {
+    fn from_int(value: i64) -> Option<Self> {
+        Some(value as f64)
+    }
+
+    fn from_float(value: f64) -> Option<Self> {
+        Some(value)
+    }
+}
+
+/// Read an integer or floating point key, converting it to `T`
+pub(crate) fn read_key_num<T: ReadsNumericKey>(f: &mut FitsFile, name: &str) -> Result<T> {
+    let (value, class) = read_raw_value(f, name)?;
+    let value = value.trim();
+    let converted = match class {
+        KeyClass::Integer => value.parse::<i64>().ok().and_then(T::from_int),
+        KeyClass::Float => value
+            .replace('D', "E")
+            .parse::<f64>()
+            .ok()
+            .and_then(T::from_float),
+        _ => {
+            return Err(Error::Message(format!(
+                "key {} with value {} is not a number",
+                name, value
+            )))
+        }
+    };
+    converted.ok_or_else(|| {
+        Error::Message(format!(
+            "cannot read key {} with value {} as {}",
+            name,
+            value,
+            std::any::type_name::<T>()
+        ))
+    })
+}
+
+/// Read the raw bytes of a string key, without checking that they are valid UTF-8
+pub(crate) fn read_key_bytes(f: &mut FitsFile, name: &str) -> Result<Vec<u8>> {
+    let c_name = ffi::CString::new(name)?;
+    let mut status = 0;
+    let mut value: Vec<c_char> = vec![0; MAX_VALUE_LENGTH];
+
+    unsafe {
+        fits_read_key_str(
+            f.fptr.as_mut() as *mut _,
+            c_name.as_ptr(),
+            value.as_mut_ptr(),
+            ptr::null_mut(),
+            &mut status,
+        );
+    }
+
+    check_status(status)?;
+    Ok(value.iter().map(|&x| x as u8).filter(|&x| x != 0).collect())
+}
+
 /// Writing a fits keyword
 pub trait WritesKey {
     #[doc(hidden)]
@@ -131,31 +538,99 @@ writes_key_impl_int!(u16, DataType::TUINT);
 writes_key_impl_int!(u32, DataType::TUINT);
 writes_key_impl_int!(u64, DataType::TULONG);
 
+/**
+Notation floating point header values are written in
+
+See [`FitsFile::set_key_format`](../fitsfile/struct.FitsFile.html#method.set_key_format) and
+[`FitsHdu::write_key_with_format`](../hdu/struct.FitsHdu.html#method.write_key_with_format).
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FloatFormat {
+    /// Exponential notation such as `6.000012346E+04`, with the given number of decimal places
+    Exponential,
+    /// Fixed point notation such as `60000.123456789`, with the given number of decimal places
+    Fixed,
+    /// The shorter of fixed point and exponential notation, like the `%G` format of C, with the
+    /// given number of significant figures
+    General,
+}
+
+impl Default for FloatFormat {
+    fn default() -> Self {
+        FloatFormat::Exponential
+    }
+}
+
+impl FloatFormat {
+    /// Number of decimal places passed to cfitsio, which uses `%G` for negative values
+    fn cfitsio_decimals(self, decimals: usize) -> c_int {
+        match self {
+            FloatFormat::General => -(decimals.max(1) as c_int),
+            FloatFormat::Exponential | FloatFormat::Fixed => decimals as c_int,
+        }
+    }
+}
+
+/// Writing a floating point key in a chosen notation
+pub(crate) trait WritesFloatKey: Sized {
+    fn write_float_key(
+        f: &mut FitsFile,
+        name: &str,
+        value: Self,
+        format: FloatFormat,
+        decimals: usize,
+    ) -> Result<()>;
+}
+
 macro_rules! writes_key_impl_flt {
-    ($t:ty, $func:ident) => {
-        impl WritesKey for $t {
-            fn write_key(f: &mut FitsFile, name: &str, value: Self) -> Result<()> {
+    ($t:ty, $exponential_func:ident, $fixed_func:ident) => {
+        impl WritesFloatKey for $t {
+            fn write_float_key(
+                f: &mut FitsFile,
+                name: &str,
+                value: Self,
+                format: FloatFormat,
+                decimals: usize,
+            ) -> Result<()> {
                 let c_name = ffi::CString::new(name)?;
+                let decimals = format.cfitsio_decimals(decimals);
                 let mut status = 0;
 
                 unsafe {
-                    $func(
-                        f.fptr.as_mut() as *mut _,
-                        c_name.as_ptr(),
-                        value,
-                        9,
-                        ptr::null_mut(),
-                        &mut status,
-                    );
+                    match format {
+                        FloatFormat::Fixed => $fixed_func(
+                            f.fptr.as_mut() as *mut _,
+                            c_name.as_ptr(),
+                            value,
+                            decimals,
+                            ptr::null_mut(),
+                            &mut status,
+                        ),
+                        FloatFormat::Exponential | FloatFormat::General => $exponential_func(
+                            f.fptr.as_mut() as *mut _,
+                            c_name.as_ptr(),
+                            value,
+                            decimals,
+                            ptr::null_mut(),
+                            &mut status,
+                        ),
+                    };
                 }
                 check_status(status)
             }
         }
+
+        impl WritesKey for $t {
+            fn write_key(f: &mut FitsFile, name: &str, value: Self) -> Result<()> {
+                let (format, decimals) = (f.key_format, f.key_decimals);
+                Self::write_float_key(f, name, value, format, decimals)
+            }
+        }
     };
 }
 
-writes_key_impl_flt!(f32, fits_write_key_flt);
-writes_key_impl_flt!(f64, fits_write_key_dbl);
+writes_key_impl_flt!(f32, fits_write_key_flt, fits_write_key_fixflt);
+writes_key_impl_flt!(f64, fits_write_key_dbl, fits_write_key_fixdbl);
 
 impl WritesKey for String {
     fn write_key(f: &mut FitsFile, name: &str, value: Self) -> Result<()> {
@@ -163,6 +638,25 @@ impl WritesKey for String {
     }
 }
 
+impl WritesKey for bool {
+    fn write_key(f: &mut FitsFile, name: &str, value: Self) -> Result<()> {
+        let c_name = ffi::CString::new(name)?;
+        let mut status = 0;
+
+        unsafe {
+            fits_write_key_log(
+                f.fptr.as_mut() as *mut _,
+                c_name.as_ptr(),
+                value as c_int,
+                ptr::null_mut(),
+                &mut status,
+            );
+        }
+
+        check_status(status)
+    }
+}
+
 impl<'a> WritesKey for &'a str {
     fn write_key(f: &mut FitsFile, name: &str, value: Self) -> Result<()> {
         let c_name = ffi::CString::new(name)?;
@@ -183,10 +677,567 @@ impl<'a> WritesKey for &'a str {
     }
 }
 
+/**
+Trait derivable with custom derive, mapping struct fields to header keywords
+
+See the [`fitsio-derive`](https://docs.rs/fitsio-derive) crate.
+*/
+pub trait FitsHeader {
+    /// Read every mapped keyword from the header of `hdu`
+    fn from_header(hdu: &FitsHdu, fits_file: &mut FitsFile) -> Result<Self>
+    where
+        Self: Sized;
+
+    /// Write every mapped keyword to the header of `hdu`
+    fn write_to_header(&self, hdu: &FitsHdu, fits_file: &mut FitsFile) -> Result<()>;
+}
+
+/**
+A header value of any supported type
+
+This allows keys of different types to be written in one call to
+[`write_keys`](../hdu/struct.FitsHdu.html#method.write_keys).
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub enum KeyValue {
+    /// Integer value
+    Int(i64),
+    /// Floating point value
+    Float(f64),
+    /// String value
+    String(String),
+    /// Logical value, written as `T` or `F`
+    Logical(bool),
+}
+
+macro_rules! key_value_from_impl {
+    ($t:ty, $variant:ident, $target:ty) => {
+        impl From<$t> for KeyValue {
+            fn from(value: $t) -> Self {
+                KeyValue::$variant(<$target>::from(value))
+            }
+        }
+    };
+}
+
+key_value_from_impl!(i8, Int, i64);
+key_value_from_impl!(i16, Int, i64);
+key_value_from_impl!(i32, Int, i64);
+key_value_from_impl!(i64, Int, i64);
+key_value_from_impl!(u8, Int, i64);
+key_value_from_impl!(u16, Int, i64);
+key_value_from_impl!(u32, Int, i64);
+key_value_from_impl!(f32, Float, f64);
+key_value_from_impl!(f64, Float, f64);
+key_value_from_impl!(String, String, String);
+key_value_from_impl!(&str, String, String);
+key_value_from_impl!(bool, Logical, bool);
+
+impl WritesKey for KeyValue {
+    fn write_key(f: &mut FitsFile, name: &str, value: Self) -> Result<()> {
+        match value {
+            KeyValue::Int(value) => WritesKey::write_key(f, name, value),
+            KeyValue::Float(value) => WritesKey::write_key(f, name, value),
+            KeyValue::String(value) => WritesKey::write_key(f, name, value),
+            KeyValue::Logical(value) => WritesKey::write_key(f, name, value),
+        }
+    }
+}
+
+/**
+A single keyword of a [`HeaderTemplate`](struct.HeaderTemplate.html)
+
+The unit is written at the start of the comment in square brackets, following the FITS
+convention (and `cfitsio`'s `fits_write_key_unit`), e.g. `EXPTIME = 30.0 / [s] exposure time`.
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub struct TemplateCard {
+    /// Name of the keyword
+    pub name: String,
+    /// Value of the keyword
+    pub value: KeyValue,
+    /// Physical unit of the value
+    pub unit: Option<String>,
+    /// Comment describing the keyword
+    pub comment: Option<String>,
+}
+
+impl TemplateCard {
+    /// Create a card without a unit or comment
+    pub fn new<T: Into<KeyValue>>(name: &str, value: T) -> Self {
+        TemplateCard {
+            name: name.to_string(),
+            value: value.into(),
+            unit: None,
+            comment: None,
+        }
+    }
+
+    /// Set the unit of the value
+    pub fn unit(mut self, unit: &str) -> Self {
+        self.unit = Some(unit.to_string());
+        self
+    }
+
+    /// Set the comment
+    pub fn comment(mut self, comment: &str) -> Self {
+        self.comment = Some(comment.to_string());
+        self
+    }
+
+    /// Format the card as an 80 character header record
+    pub(crate) fn to_record(&self) -> Result<Vec<c_char>> {
+        if let KeyValue::Float(value) = self.value {
+            if !value.is_finite() {
+                return Err(Error::Message(format!(
+                    "cannot write the non-finite value {} to keyword {:?}",
+                    value, self.name
+                )));
+            }
+        }
+        match parse_template_line(&self.to_string())? {
+            Some(record) => Ok(record),
+            None => Err(Error::Message(format!(
+                "{:?} is not a valid keyword name",
+                self.name
+            ))),
+        }
+    }
+
+    /// Convert a header record back into a card
+    fn from_record(record: &mut [c_char]) -> Result<Self> {
+        let raw: String = record
+            .iter()
+            .take_while(|&&c| c != 0)
+            .map(|&c| c as u8 as char)
+            .collect();
+        let card = HeaderCard::parse(record)?;
+
+        let raw_value = raw.get(10..).unwrap_or("").trim_start();
+        let value = if raw_value.starts_with('\'') {
+            KeyValue::String(card.value)
+        } else if card.value == "T" || card.value == "F" {
+            KeyValue::Logical(card.value == "T")
+        } else if let Ok(value) = card.value.parse::<i64>() {
+            KeyValue::Int(value)
+        } else if let Ok(value) = card.value.replace('D', "E").parse::<f64>() {
+            KeyValue::Float(value)
+        } else {
+            return Err(Error::Message(format!(
+                "cannot use the value {:?} of keyword {:?} in a template",
+                card.value, card.name
+            )));
+        };
+
+        let (unit, comment) = match card
+            .comment
+            .strip_prefix('[')
+            .and_then(|c| c.split_once(']'))
+        {
+            Some((unit, comment)) => (Some(unit.trim().to_string()), comment.trim()),
+            None => (None, card.comment.trim()),
+        };
+        Ok(TemplateCard {
+            name: card.name,
+            value,
+            unit,
+            comment: if comment.is_empty() {
+                None
+            } else {
+                Some(comment.to_string())
+            },
+        })
+    }
+}
+
+/// Formats the card as a line of a `cfitsio` template
+impl std::fmt::Display for TemplateCard {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{:<8} = ", self.name)?;
+        match self.value {
+            KeyValue::Int(value) => write!(f, "{}", value)?,
+            // The debug format always includes a decimal point or exponent
+            KeyValue::Float(value) => write!(f, "{}", format!("{:?}", value).to_uppercase())?,
+            KeyValue::String(ref value) => write!(f, "'{}'", value.replace('\'', "''"))?,
+            KeyValue::Logical(value) => write!(f, "{}", if value { "T" } else { "F" })?,
+        }
+        match (&self.unit, &self.comment) {
+            (Some(unit), Some(comment)) => write!(f, " / [{}] {}", unit, comment),
+            (Some(unit), None) => write!(f, " / [{}]", unit),
+            (None, Some(comment)) => write!(f, " / {}", comment),
+            (None, None) => Ok(()),
+        }
+    }
+}
+
+/**
+A reusable set of header keywords, with values, units and comments
+
+Templates bundle standard metadata, such as the description of an instrument, so it can be
+written to any HDU in one call with [`apply`](#method.apply). Applying a template updates any
+keywords which already exist rather than duplicating them.
+
+Templates convert to and from the ASCII template format understood by `cfitsio` (one
+`NAME = value / comment` line per keyword), so they can be kept in text files. Blank lines and
+lines starting with `#` are ignored when parsing.
+
+# Example
+
+```rust
+use fitsio::headers::{HeaderTemplate, TemplateCard};
+
+# fn main() -> Result<(), Box<dyn std::error::Error>> {
+# let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+# let filename = tdir.path().join("test.fits");
+# let mut fptr = fitsio::FitsFile::create(filename).open()?;
+let template = HeaderTemplate::new()
+    .card(TemplateCard::new("TELESCOP", "INT").comment("telescope name"))
+    .card(TemplateCard::new("GAIN", 1.5).unit("e-/adu").comment("detector gain"));
+
+let hdu = fptr.primary_hdu()?;
+template.apply(&hdu, &mut fptr)?;
+assert_eq!(hdu.read_key::<f64>(&mut fptr, "GAIN")?, 1.5);
+
+let text = template.to_string();
+assert_eq!(text.parse::<HeaderTemplate>()?, template);
+# Ok(())
+# }
+```
+*/
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HeaderTemplate {
+    cards: Vec<TemplateCard>,
+}
+
+impl HeaderTemplate {
+    /// Create an empty template
+    pub fn new() -> Self {
+        HeaderTemplate::default()
+    }
+
+    /// Add a card to the template
+    pub fn card(mut self, card: TemplateCard) -> Self {
+        self.cards.push(card);
+        self
+    }
+
+    /// The cards of the template, in order
+    pub fn cards(&self) -> &[TemplateCard] {
+        &self.cards
+    }
+
+    /// Write every card of the template to the header of `hdu`, replacing existing keywords
+    pub fn apply(&self, hdu: &FitsHdu, fits_file: &mut FitsFile) -> Result<()> {
+        fits_file.make_current(hdu)?;
+        fits_check_readwrite!(fits_file);
+
+        for card in &self.cards {
+            let record = card.to_record()?;
+            let c_name = ffi::CString::new(card.name.to_uppercase())?;
+            let mut status = 0;
+            unsafe {
+                fits_update_card(
+                    fits_file.fptr.as_mut() as *mut _,
+                    c_name.as_ptr(),
+                    record.as_ptr(),
+                    &mut status,
+                );
+            }
+            check_status(status)?;
+        }
+        Ok(())
+    }
+}
+
+/// Formats the template in the `cfitsio` ASCII template format, one line per card
+impl std::fmt::Display for HeaderTemplate {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        for card in &self.cards {
+            writeln!(f, "{}", card)?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses a template in the `cfitsio` ASCII template format
+impl std::str::FromStr for HeaderTemplate {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut cards = Vec::new();
+        for line in s.lines() {
+            let trimmed = line.trim();
+            if trimmed.is_empty() || trimmed.starts_with('#') {
+                continue;
+            }
+            match parse_template_line(line)? {
+                Some(mut record) => cards.push(TemplateCard::from_record(&mut record)?),
+                None => {
+                    return Err(Error::Message(format!(
+                        "template line {:?} does not describe a keyword",
+                        line
+                    )))
+                }
+            }
+        }
+        Ok(HeaderTemplate { cards })
+    }
+}
+
+/// Convert a line of a `cfitsio` template to a header record, if it describes a keyword
+fn parse_template_line(line: &str) -> Result<Option<Vec<c_char>>> {
+    let c_line = ffi::CString::new(line)?;
+    let mut template: Vec<c_char> = c_line
+        .as_bytes_with_nul()
+        .iter()
+        .map(|&c| c as c_char)
+        .collect();
+    let mut record: Vec<c_char> = vec![0; MAX_CARD_LENGTH];
+    let mut hdtype = 0;
+    let mut status = 0;
+    unsafe {
+        fits_parse_template(
+            template.as_mut_ptr(),
+            record.as_mut_ptr(),
+            &mut hdtype,
+            &mut status,
+        );
+    }
+    check_status(status)?;
+    // Other types delete keywords, or start a new HDU
+    Ok(if hdtype == 0 { Some(record) } else { None })
+}
+
+/// Read the keywords `root1`, `root2`, ... until one is missing
+pub(crate) fn read_keys_indexed<T: ReadsKey>(f: &mut FitsFile, root: &str) -> Result<Vec<T>> {
+    let mut values = Vec::new();
+    loop {
+        let name = format!("{}{}", root, values.len() + 1);
+        match T::read_key(f, &name) {
+            Ok(value) => values.push(value),
+            Err(Error::Fits(FitsError {
+                status: KEY_NO_EXIST,
+                ..
+            })) => return Ok(values),
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// The root of the indexed keys in a row of a matrix, e.g. `CD2_` for the keys `CD2_1`, `CD2_2`, ...
+fn matrix_row_root(root: &str, row: usize) -> String {
+    format!("{}{}_", root, row + 1)
+}
+
+pub(crate) fn read_key_matrix<T: ReadsKey>(
+    f: &mut FitsFile,
+    root: &str,
+    rows: usize,
+    cols: usize,
+) -> Result<Vec<Vec<T>>> {
+    let mut matrix = Vec::with_capacity(rows);
+    for row in 0..rows {
+        let row_root = matrix_row_root(root, row);
+        let mut values: Vec<T> = read_keys_indexed(f, &row_root)?;
+        if values.len() < cols {
+            return Err(Error::Message(format!(
+                "matrix key {}{} is missing",
+                row_root,
+                values.len() + 1
+            )));
+        }
+        values.truncate(cols);
+        matrix.push(values);
+    }
+    Ok(matrix)
+}
+
+/// Whether a keyword is present in the current header
+pub(crate) fn key_exists(f: &mut FitsFile, name: &str) -> Result<bool> {
+    let c_name = ffi::CString::new(name)?;
+    let mut card: Vec<c_char> = vec![0; MAX_CARD_LENGTH];
+    let mut status = 0;
+    unsafe {
+        fits_read_card(
+            f.fptr.as_mut() as *mut _,
+            c_name.as_ptr(),
+            card.as_mut_ptr(),
+            &mut status,
+        );
+    }
+    match check_status(status) {
+        Ok(()) => Ok(true),
+        Err(Error::Fits(FitsError {
+            status: KEY_NO_EXIST,
+            ..
+        })) => Ok(false),
+        Err(e) => Err(e),
+    }
+}
+
+/// Index of a keyword in the current header, counting from 0
+pub(crate) fn key_index(f: &mut FitsFile, name: &str) -> Result<usize> {
+    let c_name = ffi::CString::new(name)?;
+    let mut card: Vec<c_char> = vec![0; MAX_CARD_LENGTH];
+    let mut position = 0;
+    let mut status = 0;
+    unsafe {
+        fits_read_card(
+            f.fptr.as_mut() as *mut _,
+            c_name.as_ptr(),
+            card.as_mut_ptr(),
+            &mut status,
+        );
+        // Reading a card leaves the position at the next card, which is numbered from 1
+        fits_get_hdrpos(
+            f.fptr.as_mut() as *mut _,
+            ptr::null_mut(),
+            &mut position,
+            &mut status,
+        );
+    }
+    check_status(status).map(|_| position as usize - 2)
+}
+
+/// Insert a card at an index of the current header, counting from 0, moving the card at the
+/// index and all cards after it down
+pub(crate) fn insert_card(f: &mut FitsFile, index: usize, card: &TemplateCard) -> Result<()> {
+    if index == 0 {
+        return Err("cannot insert a card before SIMPLE or XTENSION, which must come first".into());
+    }
+    if key_exists(f, &card.name)? {
+        return Err(Error::Message(format!(
+            "the header already contains {:?}",
+            card.name
+        )));
+    }
+    let record = card.to_record()?;
+    let mut status = 0;
+    unsafe {
+        fits_insert_record(
+            f.fptr.as_mut() as *mut _,
+            (index + 1) as _,
+            record.as_ptr(),
+            &mut status,
+        );
+    }
+    check_status(status)
+}
+
+/// Read a key, returning `None` if it is not present in the header
+pub(crate) fn read_key_optional<T: ReadsKey>(f: &mut FitsFile, name: &str) -> Result<Option<T>> {
+    match T::read_key(f, name) {
+        Ok(value) => Ok(Some(value)),
+        Err(Error::Fits(FitsError {
+            status: KEY_NO_EXIST,
+            ..
+        })) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Write each value to the keywords `root1`, `root2`, ...
+pub(crate) fn write_keys_indexed<T: WritesKey + Clone>(
+    f: &mut FitsFile,
+    root: &str,
+    values: &[T],
+) -> Result<()> {
+    for (i, value) in values.iter().enumerate() {
+        T::write_key(f, &format!("{}{}", root, i + 1), value.clone())?;
+    }
+    Ok(())
+}
+
+pub(crate) fn write_key_matrix<T: WritesKey + Clone, R: AsRef<[T]>>(
+    f: &mut FitsFile,
+    root: &str,
+    values: &[R],
+) -> Result<()> {
+    for (row, values) in values.iter().enumerate() {
+        write_keys_indexed(f, &matrix_row_root(root, row), values.as_ref())?;
+    }
+    Ok(())
+}
+
+/**
+Whether a keyword describes the structure of an HDU
+
+These keywords (e.g. `BITPIX`, `NAXISn`, `XTENSION` and `TFORMn`) are managed by `cfitsio` and
+should not be copied between HDUs with
+[`copy_header_filtered`](../hdu/struct.FitsHdu.html#method.copy_header_filtered).
+
+# Example
+
+```rust
+use fitsio::headers::is_structural_key;
+
+assert!(is_structural_key("NAXIS2"));
+assert!(is_structural_key("TFORM10"));
+assert!(!is_structural_key("TELESCOP"));
+```
+*/
+pub fn is_structural_key(name: &str) -> bool {
+    const FIXED: &[&str] = &[
+        "SIMPLE", "XTENSION", "BITPIX", "NAXIS", "EXTEND", "PCOUNT", "GCOUNT", "GROUPS", "TFIELDS",
+        "THEAP", "END",
+    ];
+    const INDEXED: &[&str] = &["NAXIS", "TFORM", "TBCOL"];
+
+    FIXED.contains(&name)
+        || INDEXED.iter().any(|root| {
+            name.len() > root.len()
+                && name.starts_with(root)
+                && name[root.len()..].bytes().all(|b| b.is_ascii_digit())
+        })
+}
+
+/// Read every card in the current header, with its keyword name
+pub(crate) fn read_records(f: &mut FitsFile) -> Result<Vec<(String, Vec<c_char>)>> {
+    let mut num_keys = 0;
+    let mut status = 0;
+    unsafe {
+        fits_get_hdrspace(
+            f.fptr.as_mut() as *mut _,
+            &mut num_keys,
+            ptr::null_mut(),
+            &mut status,
+        );
+    }
+    check_status(status)?;
+
+    let mut records = Vec::with_capacity(num_keys as usize);
+    for i in 1..=num_keys {
+        let mut card: Vec<c_char> = vec![0; MAX_CARD_LENGTH];
+        let mut name: Vec<c_char> = vec![0; MAX_KEYNAME_LENGTH];
+        let mut length = 0;
+        unsafe {
+            fits_read_record(f.fptr.as_mut() as *mut _, i, card.as_mut_ptr(), &mut status);
+            fits_get_keyname(
+                card.as_mut_ptr(),
+                name.as_mut_ptr(),
+                &mut length,
+                &mut status,
+            );
+        }
+        check_status(status)?;
+        records.push((buf_to_string(&name)?, card));
+    }
+    Ok(records)
+}
+
+/// Append a raw card to the current header
+pub(crate) fn write_record(f: &mut FitsFile, card: &[c_char]) -> Result<()> {
+    let mut status = 0;
+    unsafe {
+        fits_write_record(f.fptr.as_mut() as *mut _, card.as_ptr(), &mut status);
+    }
+    check_status(status)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::testhelpers::{duplicate_test_file, floats_close_f64, with_temp_file};
+    use crate::testing::{duplicate_test_file, floats_close_f64, with_temp_file};
 
     #[test]
     fn test_reading_header_keys() {
@@ -213,6 +1264,484 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_read_keys_matching() {
+        let mut f = FitsFile::open("../testdata/full_example.fits").unwrap();
+        let hdu = f.hdu("TESTEXT").unwrap();
+        let cards = hdu.read_keys_matching(&mut f, "TTYPE*").unwrap();
+        let names: Vec<&str> = cards.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["TTYPE1", "TTYPE2", "TTYPE3", "TTYPE4"]);
+        let values: Vec<&str> = cards.iter().map(|c| c.value.as_str()).collect();
+        assert_eq!(values, vec!["intcol", "floatcol", "doublecol", "strcol"]);
+
+        // Reading again starts from the beginning of the header
+        let cards = hdu.read_keys_matching(&mut f, "TTYPE*").unwrap();
+        assert_eq!(cards.len(), 4);
+
+        let cards = hdu.read_keys_matching(&mut f, "NOSUCH*").unwrap();
+        assert!(cards.is_empty());
+    }
+
+    #[test]
+    fn test_indexed_keys() {
+        duplicate_test_file(|filename| {
+            let mut f = FitsFile::edit(filename).unwrap();
+            let hdu = f.hdu(0).unwrap();
+            hdu.write_keys_indexed(&mut f, "CDELT", &[0.5, 1.5, 2.5])
+                .unwrap();
+            hdu.write_keys_indexed(&mut f, "CTYPE", &["RA---TAN", "DEC--TAN"])
+                .unwrap();
+
+            let values: Vec<f64> = hdu.read_keys_indexed(&mut f, "CDELT").unwrap();
+            assert_eq!(values, vec![0.5, 1.5, 2.5]);
+            let values: Vec<String> = hdu.read_keys_indexed(&mut f, "CTYPE").unwrap();
+            assert_eq!(values, vec!["RA---TAN", "DEC--TAN"]);
+            let values: Vec<i64> = hdu.read_keys_indexed(&mut f, "NAXIS").unwrap();
+            assert_eq!(values, vec![100, 100]);
+            let values: Vec<i64> = hdu.read_keys_indexed(&mut f, "NOSUCH").unwrap();
+            assert!(values.is_empty());
+        });
+    }
+
+    #[test]
+    fn test_read_many_keys() {
+        let mut f = FitsFile::open("../testdata/full_example.fits").unwrap();
+        let hdu = f.primary_hdu().unwrap();
+        let values = hdu
+            .read_keys::<String>(&mut f, &["TEST", "NOTHERE", "TEST"])
+            .unwrap();
+        assert_eq!(values.len(), 3);
+        assert_eq!(values[0].as_ref().unwrap(), "value");
+        assert!(values[1].is_err());
+        assert_eq!(values[2].as_ref().unwrap(), "value");
+
+        // A failed read does not change the HDU that later keys are read from
+        let ext = f.hdu("TESTEXT").unwrap();
+        let values = ext
+            .read_keys::<i64>(&mut f, &["NOTHERE", "TFIELDS"])
+            .unwrap();
+        assert!(values[0].is_err());
+        assert_eq!(values[1].as_ref().unwrap(), &4);
+    }
+
+    #[test]
+    fn test_read_numeric_keys() {
+        with_temp_file(|filename| {
+            let mut f = FitsFile::create(filename).open().unwrap();
+            let hdu = f.primary_hdu().unwrap();
+            hdu.write_key(&mut f, "EXPINT", 10i64).unwrap();
+            hdu.write_key(&mut f, "EXPFLT", 10.0f64).unwrap();
+            hdu.write_key(&mut f, "FRAC", 10.5f64).unwrap();
+            hdu.write_key(&mut f, "BIG", 1e40f64).unwrap();
+            hdu.write_key(&mut f, "NEG", -3i64).unwrap();
+            hdu.write_key(&mut f, "TEXT", "10").unwrap();
+            hdu.write_key(&mut f, "FLAG", true).unwrap();
+
+            assert_eq!(hdu.read_key_num::<f64>(&mut f, "EXPINT").unwrap(), 10.0);
+            assert_eq!(hdu.read_key_num::<f64>(&mut f, "EXPFLT").unwrap(), 10.0);
+            assert_eq!(hdu.read_key_num::<f32>(&mut f, "FRAC").unwrap(), 10.5);
+            assert_eq!(hdu.read_key_num::<i32>(&mut f, "EXPFLT").unwrap(), 10);
+            assert_eq!(hdu.read_key_num::<i64>(&mut f, "NEG").unwrap(), -3);
+
+            // Values which would be truncated or overflow are errors
+            assert!(hdu.read_key_num::<i64>(&mut f, "FRAC").is_err());
+            assert!(hdu.read_key_num::<f32>(&mut f, "BIG").is_err());
+            assert!(hdu.read_key_num::<u16>(&mut f, "NEG").is_err());
+            assert!(hdu.read_key_num::<f64>(&mut f, "TEXT").is_err());
+            assert!(hdu.read_key_num::<f64>(&mut f, "FLAG").is_err());
+            assert!(hdu.read_key_num::<f64>(&mut f, "MISSING").is_err());
+        });
+    }
+
+    #[test]
+    fn test_matrix_keys() {
+        with_temp_file(|filename| {
+            let mut f = FitsFile::create(filename).open().unwrap();
+            let hdu = f.primary_hdu().unwrap();
+            hdu.write_key_matrix(&mut f, "CD", &[[-2.5e-4, 0.0], [0.0, 2.5e-4]])
+                .unwrap();
+            hdu.write_key_matrix(&mut f, "PV", &[vec![0, 1, 2]])
+                .unwrap();
+
+            assert_eq!(hdu.read_key::<f64>(&mut f, "CD2_2").unwrap(), 2.5e-4);
+            let cd: Vec<Vec<f64>> = hdu.read_key_matrix(&mut f, "CD", 2, 2).unwrap();
+            assert_eq!(cd, vec![vec![-2.5e-4, 0.0], vec![0.0, 2.5e-4]]);
+            // Keys beyond the requested shape are ignored
+            let pv: Vec<Vec<i64>> = hdu.read_key_matrix(&mut f, "PV", 1, 2).unwrap();
+            assert_eq!(pv, vec![vec![0, 1]]);
+
+            assert!(hdu.read_key_matrix::<f64>(&mut f, "CD", 3, 2).is_err());
+            assert!(hdu.read_key_matrix::<f64>(&mut f, "CD", 2, 3).is_err());
+        });
+    }
+
+    #[test]
+    fn test_write_many_keys() {
+        duplicate_test_file(|filename| {
+            let mut f = FitsFile::edit(filename).unwrap();
+            let hdu = f.hdu(0).unwrap();
+            hdu.write_keys(
+                &mut f,
+                &[
+                    ("EXPTIME", KeyValue::from(30.5)),
+                    ("NCOMBINE", KeyValue::from(5u16)),
+                    ("OBJECT", KeyValue::from("M31")),
+                    ("DARK", KeyValue::from(true)),
+                ],
+            )
+            .unwrap();
+            hdu.write_keys(&mut f, &[("GAIN1", 1.5f32), ("GAIN2", 2.5f32)])
+                .unwrap();
+
+            assert_eq!(hdu.read_key::<f64>(&mut f, "EXPTIME").unwrap(), 30.5);
+            assert_eq!(hdu.read_key::<i64>(&mut f, "NCOMBINE").unwrap(), 5);
+            assert_eq!(hdu.read_key::<String>(&mut f, "OBJECT").unwrap(), "M31");
+            assert!(hdu.read_key::<bool>(&mut f, "DARK").unwrap());
+            let gains: Vec<f32> = hdu.read_keys_indexed(&mut f, "GAIN").unwrap();
+            assert_eq!(gains, vec![1.5, 2.5]);
+        });
+    }
+
+    #[test]
+    fn test_write_many_keys_requires_write_access() {
+        let mut f = FitsFile::open("../testdata/full_example.fits").unwrap();
+        let hdu = f.hdu(0).unwrap();
+        assert!(hdu.write_keys(&mut f, &[("FOO", 1)]).is_err());
+    }
+
+    #[test]
+    fn test_rename_key() {
+        with_temp_file(|filename| {
+            let mut f = FitsFile::create(filename).open().unwrap();
+            let hdu = f.primary_hdu().unwrap();
+            HeaderTemplate::new()
+                .card(TemplateCard::new("EXPOSURE", 30.0).comment("exposure time"))
+                .card(TemplateCard::new("OBJECT", "M31"))
+                .apply(&hdu, &mut f)
+                .unwrap();
+            let names = |f: &mut FitsFile| -> Vec<String> {
+                let cards = hdu.read_keys_matching(f, "*").unwrap();
+                cards.into_iter().map(|card| card.name).collect()
+            };
+            let position = names(&mut f).iter().position(|name| name == "EXPOSURE");
+
+            hdu.rename_key(&mut f, "EXPOSURE", "EXPTIME").unwrap();
+            assert_eq!(
+                names(&mut f).iter().position(|name| name == "EXPTIME"),
+                position
+            );
+            assert!(!names(&mut f).contains(&"EXPOSURE".to_string()));
+            let cards = hdu.read_keys_matching(&mut f, "EXPTIME").unwrap();
+            assert_eq!(cards[0].value, "30.0");
+            assert_eq!(cards[0].comment, "exposure time");
+
+            assert!(hdu.rename_key(&mut f, "EXPOSURE", "EXPTIME2").is_err());
+            assert!(hdu.rename_key(&mut f, "EXPTIME", "OBJECT").is_err());
+            assert_eq!(hdu.read_key::<f64>(&mut f, "EXPTIME").unwrap(), 30.0);
+        });
+
+        let mut f = FitsFile::open("../testdata/full_example.fits").unwrap();
+        let hdu = f.hdu(0).unwrap();
+        assert!(hdu.rename_key(&mut f, "INTTEST", "INTTEST2").is_err());
+    }
+
+    #[test]
+    fn test_insert_cards() {
+        with_temp_file(|filename| {
+            let mut f = FitsFile::create(filename).open().unwrap();
+            let hdu = f.primary_hdu().unwrap();
+            hdu.write_key(&mut f, "OBJECT", "M31").unwrap();
+            hdu.write_key(&mut f, "DATE-OBS", "2024-01-01").unwrap();
+            let names = |f: &mut FitsFile| -> Vec<String> {
+                let cards = hdu.read_keys_matching(f, "*").unwrap();
+                cards.into_iter().map(|card| card.name).collect()
+            };
+
+            hdu.write_key_before(&mut f, "DATE-OBS", "TELESCOP", "HST")
+                .unwrap();
+            hdu.write_key_before(&mut f, "DATE-OBS", "SIMULATE", true)
+                .unwrap();
+            assert!(hdu.read_key::<bool>(&mut f, "SIMULATE").unwrap());
+            // SIMPLE must stay the first card
+            assert!(hdu
+                .insert_card_at(&mut f, 0, &TemplateCard::new("FIRST", 1))
+                .is_err());
+            assert!(hdu.write_key_before(&mut f, "SIMPLE", "FIRST", 1).is_err());
+            let num_keys = hdu.num_keys(&mut f).unwrap();
+            let last = TemplateCard::new("LAST", 2.5).comment("at the end");
+            hdu.insert_card_at(&mut f, num_keys, &last).unwrap();
+
+            let names = names(&mut f);
+            assert_eq!(names[0], "SIMPLE");
+            assert!(!names.contains(&"FIRST".to_string()));
+            assert!(names.ends_with(&[
+                "OBJECT".to_string(),
+                "TELESCOP".to_string(),
+                "SIMULATE".to_string(),
+                "DATE-OBS".to_string(),
+                "LAST".to_string(),
+            ]));
+            let cards = hdu.read_keys_matching(&mut f, "LAST").unwrap();
+            assert_eq!(cards[0].comment, "at the end");
+
+            // Existing keys are not duplicated
+            assert!(hdu
+                .write_key_before(&mut f, "OBJECT", "TELESCOP", "JWST")
+                .is_err());
+            assert!(hdu
+                .write_key_before(&mut f, "NOSUCH", "FILTER", "V")
+                .is_err());
+            let num_keys = hdu.num_keys(&mut f).unwrap();
+            assert!(hdu
+                .insert_card_at(&mut f, num_keys + 1, &TemplateCard::new("FILTER", "V"))
+                .is_err());
+            assert_eq!(hdu.read_key::<String>(&mut f, "TELESCOP").unwrap(), "HST");
+        });
+    }
+
+    #[test]
+    fn test_float_key_precision() {
+        with_temp_file(|filename| {
+            let mut f = FitsFile::create(filename).open().unwrap();
+            let hdu = f.primary_hdu().unwrap();
+            let mjd = 60000.123456789;
+            let raw = |f: &mut FitsFile, name: &str| -> String {
+                hdu.read_keys_matching(f, name).unwrap()[0].value.clone()
+            };
+
+            assert_eq!(f.key_precision(), 9);
+            hdu.write_key(&mut f, "DEFAULT", mjd).unwrap();
+            assert_eq!(raw(&mut f, "DEFAULT"), "6.000012346E+04");
+
+            hdu.write_key_with_precision(&mut f, "PRECISE", mjd, 15)
+                .unwrap();
+            assert_eq!(hdu.read_key::<f64>(&mut f, "PRECISE").unwrap(), mjd);
+            hdu.write_key_with_precision(&mut f, "COMPACT", 1.23456, 2)
+                .unwrap();
+            assert_eq!(raw(&mut f, "COMPACT"), "1.23E+00");
+
+            f.set_key_precision(3);
+            hdu.write_key(&mut f, "SHORT", mjd).unwrap();
+            hdu.write_key(&mut f, "SHORT32", 2.5f32).unwrap();
+            assert_eq!(raw(&mut f, "SHORT"), "6.000E+04");
+            assert_eq!(raw(&mut f, "SHORT32"), "2.500E+00");
+            // The file's precision does not override an explicit precision
+            hdu.write_key_with_precision(&mut f, "MJD-OBS", mjd, 15)
+                .unwrap();
+            assert_eq!(hdu.read_key::<f64>(&mut f, "MJD-OBS").unwrap(), mjd);
+        });
+    }
+
+    #[test]
+    fn test_float_key_formats() {
+        with_temp_file(|filename| {
+            let mut f = FitsFile::create(filename).open().unwrap();
+            let hdu = f.primary_hdu().unwrap();
+            let raw = |f: &mut FitsFile, name: &str| -> String {
+                hdu.read_keys_matching(f, name).unwrap()[0].value.clone()
+            };
+
+            assert_eq!(f.key_format(), FloatFormat::Exponential);
+            hdu.write_key_with_format(&mut f, "EXP", 30.0, FloatFormat::Exponential, 2)
+                .unwrap();
+            hdu.write_key_with_format(&mut f, "FIXED", 30.0, FloatFormat::Fixed, 2)
+                .unwrap();
+            hdu.write_key_with_format(&mut f, "GENERAL", 30.0, FloatFormat::General, 6)
+                .unwrap();
+            hdu.write_key_with_format(&mut f, "SMALL", 1.5e-12, FloatFormat::General, 3)
+                .unwrap();
+            assert_eq!(raw(&mut f, "EXP"), "3.00E+01");
+            assert_eq!(raw(&mut f, "FIXED"), "30.00");
+            assert_eq!(raw(&mut f, "GENERAL"), "30.");
+            assert_eq!(raw(&mut f, "SMALL"), "1.5E-12");
+
+            f.set_key_format(FloatFormat::Fixed);
+            f.set_key_precision(3);
+            hdu.write_key(&mut f, "FIXED32", 2.5f32).unwrap();
+            hdu.write_key(&mut f, "FIXED64", 2.5f64).unwrap();
+            assert_eq!(raw(&mut f, "FIXED32"), "2.500");
+            assert_eq!(raw(&mut f, "FIXED64"), "2.500");
+        });
+    }
+
+    #[test]
+    fn test_64_bit_key_reads() {
+        with_temp_file(|filename| {
+            let mut f = FitsFile::create(filename).open().unwrap();
+            let hdu = f.primary_hdu().unwrap();
+            hdu.write_key(&mut f, "IMAX", i64::MAX).unwrap();
+            hdu.write_key(&mut f, "IMIN", i64::MIN).unwrap();
+            hdu.write_key(&mut f, "UMAX", u64::MAX).unwrap();
+            hdu.write_key(&mut f, "NEG", -1i64).unwrap();
+
+            assert_eq!(hdu.read_key::<i64>(&mut f, "IMAX").unwrap(), i64::MAX);
+            assert_eq!(hdu.read_key::<i64>(&mut f, "IMIN").unwrap(), i64::MIN);
+            assert_eq!(hdu.read_key::<u64>(&mut f, "UMAX").unwrap(), u64::MAX);
+            assert_eq!(
+                hdu.read_key::<u64>(&mut f, "IMAX").unwrap(),
+                i64::MAX as u64
+            );
+            assert!(hdu.read_key::<i64>(&mut f, "UMAX").is_err());
+            assert!(hdu.read_key::<u64>(&mut f, "NEG").is_err());
+        });
+    }
+
+    #[test]
+    fn test_strict_key_reads() {
+        with_temp_file(|filename| {
+            let mut f = FitsFile::create(filename).open().unwrap().strict();
+            let hdu = f.primary_hdu().unwrap();
+            hdu.write_key(&mut f, "INTTEST", 42i64).unwrap();
+            hdu.write_key(&mut f, "BIGINT", 1i64 << 40).unwrap();
+            hdu.write_key(&mut f, "DBLTEST", 0.09375f64).unwrap();
+            hdu.write_key(&mut f, "STRTEST", "1.5").unwrap();
+
+            assert_eq!(hdu.read_key::<i64>(&mut f, "INTTEST").unwrap(), 42);
+            assert_eq!(hdu.read_key::<f32>(&mut f, "INTTEST").unwrap(), 42.0);
+            assert_eq!(hdu.read_key::<i64>(&mut f, "BIGINT").unwrap(), 1 << 40);
+            assert_eq!(hdu.read_key::<f64>(&mut f, "DBLTEST").unwrap(), 0.09375);
+            assert!(hdu.read_key::<bool>(&mut f, "SIMPLE").unwrap());
+            assert!(hdu.read_key::<String>(&mut f, "DBLTEST").is_ok());
+
+            match hdu.read_key::<i32>(&mut f, "BIGINT") {
+                Err(Error::Message(msg)) => assert_eq!(
+                    msg,
+                    "strict mode: cannot read key BIGINT with value 1099511627776 without loss as i32"
+                ),
+                _ => panic!("invalid result"),
+            }
+            assert!(hdu.read_key::<i64>(&mut f, "DBLTEST").is_err());
+            assert!(hdu.read_key::<f64>(&mut f, "STRTEST").is_err());
+            assert!(hdu.read_key::<bool>(&mut f, "INTTEST").is_err());
+
+            /* Missing keys are reported as usual */
+            match hdu.read_key::<i64>(&mut f, "MISSING") {
+                Err(Error::Fits(e)) => assert_eq!(e.status, KEY_NO_EXIST),
+                _ => panic!("invalid result"),
+            }
+
+            f.set_strict(false);
+            assert_eq!(hdu.read_key::<i64>(&mut f, "DBLTEST").unwrap(), 0);
+        });
+    }
+
+    #[test]
+    fn test_parse_header_card() {
+        let card: HeaderCard = "OBJECT  = 'M31 ''core'''  / target".parse().unwrap();
+        assert_eq!(card.name, "OBJECT");
+        assert_eq!(card.value, "M31 'core'");
+        assert_eq!(card.comment, "target");
+
+        let card: HeaderCard = "COMMENT just some text".parse().unwrap();
+        assert_eq!(card.name, "COMMENT");
+
+        assert!("KEY     = 1\0".parse::<HeaderCard>().is_err());
+        assert!("KEY     = '\u{e9}'".parse::<HeaderCard>().is_err());
+        assert!(format!("KEY     = {}", "1".repeat(80))
+            .parse::<HeaderCard>()
+            .is_err());
+    }
+
+    #[test]
+    fn test_key_type() {
+        duplicate_test_file(|filename| {
+            let mut f = FitsFile::edit(filename).unwrap();
+            let hdu = f.primary_hdu().unwrap();
+            hdu.write_key(&mut f, "EXPTEST", 1.5e10).unwrap();
+
+            let key_type = |f: &mut FitsFile, name| hdu.key_type(f, name).unwrap();
+            assert_eq!(key_type(&mut f, "TEST"), KeyClass::String);
+            assert_eq!(key_type(&mut f, "SIMPLE"), KeyClass::Logical);
+            assert_eq!(key_type(&mut f, "INTTEST"), KeyClass::Integer);
+            assert_eq!(key_type(&mut f, "DBLTEST"), KeyClass::Float);
+            assert_eq!(key_type(&mut f, "EXPTEST"), KeyClass::Float);
+
+            let mut status = 0;
+            let card = ffi::CString::new("UNDEF   =                      / no value").unwrap();
+            let complex = ffi::CString::new("CPLX    = (1.5, -2.0)").unwrap();
+            unsafe {
+                fits_write_record(f.fptr.as_mut() as *mut _, card.as_ptr(), &mut status);
+                fits_write_record(f.fptr.as_mut() as *mut _, complex.as_ptr(), &mut status);
+            }
+            check_status(status).unwrap();
+            assert_eq!(key_type(&mut f, "UNDEF"), KeyClass::Undefined);
+            assert_eq!(key_type(&mut f, "CPLX"), KeyClass::Complex);
+
+            match hdu.key_type(&mut f, "MISSING") {
+                Err(Error::Fits(e)) => assert_eq!(e.status, KEY_NO_EXIST),
+                _ => panic!("invalid result"),
+            }
+        });
+    }
+
+    #[test]
+    fn test_header_template() {
+        let template = HeaderTemplate::new()
+            .card(TemplateCard::new("TELESCOP", "O'Brien").comment("telescope"))
+            .card(
+                TemplateCard::new("EXPTIME", 30.0)
+                    .unit("s")
+                    .comment("exposure time"),
+            )
+            .card(TemplateCard::new("NCOMBINE", 5))
+            .card(TemplateCard::new("TINY", 1.5e-12).unit("W"))
+            .card(TemplateCard::new("DARK", false).comment("dark frame"));
+
+        let text = template.to_string();
+        assert_eq!(text.lines().count(), 5);
+        assert!(text.contains("DARK     = F / dark frame"));
+        assert!(text.contains("EXPTIME  = 30.0 / [s] exposure time"));
+        let parsed: HeaderTemplate = text.parse().unwrap();
+        assert_eq!(parsed, template);
+
+        // Free-format cfitsio templates, with comments and blank lines
+        let parsed: HeaderTemplate = "# instrument\n\nGAIN 2.5 / [e-/adu] gain\nOBSERVER = 'Jo'\n"
+            .parse()
+            .unwrap();
+        assert_eq!(
+            parsed.cards(),
+            &[
+                TemplateCard::new("GAIN", 2.5)
+                    .unit("e-/adu")
+                    .comment("gain"),
+                TemplateCard::new("OBSERVER", "Jo"),
+            ]
+        );
+        assert!("END".parse::<HeaderTemplate>().is_err());
+        let parsed: HeaderTemplate = "FLAG = T".parse().unwrap();
+        assert_eq!(parsed.cards(), &[TemplateCard::new("FLAG", true)]);
+
+        with_temp_file(|filename| {
+            let mut f = FitsFile::create(filename).open().unwrap();
+            let hdu = f.primary_hdu().unwrap();
+            template.apply(&hdu, &mut f).unwrap();
+            // Applying again updates the keywords rather than duplicating them
+            let num_keys = hdu.num_keys(&mut f).unwrap();
+            template.apply(&hdu, &mut f).unwrap();
+            assert_eq!(hdu.num_keys(&mut f).unwrap(), num_keys);
+
+            assert_eq!(
+                hdu.read_key::<String>(&mut f, "TELESCOP").unwrap(),
+                "O'Brien"
+            );
+            assert_eq!(hdu.read_key::<f64>(&mut f, "EXPTIME").unwrap(), 30.0);
+            assert_eq!(hdu.read_key::<i64>(&mut f, "NCOMBINE").unwrap(), 5);
+            assert!(!hdu.read_key::<bool>(&mut f, "DARK").unwrap());
+            let cards = hdu.read_keys_matching(&mut f, "EXPTIME").unwrap();
+            assert_eq!(cards[0].comment, "[s] exposure time");
+
+            let nan = HeaderTemplate::new().card(TemplateCard::new("BAD", f64::NAN));
+            assert!(nan.apply(&hdu, &mut f).is_err());
+        });
+    }
+
+    #[test]
+    fn test_unquote() {
+        assert_eq!(unquote("'value   '"), "value");
+        assert_eq!(unquote("'it''s'"), "it's");
+        assert_eq!(unquote("42"), "42");
+    }
+
     #[test]
     fn test_writing_header_keywords() {
         with_temp_file(|filename| {
@@ -254,6 +1783,21 @@ mod tests {
         });
     }
 
+    #[test]
+    fn test_writing_booleans() {
+        with_temp_file(|filename| {
+            let mut f = FitsFile::create(filename).open().unwrap();
+            let hdu = f.primary_hdu().unwrap();
+            hdu.write_key(&mut f, "GAINCORR", true).unwrap();
+            hdu.write_key(&mut f, "FLATCORR", false).unwrap();
+
+            let cards = hdu.read_keys_matching(&mut f, "GAINCORR").unwrap();
+            assert_eq!(cards[0].value, "T");
+            assert!(hdu.read_key::<bool>(&mut f, "GAINCORR").unwrap());
+            assert!(!hdu.read_key::<bool>(&mut f, "FLATCORR").unwrap());
+        });
+    }
+
     #[test]
     fn boolean_header_values() {
         let mut f = FitsFile::open("../testdata/full_example.fits").unwrap();
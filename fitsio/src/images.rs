@@ -1,11 +1,16 @@
 //! Image related code
-use crate::errors::{check_status, Result};
+use crate::errors::{check_status, Error, Result};
 use crate::fitsfile::FitsFile;
-use crate::hdu::{FitsHdu, HduInfo};
+use crate::hdu::{DescribesHdu, FitsHdu, HduInfo};
+use crate::headers::{read_key_optional, WritesKey, KEY_NO_EXIST, MAX_VALUE_LENGTH};
 use crate::longnam::*;
+use crate::ranges::RegionRanges;
 use crate::types::DataType;
+use std::ffi;
+use std::fmt;
 use std::ops::Range;
 use std::ptr;
+use std::str::FromStr;
 
 /// Reading fits images
 pub trait ReadImage: Sized {
@@ -27,7 +32,8 @@ pub trait ReadImage: Sized {
     fn read_region(
         fits_file: &mut FitsFile,
         hdu: &FitsHdu,
-        ranges: &[&Range<usize>],
+        ranges: &[Range<usize>],
+        steps: &[usize],
     ) -> Result<Self>;
 
     #[doc(hidden)]
@@ -60,7 +66,7 @@ pub trait WriteImage: Sized {
     fn write_region(
         fits_file: &mut FitsFile,
         hdu: &FitsHdu,
-        ranges: &[&Range<usize>],
+        ranges: &[Range<usize>],
         data: &[Self],
     ) -> Result<()>;
 
@@ -88,8 +94,104 @@ pub trait WriteImage: Sized {
     }
 }
 
+/// Number of pixels processed at once when operating on whole images
+pub(crate) const IMAGE_CHUNK_PIXELS: usize = 1 << 16;
+
+/// Override the scaling cfitsio applies to pixel values in the current HDU
+pub(crate) fn set_image_scaling(fits_file: &mut FitsFile, scale: f64, offset: f64) -> Result<()> {
+    let mut status = 0;
+    unsafe {
+        fits_set_bscale(
+            fits_file.fptr.as_mut() as *mut _,
+            scale,
+            offset,
+            &mut status,
+        );
+    }
+    check_status(status)
+}
+
+/**
+How the values of a pixel type are represented
+
+Integers are described by their number of bits, and floating point numbers by the number of
+bits in their mantissa, i.e. the size of integers they can represent exactly.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PixelKind {
+    Signed(u32),
+    Unsigned(u32),
+    Float(u32),
+}
+
+impl PixelKind {
+    /// Whether every value of this kind can be represented exactly by `other`
+    pub(crate) fn fits_in(self, other: PixelKind) -> bool {
+        match (self, other) {
+            (PixelKind::Signed(bits), PixelKind::Signed(other_bits))
+            | (PixelKind::Unsigned(bits), PixelKind::Unsigned(other_bits)) => bits <= other_bits,
+            (PixelKind::Unsigned(bits), PixelKind::Signed(other_bits)) => bits < other_bits,
+            (PixelKind::Signed(bits), PixelKind::Float(mantissa))
+            | (PixelKind::Unsigned(bits), PixelKind::Float(mantissa)) => bits <= mantissa,
+            (PixelKind::Float(mantissa), PixelKind::Float(other_mantissa)) => {
+                mantissa <= other_mantissa
+            }
+            (PixelKind::Signed(_), PixelKind::Unsigned(_)) | (PixelKind::Float(_), _) => false,
+        }
+    }
+}
+
+impl ImageType {
+    pub(crate) fn pixel_kind(self) -> PixelKind {
+        match self {
+            ImageType::UnsignedByte => PixelKind::Unsigned(8),
+            ImageType::Byte => PixelKind::Signed(8),
+            ImageType::Short => PixelKind::Signed(16),
+            ImageType::UnsignedShort => PixelKind::Unsigned(16),
+            ImageType::Long => PixelKind::Signed(32),
+            ImageType::UnsignedLong => PixelKind::Unsigned(32),
+            ImageType::LongLong => PixelKind::Signed(64),
+            ImageType::Float => PixelKind::Float(24),
+            ImageType::Double => PixelKind::Float(53),
+        }
+    }
+}
+
+/// In strict mode, refuse to read pixels of `image_type` into a type which cannot hold them
+fn check_strict_read(
+    fits_file: &FitsFile,
+    image_type: ImageType,
+    kind: PixelKind,
+    type_name: &str,
+) -> Result<()> {
+    if !fits_file.is_strict() || image_type.pixel_kind().fits_in(kind) {
+        return Ok(());
+    }
+
+    Err(format!(
+        "strict mode: cannot read {:?} image without loss into {}",
+        image_type, type_name
+    )
+    .as_str()
+    .into())
+}
+
+/// Number of pixels along each axis of a region read with the given steps
+pub(crate) fn region_shape(ranges: &[Range<usize>], steps: &[usize]) -> Vec<usize> {
+    ranges
+        .iter()
+        .zip(steps)
+        .map(|(range, &step)| (range.end.saturating_sub(range.start) + step - 1) / step)
+        .collect()
+}
+
+/// Total number of pixels in a region read with the given steps
+pub(crate) fn region_num_pixels(ranges: &[Range<usize>], steps: &[usize]) -> usize {
+    region_shape(ranges, steps).iter().product()
+}
+
 macro_rules! read_image_impl_vec {
-    ($t:ty, $default_value:expr, $data_type:expr) => {
+    ($t:ty, $default_value:expr, $data_type:expr, $pixel_kind:expr) => {
         impl ReadImage for Vec<$t> {
             fn read_section(
                 fits_file: &mut FitsFile,
@@ -97,7 +199,8 @@ macro_rules! read_image_impl_vec {
                 range: Range<usize>,
             ) -> Result<Self> {
                 match hdu.info {
-                    HduInfo::ImageInfo { .. } => {
+                    HduInfo::ImageInfo { image_type, .. } => {
+                        check_strict_read(fits_file, image_type, $pixel_kind, stringify!($t))?;
                         let nelements = range.end - range.start;
                         let mut out = vec![$default_value; nelements];
                         let mut status = 0;
@@ -156,27 +259,27 @@ macro_rules! read_image_impl_vec {
             fn read_region(
                 fits_file: &mut FitsFile,
                 hdu: &FitsHdu,
-                ranges: &[&Range<usize>],
+                ranges: &[Range<usize>],
+                steps: &[usize],
             ) -> Result<Self> {
                 match hdu.info {
-                    HduInfo::ImageInfo { .. } => {
+                    HduInfo::ImageInfo { image_type, .. } => {
+                        check_strict_read(fits_file, image_type, $pixel_kind, stringify!($t))?;
                         let n_ranges = ranges.len();
 
                         let mut fpixel = Vec::with_capacity(n_ranges);
                         let mut lpixel = Vec::with_capacity(n_ranges);
 
-                        let mut nelements = 1;
                         for range in ranges {
                             let start = range.start + 1;
                             // No +1 as the range is exclusive
                             let end = range.end;
                             fpixel.push(start as _);
                             lpixel.push(end as _);
-
-                            nelements *= (end + 1) - start;
                         }
 
-                        let mut inc: Vec<_> = (0..n_ranges).map(|_| 1).collect();
+                        let nelements = region_num_pixels(ranges, steps);
+                        let mut inc: Vec<_> = steps.iter().map(|&step| step as _).collect();
                         let vec_size = nelements;
                         let mut out = vec![$default_value; vec_size];
                         let mut status = 0;
@@ -244,7 +347,7 @@ macro_rules! write_image_impl {
             fn write_region(
                 fits_file: &mut FitsFile,
                 hdu: &FitsHdu,
-                ranges: &[&Range<usize>],
+                ranges: &[Range<usize>],
                 data: &[Self],
             ) -> Result<()> {
                 match hdu.info {
@@ -287,22 +390,47 @@ macro_rules! write_image_impl {
     };
 }
 
-read_image_impl_vec!(i8, i8::default(), DataType::TSBYTE);
-read_image_impl_vec!(i16, i16::default(), DataType::TSHORT);
-read_image_impl_vec!(i32, i32::default(), DataType::TINT);
+read_image_impl_vec!(i8, i8::default(), DataType::TSBYTE, PixelKind::Signed(8));
+read_image_impl_vec!(i16, i16::default(), DataType::TSHORT, PixelKind::Signed(16));
+read_image_impl_vec!(i32, i32::default(), DataType::TINT, PixelKind::Signed(32));
 #[cfg(all(target_pointer_width = "64", not(target_os = "windows")))]
-read_image_impl_vec!(i64, i64::default(), DataType::TLONG);
+read_image_impl_vec!(i64, i64::default(), DataType::TLONG, PixelKind::Signed(64));
 #[cfg(any(target_pointer_width = "32", target_os = "windows"))]
-read_image_impl_vec!(i64, i64::default(), DataType::TLONGLONG);
-read_image_impl_vec!(u8, u8::default(), DataType::TBYTE);
-read_image_impl_vec!(u16, u16::default(), DataType::TUSHORT);
-read_image_impl_vec!(u32, u32::default(), DataType::TUINT);
+read_image_impl_vec!(
+    i64,
+    i64::default(),
+    DataType::TLONGLONG,
+    PixelKind::Signed(64)
+);
+read_image_impl_vec!(u8, u8::default(), DataType::TBYTE, PixelKind::Unsigned(8));
+read_image_impl_vec!(
+    u16,
+    u16::default(),
+    DataType::TUSHORT,
+    PixelKind::Unsigned(16)
+);
+read_image_impl_vec!(
+    u32,
+    u32::default(),
+    DataType::TUINT,
+    PixelKind::Unsigned(32)
+);
 #[cfg(all(target_pointer_width = "64", not(target_os = "windows")))]
-read_image_impl_vec!(u64, u64::default(), DataType::TULONG);
+read_image_impl_vec!(
+    u64,
+    u64::default(),
+    DataType::TULONG,
+    PixelKind::Unsigned(64)
+);
 #[cfg(any(target_pointer_width = "32", target_os = "windows"))]
-read_image_impl_vec!(u64, u64::default(), DataType::TLONGLONG);
-read_image_impl_vec!(f32, f32::default(), DataType::TFLOAT);
-read_image_impl_vec!(f64, f64::default(), DataType::TDOUBLE);
+read_image_impl_vec!(
+    u64,
+    u64::default(),
+    DataType::TLONGLONG,
+    PixelKind::Unsigned(64)
+);
+read_image_impl_vec!(f32, f32::default(), DataType::TFLOAT, PixelKind::Float(24));
+read_image_impl_vec!(f64, f64::default(), DataType::TDOUBLE, PixelKind::Float(53));
 
 write_image_impl!(i8, i8::default(), DataType::TSBYTE);
 write_image_impl!(i16, i16::default(), DataType::TSHORT);
@@ -336,6 +464,1165 @@ pub struct ImageDescription<'a> {
     pub dimensions: &'a [usize],
 }
 
+/**
+The pixels to use along a single axis of a [`Region`]
+
+A section covers a zero-indexed range of pixels, which is inclusive of the lower bound and
+*exclusive* of the upper bound, optionally taking only every `stride`th pixel.
+
+[`Region`]: struct.Region.html
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Section {
+    /// Range of pixels along the axis
+    pub range: Range<usize>,
+    /// Step between pixels, where `1` uses every pixel
+    pub stride: usize,
+}
+
+impl Section {
+    /// Create a new section using every pixel in `range`
+    pub fn new(range: Range<usize>) -> Self {
+        Section { range, stride: 1 }
+    }
+
+    /// Only use every `stride`th pixel
+    pub fn with_stride(mut self, stride: usize) -> Self {
+        self.stride = stride;
+        self
+    }
+
+    /// Restrict the section to an axis of length `len`
+    pub fn clip_to(mut self, len: usize) -> Self {
+        self.range.end = self.range.end.min(len);
+        self.range.start = self.range.start.min(self.range.end);
+        self
+    }
+
+    /// Number of pixels in the section, accounting for the stride
+    pub fn len(&self) -> usize {
+        let stride = self.stride.max(1);
+        (self.range.end.saturating_sub(self.range.start) + stride - 1) / stride
+    }
+
+    /// Return `true` if the section contains no pixels
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl From<Range<usize>> for Section {
+    fn from(range: Range<usize>) -> Self {
+        Section::new(range)
+    }
+}
+
+/**
+A rectangular, possibly strided, window into an image
+
+The axes are given in FITS order, i.e. the first axis is the one which varies fastest (`x`),
+which is the same order as the ranges given to
+[`read_region`](../hdu/struct.FitsHdu.html#method.read_region). Regions can be given to
+`read_region` and `write_region` directly, and reused between calls.
+
+# Example
+
+```rust
+use fitsio::images::Region;
+
+# fn main() -> Result<(), Box<dyn std::error::Error>> {
+# let filename = "../testdata/full_example.fits";
+# let mut fptr = fitsio::FitsFile::open(filename)?;
+# let hdu = fptr.hdu(0)?;
+// The region may extend past the edge of the 100x100 image, so clip it first
+let region = Region::rect(90..110, 0..20).clip_to(hdu.shape()?);
+let pixels: Vec<i32> = hdu.read_region(&mut fptr, &region)?;
+assert_eq!(pixels.len(), 10 * 20);
+
+// Every other pixel
+let preview: Vec<i32> = hdu.read_region(&mut fptr, &region.with_stride(2))?;
+assert_eq!(preview.len(), 5 * 10);
+# Ok(())
+# }
+```
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Region {
+    /// The section along each axis
+    pub axes: Vec<Section>,
+}
+
+impl Region {
+    /// Create a region from a section along each axis
+    pub fn new<S: Into<Section>>(axes: Vec<S>) -> Self {
+        Region {
+            axes: axes.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Create a two dimensional region
+    pub fn rect(x: Range<usize>, y: Range<usize>) -> Self {
+        Region::new(vec![x, y])
+    }
+
+    /// Only use every `stride`th pixel along every axis
+    pub fn with_stride(self, stride: usize) -> Self {
+        Region {
+            axes: self
+                .axes
+                .into_iter()
+                .map(|axis| axis.with_stride(stride))
+                .collect(),
+        }
+    }
+
+    /**
+    Restrict the region to fit within an image
+
+    `shape` is in C order, as returned by [`FitsHdu::shape`], so the last dimension of `shape`
+    clips the first axis of the region.
+
+    [`FitsHdu::shape`]: ../hdu/struct.FitsHdu.html#method.shape
+    */
+    pub fn clip_to(self, shape: &[usize]) -> Self {
+        let naxis = shape.len();
+        Region {
+            axes: self
+                .axes
+                .into_iter()
+                .enumerate()
+                .map(|(i, axis)| match naxis.checked_sub(i + 1) {
+                    Some(dim) => axis.clip_to(shape[dim]),
+                    None => axis.clip_to(0),
+                })
+                .collect(),
+        }
+    }
+
+    /// Total number of pixels in the region, accounting for strides
+    pub fn num_pixels(&self) -> usize {
+        self.axes.iter().map(Section::len).product()
+    }
+}
+
+impl RegionRanges for Region {
+    fn ranges(&self) -> Vec<Range<usize>> {
+        self.axes.iter().map(|axis| axis.range.clone()).collect()
+    }
+
+    fn steps(&self) -> Vec<usize> {
+        self.axes.iter().map(|axis| axis.stride).collect()
+    }
+}
+
+/**
+Coordinate keywords describing a single image axis
+
+Each field corresponds to the keyword of the same name with the axis number appended, for
+example `crval` is `CRVAL1` for the first axis. Fields are `None` when the keyword is not present
+in the header.
+*/
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImageAxis {
+    /// Coordinate type, e.g. `RA---TAN` (`CTYPEi`)
+    pub ctype: Option<String>,
+    /// Units of `crval` and `cdelt`, e.g. `deg` (`CUNITi`)
+    pub cunit: Option<String>,
+    /// Coordinate value at the reference pixel (`CRVALi`)
+    pub crval: Option<f64>,
+    /// Coordinate increment per pixel (`CDELTi`)
+    pub cdelt: Option<f64>,
+    /// One-indexed location of the reference pixel (`CRPIXi`)
+    pub crpix: Option<f64>,
+}
+
+/**
+Coordinate keywords of every axis of an image
+
+The axes are in FITS order, so `axes[0]` describes the first (fastest varying) axis, with
+keywords ending in `1`.
+
+# Example
+
+```rust
+# fn main() -> Result<(), Box<dyn std::error::Error>> {
+# let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+# let filename = tdir.path().join("test.fits");
+# std::fs::copy("../testdata/full_example.fits", &filename)?;
+# let mut fptr = fitsio::FitsFile::edit(filename)?;
+# let hdu = fptr.primary_hdu()?;
+let mut axes = hdu.read_axes(&mut fptr)?;
+assert_eq!(axes.axes.len(), 2);
+
+axes.axes[0].ctype = Some("RA---TAN".to_string());
+axes.axes[0].cunit = Some("deg".to_string());
+axes.axes[0].cdelt = Some(-0.001);
+hdu.write_axes(&mut fptr, &axes)?;
+
+assert_eq!(hdu.read_key::<String>(&mut fptr, "CTYPE1")?, "RA---TAN");
+# Ok(())
+# }
+```
+*/
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ImageAxes {
+    /// The keywords of each axis
+    pub axes: Vec<ImageAxis>,
+}
+
+impl ImageAxes {
+    /// Read the keywords of `naxis` axes from the current HDU
+    pub(crate) fn read(fits_file: &mut FitsFile, naxis: usize) -> Result<Self> {
+        let mut axes = Vec::with_capacity(naxis);
+        for i in 1..=naxis {
+            axes.push(ImageAxis {
+                ctype: read_key_optional(fits_file, &format!("CTYPE{}", i))?,
+                cunit: read_key_optional(fits_file, &format!("CUNIT{}", i))?,
+                crval: read_key_optional(fits_file, &format!("CRVAL{}", i))?,
+                cdelt: read_key_optional(fits_file, &format!("CDELT{}", i))?,
+                crpix: read_key_optional(fits_file, &format!("CRPIX{}", i))?,
+            });
+        }
+        Ok(ImageAxes { axes })
+    }
+
+    /// Write the keywords which are set to the current HDU, replacing any existing values
+    pub(crate) fn write(&self, fits_file: &mut FitsFile) -> Result<()> {
+        for (i, axis) in self.axes.iter().enumerate() {
+            let i = i + 1;
+            if let Some(ref ctype) = axis.ctype {
+                replace_key(fits_file, &format!("CTYPE{}", i), ctype.clone())?;
+            }
+            if let Some(ref cunit) = axis.cunit {
+                replace_key(fits_file, &format!("CUNIT{}", i), cunit.clone())?;
+            }
+            if let Some(crval) = axis.crval {
+                replace_key(fits_file, &format!("CRVAL{}", i), crval)?;
+            }
+            if let Some(cdelt) = axis.cdelt {
+                replace_key(fits_file, &format!("CDELT{}", i), cdelt)?;
+            }
+            if let Some(crpix) = axis.crpix {
+                replace_key(fits_file, &format!("CRPIX{}", i), crpix)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// How a [`LinearTransform`](struct.LinearTransform.html) is written to a header
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LinearConvention {
+    /// A single `CDi_j` matrix
+    Cd,
+    /// A `PCi_j` matrix, with the scale of each axis in `CDELTi`
+    PcCdelt,
+}
+
+/**
+The linear transformation from pixel to intermediate world coordinates of a WCS
+
+Headers describe this transformation in one of three ways: a `CDi_j` matrix, a `PCi_j` matrix
+scaled by `CDELTi`, or (in older headers) `CDELTi` with a rotation angle `CROTA2`. Whichever is
+used, it is read as the equivalent `CDi_j` matrix, and can be written back in either modern
+convention with [`write_linear_transform`](../hdu/struct.FitsHdu.html#method.write_linear_transform).
+
+Indices are zero-indexed and in FITS order, so `get(0, 1)` is `CD1_2`, the contribution of the
+second pixel axis to the first world axis.
+
+# Example
+
+```rust
+use fitsio::images::{LinearConvention, LinearTransform};
+
+# fn main() -> Result<(), Box<dyn std::error::Error>> {
+# let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+# let filename = tdir.path().join("test.fits");
+# std::fs::copy("../testdata/full_example.fits", &filename)?;
+# let mut fptr = fitsio::FitsFile::edit(filename)?;
+let hdu = fptr.primary_hdu()?;
+hdu.write_keys_indexed(&mut fptr, "CDELT", &[-0.001, 0.001])?;
+
+let transform = hdu.read_linear_transform(&mut fptr)?;
+assert_eq!(transform.get(0, 0), -0.001);
+
+// Rewrite the header with a CD matrix instead
+hdu.write_linear_transform(&mut fptr, &transform, LinearConvention::Cd)?;
+assert_eq!(hdu.read_key::<f64>(&mut fptr, "CD2_2")?, 0.001);
+# Ok(())
+# }
+```
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinearTransform {
+    naxis: usize,
+    cd: Vec<f64>,
+}
+
+impl LinearTransform {
+    /// The identity transformation for `naxis` axes
+    pub fn identity(naxis: usize) -> Self {
+        let mut cd = vec![0.0; naxis * naxis];
+        for i in 0..naxis {
+            cd[i * naxis + i] = 1.0;
+        }
+        LinearTransform { naxis, cd }
+    }
+
+    /// Create a transformation from a square `CDi_j` matrix, given row by row
+    pub fn from_cd(naxis: usize, cd: Vec<f64>) -> Result<Self> {
+        if cd.len() != naxis * naxis {
+            return Err(Error::Message(format!(
+                "{} matrix elements given for {} axes",
+                cd.len(),
+                naxis
+            )));
+        }
+        Ok(LinearTransform { naxis, cd })
+    }
+
+    /// Create a transformation from a `PCi_j` matrix, given row by row, and the `CDELTi` values
+    pub fn from_pc_cdelt(pc: &[f64], cdelt: &[f64]) -> Result<Self> {
+        let naxis = cdelt.len();
+        let mut transform = LinearTransform::from_cd(naxis, pc.to_vec())?;
+        for (i, row) in transform.cd.chunks_mut(naxis.max(1)).enumerate() {
+            row.iter_mut().for_each(|value| *value *= cdelt[i]);
+        }
+        Ok(transform)
+    }
+
+    /// The number of axes
+    pub fn naxis(&self) -> usize {
+        self.naxis
+    }
+
+    /// The `CDi_j` matrix, row by row
+    pub fn cd(&self) -> &[f64] {
+        &self.cd
+    }
+
+    /**
+    The element `CD{i+1}_{j+1}` of the matrix
+
+    # Panics
+
+    If `i` or `j` is not less than the number of axes
+    */
+    pub fn get(&self, i: usize, j: usize) -> f64 {
+        assert!(
+            i < self.naxis && j < self.naxis,
+            "matrix index out of range"
+        );
+        self.cd[i * self.naxis + j]
+    }
+
+    /**
+    Split the matrix into a `PCi_j` matrix and `CDELTi` values
+
+    Each `CDELTi` is the length of row `i` of the matrix, with the sign of its diagonal element, so
+    the diagonal of the `PCi_j` matrix is never negative.
+    */
+    pub fn to_pc_cdelt(&self) -> (Vec<f64>, Vec<f64>) {
+        let n = self.naxis;
+        let mut pc = self.cd.clone();
+        let mut cdelt = Vec::with_capacity(n);
+        for (i, row) in pc.chunks_mut(n.max(1)).enumerate() {
+            let length = row.iter().map(|v| v * v).sum::<f64>().sqrt();
+            let scale = if length == 0.0 {
+                1.0
+            } else if row[i] < 0.0 {
+                -length
+            } else {
+                length
+            };
+            row.iter_mut().for_each(|value| *value /= scale);
+            cdelt.push(scale);
+        }
+        (pc, cdelt)
+    }
+
+    /// Read the transformation of `naxis` axes from the current HDU
+    pub(crate) fn read(fits_file: &mut FitsFile, naxis: usize) -> Result<Self> {
+        let mut cd = vec![None; naxis * naxis];
+        let mut pc = vec![None; naxis * naxis];
+        for i in 0..naxis {
+            for j in 0..naxis {
+                cd[i * naxis + j] =
+                    read_key_optional::<f64>(fits_file, &format!("CD{}_{}", i + 1, j + 1))?;
+                pc[i * naxis + j] =
+                    read_key_optional::<f64>(fits_file, &format!("PC{}_{}", i + 1, j + 1))?;
+            }
+        }
+
+        // Any CDi_j keyword takes precedence, with missing elements being zero
+        if cd.iter().any(Option::is_some) {
+            return LinearTransform::from_cd(naxis, cd.iter().map(|v| v.unwrap_or(0.0)).collect());
+        }
+
+        let mut cdelt = Vec::with_capacity(naxis);
+        for i in 1..=naxis {
+            cdelt.push(read_key_optional::<f64>(fits_file, &format!("CDELT{}", i))?.unwrap_or(1.0));
+        }
+
+        let identity = LinearTransform::identity(naxis);
+        let mut pc: Vec<f64> = pc
+            .iter()
+            .zip(&identity.cd)
+            .map(|(value, default)| value.unwrap_or(*default))
+            .collect();
+        if naxis >= 2 && pc == identity.cd {
+            // The rotation of the latitude axis from the original AIPS convention
+            if let Some(crota) = read_key_optional::<f64>(fits_file, "CROTA2")? {
+                let (sin, cos) = crota.to_radians().sin_cos();
+                let ratio = cdelt[1] / cdelt[0];
+                pc[0] = cos;
+                pc[1] = -sin * ratio;
+                pc[naxis] = sin / ratio;
+                pc[naxis + 1] = cos;
+            }
+        }
+        LinearTransform::from_pc_cdelt(&pc, &cdelt)
+    }
+
+    /**
+    Write the transformation to the current HDU, removing the keywords of the other conventions
+    so the header is not ambiguous
+    */
+    pub(crate) fn write(
+        &self,
+        fits_file: &mut FitsFile,
+        convention: LinearConvention,
+    ) -> Result<()> {
+        let n = self.naxis;
+        for i in 1..=n {
+            for j in 1..=n {
+                delete_key_if_present(fits_file, &format!("CD{}_{}", i, j))?;
+                delete_key_if_present(fits_file, &format!("PC{}_{}", i, j))?;
+            }
+            delete_key_if_present(fits_file, &format!("CDELT{}", i))?;
+            delete_key_if_present(fits_file, &format!("CROTA{}", i))?;
+        }
+
+        let (root, matrix) = match convention {
+            LinearConvention::Cd => ("CD", self.cd.clone()),
+            LinearConvention::PcCdelt => {
+                let (pc, cdelt) = self.to_pc_cdelt();
+                for (i, value) in cdelt.into_iter().enumerate() {
+                    f64::write_key(fits_file, &format!("CDELT{}", i + 1), value)?;
+                }
+                ("PC", pc)
+            }
+        };
+        for i in 0..n {
+            for j in 0..n {
+                let name = format!("{}{}_{}", root, i + 1, j + 1);
+                f64::write_key(fits_file, &name, matrix[i * n + j])?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Write a keyword, removing any existing cards with the same name first
+fn replace_key<T: WritesKey>(fits_file: &mut FitsFile, name: &str, value: T) -> Result<()> {
+    delete_key_if_present(fits_file, name)?;
+    T::write_key(fits_file, name, value)
+}
+
+/// Remove every copy of a keyword from the current header
+fn delete_key_if_present(fits_file: &mut FitsFile, name: &str) -> Result<()> {
+    let keyname = ffi::CString::new(name)?;
+    loop {
+        let mut status = 0;
+        unsafe {
+            fits_delete_key(
+                fits_file.fptr.as_mut() as *mut _,
+                keyname.as_ptr(),
+                &mut status,
+            );
+        }
+        if status == KEY_NO_EXIST {
+            return Ok(());
+        }
+        check_status(status)?;
+    }
+}
+
+/**
+The celestial coordinate system of the first two axes of an image
+
+This is the description used by the `cfitsio` world coordinate routines: a reference point, the
+scale of each axis in degrees per pixel, and a rotation. It is read from either `CDi_j` or
+`CDELTi`/`CROTA2` keywords, and converts between sky positions and pixel positions for the common
+projections (`-TAN`, `-SIN`, `-ARC`, `-CAR` and others).
+
+Pixel positions are in FITS convention, so the centre of the first pixel is at `(1.0, 1.0)`.
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub struct CelestialWcs {
+    /// `CTYPE1` and `CTYPE2`, for example `RA---TAN` and `DEC--TAN`
+    pub ctype: [String; 2],
+
+    /// World coordinates of the reference pixel in degrees (`CRVAL1` and `CRVAL2`)
+    pub crval: [f64; 2],
+
+    /// Pixel position of the reference point (`CRPIX1` and `CRPIX2`)
+    pub crpix: [f64; 2],
+
+    /// Size of a pixel along each axis in degrees
+    pub cdelt: [f64; 2],
+
+    /// Rotation of the second axis in degrees
+    pub rotation: f64,
+}
+
+impl CelestialWcs {
+    /// The projection code, for example `-TAN`, or an empty string for linear axes
+    pub fn projection(&self) -> &str {
+        self.ctype[0].get(4..8).unwrap_or("")
+    }
+
+    /// Convert a sky position in degrees to a pixel position
+    pub fn world_to_pixel(&self, lon: f64, lat: f64) -> Result<(f64, f64)> {
+        let mut coordtype = ffi::CString::new(self.projection())?.into_bytes_with_nul();
+        let (mut x, mut y) = (0.0, 0.0);
+        let mut status = 0;
+        unsafe {
+            fits_world_to_pix(
+                lon,
+                lat,
+                self.crval[0],
+                self.crval[1],
+                self.crpix[0],
+                self.crpix[1],
+                self.cdelt[0],
+                self.cdelt[1],
+                self.rotation,
+                coordtype.as_mut_ptr() as *mut _,
+                &mut x,
+                &mut y,
+                &mut status,
+            );
+        }
+        check_status(status).map(|_| (x, y))
+    }
+
+    /// Convert a pixel position to a sky position in degrees
+    pub fn pixel_to_world(&self, x: f64, y: f64) -> Result<(f64, f64)> {
+        let mut coordtype = ffi::CString::new(self.projection())?.into_bytes_with_nul();
+        let (mut lon, mut lat) = (0.0, 0.0);
+        let mut status = 0;
+        unsafe {
+            fits_pix_to_world(
+                x,
+                y,
+                self.crval[0],
+                self.crval[1],
+                self.crpix[0],
+                self.crpix[1],
+                self.cdelt[0],
+                self.cdelt[1],
+                self.rotation,
+                coordtype.as_mut_ptr() as *mut _,
+                &mut lon,
+                &mut lat,
+                &mut status,
+            );
+        }
+        check_status(status).map(|_| (lon, lat))
+    }
+
+    /// The linear part of the coordinate system as a `CDi_j` matrix
+    pub fn linear_transform(&self) -> LinearTransform {
+        let (sin, cos) = self.rotation.to_radians().sin_cos();
+        let [cdelt1, cdelt2] = self.cdelt;
+        LinearTransform {
+            naxis: 2,
+            cd: vec![cdelt1 * cos, -cdelt2 * sin, cdelt1 * sin, cdelt2 * cos],
+        }
+    }
+
+    /// Read the coordinate system of the current HDU
+    pub(crate) fn read(fits_file: &mut FitsFile) -> Result<Self> {
+        let mut crval = [0.0; 2];
+        let mut crpix = [0.0; 2];
+        let mut cdelt = [0.0; 2];
+        let mut rotation = 0.0;
+        let mut coordtype = vec![0; MAX_VALUE_LENGTH];
+        let mut status = 0;
+        unsafe {
+            fits_read_img_coord(
+                fits_file.fptr.as_mut() as *mut _,
+                &mut crval[0],
+                &mut crval[1],
+                &mut crpix[0],
+                &mut crpix[1],
+                &mut cdelt[0],
+                &mut cdelt[1],
+                &mut rotation,
+                coordtype.as_mut_ptr(),
+                &mut status,
+            );
+        }
+        check_status(status)?;
+
+        let ctype1 = read_key_optional::<String>(fits_file, "CTYPE1")?.unwrap_or_default();
+        let ctype2 = read_key_optional::<String>(fits_file, "CTYPE2")?.unwrap_or_default();
+        Ok(CelestialWcs {
+            ctype: [ctype1, ctype2],
+            crval,
+            crpix,
+            cdelt,
+            rotation,
+        })
+    }
+
+    /**
+    Write the coordinate system to the current HDU
+
+    Existing `CTYPEi`, `CRVALi` and `CRPIXi` keywords of the first two axes are replaced, and the
+    scale and rotation are written as a `CDi_j` matrix, replacing any `PCi_j`, `CDELTi` and
+    `CROTAi` keywords.
+    */
+    pub(crate) fn write(&self, fits_file: &mut FitsFile) -> Result<()> {
+        for i in 0..2 {
+            let (ctype, crval, crpix) = (
+                format!("CTYPE{}", i + 1),
+                format!("CRVAL{}", i + 1),
+                format!("CRPIX{}", i + 1),
+            );
+            for name in &[&ctype, &crval, &crpix] {
+                delete_key_if_present(fits_file, name)?;
+            }
+            String::write_key(fits_file, &ctype, self.ctype[i].clone())?;
+            f64::write_key(fits_file, &crval, self.crval[i])?;
+            f64::write_key(fits_file, &crpix, self.crpix[i])?;
+        }
+        self.linear_transform()
+            .write(fits_file, LinearConvention::Cd)
+    }
+}
+
+/**
+A cutout of an image around a sky position
+
+Returned by [`read_cutout_world`](../hdu/struct.FitsHdu.html#method.read_cutout_world).
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cutout<T> {
+    /// The pixel data of the cutout
+    pub data: T,
+
+    /// The zero-indexed pixel ranges read from the image, one per axis with the first axis first
+    pub region: Vec<Range<usize>>,
+
+    /// The coordinate system of the cutout, with the reference pixel moved to match the cutout
+    pub wcs: CelestialWcs,
+}
+
+impl<T> Cutout<T> {
+    /// The shape of the cutout, with the slowest varying axis first like `FitsHdu::shape`
+    pub fn shape(&self) -> Vec<usize> {
+        self.region.iter().rev().map(|range| range.len()).collect()
+    }
+}
+
+/**
+The zero-indexed pixels of a window `2 * half_width` pixels wide around the FITS pixel position
+`centre`, rounded to whole pixels and clipped to an axis of `len` pixels
+*/
+pub(crate) fn cutout_range(centre: f64, half_width: f64, len: usize) -> Range<usize> {
+    let clip = |value: f64| value.max(0.0).min(len as f64) as usize;
+    let first = (centre - half_width - 1.0).round();
+    let start = clip(first);
+    let end = clip(first + (2.0 * half_width).round().max(1.0));
+    start..end.max(start)
+}
+
+/// How spectral coordinates vary with pixel position, see [`SpectralAxis`](struct.SpectralAxis.html)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpectralScale {
+    /// `CRVAL1 + CDELT1 * (p - CRPIX1)`
+    Linear,
+    /**
+    Logarithmic sampling of the FITS WCS standard, used when `CTYPE1` ends in `-LOG`:
+    `CRVAL1 * exp(CDELT1 * (p - CRPIX1) / CRVAL1)`
+    */
+    Log,
+    /**
+    Base 10 logarithmic sampling of IRAF, used when `DC-FLAG = 1`, where `CRVAL1` and `CDELT1`
+    are in `log10` units: `10 ^ (CRVAL1 + CDELT1 * (p - CRPIX1))`
+    */
+    Log10,
+}
+
+/**
+The spectral coordinate system of the first axis of an image
+
+Reads `CRVAL1`, `CDELT1` (or `CD1_1`), `CRPIX1` and `CTYPE1`, and works out the wavelength or
+frequency of each pixel, so one dimensional spectra can be plotted against their spectral axis.
+Missing keywords take the defaults of the FITS standard, so an image without any gives the
+pixel numbers.
+
+# Example
+
+```rust
+# fn main() -> Result<(), Box<dyn std::error::Error>> {
+# let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+# let filename = tdir.path().join("test.fits");
+# let mut fptr = fitsio::FitsFile::create(filename).open()?;
+use fitsio::images::{ImageDescription, ImageType};
+
+let description = ImageDescription {
+    data_type: ImageType::Float,
+    dimensions: &[4],
+};
+let hdu = fptr.create_image("SPECTRUM", &description)?;
+hdu.write_image(&mut fptr, &[1.0f32, 2.0, 3.0, 4.0])?;
+hdu.write_key(&mut fptr, "CTYPE1", "WAVE")?;
+hdu.write_key(&mut fptr, "CRVAL1", 5000.0)?;
+hdu.write_key(&mut fptr, "CDELT1", 2.5)?;
+hdu.write_key(&mut fptr, "CRPIX1", 1.0)?;
+
+let (wavelengths, flux): (Vec<f64>, Vec<f32>) = hdu.read_spectrum(&mut fptr)?;
+assert_eq!(wavelengths, vec![5000.0, 5002.5, 5005.0, 5007.5]);
+assert_eq!(flux, vec![1.0, 2.0, 3.0, 4.0]);
+# Ok(())
+# }
+```
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub struct SpectralAxis {
+    /// `CTYPE1`, for example `WAVE` or `FREQ`, if set
+    pub ctype: Option<String>,
+
+    /// Coordinate value at the reference pixel (`CRVAL1`)
+    pub crval: f64,
+
+    /// Coordinate increment per pixel (`CD1_1`, or `PC1_1` times `CDELT1`)
+    pub cdelt: f64,
+
+    /// Reference pixel, one-indexed (`CRPIX1`)
+    pub crpix: f64,
+
+    /// How the coordinate varies with pixel position
+    pub scale: SpectralScale,
+}
+
+impl SpectralAxis {
+    /// The spectral coordinate at a pixel position, where the first pixel is at `1.0`
+    pub fn value(&self, pixel: f64) -> f64 {
+        let offset = self.cdelt * (pixel - self.crpix);
+        match self.scale {
+            SpectralScale::Linear => self.crval + offset,
+            SpectralScale::Log => self.crval * (offset / self.crval).exp(),
+            SpectralScale::Log10 => 10f64.powf(self.crval + offset),
+        }
+    }
+
+    /// The spectral coordinates of the first `len` pixels
+    pub fn values(&self, len: usize) -> Vec<f64> {
+        (1..=len).map(|pixel| self.value(pixel as f64)).collect()
+    }
+
+    /// Read the coordinate system of the first axis of the current HDU
+    pub(crate) fn read(fits_file: &mut FitsFile) -> Result<Self> {
+        let ctype = read_key_optional::<String>(fits_file, "CTYPE1")?;
+        let crval = read_key_optional(fits_file, "CRVAL1")?.unwrap_or(0.0);
+        let crpix = read_key_optional(fits_file, "CRPIX1")?.unwrap_or(0.0);
+        // `CD1_1` takes precedence, otherwise the increment is `PC1_1 * CDELT1`
+        let cdelt = LinearTransform::read(fits_file, 1)?.get(0, 0);
+        let dc_flag = read_key_optional::<i64>(fits_file, "DC-FLAG")?;
+
+        let scale = if dc_flag == Some(1) {
+            SpectralScale::Log10
+        } else if ctype
+            .as_deref()
+            .map_or(false, |ctype| ctype.trim_end().ends_with("-LOG"))
+        {
+            if crval == 0.0 {
+                return Err("logarithmic spectral axis needs a non-zero CRVAL1".into());
+            }
+            SpectralScale::Log
+        } else {
+            SpectralScale::Linear
+        };
+
+        Ok(SpectralAxis {
+            ctype,
+            crval,
+            cdelt,
+            crpix,
+            scale,
+        })
+    }
+}
+
+/// Summary statistics of an image, see [`FitsHdu::image_stats`](../hdu/struct.FitsHdu.html#method.image_stats)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ImageStats {
+    /// Number of pixels, excluding NaN pixels
+    pub count: usize,
+    /// Smallest pixel value
+    pub min: f64,
+    /// Largest pixel value
+    pub max: f64,
+    /// Mean of the pixel values
+    pub mean: f64,
+    /// Population standard deviation of the pixel values
+    pub std: f64,
+}
+
+/**
+Image data with a mask of bad pixels
+
+Instruments such as HST and JWST store each image in a science (`SCI`) extension, with the
+quality of each pixel in a separate data quality (`DQ`) extension. A `MaskedImage` bundles the
+two together, treating any pixel with a non-zero quality value as masked.
+
+# Example
+
+```rust
+use fitsio::images::{ImageType, MaskedImage};
+
+# fn main() -> Result<(), Box<dyn std::error::Error>> {
+# let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+# let filename = tdir.path().join("test.fits");
+# let mut fptr = fitsio::FitsFile::create(filename).open()?;
+let image = MaskedImage::new(
+    vec![1.0f32, 2.0, 3.0, 4.0],
+    vec![false, false, true, false],
+    vec![2, 2],
+)?;
+image.create(&mut fptr, "SCI", ImageType::Float, "DQ")?;
+
+let image: MaskedImage<f32> = MaskedImage::read_sci_dq(&mut fptr)?;
+let good: Vec<f32> = image.unmasked().cloned().collect();
+assert_eq!(good, [1.0, 2.0, 4.0]);
+# Ok(())
+# }
+```
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub struct MaskedImage<T> {
+    /// Pixel values, in the same order as [`read_image`](../hdu/struct.FitsHdu.html#method.read_image)
+    pub data: Vec<T>,
+    /// Whether each pixel is masked
+    pub mask: Vec<bool>,
+    /// Shape of the image, in C order like [`FitsHdu::shape`](../hdu/struct.FitsHdu.html#method.shape)
+    pub shape: Vec<usize>,
+}
+
+impl<T> MaskedImage<T> {
+    /// Create a masked image, checking that the data and mask both match the shape
+    pub fn new(data: Vec<T>, mask: Vec<bool>, shape: Vec<usize>) -> Result<Self> {
+        let num_pixels: usize = shape.iter().product();
+        if data.len() != num_pixels || mask.len() != num_pixels {
+            return Err(format!(
+                "image of shape {:?} needs {} pixels, found {} data values and {} mask values",
+                shape,
+                num_pixels,
+                data.len(),
+                mask.len()
+            )
+            .as_str()
+            .into());
+        }
+        Ok(MaskedImage { data, mask, shape })
+    }
+
+    /**
+    Read the science data and mask from two image HDUs
+
+    Pixels with a non-zero value in the mask HDU are masked. The two images must have the same
+    shape.
+    */
+    pub fn read<S: DescribesHdu, M: DescribesHdu>(
+        fits_file: &mut FitsFile,
+        science: S,
+        mask: M,
+    ) -> Result<Self>
+    where
+        Vec<T>: ReadImage,
+    {
+        let science = fits_file.hdu(science)?;
+        let mask = fits_file.hdu(mask)?;
+        let shape = science.shape()?.to_vec();
+        if mask.shape()? != shape.as_slice() {
+            return Err(format!(
+                "mask shape {:?} does not match image shape {:?}",
+                mask.shape()?,
+                shape
+            )
+            .as_str()
+            .into());
+        }
+
+        let data: Vec<T> = science.read_image(fits_file)?;
+        let flags: Vec<i64> = mask.read_image(fits_file)?;
+        Ok(MaskedImage {
+            data,
+            mask: flags.into_iter().map(|flag| flag != 0).collect(),
+            shape,
+        })
+    }
+
+    /// Read the science data from the `SCI` extension and the mask from the `DQ` extension
+    pub fn read_sci_dq(fits_file: &mut FitsFile) -> Result<Self>
+    where
+        Vec<T>: ReadImage,
+    {
+        MaskedImage::read(fits_file, "SCI", "DQ")
+    }
+
+    /**
+    Write the science data and mask to two existing image HDUs
+
+    Masked pixels are written as `1` and unmasked pixels as `0`, so any other flag values
+    previously in the mask HDU are replaced.
+    */
+    pub fn write(&self, fits_file: &mut FitsFile, science: &FitsHdu, mask: &FitsHdu) -> Result<()>
+    where
+        T: WriteImage,
+    {
+        for hdu in &[science, mask] {
+            if hdu.shape()? != self.shape.as_slice() {
+                return Err(format!(
+                    "image shape {:?} does not match hdu shape {:?}",
+                    self.shape,
+                    hdu.shape()?
+                )
+                .as_str()
+                .into());
+            }
+        }
+        science.write_image(fits_file, &self.data)?;
+        let flags: Vec<u8> = self.mask.iter().map(|&masked| masked as u8).collect();
+        mask.write_image(fits_file, &flags)
+    }
+
+    /// Create new science and mask image extensions and write the image to them
+    pub fn create(
+        &self,
+        fits_file: &mut FitsFile,
+        science_name: &str,
+        data_type: ImageType,
+        mask_name: &str,
+    ) -> Result<(FitsHdu, FitsHdu)>
+    where
+        T: WriteImage,
+    {
+        let science = fits_file.create_image(
+            science_name,
+            &ImageDescription {
+                data_type,
+                dimensions: &self.shape,
+            },
+        )?;
+        let mask = fits_file.create_image(
+            mask_name,
+            &ImageDescription {
+                data_type: ImageType::UnsignedByte,
+                dimensions: &self.shape,
+            },
+        )?;
+        self.write(fits_file, &science, &mask)?;
+        Ok((science, mask))
+    }
+
+    /// Number of masked pixels
+    pub fn num_masked(&self) -> usize {
+        self.mask.iter().filter(|&&masked| masked).count()
+    }
+
+    /// Iterate over the values of the pixels which are not masked
+    pub fn unmasked(&self) -> impl Iterator<Item = &T> {
+        self.data
+            .iter()
+            .zip(&self.mask)
+            .filter(|(_, &masked)| !masked)
+            .map(|(value, _)| value)
+    }
+}
+
+/// Tile compression algorithms for images
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum CompressionAlgorithm {
+    /// Rice compression, the default used by `fpack`
+    Rice,
+    /// Gzip compression
+    Gzip,
+    /// Gzip compression after shuffling the bytes of each pixel
+    Gzip2,
+    /// IRAF pixel list compression, for integer images only
+    Plio,
+    /// H-compress, for two dimensional images only
+    Hcompress,
+    /// Bzip2 compression
+    Bzip2,
+}
+
+impl From<CompressionAlgorithm> for i32 {
+    fn from(algorithm: CompressionAlgorithm) -> i32 {
+        match algorithm {
+            CompressionAlgorithm::Rice => 11,
+            CompressionAlgorithm::Gzip => 21,
+            CompressionAlgorithm::Gzip2 => 22,
+            CompressionAlgorithm::Plio => 31,
+            CompressionAlgorithm::Hcompress => 41,
+            CompressionAlgorithm::Bzip2 => 51,
+        }
+    }
+}
+
+/// How floating point pixels are dithered when they are quantized
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum QuantizeMethod {
+    /// Quantize without dithering
+    NoDither,
+    /// Subtractive dithering, the default
+    SubtractiveDither1,
+    /// Subtractive dithering which preserves pixels with a value of exactly zero
+    SubtractiveDither2,
+}
+
+impl From<QuantizeMethod> for i32 {
+    fn from(method: QuantizeMethod) -> i32 {
+        match method {
+            QuantizeMethod::NoDither => -1,
+            QuantizeMethod::SubtractiveDither1 => 1,
+            QuantizeMethod::SubtractiveDither2 => 2,
+        }
+    }
+}
+
+/**
+Parameters used to compress an image
+
+Options which are not set use the `cfitsio` defaults: tiles are one row of the image, and
+floating point images are quantized with a level of 4 using subtractive dithering.
+
+# Example
+
+```rust
+use fitsio::images::{CompressionAlgorithm, CompressionOptions, QuantizeMethod};
+
+// Lossy compression of a floating point image in 100x100 tiles
+let options = CompressionOptions::new(CompressionAlgorithm::Rice)
+    .with_tile_dimensions(&[100, 100])
+    .with_quantize_level(16.0)
+    .with_quantize_method(QuantizeMethod::SubtractiveDither2);
+```
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompressionOptions {
+    /// The compression algorithm
+    pub algorithm: CompressionAlgorithm,
+    /// Shape of each compression tile, in C order like
+    /// [`ImageDescription::dimensions`](struct.ImageDescription.html#structfield.dimensions)
+    pub tile_dimensions: Option<Vec<usize>>,
+    /**
+    Quantization level of floating point images
+
+    Positive values give the level relative to the noise in each tile, and negative values give
+    the absolute size of the quantization step. A level of `0.0` compresses floating point
+    images losslessly.
+    */
+    pub quantize_level: Option<f32>,
+    /// Dithering applied when quantizing floating point pixels
+    pub quantize_method: Option<QuantizeMethod>,
+    /// Seed of the dithering random number generator, between 1 and 10000
+    pub dither_seed: Option<i32>,
+    /// Scale factor of the H-compress algorithm, where `0.0` is lossless
+    pub hcompress_scale: Option<f32>,
+    /// Quantize integer images as if they were floating point images
+    pub lossy_int: bool,
+}
+
+impl CompressionOptions {
+    /// Create options for the given algorithm, with every other option left at its default
+    pub fn new(algorithm: CompressionAlgorithm) -> Self {
+        CompressionOptions {
+            algorithm,
+            tile_dimensions: None,
+            quantize_level: None,
+            quantize_method: None,
+            dither_seed: None,
+            hcompress_scale: None,
+            lossy_int: false,
+        }
+    }
+
+    /// Set the shape of each compression tile
+    pub fn with_tile_dimensions(mut self, dimensions: &[usize]) -> Self {
+        self.tile_dimensions = Some(dimensions.to_vec());
+        self
+    }
+
+    /// Set the quantization level of floating point images
+    pub fn with_quantize_level(mut self, level: f32) -> Self {
+        self.quantize_level = Some(level);
+        self
+    }
+
+    /// Set the dithering applied when quantizing
+    pub fn with_quantize_method(mut self, method: QuantizeMethod) -> Self {
+        self.quantize_method = Some(method);
+        self
+    }
+
+    /// Set the seed of the dithering random number generator
+    pub fn with_dither_seed(mut self, seed: i32) -> Self {
+        self.dither_seed = Some(seed);
+        self
+    }
+
+    /// Set the scale factor of the H-compress algorithm
+    pub fn with_hcompress_scale(mut self, scale: f32) -> Self {
+        self.hcompress_scale = Some(scale);
+        self
+    }
+
+    /// Quantize integer images as if they were floating point images
+    pub fn with_lossy_int(mut self) -> Self {
+        self.lossy_int = true;
+        self
+    }
+
+    /// Request these parameters for the next image compressed into `fits_file`
+    pub(crate) fn apply(&self, fits_file: &mut FitsFile) -> Result<()> {
+        let mut status = 0;
+        unsafe {
+            let fptr = fits_file.fptr.as_mut() as *mut _;
+            fits_set_compression_type(fptr, self.algorithm.into(), &mut status);
+            if let Some(ref dimensions) = self.tile_dimensions {
+                let mut dimensions: Vec<c_long> =
+                    dimensions.iter().rev().map(|&d| d as c_long).collect();
+                fits_set_tile_dim(
+                    fptr,
+                    dimensions.len() as c_int,
+                    dimensions.as_mut_ptr(),
+                    &mut status,
+                );
+            }
+            if let Some(level) = self.quantize_level {
+                fits_set_quantize_level(fptr, level, &mut status);
+            }
+            if let Some(method) = self.quantize_method {
+                fits_set_quantize_method(fptr, method.into(), &mut status);
+            }
+            if let Some(seed) = self.dither_seed {
+                fits_set_dither_seed(fptr, seed, &mut status);
+            }
+            if let Some(scale) = self.hcompress_scale {
+                fits_set_hcomp_scale(fptr, scale, &mut status);
+            }
+            fits_set_lossy_int(fptr, self.lossy_int as c_int, &mut status);
+        }
+        check_status(status)
+    }
+}
+
 /// Data types used for defining images
 #[allow(missing_docs)]
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
@@ -351,21 +1638,119 @@ pub enum ImageType {
     Double,
 }
 
+impl ImageType {
+    /**
+    The `BITPIX` code of this type
+
+    Types without a native FITS representation, which are stored as scaled integers, use the
+    codes `cfitsio` gives them: `10` for `Byte`, `20` for `UnsignedShort` and `40` for
+    `UnsignedLong`.
+    */
+    pub fn bitpix(self) -> i32 {
+        match self {
+            ImageType::UnsignedByte => 8,
+            ImageType::Byte => 10,
+            ImageType::Short => 16,
+            ImageType::UnsignedShort => 20,
+            ImageType::Long => 32,
+            ImageType::UnsignedLong => 40,
+            ImageType::LongLong => 64,
+            ImageType::Float => -32,
+            ImageType::Double => -64,
+        }
+    }
+
+    /**
+    The image type for a `BITPIX` code, as returned by [`bitpix`](#method.bitpix)
+
+    # Example
+
+    ```rust
+    use fitsio::images::ImageType;
+
+    assert_eq!(ImageType::from_bitpix(-32).unwrap(), ImageType::Float);
+    assert!(ImageType::from_bitpix(12).is_err());
+    ```
+    */
+    pub fn from_bitpix(bitpix: i32) -> Result<Self> {
+        match bitpix {
+            8 => Ok(ImageType::UnsignedByte),
+            10 => Ok(ImageType::Byte),
+            16 => Ok(ImageType::Short),
+            20 => Ok(ImageType::UnsignedShort),
+            32 => Ok(ImageType::Long),
+            40 => Ok(ImageType::UnsignedLong),
+            64 => Ok(ImageType::LongLong),
+            -32 => Ok(ImageType::Float),
+            -64 => Ok(ImageType::Double),
+            _ => Err(Error::Message(format!(
+                "unsupported image BITPIX value {}",
+                bitpix
+            ))),
+        }
+    }
+}
+
+/// Prints the numpy style name of the type, e.g. `uint8` or `float32`
+impl fmt::Display for ImageType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            ImageType::UnsignedByte => "uint8",
+            ImageType::Byte => "int8",
+            ImageType::Short => "int16",
+            ImageType::UnsignedShort => "uint16",
+            ImageType::Long => "int32",
+            ImageType::UnsignedLong => "uint32",
+            ImageType::LongLong => "int64",
+            ImageType::Float => "float32",
+            ImageType::Double => "float64",
+        };
+        f.write_str(name)
+    }
+}
+
+/**
+Parses the names printed by `Display`, the variant names in any case, e.g. `float` or
+`UnsignedShort`, and `BITPIX` codes, e.g. `-32`
+
+# Example
+
+```rust
+use fitsio::images::ImageType;
+
+assert_eq!("uint16".parse::<ImageType>().unwrap(), ImageType::UnsignedShort);
+assert_eq!("Double".parse::<ImageType>().unwrap(), ImageType::Double);
+assert_eq!("-32".parse::<ImageType>().unwrap(), ImageType::Float);
+assert_eq!(ImageType::Long.to_string(), "int32");
+```
+*/
+impl FromStr for ImageType {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        if let Ok(bitpix) = s.trim().parse::<i32>() {
+            return ImageType::from_bitpix(bitpix);
+        }
+        match s.trim().to_ascii_lowercase().as_str() {
+            "uint8" | "unsignedbyte" => Ok(ImageType::UnsignedByte),
+            "int8" | "byte" => Ok(ImageType::Byte),
+            "int16" | "short" => Ok(ImageType::Short),
+            "uint16" | "unsignedshort" => Ok(ImageType::UnsignedShort),
+            "int32" | "long" => Ok(ImageType::Long),
+            "uint32" | "unsignedlong" => Ok(ImageType::UnsignedLong),
+            "int64" | "longlong" => Ok(ImageType::LongLong),
+            "float32" | "float" => Ok(ImageType::Float),
+            "float64" | "double" => Ok(ImageType::Double),
+            _ => Err(Error::Message(format!("unknown image type {:?}", s))),
+        }
+    }
+}
+
 macro_rules! imagetype_into_impl {
     ($t:ty) => {
         impl From<ImageType> for $t {
             fn from(original: ImageType) -> $t {
-                match original {
-                    ImageType::UnsignedByte => 8,
-                    ImageType::Byte => 10,
-                    ImageType::Short => 16,
-                    ImageType::UnsignedShort => 20,
-                    ImageType::Long => 32,
-                    ImageType::UnsignedLong => 40,
-                    ImageType::LongLong => 64,
-                    ImageType::Float => -32,
-                    ImageType::Double => -64,
-                }
+                original.bitpix() as $t
             }
         }
     };
@@ -381,7 +1766,45 @@ mod tests {
     use super::*;
     use crate::errors::Error;
     use crate::fitsfile::FitsFile;
-    use crate::testhelpers::with_temp_file;
+    use crate::testing::{duplicate_test_file, with_temp_file};
+
+    #[test]
+    fn test_image_type_conversions() {
+        let types = [
+            ImageType::UnsignedByte,
+            ImageType::Byte,
+            ImageType::Short,
+            ImageType::UnsignedShort,
+            ImageType::Long,
+            ImageType::UnsignedLong,
+            ImageType::LongLong,
+            ImageType::Float,
+            ImageType::Double,
+        ];
+        for image_type in &types {
+            assert_eq!(
+                ImageType::from_bitpix(image_type.bitpix()).unwrap(),
+                *image_type
+            );
+            assert_eq!(
+                image_type.to_string().parse::<ImageType>().unwrap(),
+                *image_type
+            );
+            assert_eq!(
+                format!("{:?}", image_type).parse::<ImageType>().unwrap(),
+                *image_type
+            );
+            assert_eq!(i32::from(*image_type), image_type.bitpix());
+        }
+
+        assert_eq!(" float ".parse::<ImageType>().unwrap(), ImageType::Float);
+        assert_eq!("16".parse::<ImageType>().unwrap(), ImageType::Short);
+        assert!("complex".parse::<ImageType>().is_err());
+        match ImageType::from_bitpix(80) {
+            Err(Error::Message(msg)) => assert_eq!(msg, "unsupported image BITPIX value 80"),
+            other => panic!("unexpected result {:?}", other),
+        }
+    }
 
     #[test]
     fn test_read_image_data() {
@@ -398,6 +1821,68 @@ mod tests {
         assert_eq!(second_row[49], 168);
     }
 
+    #[test]
+    fn test_strict_image_reads() {
+        with_temp_file(|filename| {
+            let mut f = FitsFile::create(filename).open().unwrap().strict();
+            let description = ImageDescription {
+                data_type: ImageType::Float,
+                dimensions: &[2, 2],
+            };
+            let hdu = f.create_image("FLOAT".to_string(), &description).unwrap();
+            hdu.write_image(&mut f, &[1.5f32, 2.0, 3.0, 4.0]).unwrap();
+
+            let data: Vec<f64> = hdu.read_image(&mut f).unwrap();
+            assert_eq!(data, vec![1.5, 2.0, 3.0, 4.0]);
+            match hdu.read_image::<Vec<i32>>(&mut f) {
+                Err(Error::Message(msg)) => {
+                    assert_eq!(
+                        msg,
+                        "strict mode: cannot read Float image without loss into i32"
+                    )
+                }
+                _ => panic!("invalid result"),
+            }
+            assert!(hdu
+                .read_region::<Vec<i64>, _>(&mut f, &[0..1, 0..1])
+                .is_err());
+
+            let description = ImageDescription {
+                data_type: ImageType::UnsignedShort,
+                dimensions: &[2, 2],
+            };
+            let hdu = f.create_image("USHORT".to_string(), &description).unwrap();
+            hdu.write_image(&mut f, &[1u16, 2, 3, 65535]).unwrap();
+            let data: Vec<i32> = hdu.read_image(&mut f).unwrap();
+            assert_eq!(data[3], 65535);
+            let _: Vec<f32> = hdu.read_image(&mut f).unwrap();
+            assert!(hdu.read_image::<Vec<i16>>(&mut f).is_err());
+            assert!(hdu.read_section::<Vec<u8>>(&mut f, 0, 2).is_err());
+
+            /* Lossy reads are allowed when strict mode is disabled */
+            f.set_strict(false);
+            let data: Vec<i16> = hdu.read_section(&mut f, 0, 3).unwrap();
+            assert_eq!(data, vec![1, 2, 3]);
+        });
+    }
+
+    #[test]
+    fn test_pixel_kinds() {
+        use PixelKind::*;
+
+        assert!(Signed(16).fits_in(Signed(32)));
+        assert!(!Signed(32).fits_in(Signed(16)));
+        assert!(Unsigned(16).fits_in(Signed(32)));
+        assert!(!Unsigned(16).fits_in(Signed(16)));
+        assert!(!Signed(8).fits_in(Unsigned(64)));
+        assert!(Signed(16).fits_in(Float(24)));
+        assert!(!Signed(32).fits_in(Float(24)));
+        assert!(Unsigned(32).fits_in(Float(53)));
+        assert!(Float(24).fits_in(Float(53)));
+        assert!(!Float(53).fits_in(Float(24)));
+        assert!(!Float(24).fits_in(Signed(64)));
+    }
+
     #[test]
     fn test_read_table_as_image() {
         let mut f = FitsFile::open("../testdata/full_example.fits").unwrap();
@@ -414,6 +1899,92 @@ mod tests {
         assert_eq!(image.len(), 10000);
     }
 
+    #[test]
+    fn test_read_image_scaled() {
+        let mut f = FitsFile::open("../testdata/full_example.fits").unwrap();
+        let hdu = f.hdu(0).unwrap();
+        let raw: Vec<f64> = hdu.read_image(&mut f).unwrap();
+        let scaled: Vec<f64> = hdu.read_image_scaled(&mut f, 0.5, -3.0).unwrap();
+        assert_eq!(scaled.len(), raw.len());
+        for (s, r) in scaled.iter().zip(&raw) {
+            assert_eq!(*s, 0.5 * r - 3.0);
+        }
+
+        // The header scaling is restored afterwards
+        let after: Vec<f64> = hdu.read_image(&mut f).unwrap();
+        assert_eq!(after, raw);
+    }
+
+    #[test]
+    fn test_read_image_scaled_from_table() {
+        let mut f = FitsFile::open("../testdata/full_example.fits").unwrap();
+        let hdu = f.hdu(1).unwrap();
+        assert!(hdu.read_image_scaled::<Vec<f32>>(&mut f, 1.0, 0.0).is_err());
+    }
+
+    #[test]
+    fn test_apply() {
+        duplicate_test_file(|filename| {
+            let mut f = FitsFile::edit(filename).unwrap();
+            let hdu = f.hdu(0).unwrap();
+            let before: Vec<i32> = hdu.read_image(&mut f).unwrap();
+            hdu.apply(&mut f, |pixel| pixel * 2.0 + 1.0).unwrap();
+            let after: Vec<i32> = hdu.read_image(&mut f).unwrap();
+            for (a, b) in after.iter().zip(&before) {
+                assert_eq!(*a, b * 2 + 1);
+            }
+        });
+    }
+
+    #[test]
+    fn test_add_and_subtract_images() {
+        duplicate_test_file(|filename| {
+            let mut f = FitsFile::edit(filename).unwrap();
+            let hdu = f.hdu(0).unwrap();
+            let original: Vec<f64> = hdu.read_image(&mut f).unwrap();
+
+            let sum = hdu.add_image(&mut f, &hdu, "SUM").unwrap();
+            let difference = sum.subtract_image(&mut f, &hdu, "DIFF").unwrap();
+
+            let sum_data: Vec<f64> = f.hdu("SUM").unwrap().read_image(&mut f).unwrap();
+            let difference_data: Vec<f64> = difference.read_image(&mut f).unwrap();
+            for i in 0..original.len() {
+                assert_eq!(sum_data[i], 2.0 * original[i]);
+                assert_eq!(difference_data[i], original[i]);
+            }
+        });
+    }
+
+    #[test]
+    fn test_combine_images_of_different_shapes() {
+        with_temp_file(|filename| {
+            let mut f = FitsFile::create(filename).open().unwrap();
+            let small = f
+                .create_image(
+                    "SMALL",
+                    &ImageDescription {
+                        data_type: ImageType::Float,
+                        dimensions: &[2, 2],
+                    },
+                )
+                .unwrap();
+            let large = f
+                .create_image(
+                    "LARGE",
+                    &ImageDescription {
+                        data_type: ImageType::Float,
+                        dimensions: &[3, 3],
+                    },
+                )
+                .unwrap();
+            assert!(small.add_image(&mut f, &large, "SUM").is_err());
+
+            // The empty primary image has no pixels to process
+            let primary = f.hdu(0).unwrap();
+            assert!(primary.apply(&mut f, |p| p).is_ok());
+        });
+    }
+
     #[test]
     fn test_read_image_rows() {
         let mut f = FitsFile::open("../testdata/full_example.fits").unwrap();
@@ -446,6 +2017,163 @@ mod tests {
         assert_eq!(chunk[chunk.len() - 1], 112);
     }
 
+    #[test]
+    fn test_read_image_region_owned_ranges() {
+        let mut f = FitsFile::open("../testdata/full_example.fits").unwrap();
+        let hdu = f.hdu(0).unwrap();
+
+        let borrowed: Vec<i32> = hdu.read_region(&mut f, &[&(2..3), &(5..7)]).unwrap();
+        let owned: Vec<i32> = hdu.read_region(&mut f, &[2..3, 5..7]).unwrap();
+        let from_vec: Vec<i32> = hdu.read_region(&mut f, vec![2..3, 5..7]).unwrap();
+        assert_eq!(borrowed, owned);
+        assert_eq!(borrowed, from_vec);
+    }
+
+    #[test]
+    fn test_read_image_region_strided() {
+        let mut f = FitsFile::open("../testdata/full_example.fits").unwrap();
+        let hdu = f.hdu(0).unwrap();
+        let image: Vec<i32> = hdu.read_image(&mut f).unwrap();
+
+        let chunk: Vec<i32> = hdu
+            .read_region_strided(&mut f, &[10..20, 30..45], &[3, 5])
+            .unwrap();
+        let mut expected = Vec::new();
+        for y in (30..45).step_by(5) {
+            for x in (10..20).step_by(3) {
+                expected.push(image[y * 100 + x]);
+            }
+        }
+        assert_eq!(chunk.len(), 4 * 3);
+        assert_eq!(chunk, expected);
+
+        /* Unit steps match read_region */
+        let strided: Vec<i32> = hdu
+            .read_region_strided(&mut f, &[10..20, 30..45], &[1, 1])
+            .unwrap();
+        let region: Vec<i32> = hdu.read_region(&mut f, &[10..20, 30..45]).unwrap();
+        assert_eq!(strided, region);
+    }
+
+    #[test]
+    fn test_read_image_region_invalid_steps() {
+        let mut f = FitsFile::open("../testdata/full_example.fits").unwrap();
+        let hdu = f.hdu(0).unwrap();
+        assert!(hdu
+            .read_region_strided::<Vec<i32>, _>(&mut f, &[0..10, 0..10], &[2])
+            .is_err());
+        assert!(hdu
+            .read_region_strided::<Vec<i32>, _>(&mut f, &[0..10, 0..10], &[2, 0])
+            .is_err());
+    }
+
+    #[test]
+    fn test_region() {
+        let region = Region::rect(90..110, 0..20);
+        assert_eq!(region.num_pixels(), 20 * 20);
+
+        let clipped = region.clone().clip_to(&[100, 100]);
+        assert_eq!(clipped.axes[0].range, 90..100);
+        assert_eq!(clipped.axes[1].range, 0..20);
+        assert_eq!(clipped.num_pixels(), 10 * 20);
+
+        /* The last dimension of the C order shape clips the first axis */
+        let clipped = region.clone().clip_to(&[10, 95]);
+        assert_eq!(clipped.axes[0].range, 90..95);
+        assert_eq!(clipped.axes[1].range, 0..10);
+
+        let clipped = region.clone().clip_to(&[50, 80]);
+        assert!(clipped.axes[0].is_empty());
+
+        let strided = region.with_stride(3);
+        assert_eq!(strided.axes[0].len(), 7);
+        assert_eq!(strided.steps(), vec![3, 3]);
+        assert_eq!(strided.num_pixels(), 49);
+
+        let region = Region::new(vec![Section::new(0..10).with_stride(2), (0..5).into()]);
+        assert_eq!(region.ranges(), vec![0..10, 0..5]);
+        assert_eq!(region.steps(), vec![2, 1]);
+    }
+
+    #[test]
+    fn test_masked_image() {
+        with_temp_file(|filename| {
+            let mut f = FitsFile::create(filename).open().unwrap();
+            let image = MaskedImage::new(
+                (0..6).collect::<Vec<i32>>(),
+                vec![true, false, false, false, true, false],
+                vec![2, 3],
+            )
+            .unwrap();
+            assert_eq!(image.num_masked(), 2);
+            let (science, mask) = image.create(&mut f, "SCI", ImageType::Long, "DQ").unwrap();
+            assert_eq!(mask.image_type().unwrap(), ImageType::UnsignedByte);
+
+            let read_back: MaskedImage<i32> = MaskedImage::read_sci_dq(&mut f).unwrap();
+            assert_eq!(read_back, image);
+            let values: Vec<i32> = read_back.unmasked().cloned().collect();
+            assert_eq!(values, vec![1, 2, 3, 5]);
+
+            /* Any non-zero flag value is masked */
+            mask.write_image(&mut f, &[0u8, 4, 0, 0, 0, 128]).unwrap();
+            let read_back: MaskedImage<f64> = MaskedImage::read(&mut f, 1, "DQ").unwrap();
+            assert_eq!(read_back.mask, vec![false, true, false, false, false, true]);
+
+            /* Write back to the existing HDUs */
+            let mut edited = read_back;
+            edited.data[0] = 10.0;
+            edited.mask[1] = false;
+            edited.write(&mut f, &science, &mask).unwrap();
+            let read_back: MaskedImage<i32> = MaskedImage::read_sci_dq(&mut f).unwrap();
+            assert_eq!(read_back.data, vec![10, 1, 2, 3, 4, 5]);
+            assert_eq!(read_back.num_masked(), 1);
+
+            /* Shapes must match */
+            assert!(MaskedImage::new(vec![1, 2, 3], vec![false; 3], vec![2, 2]).is_err());
+            let other = f
+                .create_image(
+                    "OTHER",
+                    &ImageDescription {
+                        data_type: ImageType::UnsignedByte,
+                        dimensions: &[3, 2],
+                    },
+                )
+                .unwrap();
+            assert!(MaskedImage::<i32>::read(&mut f, "SCI", "OTHER").is_err());
+            assert!(image.write(&mut f, &science, &other).is_err());
+        });
+    }
+
+    #[test]
+    fn test_read_write_region_type() {
+        with_temp_file(|filename| {
+            let mut f = FitsFile::create(filename).open().unwrap();
+            let image_description = ImageDescription {
+                data_type: ImageType::Long,
+                dimensions: &[20, 30],
+            };
+            let hdu = f
+                .create_image("foo".to_string(), &image_description)
+                .unwrap();
+
+            let region = Region::rect(5..15, 2..6);
+            let data: Vec<i64> = (0..40).collect();
+            hdu.write_region(&mut f, &region, &data).unwrap();
+
+            let chunk: Vec<i64> = hdu.read_region(&mut f, &region).unwrap();
+            assert_eq!(chunk, data);
+
+            let chunk: Vec<i64> = hdu
+                .read_region(&mut f, &region.clone().with_stride(2))
+                .unwrap();
+            assert_eq!(chunk, vec![0, 2, 4, 6, 8, 20, 22, 24, 26, 28]);
+
+            assert!(hdu
+                .write_region(&mut f, &region.with_stride(2), &data)
+                .is_err());
+        });
+    }
+
     #[test]
     fn test_write_image_section() {
         with_temp_file(|filename| {
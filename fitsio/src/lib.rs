@@ -129,7 +129,9 @@ let fptr = FitsFile::edit(filename)?;
 ## Pretty printing
 
 Fits files can be pretty-printed with [`pretty_print`][pretty-print], or its more powerful
-cousin [`pretty_write`][pretty-write].
+cousin [`pretty_write`][pretty-write]. [`pretty_write_with`][pretty-write-with] additionally
+lists table columns and header cards, as configured by
+[`PrettyPrintOptions`][pretty-print-options].
 
 ```rust
 # fn try_main() -> Result<(), Box<std::error::Error>> {
@@ -147,8 +149,9 @@ fptr.pretty_write(&mut io::stdout())?;
 ```
 
 In the continuing tradition of releasing fits summary programs with each fits library, this
-create contains a binary program [`fitssummary`] which can be installed with `cargo install`. This
-takes fits files on the command line and prints their summaries to stdout.
+create contains a binary program [`fitssummary`] which can be installed with
+`cargo install fitsio --features cli`. This takes fits files on the command line and prints their
+summaries to stdout.
 
 ```sh
 $ fitssummary ../testdata/full_example.fits
@@ -156,10 +159,25 @@ $ fitssummary ../testdata/full_example.fits
   file: ../testdata/full_example.fits
   mode: READONLY
   extnum hdutype      hduname    details
-  0      IMAGE_HDU               dimensions: [100, 100], type: Long
+  0      IMAGE_HDU    <unnamed>  dimensions: [100, 100], type: Long
   1      BINARY_TBL   TESTEXT    num_cols: 4, num_rows: 50
 ```
 
+Pass `--verify` to check the checksums and required keywords of each HDU, exiting with a non-zero
+status if any problems are found, and `--stats` to print the minimum, maximum and mean pixel value
+of each image HDU.
+
+Directories, which are searched recursively, and glob patterns print one line per file instead,
+reading the files in parallel. This is useful to take an inventory of a data directory:
+
+```sh
+$ fitssummary ../testdata
+
+n_hdus   primary          size         file
+1        2x3x6            5.6 KiB      ../testdata/cube.fits
+2        100x100          47.8 KiB     ../testdata/full_example.fits
+```
+
 # HDU access
 
 HDU information belongs to the [`FitsHdu`][fits-hdu] object. HDUs can be fetched by
@@ -412,6 +430,40 @@ assert_eq!(fptr.hdu(0)?.read_key::<i64>(&mut fptr, "foo")?, 1i64);
 # fn main() { try_main().unwrap(); }
 ```
 
+Groups of header keys can be read and written in one call by deriving
+[`FitsHeader`][fits-header] with the [`fitsio-derive`][fitsio-derive] crate. Each field maps to
+the keyword given by `#[fitsio(key = "...")]`, or to the field name (converted with
+`#[fitsio(rename_all = "...")]` on the struct, if given). Fields marked `#[fitsio(skip)]` are not
+read or written, and take their default value when reading.
+
+```rust
+use fitsio::headers::FitsHeader;
+use fitsio_derive::FitsHeader;
+
+#[derive(FitsHeader)]
+struct Observation {
+    #[fitsio(key = "EXPTIME")]
+    exposure: f64,
+    #[fitsio(key = "OBJECT")]
+    target: String,
+}
+#
+# fn try_main() -> Result<(), Box<std::error::Error>> {
+# let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+# let filename = tdir.path().join("test.fits");
+# let mut fptr = fitsio::FitsFile::create(filename).open()?;
+let hdu = fptr.primary_hdu()?;
+
+let observation = Observation { exposure: 30.0, target: "M31".to_string() };
+observation.write_to_header(&hdu, &mut fptr)?;
+
+let observation = Observation::from_header(&hdu, &mut fptr)?;
+assert_eq!(observation.target, "M31");
+# Ok(())
+# }
+# fn main() { try_main().unwrap(); }
+```
+
 # Reading file data
 
 Methods taking ranges are exclusive of the upper range value, reflecting the nature of Rust's
@@ -526,6 +578,20 @@ let integer_data: Vec<i32> = hdu.and_then(|hdu| hdu.read_col(&mut fptr, "intcol"
 # fn main() { try_main().unwrap(); }
 ```
 
+Numeric columns can also be read as `Option`s, where undefined values (cells equal to the
+column's `TNULLn` value, or NaN for floating point columns) are returned as `None`:
+
+```rust
+# fn try_main() -> Result<(), Box<std::error::Error>> {
+# let filename = "../testdata/full_example.fits";
+# let mut fptr = fitsio::FitsFile::open(filename)?;
+# let hdu = fptr.hdu(1)?;
+let integer_data: Vec<Option<i32>> = hdu.read_col(&mut fptr, "intcol")?;
+# Ok(())
+# }
+# fn main() { try_main().unwrap(); }
+```
+
 ### Reading cell values
 
 Individual cell values can be read from FITS tables:
@@ -576,6 +642,74 @@ assert_eq!(row.foobar, "value4");
 # fn main() { try_main().unwrap(); }
 ```
 
+Fields without a `colname` are read from the column with the same name as the field. The derive
+also supports:
+
+* `#[fitsio(rename_all = "...")]` on the struct, converting field names to column names with one
+  of `"lowercase"`, `"UPPERCASE"`, `"PascalCase"`, `"camelCase"` or `"SCREAMING_SNAKE_CASE"`,
+* `#[fitsio(writable)]` on the struct, also generating the code to write rows with a
+  [`TableAppender`][table-appender], which requires every field to implement `WritesCell` and
+  `Clone`,
+* `#[fitsio(skip)]` on a field, leaving it as its default value,
+* `#[fitsio(with = "path")]` on a field, passing the value read from the column through the
+  function `path`, which returns a `fitsio::errors::Result`, and
+* `#[fitsio(repr = "i32")]` on a field holding a fieldless enum, reading the column as the given
+  integer type and converting it with `TryFrom`, and writing it back with an `as` cast.
+
+Tuple structs read their fields from the columns in the same position, unless a field has a
+`colname`.
+
+Quick scripts can read rows as tuples without defining a struct, with each value read from the
+column in the same position:
+
+```rust
+# fn try_main() -> Result<(), Box<dyn std::error::Error>> {
+# let filename = "../testdata/full_example.fits";
+# let mut f = fitsio::FitsFile::open(filename)?;
+# let hdu = f.hdu("TESTEXT")?;
+let (intcol, floatcol): (i32, f32) = hdu.row(&mut f, 4)?;
+assert_eq!(intcol, 16);
+# Ok(())
+# }
+# fn main() { try_main().unwrap(); }
+```
+
+Fields of type `Option<T>` for numeric `T` are read as `None` when the cell is undefined, so
+incomplete rows do not silently read as zero.
+
+```rust
+use fitsio::tables::FitsRow;
+use fitsio_derive::FitsRow;
+
+#[derive(Default, FitsRow)]
+struct Row {
+    intcol: i32,
+    #[fitsio(skip)]
+    cache: Vec<f64>,
+    #[fitsio(colname = "strcol", with = "label_number")]
+    number: u32,
+}
+
+fn label_number(label: String) -> fitsio::errors::Result<u32> {
+    label
+        .trim_start_matches("value")
+        .parse()
+        .map_err(|_| "invalid label".into())
+}
+#
+# fn try_main() -> Result<(), Box<std::error::Error>> {
+# let filename = "../testdata/full_example.fits";
+# let mut f = fitsio::FitsFile::open(filename)?;
+# let hdu = f.hdu("TESTEXT")?;
+
+let row: Row = hdu.row(&mut f, 4)?;
+assert_eq!(row.intcol, 16);
+assert_eq!(row.number, 4);
+# Ok(())
+# }
+# fn main() { try_main().unwrap(); }
+```
+
 ## Iterating over columns
 
 Iterate over the columns with [`columns`][fits-hdu-columns].
@@ -585,7 +719,8 @@ Iterate over the columns with [`columns`][fits-hdu-columns].
 # let filename = "../testdata/full_example.fits";
 # let mut fptr = fitsio::FitsFile::open(filename)?;
 # let hdu = fptr.hdu("TESTEXT")?;
-for column in hdu.columns(&mut fptr) {
+for column in hdu.columns(&mut fptr)? {
+    let column = column?;
     // Do something with column
 }
 # Ok(())
@@ -755,8 +890,9 @@ assert_eq!(data, vec![10101, 10101, 10101, 10101, 10101]);
 # fn main() { try_main().unwrap(); }
 ```
 
-[`write_col_range`][fits-hdu-write-col-range] writes data to a range of rows in a table. The
-range is inclusive of both the upper and lower bounds, so `0..4` writes 5 elements.
+[`write_col_range`][fits-hdu-write-col-range] writes data to a range of rows in a table. Any
+of the standard range types can be used, e.g. `0..5` or `0..=4` both write 5 elements. The length
+of the data must match the range.
 
 ```rust
 # use std::fs::copy;
@@ -773,8 +909,8 @@ range is inclusive of both the upper and lower bounds, so `0..4` writes 5 elemen
 #         .create()?,
 # ];
 # let hdu = fptr.create_table("foo".to_string(), &table_description)?;
-let data_to_write: Vec<i32> = vec![10101; 10];
-hdu.write_col_range(&mut fptr, "bar", &data_to_write, &(0..5))?;
+let data_to_write: Vec<i32> = vec![10101; 5];
+hdu.write_col_range(&mut fptr, "bar", &data_to_write, 0..5)?;
 let data: Vec<i32> = hdu.read_col(&mut fptr, "bar")?;
 assert_eq!(data, vec![10101, 10101, 10101, 10101, 10101]);
 # Ok(())
@@ -928,14 +1064,25 @@ Access to a [`FitsFile`][fits-file] is not threadsafe. Behind the scenes, fetchi
 [`FitsHdu`][fits-hdu] changes internal state, and `fitsio` does not provide any concurrent access
 gauruntees. Therefore, a [`FitsFile`][fits-file] does not implement `Send` or `Sync`.
 
+If the linked `cfitsio` was compiled with `--enable-reentrant` (as the bundled `fitsio-src`
+build is), [`into_send`][fits-file-into-send] wraps a [`FitsFile`][fits-file] in a
+[`SendFitsFile`][send-fits-file] which implements `Send`, so a file can be moved to another
+thread. Whether the library is reentrant can be checked at runtime with [`sys_info`][sys-info].
+
 In order to allow for threadsafe access, the [`FitsFile`][fits-file] struct has a
 [`threadsafe`][fits-file-threadsafe] method, which returns a threadsafe
 [`ThreadsafeFitsFile`][threadsafe-fits-file] struct (a tuple-type wrapper around
-`Arc<Mutex<FitsFile>>`) which can be shared between threads safely.
+`Arc<Mutex<FitsFile>>`) which can be shared between threads safely. When `cfitsio` is not
+reentrant, locking a [`ThreadsafeFitsFile`][threadsafe-fits-file] also prevents any other
+[`ThreadsafeFitsFile`][threadsafe-fits-file] from being used at the same time.
 
 The same concerns with `Arc<Mutex<T>>` data should be applied here. Additionally, the library is
 subject to OS level limits, such as the maximum number of open files.
 
+For files which are only read, and a reentrant `cfitsio`, [`into_shared`][fits-file-into-shared]
+returns a [`SharedFitsFile`][shared-fits-file] which is `Sync`. Each thread borrows its own
+`cfitsio` handle to the file, so threads reading different HDUs do not wait for each other.
+
 ## Example
 
 ```rust
@@ -977,6 +1124,8 @@ let _hdu = t.hdu(hdu_num).unwrap();
 [fits-file-create]: fitsfile/struct.FitsFile.html#method.create
 [fits-file-edit]: fitsfile/struct.FitsFile.html#method.edit
 [fits-file-threadsafe]: fitsfile/struct.FitsFile.html#method.threadsafe
+[fits-file-into-shared]: fitsfile/struct.FitsFile.html#method.into_shared
+[fits-file-into-send]: fitsfile/struct.FitsFile.html#method.into_send
 [fits-file]: fitsfile/struct.FitsFile.html
 [fits-hdu]: hdu/struct.FitsHdu.html
 [fits-hdu-append-column]: hdu/struct.FitsHdu.html#method.append_column
@@ -984,6 +1133,7 @@ let _hdu = t.hdu(hdu_num).unwrap();
 [fits-hdu-delete-column]: hdu/struct.FitsHdu.html#method.delete_column
 [fits-hdu-insert-column]: hdu/struct.FitsHdu.html#method.insert_column
 [fits-hdu-read-col]: hdu/struct.FitsHdu.html#method.read_col
+[fits-header]: headers/trait.FitsHeader.html
 [fits-hdu-read-key]: hdu/struct.FitsHdu.html#method.read_key
 [fits-hdu-read-region]: hdu/struct.FitsHdu.html#method.read_region
 [fits-hdu-read-section]: hdu/struct.FitsHdu.html#method.read_section
@@ -1007,6 +1157,8 @@ let _hdu = t.hdu(hdu_num).unwrap();
 [new-fits-file-with-custom-primary]: fitsfile/struct.NewFitsFile.html#method.with_custom_primary
 [pretty-print]: fitsfile/struct.FitsFile.html#method.pretty_print
 [pretty-write]: fitsfile/struct.FitsFile.html#method.pretty_write
+[pretty-write-with]: fitsfile/struct.FitsFile.html#method.pretty_write_with
+[pretty-print-options]: struct.PrettyPrintOptions.html
 [fitsio-derive]: https://crates.io/crates/fitsio-derive
 [ndarray]: https://crates.io/crates/ndarray
 [arrayd]: https://docs.rs/ndarray/0.11.2/ndarray/type.ArrayD.html
@@ -1014,6 +1166,10 @@ let _hdu = t.hdu(hdu_num).unwrap();
 [`fitssummary`]: ../fitssummary/index.html
 [fitsfile-hdu]: fitsfile/struct.FitsFile.html#method.hdu
 [threadsafe-fits-file]: threadsafe_fitsfile/struct.ThreadsafeFitsFile.html
+[send-fits-file]: threadsafe_fitsfile/struct.SendFitsFile.html
+[table-appender]: tables/struct.TableAppender.html
+[shared-fits-file]: threadsafe_fitsfile/struct.SharedFitsFile.html
+[sys-info]: fn.sys_info.html
 */
 
 #![doc(html_root_url = "https://docs.rs/fitsio/0.21.2")]
@@ -1028,24 +1184,34 @@ pub use fitsio_sys as sys;
 mod macros;
 mod fitsfile;
 mod longnam;
+mod memfile;
 #[cfg(feature = "array")]
 mod ndarray_compat;
+mod ranges;
 mod stringutils;
-#[cfg(test)]
-mod testhelpers;
+mod sysinfo;
 mod types;
 
 // Public mods
+pub mod diff;
+pub mod gti;
 pub mod hdu;
 pub mod headers;
 pub mod images;
+#[cfg(feature = "reproject")]
+pub mod reproject;
 pub mod tables;
+#[cfg(any(test, feature = "testing"))]
+pub mod testing;
 pub mod threadsafe_fitsfile;
+pub mod verify;
 
 pub mod errors;
 
 // Re-exports
-pub use crate::fitsfile::{FileOpenMode, FitsFile};
+pub use crate::fitsfile::{FileOpenMode, FitsFile, PrettyPrintOptions};
+pub use crate::ranges::{IndexRange, RegionRanges};
+pub use crate::sysinfo::{sys_info, SysInfo};
 
 // For custom derive purposes
 // pub use tables::FitsRow;
@@ -5,17 +5,35 @@
 #![allow(unused_imports, dead_code)]
 
 pub(crate) use crate::sys::{
-    ffclos, ffcopy, ffcrim, ffcrtb, ffdcol, ffdhdu, ffflmd, ffgbcl, ffgcdw, ffgcno, ffgcvd, ffgcve,
-    ffgcvi, ffgcvj, ffgcvjj, ffgcvk, ffgcvs, ffgcvui, ffgcvuj, ffgcvujj, ffgcvuk, ffghdn, ffghdt,
-    ffgidm, ffgiet, ffgisz, ffgkyd, ffgkye, ffgkyj, ffgkyjj, ffgkyl, ffgkys, ffgncl, ffgnrw, ffgpv,
-    ffgsv, fficol, ffinit, ffmahd, ffmnhd, ffopen, ffpcl, ffpcls, ffphps, ffpky, ffpkyd, ffpkye,
-    ffpkys, ffppr, ffpss, ffrsim, ffthdu, fitsfile, LONGLONG,
+    ffclos, ffcopy, ffcrim, ffcrtb, ffdcol, ffdhdu, ffdkey, ffdkopn, ffdrws, ffdtyp, ffeqtyll,
+    ffflmd, ffflus, ffgbcl, ffgcdw, ffgcf, ffgcno, ffgcrd, ffgcvb, ffgcvd, ffgcve, ffgcvi, ffgcvj,
+    ffgcvjj, ffgcvk, ffgcvl, ffgcvs, ffgcvui, ffgcvuj, ffgcvujj, ffgcvuk, ffghadll, ffghdn, ffghdt,
+    ffghps, ffghsp, ffgics, ffgidm, ffgidt, ffgiet, ffgisz, ffgkey, ffgknm, ffgkyd, ffgkye, ffgkyj,
+    ffgkyjj, ffgkyl, ffgkys, ffgkyujj, ffgncl, ffgnrw, ffgnxk, ffgpv, ffgrec, ffgrsz, ffgsv,
+    ffgtbb, ffgthd, fficol, ffiimg, ffimem, ffinit, ffirec, ffirow, ffiter, ffmahd, ffmbyt, ffmnam,
+    ffmnhd, ffomem, ffopen, ffpcl, ffpcls, ffpclu, ffphps, ffpky, ffpkyd, ffpkye, ffpkyf, ffpkyg,
+    ffpkyl, ffpkys, ffppr, ffpprn, ffprec, ffpscl, ffpss, ffpsvc, ffptbb, ffrdef, ffrsim, ffthdu,
+    ffucrd, ffuky, ffvcks, ffwldp, ffxypx, fits_img_compress, fits_img_decompress,
+    fits_is_compressed_image, fits_set_compression_type, fits_set_dither_seed,
+    fits_set_hcomp_scale, fits_set_lossy_int, fits_set_quantize_level, fits_set_quantize_method,
+    fits_set_tile_dim, fitsfile, iteratorCol, LONGLONG, ULONGLONG,
 };
 pub use libc::{
-    c_char, c_double, c_float, c_int, c_long, c_short, c_uint, c_ulong, c_ulonglong, c_ushort,
-    c_void,
+    c_char, c_double, c_float, c_int, c_long, c_short, c_uchar, c_uint, c_ulong, c_ulonglong,
+    c_ushort, c_void,
 };
 
+extern "C" {
+    // Part of the private cfitsio API, so not in the generated bindings, but exported by the
+    // library. Writes bytes at the position set by `ffmbyt`.
+    pub(crate) fn ffpbyt(
+        fptr: *mut fitsfile,
+        nbytes: LONGLONG,
+        buffer: *mut c_void,
+        status: *mut c_int,
+    ) -> c_int;
+}
+
 pub(crate) unsafe fn fits_close_file(fptr: *mut fitsfile, status: *mut libc::c_int) -> c_int {
     ffclos(fptr, status)
 }
@@ -39,6 +57,16 @@ pub(crate) unsafe fn fits_create_img(
     ffcrim(fptr, bitpix, naxis, naxes, status)
 }
 
+pub(crate) unsafe fn fits_insert_img(
+    fptr: *mut fitsfile,
+    bitpix: c_int,
+    naxis: c_int,
+    naxes: *mut c_long,
+    status: *mut c_int,
+) -> c_int {
+    ffiimg(fptr, bitpix, naxis, naxes, status)
+}
+
 pub(crate) unsafe fn fits_create_tbl(
     fptr: *mut fitsfile,
     tbltype: c_int,
@@ -71,6 +99,23 @@ pub(crate) unsafe fn fits_delete_hdu(
     ffdhdu(fptr, hdutype, status)
 }
 
+pub(crate) unsafe fn fits_delete_key(
+    fptr: *mut fitsfile,
+    keyname: *const c_char,
+    status: *mut c_int,
+) -> c_int {
+    ffdkey(fptr, keyname, status)
+}
+
+pub(crate) unsafe fn fits_modify_name(
+    fptr: *mut fitsfile,
+    oldname: *const c_char,
+    newname: *const c_char,
+    status: *mut c_int,
+) -> c_int {
+    ffmnam(fptr, oldname, newname, status)
+}
+
 pub(crate) unsafe fn fits_file_mode(
     fptr: *mut fitsfile,
     filemode: *mut c_int,
@@ -116,6 +161,23 @@ pub(crate) unsafe fn fits_get_colnum(
     ffgcno(fptr, casesen, templt, colnum, status)
 }
 
+pub(crate) unsafe fn fits_read_colnull(
+    fptr: *mut fitsfile,
+    datatype: c_int,
+    colnum: c_int,
+    firstrow: LONGLONG,
+    firstelem: LONGLONG,
+    nelem: LONGLONG,
+    array: *mut c_void,
+    nullarray: *mut c_char,
+    anynul: *mut c_int,
+    status: *mut c_int,
+) -> c_int {
+    ffgcf(
+        fptr, datatype, colnum, firstrow, firstelem, nelem, array, nullarray, anynul, status,
+    )
+}
+
 pub(crate) unsafe fn fits_read_col_str(
     fptr: *mut fitsfile,
     colnum: c_int,
@@ -132,6 +194,47 @@ pub(crate) unsafe fn fits_read_col_str(
     )
 }
 
+pub(crate) unsafe fn fits_read_col_byt(
+    fptr: *mut fitsfile,
+    colnum: c_int,
+    firstrow: LONGLONG,
+    firstelem: LONGLONG,
+    nelem: LONGLONG,
+    nulval: c_uchar,
+    array: *mut c_uchar,
+    anynul: *mut c_int,
+    status: *mut c_int,
+) -> c_int {
+    ffgcvb(
+        fptr, colnum, firstrow, firstelem, nelem, nulval, array, anynul, status,
+    )
+}
+
+/// `cfitsio` only stores 0 or 1 in the array, so logical values can be read directly into `bool`s
+pub(crate) unsafe fn fits_read_col_log(
+    fptr: *mut fitsfile,
+    colnum: c_int,
+    firstrow: LONGLONG,
+    firstelem: LONGLONG,
+    nelem: LONGLONG,
+    nulval: bool,
+    array: *mut bool,
+    anynul: *mut c_int,
+    status: *mut c_int,
+) -> c_int {
+    ffgcvl(
+        fptr,
+        colnum,
+        firstrow,
+        firstelem,
+        nelem,
+        nulval as c_char,
+        array as *mut c_char,
+        anynul,
+        status,
+    )
+}
+
 pub(crate) unsafe fn fits_read_col_sht(
     fptr: *mut fitsfile,
     colnum: c_int,
@@ -325,6 +428,16 @@ pub(crate) unsafe fn fits_read_key_lnglng(
     ffgkyjj(fptr, keyname, value, comm, status)
 }
 
+pub(crate) unsafe fn fits_read_key_ulnglng(
+    fptr: *mut fitsfile,
+    keyname: *const c_char,
+    value: *mut ULONGLONG,
+    comm: *mut c_char,
+    status: *mut c_int,
+) -> c_int {
+    ffgkyujj(fptr, keyname, value, comm, status)
+}
+
 pub(crate) unsafe fn fits_read_key_flt(
     fptr: *mut fitsfile,
     keyname: *const c_char,
@@ -345,6 +458,54 @@ pub(crate) unsafe fn fits_read_key_dbl(
     ffgkyd(fptr, keyname, value, comm, status)
 }
 
+pub(crate) unsafe fn fits_find_nextkey(
+    fptr: *mut fitsfile,
+    inclist: *mut *mut c_char,
+    ninc: c_int,
+    exclist: *mut *mut c_char,
+    nexc: c_int,
+    card: *mut c_char,
+    status: *mut c_int,
+) -> c_int {
+    ffgnxk(fptr, inclist, ninc, exclist, nexc, card, status)
+}
+
+pub(crate) unsafe fn fits_read_record(
+    fptr: *mut fitsfile,
+    nrec: c_int,
+    card: *mut c_char,
+    status: *mut c_int,
+) -> c_int {
+    ffgrec(fptr, nrec, card, status)
+}
+
+pub(crate) unsafe fn fits_read_card(
+    fptr: *mut fitsfile,
+    keyname: *const c_char,
+    card: *mut c_char,
+    status: *mut c_int,
+) -> c_int {
+    ffgcrd(fptr, keyname, card, status)
+}
+
+pub(crate) unsafe fn fits_get_keyname(
+    card: *mut c_char,
+    name: *mut c_char,
+    length: *mut c_int,
+    status: *mut c_int,
+) -> c_int {
+    ffgknm(card, name, length, status)
+}
+
+pub(crate) unsafe fn fits_parse_value(
+    card: *mut c_char,
+    value: *mut c_char,
+    comm: *mut c_char,
+    status: *mut c_int,
+) -> c_int {
+    ffpsvc(card, value, comm, status)
+}
+
 pub(crate) unsafe fn fits_get_hdu_num(fptr: *mut fitsfile, chdunum: *mut c_int) -> c_int {
     ffghdn(fptr, chdunum)
 }
@@ -365,6 +526,14 @@ pub(crate) unsafe fn fits_get_img_dim(
     ffgidm(fptr, naxis, status)
 }
 
+pub(crate) unsafe fn fits_get_img_type(
+    fptr: *mut fitsfile,
+    imgtype: *mut c_int,
+    status: *mut c_int,
+) -> c_int {
+    ffgidt(fptr, imgtype, status)
+}
+
 pub(crate) unsafe fn fits_get_img_equivtype(
     fptr: *mut fitsfile,
     imgtype: *mut c_int,
@@ -408,6 +577,14 @@ pub(crate) unsafe fn fits_get_num_rows(
     ffgnrw(fptr, nrows, status)
 }
 
+pub(crate) unsafe fn fits_get_rowsize(
+    fptr: *mut fitsfile,
+    nrows: *mut c_long,
+    status: *mut c_int,
+) -> c_int {
+    ffgrsz(fptr, nrows, status)
+}
+
 pub(crate) unsafe fn fits_read_img(
     fptr: *mut fitsfile,
     datatype: c_int,
@@ -447,6 +624,36 @@ pub(crate) unsafe fn fits_insert_col(
     fficol(fptr, numcol, ttype, tform, status)
 }
 
+pub(crate) type IteratorWorkFn = unsafe extern "C" fn(
+    totaln: c_long,
+    offset: c_long,
+    firstn: c_long,
+    nvalues: c_long,
+    narrays: c_int,
+    data: *mut iteratorCol,
+    user_pointer: *mut c_void,
+) -> c_int;
+
+pub(crate) unsafe fn fits_iterate_data(
+    ncols: c_int,
+    data: *mut iteratorCol,
+    offset: c_long,
+    n_per_loop: c_long,
+    work_fn: Option<IteratorWorkFn>,
+    user_pointer: *mut c_void,
+    status: *mut c_int,
+) -> c_int {
+    ffiter(
+        ncols,
+        data,
+        offset,
+        n_per_loop,
+        work_fn,
+        user_pointer,
+        status,
+    )
+}
+
 pub(crate) unsafe fn fits_movabs_hdu(
     fptr: *mut fitsfile,
     hdunum: c_int,
@@ -501,6 +708,17 @@ pub(crate) unsafe fn fits_write_col_str(
     ffpcls(fptr, colnum, firstrow, firstelem, nelem, array, status)
 }
 
+pub(crate) unsafe fn fits_write_col_null(
+    fptr: *mut fitsfile,
+    colnum: c_int,
+    firstrow: LONGLONG,
+    firstelem: LONGLONG,
+    nelem: LONGLONG,
+    status: *mut c_int,
+) -> c_int {
+    ffpclu(fptr, colnum, firstrow, firstelem, nelem, status)
+}
+
 pub(crate) unsafe fn fits_write_imghdr(
     fptr: *mut fitsfile,
     bitpix: c_int,
@@ -532,6 +750,38 @@ pub(crate) unsafe fn fits_write_key_dbl(
 ) -> c_int {
     ffpkyd(fptr, keyname, value, decim, comm, status)
 }
+pub(crate) unsafe fn fits_write_key_fixflt(
+    fptr: *mut fitsfile,
+    keyname: *const c_char,
+    value: c_float,
+    decim: c_int,
+    comm: *const c_char,
+    status: *mut c_int,
+) -> c_int {
+    ffpkyf(fptr, keyname, value, decim, comm, status)
+}
+
+pub(crate) unsafe fn fits_write_key_fixdbl(
+    fptr: *mut fitsfile,
+    keyname: *const c_char,
+    value: c_double,
+    decim: c_int,
+    comm: *const c_char,
+    status: *mut c_int,
+) -> c_int {
+    ffpkyg(fptr, keyname, value, decim, comm, status)
+}
+
+pub(crate) unsafe fn fits_write_key_log(
+    fptr: *mut fitsfile,
+    keyname: *const c_char,
+    value: c_int,
+    comm: *const c_char,
+    status: *mut c_int,
+) -> c_int {
+    ffpkyl(fptr, keyname, value, comm, status)
+}
+
 pub(crate) unsafe fn fits_write_key_str(
     fptr: *mut fitsfile,
     keyname: *const c_char,
@@ -553,6 +803,24 @@ pub(crate) unsafe fn fits_write_img(
     ffppr(fptr, datatype, firstelem, nelem, array, status)
 }
 
+pub(crate) unsafe fn fits_write_null_img(
+    fptr: *mut fitsfile,
+    firstelem: LONGLONG,
+    nelem: LONGLONG,
+    status: *mut c_int,
+) -> c_int {
+    ffpprn(fptr, firstelem, nelem, status)
+}
+
+pub(crate) unsafe fn fits_set_bscale(
+    fptr: *mut fitsfile,
+    scale: c_double,
+    zero: c_double,
+    status: *mut c_int,
+) -> c_int {
+    ffpscl(fptr, scale, zero, status)
+}
+
 pub(crate) unsafe fn fits_write_subset(
     fptr: *mut fitsfile,
     datatype: c_int,
@@ -564,6 +832,10 @@ pub(crate) unsafe fn fits_write_subset(
     ffpss(fptr, datatype, fpixel, lpixel, array, status)
 }
 
+pub(crate) unsafe fn fits_set_hdustruc(fptr: *mut fitsfile, status: *mut c_int) -> c_int {
+    ffrdef(fptr, status)
+}
+
 pub(crate) unsafe fn fits_resize_img(
     fptr: *mut fitsfile,
     bitpix: c_int,
@@ -591,6 +863,15 @@ pub(crate) unsafe fn fits_open_file(
     ffopen(fptr, filename, iomode, status)
 }
 
+pub(crate) unsafe fn fits_open_diskfile(
+    fptr: *mut *mut fitsfile,
+    filename: *const c_char,
+    iomode: c_int,
+    status: *mut c_int,
+) -> c_int {
+    ffdkopn(fptr, filename, iomode, status)
+}
+
 pub(crate) unsafe fn fits_write_key(
     fptr: *mut fitsfile,
     datatype: c_int,
@@ -601,3 +882,249 @@ pub(crate) unsafe fn fits_write_key(
 ) -> c_int {
     ffpky(fptr, datatype, keyname, value, comm, status)
 }
+
+pub(crate) unsafe fn fits_update_key(
+    fptr: *mut fitsfile,
+    datatype: c_int,
+    keyname: *const c_char,
+    value: *mut c_void,
+    comm: *const c_char,
+    status: *mut c_int,
+) -> c_int {
+    ffuky(fptr, datatype, keyname, value, comm, status)
+}
+
+pub(crate) unsafe fn fits_verify_chksum(
+    fptr: *mut fitsfile,
+    datastatus: *mut c_int,
+    hdustatus: *mut c_int,
+    status: *mut c_int,
+) -> c_int {
+    ffvcks(fptr, datastatus, hdustatus, status)
+}
+
+pub(crate) unsafe fn fits_get_hdrspace(
+    fptr: *mut fitsfile,
+    nexist: *mut c_int,
+    nmore: *mut c_int,
+    status: *mut c_int,
+) -> c_int {
+    ffghsp(fptr, nexist, nmore, status)
+}
+
+pub(crate) unsafe fn fits_parse_template(
+    tmplt: *mut c_char,
+    card: *mut c_char,
+    hdtype: *mut c_int,
+    status: *mut c_int,
+) -> c_int {
+    ffgthd(tmplt, card, hdtype, status)
+}
+
+pub(crate) unsafe fn fits_get_hdrpos(
+    fptr: *mut fitsfile,
+    nexist: *mut c_int,
+    position: *mut c_int,
+    status: *mut c_int,
+) -> c_int {
+    ffghps(fptr, nexist, position, status)
+}
+
+pub(crate) unsafe fn fits_insert_record(
+    fptr: *mut fitsfile,
+    nkey: c_int,
+    card: *const c_char,
+    status: *mut c_int,
+) -> c_int {
+    ffirec(fptr, nkey, card, status)
+}
+
+pub(crate) unsafe fn fits_update_card(
+    fptr: *mut fitsfile,
+    keyname: *const c_char,
+    card: *const c_char,
+    status: *mut c_int,
+) -> c_int {
+    ffucrd(fptr, keyname, card, status)
+}
+
+pub(crate) unsafe fn fits_write_record(
+    fptr: *mut fitsfile,
+    card: *const c_char,
+    status: *mut c_int,
+) -> c_int {
+    ffprec(fptr, card, status)
+}
+
+pub(crate) unsafe fn fits_open_memfile(
+    fptr: *mut *mut fitsfile,
+    name: *const c_char,
+    mode: c_int,
+    buffptr: *mut *mut c_void,
+    buffsize: *mut usize,
+    deltasize: usize,
+    mem_realloc: Option<unsafe extern "C" fn(p: *mut c_void, newsize: usize) -> *mut c_void>,
+    status: *mut c_int,
+) -> c_int {
+    ffomem(
+        fptr,
+        name,
+        mode,
+        buffptr,
+        buffsize,
+        deltasize,
+        mem_realloc,
+        status,
+    )
+}
+
+pub(crate) unsafe fn fits_create_memfile(
+    fptr: *mut *mut fitsfile,
+    buffptr: *mut *mut c_void,
+    buffsize: *mut usize,
+    deltasize: usize,
+    mem_realloc: Option<unsafe extern "C" fn(p: *mut c_void, newsize: usize) -> *mut c_void>,
+    status: *mut c_int,
+) -> c_int {
+    ffimem(fptr, buffptr, buffsize, deltasize, mem_realloc, status)
+}
+
+pub(crate) unsafe fn fits_flush_file(fptr: *mut fitsfile, status: *mut c_int) -> c_int {
+    ffflus(fptr, status)
+}
+
+pub(crate) unsafe fn fits_get_hduaddrll(
+    fptr: *mut fitsfile,
+    headstart: *mut LONGLONG,
+    datastart: *mut LONGLONG,
+    dataend: *mut LONGLONG,
+    status: *mut c_int,
+) -> c_int {
+    ffghadll(fptr, headstart, datastart, dataend, status)
+}
+
+pub(crate) unsafe fn fits_read_keyword(
+    fptr: *mut fitsfile,
+    keyname: *const c_char,
+    keyval: *mut c_char,
+    comm: *mut c_char,
+    status: *mut c_int,
+) -> c_int {
+    ffgkey(fptr, keyname, keyval, comm, status)
+}
+
+pub(crate) unsafe fn fits_get_keytype(
+    cval: *const c_char,
+    dtype: *mut c_char,
+    status: *mut c_int,
+) -> c_int {
+    ffdtyp(cval, dtype, status)
+}
+
+pub(crate) unsafe fn fits_get_eqcoltypell(
+    fptr: *mut fitsfile,
+    colnum: c_int,
+    typecode: *mut c_int,
+    repeat: *mut LONGLONG,
+    width: *mut LONGLONG,
+    status: *mut c_int,
+) -> c_int {
+    ffeqtyll(fptr, colnum, typecode, repeat, width, status)
+}
+
+pub(crate) unsafe fn fits_read_tblbytes(
+    fptr: *mut fitsfile,
+    firstrow: LONGLONG,
+    firstchar: LONGLONG,
+    nchars: LONGLONG,
+    values: *mut c_uchar,
+    status: *mut c_int,
+) -> c_int {
+    ffgtbb(fptr, firstrow, firstchar, nchars, values, status)
+}
+
+pub(crate) unsafe fn fits_write_tblbytes(
+    fptr: *mut fitsfile,
+    firstrow: LONGLONG,
+    firstchar: LONGLONG,
+    nchars: LONGLONG,
+    values: *mut c_uchar,
+    status: *mut c_int,
+) -> c_int {
+    ffptbb(fptr, firstrow, firstchar, nchars, values, status)
+}
+
+pub(crate) unsafe fn fits_delete_rowlist(
+    fptr: *mut fitsfile,
+    rownum: *mut c_long,
+    nrows: c_long,
+    status: *mut c_int,
+) -> c_int {
+    ffdrws(fptr, rownum, nrows, status)
+}
+
+pub(crate) unsafe fn fits_insert_rows(
+    fptr: *mut fitsfile,
+    firstrow: LONGLONG,
+    nrows: LONGLONG,
+    status: *mut c_int,
+) -> c_int {
+    ffirow(fptr, firstrow, nrows, status)
+}
+
+pub(crate) unsafe fn fits_read_img_coord(
+    fptr: *mut fitsfile,
+    xrval: *mut c_double,
+    yrval: *mut c_double,
+    xrpix: *mut c_double,
+    yrpix: *mut c_double,
+    xinc: *mut c_double,
+    yinc: *mut c_double,
+    rot: *mut c_double,
+    coordtype: *mut c_char,
+    status: *mut c_int,
+) -> c_int {
+    ffgics(
+        fptr, xrval, yrval, xrpix, yrpix, xinc, yinc, rot, coordtype, status,
+    )
+}
+
+pub(crate) unsafe fn fits_pix_to_world(
+    xpix: c_double,
+    ypix: c_double,
+    xrval: c_double,
+    yrval: c_double,
+    xrpix: c_double,
+    yrpix: c_double,
+    xinc: c_double,
+    yinc: c_double,
+    rot: c_double,
+    coordtype: *mut c_char,
+    xpos: *mut c_double,
+    ypos: *mut c_double,
+    status: *mut c_int,
+) -> c_int {
+    ffwldp(
+        xpix, ypix, xrval, yrval, xrpix, yrpix, xinc, yinc, rot, coordtype, xpos, ypos, status,
+    )
+}
+
+pub(crate) unsafe fn fits_world_to_pix(
+    xpos: c_double,
+    ypos: c_double,
+    xrval: c_double,
+    yrval: c_double,
+    xrpix: c_double,
+    yrpix: c_double,
+    xinc: c_double,
+    yinc: c_double,
+    rot: c_double,
+    coordtype: *mut c_char,
+    xpix: *mut c_double,
+    ypix: *mut c_double,
+    status: *mut c_int,
+) -> c_int {
+    ffxypx(
+        xpos, ypos, xrval, yrval, xrpix, yrpix, xinc, yinc, rot, coordtype, xpix, ypix, status,
+    )
+}
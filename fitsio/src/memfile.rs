@@ -0,0 +1,173 @@
+//! Memory backing for files opened with the `cfitsio` memory driver
+
+use crate::errors::{check_status, Result};
+use crate::fitsfile::FileOpenMode;
+use crate::longnam::*;
+use std::ffi;
+use std::ptr;
+use std::sync::Arc;
+
+/// Size of the first allocation, and of each later reallocation, of new files
+const ALLOCATION_SIZE: usize = 2880 * 32;
+
+/// Where the memory of a [`MemoryBuffer`] came from, and so how it must be released
+enum Owner {
+    /// A read only mapping of a file on disk
+    #[cfg(unix)]
+    Mapped,
+    /// Memory allocated with `malloc`, which `cfitsio` may `realloc` as the file grows
+    Allocated,
+    /// Bytes owned by the buffer, which are only held to keep them alive
+    #[allow(dead_code)]
+    Bytes(Vec<u8>),
+    /// Bytes shared with other buffers, which are only ever opened read only
+    #[allow(dead_code)]
+    Shared(Arc<Vec<u8>>),
+}
+
+/**
+A block of memory containing a fits file
+
+`cfitsio` keeps pointers to the `ptr` and `size` fields while the file is open, so a
+`MemoryBuffer` is always boxed and must outlive the `fitsfile` opened from it.
+*/
+pub(crate) struct MemoryBuffer {
+    ptr: *mut c_void,
+    size: usize,
+    owner: Owner,
+}
+
+impl MemoryBuffer {
+    /// Map the contents of the file at `path` read only into memory
+    ///
+    /// # Safety
+    ///
+    /// The file must not be modified or truncated while the buffer exists.
+    #[cfg(unix)]
+    pub(crate) unsafe fn map(path: &std::path::Path) -> Result<Box<Self>> {
+        use std::os::unix::io::AsRawFd;
+
+        let file = std::fs::File::open(path)?;
+        let size = file.metadata()?.len() as usize;
+        if size == 0 {
+            return Err(format!("cannot map empty file {:?}", path).as_str().into());
+        }
+
+        let ptr = libc::mmap(
+            ptr::null_mut(),
+            size,
+            libc::PROT_READ,
+            libc::MAP_SHARED,
+            file.as_raw_fd(),
+            0,
+        );
+        if ptr == libc::MAP_FAILED {
+            return Err(std::io::Error::last_os_error().into());
+        }
+
+        Ok(Box::new(MemoryBuffer {
+            ptr,
+            size,
+            owner: Owner::Mapped,
+        }))
+    }
+
+    /// Allocate memory for a new fits file
+    pub(crate) fn allocate() -> Result<Box<Self>> {
+        let ptr = unsafe { libc::malloc(ALLOCATION_SIZE) };
+        if ptr.is_null() {
+            return Err("cannot allocate memory for a fits file".into());
+        }
+
+        Ok(Box::new(MemoryBuffer {
+            ptr,
+            size: ALLOCATION_SIZE,
+            owner: Owner::Allocated,
+        }))
+    }
+
+    /// Take ownership of the bytes of a fits file
+    pub(crate) fn from_bytes(mut bytes: Vec<u8>) -> Result<Box<Self>> {
+        if bytes.is_empty() {
+            return Err("cannot open an empty fits file".into());
+        }
+
+        Ok(Box::new(MemoryBuffer {
+            ptr: bytes.as_mut_ptr() as *mut c_void,
+            size: bytes.len(),
+            owner: Owner::Bytes(bytes),
+        }))
+    }
+
+    /// Share the bytes of a fits file with other buffers
+    ///
+    /// `cfitsio` never writes to a file opened read only, so the bytes are not modified.
+    pub(crate) fn from_shared(bytes: Arc<Vec<u8>>) -> Result<Box<Self>> {
+        if bytes.is_empty() {
+            return Err("cannot open an empty fits file".into());
+        }
+
+        Ok(Box::new(MemoryBuffer {
+            ptr: bytes.as_ptr() as *mut c_void,
+            size: bytes.len(),
+            owner: Owner::Shared(bytes),
+        }))
+    }
+
+    /// Create a new, empty, fits file in allocated memory
+    pub(crate) fn create(&mut self) -> Result<*mut fitsfile> {
+        let mut fptr = ptr::null_mut();
+        let mut status = 0;
+        unsafe {
+            fits_create_memfile(
+                &mut fptr as *mut *mut _,
+                &mut self.ptr,
+                &mut self.size,
+                ALLOCATION_SIZE,
+                Some(libc::realloc),
+                &mut status,
+            );
+        }
+        check_status(status).map(|_| fptr)
+    }
+
+    /// The whole buffer, which may be larger than the file it contains
+    pub(crate) fn bytes(&self) -> &[u8] {
+        unsafe { std::slice::from_raw_parts(self.ptr as *const u8, self.size) }
+    }
+
+    /// Open the buffer as a read only fits file
+    pub(crate) fn open(&mut self, name: &str) -> Result<*mut fitsfile> {
+        let c_name = ffi::CString::new(name)?;
+        let mut fptr = ptr::null_mut();
+        let mut status = 0;
+        unsafe {
+            fits_open_memfile(
+                &mut fptr as *mut *mut _,
+                c_name.as_ptr(),
+                FileOpenMode::READONLY as c_int,
+                &mut self.ptr,
+                &mut self.size,
+                0,
+                None,
+                &mut status,
+            );
+        }
+        check_status(status).map(|_| fptr)
+    }
+}
+
+impl Drop for MemoryBuffer {
+    fn drop(&mut self) {
+        match self.owner {
+            #[cfg(unix)]
+            Owner::Mapped => unsafe {
+                libc::munmap(self.ptr, self.size);
+            },
+            Owner::Allocated => unsafe {
+                libc::free(self.ptr);
+            },
+            Owner::Bytes(_) | Owner::Shared(_) => {}
+        }
+    }
+}
@@ -14,6 +14,15 @@ Data is read into the [`ndarray::ArrayD`][arrayd] type. The following methods fr
 * [`read_rows`][read-rows]
 * [`read_section`][read-section]
 
+Arrays can also be written to an image with [`write_array`][write-array].
+
+Table columns can be read into the [`ndarray::Array1`][array1] type with
+[`read_col`][read-col].
+
+Same-shaped image extensions, for example one per amplifier or chip, can be stacked into a
+single array with [`FitsFile::read_cube`][read-cube], and split back into extensions with
+[`FitsFile::write_cube`][write-cube].
+
 ## `read_image`
 
 ```rust
@@ -50,12 +59,14 @@ use ndarray::ArrayD;
 let mut f = FitsFile::open("../testdata/full_example.fits").unwrap();
 let hdu = f.primary_hdu().unwrap();
 
+// 10 pixels along the first (x) axis and 30 along the second (y) axis
 let data: ArrayD<u32> = hdu.read_region(&mut f, &[&(70..80), &(20..50)]).unwrap();
 let dim = data.dim();
 assert_eq!(data.ndim(), 2);
-assert_eq!(dim[0], 10);
-assert_eq!(dim[1], 30);
-assert_eq!(data[[5, 10]], 177);
+// The array is in C order, so the last dimension varies fastest
+assert_eq!(dim[0], 30);
+assert_eq!(dim[1], 10);
+assert_eq!(data[[10, 5]], 193);
 # }
 #
 # #[cfg(not(feature = "array"))]
@@ -144,21 +155,222 @@ assert_eq!(data[[0, 10]], 160);
 
 [ndarray]: https://crates.io/crates/ndarray
 [arrayd]: https://docs.rs/ndarray/0.11.2/ndarray/type.ArrayD.html
+[array1]: https://docs.rs/ndarray/0.11.2/ndarray/type.Array1.html
 [fits-hdu]: hdu/struct.FitsHdu.html
 [read-image]: images/struct.FitsHdu.html#method.read_image
 [read-region]: images/struct.FitsHdu.html#method.read_region
 [read-row]: images/struct.FitsHdu.html#method.read_row
 [read-rows]: images/struct.FitsHdu.html#method.read_rows
 [read-section]: images/struct.FitsHdu.html#method.read_section
+[write-array]: hdu/struct.FitsHdu.html#method.write_array
+[read-col]: hdu/struct.FitsHdu.html#method.read_col
+[read-cube]: fitsfile/struct.FitsFile.html#method.read_cube
+[write-cube]: fitsfile/struct.FitsFile.html#method.write_cube
 */
 
 use crate::errors::Result;
 use crate::fitsfile::FitsFile;
-use crate::hdu::{FitsHdu, HduInfo};
-use crate::images::ReadImage;
-use ndarray::{Array, ArrayD};
+use crate::hdu::{DescribesHdu, FitsHdu, HduInfo};
+use crate::images::{region_shape, ImageDescription, ImageType, ReadImage, WriteImage};
+use crate::tables::{ColumnSink, ReadsCol};
+use ndarray::{Array, Array1, ArrayD, ArrayViewD};
 use std::ops::Range;
 
+impl FitsHdu {
+    /**
+    Write an entire image from an array
+
+    The shape of `data` must match the shape of the image exactly. Arrays in any memory
+    layout are supported, and are written in logical (C) order.
+
+    # Example
+
+    ```rust
+    # #[cfg(feature = "array")]
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # use fitsio::images::{ImageDescription, ImageType};
+    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+    # let filename = tdir.path().join("test.fits");
+    # let mut fptr = fitsio::FitsFile::create(filename).open()?;
+    let description = ImageDescription {
+        data_type: ImageType::Double,
+        dimensions: &[2, 3],
+    };
+    let hdu = fptr.create_image("ARRAY".to_string(), &description)?;
+
+    let data = ndarray::arr2(&[[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]).into_dyn();
+    hdu.write_array(&mut fptr, &data.view())?;
+
+    let read_back: ndarray::ArrayD<f64> = hdu.read_image(&mut fptr)?;
+    assert_eq!(read_back, data);
+    # Ok(())
+    # }
+    # #[cfg(not(feature = "array"))]
+    # fn main() {}
+    ```
+    */
+    pub fn write_array<T: WriteImage + Clone>(
+        &self,
+        fits_file: &mut FitsFile,
+        data: &ArrayViewD<T>,
+    ) -> Result<()> {
+        fits_file.make_current(self)?;
+        match fits_file.fetch_hdu_info()? {
+            HduInfo::ImageInfo { ref shape, .. } if shape.as_slice() != data.shape() => {
+                Err(format!(
+                    "array shape {:?} does not match image shape {:?}",
+                    data.shape(),
+                    shape
+                )
+                .as_str()
+                .into())
+            }
+            HduInfo::ImageInfo { .. } => match data.as_slice() {
+                Some(pixels) => self.write_image(fits_file, pixels),
+                None => {
+                    let pixels: Vec<T> = data.iter().cloned().collect();
+                    self.write_image(fits_file, &pixels)
+                }
+            },
+            _ => Err("cannot write image data to a table hdu".into()),
+        }
+    }
+}
+
+impl FitsFile {
+    /**
+    Stack same-shaped image extensions into a single array
+
+    The images are stacked along a new leading axis, so an array read from `n` extensions
+    each of shape `[ny, nx]` has shape `[n, ny, nx]`. Any collection of hdu descriptions
+    can be given, e.g. a list of extension names or a range of hdu indices.
+
+    # Example
+
+    ```rust
+    # #[cfg(feature = "array")]
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # use fitsio::images::ImageType;
+    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+    # let filename = tdir.path().join("test.fits");
+    # let mut fptr = fitsio::FitsFile::create(filename).open()?;
+    let cube = ndarray::Array::from_shape_fn(vec![4, 2, 3], |idx| idx[0] as i32).into_dyn();
+    fptr.write_cube(&["AMP1", "AMP2", "AMP3", "AMP4"], ImageType::Long, &cube.view())?;
+
+    let by_name: ndarray::ArrayD<i32> = fptr.read_cube(vec!["AMP1", "AMP2"])?;
+    assert_eq!(by_name.shape(), &[2, 2, 3]);
+
+    let by_index: ndarray::ArrayD<i32> = fptr.read_cube(1..5)?;
+    assert_eq!(by_index, cube);
+    # Ok(())
+    # }
+    # #[cfg(not(feature = "array"))]
+    # fn main() {}
+    ```
+    */
+    pub fn read_cube<T, I>(&mut self, hdus: I) -> Result<ArrayD<T>>
+    where
+        T: Clone,
+        Vec<T>: ReadImage,
+        I: IntoIterator,
+        I::Item: DescribesHdu,
+    {
+        let mut image_shape: Option<Vec<usize>> = None;
+        let mut num_images = 0;
+        let mut data = Vec::new();
+        for description in hdus {
+            let hdu = self.hdu(description)?;
+            let shape = match hdu.info {
+                HduInfo::ImageInfo { ref shape, .. } => shape.clone(),
+                _ => return Err("cannot read image data from a table hdu".into()),
+            };
+            match image_shape {
+                Some(ref expected) if *expected != shape => {
+                    return Err(format!(
+                        "image shape {:?} does not match cube image shape {:?}",
+                        shape, expected
+                    )
+                    .as_str()
+                    .into());
+                }
+                Some(_) => {}
+                None => image_shape = Some(shape),
+            }
+
+            let pixels: Vec<T> = hdu.read_image(self)?;
+            data.extend(pixels);
+            num_images += 1;
+        }
+
+        let mut shape = match image_shape {
+            Some(shape) => shape,
+            None => return Err("no hdus given to read a cube from".into()),
+        };
+        shape.insert(0, num_images);
+        Ok(Array::from_shape_vec(shape, data).unwrap())
+    }
+
+    /**
+    Split an array into one image extension per entry along its first axis
+
+    This is the inverse of [`read_cube`](#method.read_cube). A new image extension of type
+    `data_type` is created for each name in `extnames`, which must have the same length as
+    the first axis of `data`. The new hdus are returned in order.
+
+    # Example
+
+    ```rust
+    # #[cfg(feature = "array")]
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # use fitsio::images::ImageType;
+    # let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+    # let filename = tdir.path().join("test.fits");
+    # let mut fptr = fitsio::FitsFile::create(filename).open()?;
+    let cube = ndarray::Array::from_shape_fn(vec![2, 10, 20], |idx| idx[0] as f32).into_dyn();
+    let hdus = fptr.write_cube(&["CHIP1", "CHIP2"], ImageType::Float, &cube.view())?;
+
+    let chip: ndarray::ArrayD<f32> = hdus[1].read_image(&mut fptr)?;
+    assert_eq!(chip.shape(), &[10, 20]);
+    assert_eq!(chip[[0, 0]], 1.0);
+    # Ok(())
+    # }
+    # #[cfg(not(feature = "array"))]
+    # fn main() {}
+    ```
+    */
+    pub fn write_cube<T: WriteImage + Clone>(
+        &mut self,
+        extnames: &[&str],
+        data_type: ImageType,
+        data: &ArrayViewD<T>,
+    ) -> Result<Vec<FitsHdu>> {
+        if data.ndim() < 2 {
+            return Err("cube must have at least two dimensions".into());
+        }
+        if data.shape()[0] != extnames.len() {
+            return Err(format!(
+                "{} extension names given for a cube of {} images",
+                extnames.len(),
+                data.shape()[0]
+            )
+            .as_str()
+            .into());
+        }
+
+        let description = ImageDescription {
+            data_type,
+            dimensions: &data.shape()[1..],
+        };
+        let mut hdus = Vec::with_capacity(extnames.len());
+        for (extname, image) in extnames.iter().zip(data.outer_iter()) {
+            let hdu = self.create_image(*extname, &description)?;
+            hdu.write_array(self, &image)?;
+            hdus.push(hdu);
+        }
+        Ok(hdus)
+    }
+}
+
 impl<T> ReadImage for ArrayD<T>
 where
     T: Clone,
@@ -214,12 +426,13 @@ where
     fn read_region(
         fits_file: &mut FitsFile,
         hdu: &FitsHdu,
-        ranges: &[&Range<usize>],
+        ranges: &[Range<usize>],
+        steps: &[usize],
     ) -> Result<Self> {
-        let data: Vec<T> = ReadImage::read_region(fits_file, hdu, ranges)?;
-        let shape: Vec<usize> = (0..ranges.len())
-            .map(|i| ranges[i].end - ranges[i].start)
-            .collect();
+        let data: Vec<T> = ReadImage::read_region(fits_file, hdu, ranges, steps)?;
+        // The first range is the fastest varying axis, which is the last axis in C order
+        let mut shape = region_shape(ranges, steps);
+        shape.reverse();
         let arr = Array::from_shape_vec(shape, data).unwrap();
         Ok(arr)
     }
@@ -237,6 +450,13 @@ where
     }
 }
 
+impl<T: ReadsCol> ColumnSink for Array1<T> {
+    fn read_col<N: Into<String>>(fits_file: &mut FitsFile, name: N) -> Result<Self> {
+        let data: Vec<T> = ColumnSink::read_col(fits_file, name)?;
+        Ok(Array1::from(data))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::super::errors::Error;
@@ -286,9 +506,134 @@ mod tests {
         let data: ArrayD<u32> = hdu.read_region(&mut f, &[&(70..80), &(20..50)]).unwrap();
         let dim = data.dim();
         assert_eq!(data.ndim(), 2);
-        assert_eq!(dim[0], 10);
-        assert_eq!(dim[1], 30);
-        assert_eq!(data[[5, 10]], 177);
+        assert_eq!(dim[0], 30);
+        assert_eq!(dim[1], 10);
+        assert_eq!(data[[10, 5]], 193);
+
+        /* Every pixel matches the same pixel of the whole image */
+        let image: ArrayD<u32> = hdu.read_image(&mut f).unwrap();
+        for y in 0..30 {
+            for x in 0..10 {
+                assert_eq!(data[[y, x]], image[[y + 20, x + 70]]);
+            }
+        }
+    }
+
+    #[test]
+    fn test_read_region_strided() {
+        let mut f = FitsFile::open("../testdata/full_example.fits").unwrap();
+        let hdu = f.primary_hdu().unwrap();
+
+        let data: ArrayD<u32> = hdu
+            .read_region_strided(&mut f, &[70..80, 20..50], &[2, 10])
+            .unwrap();
+        assert_eq!(data.shape(), &[3, 5]);
+    }
+
+    #[test]
+    fn test_write_array() {
+        use crate::images::{ImageDescription, ImageType};
+        use crate::testing::with_temp_file;
+
+        with_temp_file(|filename| {
+            let mut f = FitsFile::create(filename).open().unwrap();
+            let description = ImageDescription {
+                data_type: ImageType::Long,
+                dimensions: &[20, 30],
+            };
+            let hdu = f.create_image("FOO".to_string(), &description).unwrap();
+
+            let data = Array::from_shape_fn(vec![20, 30], |idx| (idx[0] * 100 + idx[1]) as i64);
+            hdu.write_array(&mut f, &data.view()).unwrap();
+            let read_back: ArrayD<i64> = hdu.read_image(&mut f).unwrap();
+            assert_eq!(read_back, data);
+
+            /* Non-contiguous views are written in logical order */
+            let description = ImageDescription {
+                data_type: ImageType::Long,
+                dimensions: &[30, 20],
+            };
+            let hdu = f.create_image("BAR".to_string(), &description).unwrap();
+            hdu.write_array(&mut f, &data.t()).unwrap();
+            let read_back: ArrayD<i64> = hdu.read_image(&mut f).unwrap();
+            assert_eq!(read_back, data.t());
+
+            /* Mismatched shapes are rejected */
+            match hdu.write_array(&mut f, &data.view()) {
+                Err(Error::Message(msg)) => {
+                    assert_eq!(
+                        msg,
+                        "array shape [20, 30] does not match image shape [30, 20]"
+                    )
+                }
+                _ => panic!("invalid result"),
+            }
+        });
+    }
+
+    #[test]
+    fn test_read_write_cube() {
+        use crate::images::ImageType;
+        use crate::testing::with_temp_file;
+
+        with_temp_file(|filename| {
+            let mut f = FitsFile::create(filename).open().unwrap();
+            let cube = Array::from_shape_fn(vec![3, 4, 5], |idx| {
+                (idx[0] * 100 + idx[1] * 10 + idx[2]) as i32
+            });
+            let hdus = f
+                .write_cube(&["A", "B", "C"], ImageType::Long, &cube.view())
+                .unwrap();
+            assert_eq!(hdus.len(), 3);
+
+            let image: ArrayD<i32> = f.hdu("B").unwrap().read_image(&mut f).unwrap();
+            assert_eq!(image, cube.index_axis(ndarray::Axis(0), 1));
+
+            let by_name: ArrayD<i32> = f.read_cube(vec!["C", "A"]).unwrap();
+            assert_eq!(by_name.shape(), &[2, 4, 5]);
+            assert_eq!(by_name[[0, 3, 4]], 234);
+            assert_eq!(by_name[[1, 3, 4]], 34);
+
+            let by_index: ArrayD<i32> = f.read_cube(1..4).unwrap();
+            assert_eq!(by_index, cube);
+
+            /* Mismatched lengths and shapes are rejected */
+            match f.write_cube(&["D"], ImageType::Long, &cube.view()) {
+                Err(Error::Message(msg)) => {
+                    assert_eq!(msg, "1 extension names given for a cube of 3 images")
+                }
+                _ => panic!("invalid result"),
+            }
+
+            let other = Array::from_shape_fn(vec![1, 5, 4], |_| 0i32);
+            f.write_cube(&["D"], ImageType::Long, &other.view())
+                .unwrap();
+            match f.read_cube::<i32, _>(vec!["A", "D"]) {
+                Err(Error::Message(msg)) => {
+                    assert_eq!(
+                        msg,
+                        "image shape [5, 4] does not match cube image shape [4, 5]"
+                    )
+                }
+                _ => panic!("invalid result"),
+            }
+
+            match f.read_cube::<i32, _>(Vec::<usize>::new()) {
+                Err(Error::Message(msg)) => assert_eq!(msg, "no hdus given to read a cube from"),
+                _ => panic!("invalid result"),
+            }
+        });
+    }
+
+    #[test]
+    fn test_read_col() {
+        let mut f = FitsFile::open("../testdata/full_example.fits").unwrap();
+        let hdu = f.hdu("TESTEXT").unwrap();
+
+        let data: Array1<f64> = hdu.read_col(&mut f, "doublecol").unwrap();
+        let expected: Vec<f64> = hdu.read_col(&mut f, "doublecol").unwrap();
+        assert_eq!(data.len(), 50);
+        assert_eq!(data.to_vec(), expected);
     }
 
     #[test]
@@ -0,0 +1,168 @@
+//! Ranges of rows and pixels
+
+use std::borrow::Borrow;
+use std::ops::{
+    Bound, Range, RangeBounds, RangeFrom, RangeFull, RangeInclusive, RangeTo, RangeToInclusive,
+};
+
+/**
+Range of zero-indexed rows or pixels
+
+All of the standard range types are supported, with their usual meaning:
+
+* `a..b` includes `a` and excludes `b`,
+* `a..=b` includes both `a` and `b`,
+* `a..` runs from `a` to the end,
+* `..b` and `..=b` start from the beginning, and
+* `..` covers everything.
+
+An inclusive range ending at `usize::MAX` runs to the end, as `usize::MAX + 1` cannot be
+represented.
+
+Unlike `cfitsio`, all indices are zero-indexed.
+
+# Example
+
+```rust
+# fn main() -> Result<(), Box<dyn std::error::Error>> {
+# let filename = "../testdata/full_example.fits";
+# let mut fptr = fitsio::FitsFile::open(filename)?;
+# let hdu = fptr.hdu("TESTEXT")?;
+let first_two: Vec<i32> = hdu.read_col_range(&mut fptr, "intcol", 0..2)?;
+let same: Vec<i32> = hdu.read_col_range(&mut fptr, "intcol", ..=1)?;
+assert_eq!(first_two, same);
+
+let everything: Vec<i32> = hdu.read_col_range(&mut fptr, "intcol", ..)?;
+assert_eq!(everything.len(), 50);
+# Ok(())
+# }
+```
+*/
+pub trait IndexRange {
+    /// Convert to a half-open range, where `len` is the total number of rows or pixels
+    fn to_range(&self, len: usize) -> Range<usize>;
+}
+
+fn bounds_to_range<R: RangeBounds<usize>>(range: &R, len: usize) -> Range<usize> {
+    let start = match range.start_bound() {
+        Bound::Included(&start) => start,
+        Bound::Excluded(&start) => start.saturating_add(1),
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&end) => match end.saturating_add(1) {
+            // e.g. `..=usize::MAX`, which cannot be made half-open, so runs to the end like `..`
+            usize::MAX => len,
+            end => end,
+        },
+        Bound::Excluded(&end) => end,
+        Bound::Unbounded => len,
+    };
+    start..end
+}
+
+macro_rules! index_range_impl {
+    ($t:ty) => {
+        impl IndexRange for $t {
+            fn to_range(&self, len: usize) -> Range<usize> {
+                bounds_to_range(self, len)
+            }
+        }
+
+        impl<'a> IndexRange for &'a $t {
+            fn to_range(&self, len: usize) -> Range<usize> {
+                bounds_to_range(*self, len)
+            }
+        }
+    };
+}
+
+index_range_impl!(Range<usize>);
+index_range_impl!(RangeInclusive<usize>);
+index_range_impl!(RangeFrom<usize>);
+index_range_impl!(RangeTo<usize>);
+index_range_impl!(RangeToInclusive<usize>);
+index_range_impl!(RangeFull);
+
+/**
+A collection of ranges, one per image axis, describing a region of an image
+
+This is implemented for [`Region`](images/struct.Region.html), and for slices, arrays and `Vec`s
+of either `Range<usize>` or `&Range<usize>`, so `&[0..10, 0..20]`, `&[&xrange, &yrange]` and
+`Region::rect(0..10, 0..20)` can all be given to
+[`read_region`](hdu/struct.FitsHdu.html#method.read_region).
+*/
+pub trait RegionRanges {
+    /// The range along each axis
+    fn ranges(&self) -> Vec<Range<usize>>;
+
+    /// The step between pixels read along each axis, by default every pixel is read
+    fn steps(&self) -> Vec<usize> {
+        vec![1; self.ranges().len()]
+    }
+}
+
+impl<X: Borrow<Range<usize>>> RegionRanges for [X] {
+    fn ranges(&self) -> Vec<Range<usize>> {
+        self.iter().map(|range| range.borrow().clone()).collect()
+    }
+}
+
+impl<X: Borrow<Range<usize>>, const N: usize> RegionRanges for [X; N] {
+    fn ranges(&self) -> Vec<Range<usize>> {
+        self[..].ranges()
+    }
+}
+
+impl<X: Borrow<Range<usize>>> RegionRanges for Vec<X> {
+    fn ranges(&self) -> Vec<Range<usize>> {
+        self[..].ranges()
+    }
+}
+
+impl<'a, R: RegionRanges + ?Sized> RegionRanges for &'a R {
+    fn ranges(&self) -> Vec<Range<usize>> {
+        (**self).ranges()
+    }
+
+    fn steps(&self) -> Vec<usize> {
+        (**self).steps()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resolve<R: IndexRange>(range: R, len: usize) -> Range<usize> {
+        range.to_range(len)
+    }
+
+    #[test]
+    fn test_index_ranges() {
+        assert_eq!((2..5).to_range(10), 2..5);
+        assert_eq!(resolve(&(2..5), 10), 2..5);
+        assert_eq!((2..=5).to_range(10), 2..6);
+        assert_eq!((2..).to_range(10), 2..10);
+        assert_eq!((..5).to_range(10), 0..5);
+        assert_eq!((..=5).to_range(10), 0..6);
+        assert_eq!((..).to_range(10), 0..10);
+        assert_eq!((..=usize::MAX).to_range(10), 0..10);
+        assert_eq!((2..=usize::MAX).to_range(10), 2..10);
+        assert_eq!((2..=20).to_range(10), 2..21);
+    }
+
+    fn region<R: RegionRanges>(ranges: R) -> Vec<Range<usize>> {
+        ranges.ranges()
+    }
+
+    #[test]
+    fn test_region_ranges() {
+        let xrange = 0..10;
+        let yrange = 5..20;
+        assert_eq!(region(&[&xrange, &yrange]), vec![0..10, 5..20]);
+        assert_eq!(region([0..10, 5..20]), vec![0..10, 5..20]);
+        assert_eq!(region(&vec![0..10, 5..20]), vec![0..10, 5..20]);
+        assert_eq!(region(&vec![&xrange][..]), vec![0..10]);
+    }
+}
@@ -0,0 +1,181 @@
+/*!
+Nearest-neighbour reprojection of images onto another celestial coordinate system
+
+This is a quick-look tool, available with the `reproject` feature: each output pixel takes the
+value of the source pixel its centre falls in, with no interpolation or flux conservation. Both
+coordinate systems must use the same celestial frame, as no conversion between frames (for
+example equatorial to galactic) is made.
+
+# Example
+
+```rust
+use fitsio::images::CelestialWcs;
+use fitsio::reproject::reproject_nearest;
+
+# fn main() -> Result<(), Box<dyn std::error::Error>> {
+# let tdir = tempfile::Builder::new().prefix("fitsio-").tempdir().unwrap();
+# let filename = tdir.path().join("test.fits");
+# std::fs::copy("../testdata/full_example.fits", &filename)?;
+# let mut fptr = fitsio::FitsFile::edit(filename)?;
+let hdu = fptr.primary_hdu()?;
+let wcs = CelestialWcs {
+    ctype: ["RA---TAN".to_string(), "DEC--TAN".to_string()],
+    crval: [150.0, 2.0],
+    crpix: [50.5, 50.5],
+    cdelt: [-1.0 / 3600.0, 1.0 / 3600.0],
+    rotation: 0.0,
+};
+hdu.write_celestial_wcs(&mut fptr, &wcs)?;
+
+// Regrid onto pixels twice the size
+let target = CelestialWcs {
+    crpix: [25.5, 25.5],
+    cdelt: [-2.0 / 3600.0, 2.0 / 3600.0],
+    ..wcs
+};
+let reprojected = reproject_nearest(&mut fptr, &hdu, &target, &[50, 50])?;
+assert_eq!(reprojected.data.len(), 2500);
+
+let new_hdu = reprojected.write(&mut fptr, "REGRID")?;
+assert_eq!(new_hdu.shape()?, &[50, 50]);
+# Ok(())
+# }
+```
+*/
+use crate::errors::{Error, Result};
+use crate::fitsfile::FitsFile;
+use crate::hdu::{FitsHdu, HduIdentity};
+use crate::images::{CelestialWcs, ImageDescription, ImageType};
+
+/// An image regridded onto a new coordinate system
+#[derive(Debug, Clone, PartialEq)]
+pub struct Reprojected {
+    /// The pixel values, with the first axis varying fastest and `NaN` where there is no data
+    pub data: Vec<f64>,
+
+    /// The shape of the image, with the slowest varying axis first like `FitsHdu::shape`
+    pub shape: Vec<usize>,
+
+    /// The coordinate system of the image
+    pub wcs: CelestialWcs,
+}
+
+impl Reprojected {
+    /// Write the image, with its coordinate system, to a new double precision image HDU
+    pub fn write<T: Into<HduIdentity>>(
+        &self,
+        fits_file: &mut FitsFile,
+        extname: T,
+    ) -> Result<FitsHdu> {
+        let description = ImageDescription {
+            data_type: ImageType::Double,
+            dimensions: &self.shape,
+        };
+        let hdu = fits_file.create_image(extname, &description)?;
+        hdu.write_image(fits_file, &self.data)?;
+        hdu.write_celestial_wcs(fits_file, &self.wcs)?;
+        Ok(hdu)
+    }
+}
+
+/**
+Regrid a two dimensional image onto `target_wcs`, taking the nearest source pixel for each output
+pixel
+
+`target_shape` is the shape of the output image, with the slowest varying axis first like
+[`FitsHdu::shape`](../hdu/struct.FitsHdu.html#method.shape). Output pixels which fall outside the
+source image, or which cannot be projected, are `NaN`, as are blank source pixels.
+*/
+pub fn reproject_nearest(
+    src_f: &mut FitsFile,
+    src_hdu: &FitsHdu,
+    target_wcs: &CelestialWcs,
+    target_shape: &[usize],
+) -> Result<Reprojected> {
+    let (src_ny, src_nx) = match *src_hdu.shape()? {
+        [ny, nx] => (ny, nx),
+        ref shape => {
+            return Err(Error::Message(format!(
+                "can only reproject two dimensional images, not {} dimensions",
+                shape.len()
+            )))
+        }
+    };
+    let (ny, nx) = match *target_shape {
+        [ny, nx] => (ny, nx),
+        _ => return Err("target shape must have two dimensions".into()),
+    };
+
+    let src_wcs = src_hdu.read_celestial_wcs(src_f)?;
+    let src: Vec<f64> = src_hdu.read_image(src_f)?;
+
+    let source_index = |x: usize, y: usize| -> Option<usize> {
+        let (lon, lat) = target_wcs
+            .pixel_to_world(x as f64 + 1.0, y as f64 + 1.0)
+            .ok()?;
+        let (sx, sy) = src_wcs.world_to_pixel(lon, lat).ok()?;
+        let (ix, iy) = ((sx - 1.0).round(), (sy - 1.0).round());
+        if ix >= 0.0 && iy >= 0.0 && ix < src_nx as f64 && iy < src_ny as f64 {
+            Some(iy as usize * src_nx + ix as usize)
+        } else {
+            None
+        }
+    };
+
+    let mut data = Vec::with_capacity(nx * ny);
+    for y in 0..ny {
+        for x in 0..nx {
+            data.push(source_index(x, y).map_or(f64::NAN, |i| src[i]));
+        }
+    }
+
+    Ok(Reprojected {
+        data,
+        shape: target_shape.to_vec(),
+        wcs: target_wcs.clone(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::duplicate_test_file;
+
+    #[test]
+    fn test_reproject_nearest() {
+        duplicate_test_file(|filename| {
+            let mut f = FitsFile::edit(filename).unwrap();
+            let hdu = f.primary_hdu().unwrap();
+            let wcs = CelestialWcs {
+                ctype: ["RA---TAN".to_string(), "DEC--TAN".to_string()],
+                crval: [150.0, 2.0],
+                crpix: [50.5, 50.5],
+                cdelt: [-1.0 / 3600.0, 1.0 / 3600.0],
+                rotation: 0.0,
+            };
+            hdu.write_celestial_wcs(&mut f, &wcs).unwrap();
+            let src: Vec<f64> = hdu.read_image(&mut f).unwrap();
+
+            // The same grid reproduces the image
+            let same = reproject_nearest(&mut f, &hdu, &wcs, &[100, 100]).unwrap();
+            assert_eq!(same.data, src);
+
+            // Shifted by ten pixels, so part of the output is off the source image
+            let shifted = CelestialWcs {
+                crpix: [60.5, 50.5],
+                ..wcs.clone()
+            };
+            let out = reproject_nearest(&mut f, &hdu, &shifted, &[100, 100]).unwrap();
+            assert!(out.data[..10].iter().all(|v| v.is_nan()));
+            assert_eq!(out.data[10], src[0]);
+            assert_eq!(out.data[99], src[89]);
+
+            let new_hdu = out.write(&mut f, "REGRID").unwrap();
+            assert_eq!(new_hdu.shape().unwrap(), &[100, 100]);
+            let written = new_hdu.read_celestial_wcs(&mut f).unwrap();
+            assert!((written.crpix[0] - 60.5).abs() < 1e-12);
+
+            assert!(reproject_nearest(&mut f, &hdu, &wcs, &[100]).is_err());
+        });
+    }
+}
@@ -1,12 +1,29 @@
-use crate::errors::Result;
+use crate::errors::{Error, Result};
 use crate::sys::ffgerr;
 use libc::{c_char, c_int, size_t};
-use std::ffi::{CStr, CString};
+use std::ffi::CString;
 
-/// Helper function converting a C string pointer to Rust String
+/// Helper function converting a nul terminated C string buffer to Rust String
+///
+/// An error is returned if the buffer has no nul terminator, or is not valid UTF-8.
 pub fn buf_to_string(buffer: &[c_char]) -> Result<String> {
-    let c_str = unsafe { CStr::from_ptr(buffer.as_ptr()) };
-    Ok(c_str.to_str()?.to_string())
+    Ok(String::from_utf8(buf_to_bytes(buffer)?)?)
+}
+
+/// Convert a nul terminated C string buffer to Rust String, replacing invalid UTF-8
+pub fn buf_to_string_lossy(buffer: &[c_char]) -> Result<String> {
+    Ok(String::from_utf8_lossy(&buf_to_bytes(buffer)?).into_owned())
+}
+
+/// The bytes of a nul terminated C string buffer, excluding the terminator
+fn buf_to_bytes(buffer: &[c_char]) -> Result<Vec<u8>> {
+    // `c_char` is either `i8` or `u8`, which have the same layout
+    let bytes = unsafe { std::slice::from_raw_parts(buffer.as_ptr() as *const u8, buffer.len()) };
+    let end = bytes
+        .iter()
+        .position(|&c| c == 0)
+        .ok_or_else(|| Error::Message("string is not nul terminated".to_string()))?;
+    Ok(bytes[..end].to_vec())
 }
 
 #[repr(C)]
@@ -18,10 +35,13 @@ pub struct StringList {
 
 impl StringList {
     pub fn from_slice(stringvec: &[String]) -> Result<Self> {
-        let converted: Vec<*mut c_char> = stringvec
+        // Convert every string before taking ownership of any pointers, so nothing leaks if one
+        // contains a nul byte
+        let strings = stringvec
             .iter()
-            .map(|x| CString::new(x.clone()).unwrap().into_raw())
-            .collect();
+            .map(|x| CString::new(x.as_str()))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        let converted: Vec<*mut c_char> = strings.into_iter().map(CString::into_raw).collect();
         let listlen = converted.len();
         let listcap = converted.capacity();
 
@@ -70,7 +90,40 @@ pub fn status_to_string(status: c_int) -> Result<Option<String>> {
 
 #[cfg(test)]
 mod test {
-    use super::status_to_string;
+    use super::{buf_to_string, buf_to_string_lossy, status_to_string, StringList};
+    use crate::errors::Error;
+    use libc::c_char;
+
+    fn buffer(bytes: &[u8]) -> Vec<c_char> {
+        bytes.iter().map(|&b| b as c_char).collect()
+    }
+
+    #[test]
+    fn test_buf_to_string() {
+        assert_eq!(buf_to_string(&buffer(b"abc\0def\0")).unwrap(), "abc");
+        assert_eq!(buf_to_string(&buffer(b"\0")).unwrap(), "");
+        assert!(buf_to_string(&buffer(b"no terminator")).is_err());
+        assert!(buf_to_string(&[]).is_err());
+        match buf_to_string(&buffer(b"caf\xe9\0")) {
+            Err(Error::InvalidUtf8 { bytes }) => assert_eq!(bytes, b"caf\xe9"),
+            other => panic!("unexpected result {:?}", other),
+        }
+        assert_eq!(
+            buf_to_string_lossy(&buffer(b"caf\xe9\0")).unwrap(),
+            "caf\u{fffd}"
+        );
+    }
+
+    #[test]
+    fn test_string_list_with_nul() {
+        assert_eq!(
+            StringList::from_slice(&["a".to_string(), "b".to_string()])
+                .unwrap()
+                .len,
+            2
+        );
+        assert!(StringList::from_slice(&["a".to_string(), "b\0c".to_string()]).is_err());
+    }
 
     #[test]
     fn test_returning_error_messages() {
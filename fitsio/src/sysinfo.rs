@@ -0,0 +1,48 @@
+//! Information about the underlying `cfitsio` library
+
+use crate::sys::Capabilities;
+
+/// Version and build information of the linked `cfitsio` library
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SysInfo {
+    /// The `(major, minor)` version of `cfitsio`
+    pub version: (u32, u32),
+    /// Optional features `cfitsio` was built with
+    pub capabilities: Capabilities,
+}
+
+/**
+Query the linked `cfitsio` library
+
+This allows applications to adapt their behaviour to the library in use, for example only
+offering remote URLs when `cfitsio` has `curl` support.
+
+# Example
+
+```rust
+let info = fitsio::sys_info();
+let (major, minor) = info.version;
+println!("cfitsio {}.{}", major, minor);
+if !info.capabilities.curl {
+    println!("remote files are not supported");
+}
+```
+*/
+pub fn sys_info() -> SysInfo {
+    SysInfo {
+        version: crate::sys::cfitsio_version(),
+        capabilities: crate::sys::capabilities(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sys_info() {
+        let info = sys_info();
+        assert!(info.version.0 >= 3);
+        assert_eq!(info.version, crate::sys::cfitsio_version());
+    }
+}
@@ -2,9 +2,12 @@
 use crate::errors::{check_status, Error, FitsError, IndexError, Result};
 use crate::fitsfile::FitsFile;
 use crate::hdu::{FitsHdu, HduInfo};
+use crate::headers::read_key_optional;
 use crate::longnam::*;
+use crate::ranges::IndexRange;
 use crate::stringutils::status_to_string;
 use crate::types::DataType;
+use std::any::{Any, TypeId};
 use std::ffi;
 use std::ops::Range;
 use std::ptr;
@@ -43,6 +46,24 @@ pub trait ReadsCol {
     }
 }
 
+/**
+Containers a column can be read into
+
+This is implemented for `Vec<T>`, and with the `array` feature for `ndarray::Array1<T>`, where
+`T` implements [`ReadsCol`], so [`read_col`](../hdu/struct.FitsHdu.html#method.read_col) can
+return either without copying the data.
+*/
+pub trait ColumnSink: Sized {
+    #[doc(hidden)]
+    fn read_col<T: Into<String>>(fits_file: &mut FitsFile, name: T) -> Result<Self>;
+}
+
+impl<T: ReadsCol> ColumnSink for Vec<T> {
+    fn read_col<N: Into<String>>(fits_file: &mut FitsFile, name: N) -> Result<Self> {
+        T::read_col(fits_file, name)
+    }
+}
+
 macro_rules! reads_col_impl {
     ($t:ty, $func:ident, $nullval:expr) => {
         impl ReadsCol for $t {
@@ -145,6 +166,8 @@ macro_rules! reads_col_impl {
     };
 }
 
+reads_col_impl!(bool, fits_read_col_log, false);
+reads_col_impl!(u8, fits_read_col_byt, 0);
 reads_col_impl!(i16, fits_read_col_sht, 0);
 reads_col_impl!(u16, fits_read_col_usht, 0);
 reads_col_impl!(i32, fits_read_col_int, 0);
@@ -160,66 +183,107 @@ reads_col_impl!(u64, fits_read_col_ulng, 0);
 #[cfg(any(target_pointer_width = "32", target_os = "windows"))]
 reads_col_impl!(u64, fits_read_col_ulnglng, 0);
 
-impl ReadsCol for String {
-    fn read_col_range<T: Into<String>>(
-        fits_file: &mut FitsFile,
-        name: T,
-        range: &Range<usize>,
-    ) -> Result<Vec<Self>> {
-        match fits_file.fetch_hdu_info() {
-            Ok(HduInfo::TableInfo {
-                column_descriptions,
-                ..
-            }) => {
-                let num_output_rows = range.end - range.start;
-                let test_name = name.into();
-                let column_number = column_descriptions
-                    .iter()
-                    .position(|desc| desc.name == test_name)
-                    .ok_or_else(|| Error::Message(format!("Cannot find column {:?}", test_name)))?;
-
-                /* Set up the storage arrays for the column string values */
-                let mut raw_char_data: Vec<*mut libc::c_char> = Vec::with_capacity(num_output_rows);
-
-                let mut status = 0;
-                let width = column_display_width(fits_file, column_number)?;
-
-                let mut vecs: Vec<Vec<libc::c_char>> = Vec::with_capacity(num_output_rows);
-                for _ in 0..num_output_rows {
-                    let mut data: Vec<libc::c_char> = vec![0; width as _];
-                    let data_p = data.as_mut_ptr();
-                    vecs.push(data);
-                    raw_char_data.push(data_p);
-                }
+/// Read a range of a numeric column, flagging undefined values
+///
+/// Cells equal to the column's `TNULLn` value, or NaN for floating point columns, are returned
+/// as `None`.
+fn read_col_range_nullable<T: Default + Clone>(
+    fits_file: &mut FitsFile,
+    name: String,
+    range: &Range<usize>,
+    datatype: libc::c_int,
+) -> Result<Vec<Option<T>>> {
+    match fits_file.fetch_hdu_info() {
+        Ok(HduInfo::TableInfo {
+            column_descriptions,
+            ..
+        }) => {
+            let num_output_rows = range.end - range.start;
+            let mut values = vec![T::default(); num_output_rows];
+            let mut nulls: Vec<libc::c_char> = vec![0; num_output_rows];
+            let column_number = column_descriptions
+                .iter()
+                .position(|desc| desc.name == name)
+                .ok_or_else(|| Error::Message(format!("Cannot find column {:?}", name)))?;
+            let mut anynul = 0;
+            let mut status = 0;
+            unsafe {
+                fits_read_colnull(
+                    fits_file.fptr.as_mut() as *mut _,
+                    datatype,
+                    (column_number + 1) as i32,
+                    (range.start + 1) as i64,
+                    1,
+                    num_output_rows as _,
+                    values.as_mut_ptr() as *mut _,
+                    nulls.as_mut_ptr(),
+                    &mut anynul,
+                    &mut status,
+                );
+            }
 
-                unsafe {
-                    fits_read_col_str(
-                        fits_file.fptr.as_mut() as *mut _,
-                        (column_number + 1) as _,
-                        (range.start + 1) as _,
-                        1,
-                        raw_char_data.len() as _,
-                        ptr::null_mut(),
-                        raw_char_data.as_ptr() as *mut *mut _,
-                        ptr::null_mut(),
-                        &mut status,
-                    );
+            if status == 307 {
+                return Err(IndexError {
+                    message: "given indices out of range".to_string(),
+                    given: range.clone(),
                 }
+                .into());
+            }
+            check_status(status)?;
+            Ok(values
+                .into_iter()
+                .zip(nulls)
+                .map(|(value, null)| if null == 0 { Some(value) } else { None })
+                .collect())
+        }
+        Ok(_) => Err("cannot read a column from a non-table hdu".into()),
+        Err(e) => Err(e),
+    }
+}
 
-                check_status(status)?;
+macro_rules! reads_col_nullable_impl {
+    ($t:ty, $datatype:expr) => {
+        impl ReadsCol for Option<$t> {
+            fn read_col_range<T: Into<String>>(
+                fits_file: &mut FitsFile,
+                name: T,
+                range: &Range<usize>,
+            ) -> Result<Vec<Self>> {
+                read_col_range_nullable(fits_file, name.into(), range, $datatype)
+            }
 
-                let mut out = Vec::with_capacity(num_output_rows);
-                for val in &vecs {
-                    let bytes: Vec<u8> =
-                        val.iter().filter(|v| **v != 0).map(|v| *v as u8).collect();
-                    let cstr = String::from_utf8(bytes)?;
-                    out.push(cstr);
-                }
-                Ok(out)
+            fn read_cell_value<T>(fits_file: &mut FitsFile, name: T, idx: usize) -> Result<Self>
+            where
+                T: Into<String>,
+                Self: Sized,
+            {
+                read_col_range_nullable(fits_file, name.into(), &(idx..idx + 1), $datatype)
+                    .map(|v| v[0])
             }
-            Err(e) => Err(e),
-            _ => panic!("Unknown error occurred"),
         }
+    };
+}
+
+reads_col_nullable_impl!(i16, DataType::TSHORT.into());
+reads_col_nullable_impl!(u16, DataType::TUSHORT.into());
+reads_col_nullable_impl!(i32, DataType::TINT.into());
+reads_col_nullable_impl!(u32, DataType::TUINT.into());
+reads_col_nullable_impl!(f32, DataType::TFLOAT.into());
+reads_col_nullable_impl!(f64, DataType::TDOUBLE.into());
+reads_col_nullable_impl!(i64, DataType::TLONGLONG.into());
+// `DataType` has no unsigned 64 bit type, and `TLONGLONG` would wrap values above `i64::MAX`
+reads_col_nullable_impl!(u64, crate::sys::TULONGLONG as libc::c_int);
+
+impl ReadsCol for String {
+    fn read_col_range<T: Into<String>>(
+        fits_file: &mut FitsFile,
+        name: T,
+        range: &Range<usize>,
+    ) -> Result<Vec<Self>> {
+        read_string_col_bytes(fits_file, name, range)?
+            .into_iter()
+            .map(|bytes| Ok(String::from_utf8(bytes)?))
+            .collect()
     }
 
     fn read_cell_value<T>(fits_file: &mut FitsFile, name: T, idx: usize) -> Result<Self>
@@ -232,6 +296,69 @@ impl ReadsCol for String {
     }
 }
 
+/// Read the raw bytes of the values of a string column, without checking they are valid UTF-8
+pub(crate) fn read_string_col_bytes<T: Into<String>>(
+    fits_file: &mut FitsFile,
+    name: T,
+    range: &Range<usize>,
+) -> Result<Vec<Vec<u8>>> {
+    match fits_file.fetch_hdu_info() {
+        Ok(HduInfo::TableInfo {
+            column_descriptions,
+            ..
+        }) => {
+            let num_output_rows = range.end - range.start;
+            let test_name = name.into();
+            let column_number = column_descriptions
+                .iter()
+                .position(|desc| desc.name == test_name)
+                .ok_or_else(|| Error::Message(format!("Cannot find column {:?}", test_name)))?;
+
+            /* Set up the storage arrays for the column string values */
+            let mut raw_char_data: Vec<*mut libc::c_char> = Vec::with_capacity(num_output_rows);
+
+            let mut status = 0;
+            /* String columns are read at up to their TFORM width, but other columns are formatted
+            to their display width, which `TDISPn` may make narrower or wider. Leave room for
+            either, and the trailing nul byte. */
+            let width = column_storage_width(fits_file, column_number)?
+                .max(column_display_width(fits_file, column_number)?)
+                + 1;
+
+            let mut vecs: Vec<Vec<libc::c_char>> = Vec::with_capacity(num_output_rows);
+            for _ in 0..num_output_rows {
+                let mut data: Vec<libc::c_char> = vec![0; width as _];
+                let data_p = data.as_mut_ptr();
+                vecs.push(data);
+                raw_char_data.push(data_p);
+            }
+
+            unsafe {
+                fits_read_col_str(
+                    fits_file.fptr.as_mut() as *mut _,
+                    (column_number + 1) as _,
+                    (range.start + 1) as _,
+                    1,
+                    raw_char_data.len() as _,
+                    ptr::null_mut(),
+                    raw_char_data.as_ptr() as *mut *mut _,
+                    ptr::null_mut(),
+                    &mut status,
+                );
+            }
+
+            check_status(status)?;
+
+            Ok(vecs
+                .iter()
+                .map(|val| val.iter().filter(|v| **v != 0).map(|v| *v as u8).collect())
+                .collect())
+        }
+        Err(e) => Err(e),
+        _ => panic!("Unknown error occurred"),
+    }
+}
+
 /// Trait representing the ability to write column data
 pub trait WritesCol {
     #[doc(hidden)]
@@ -312,6 +439,8 @@ macro_rules! writes_col_impl {
     };
 }
 
+writes_col_impl!(bool, DataType::TLOGICAL);
+writes_col_impl!(u8, DataType::TBYTE);
 writes_col_impl!(u32, DataType::TUINT);
 #[cfg(all(target_pointer_width = "64", not(target_os = "windows")))]
 writes_col_impl!(u64, DataType::TULONG);
@@ -335,19 +464,33 @@ impl WritesCol for String {
     ) -> Result<FitsHdu> {
         match fits_file.fetch_hdu_info() {
             Ok(HduInfo::TableInfo { .. }) => {
-                let colno = hdu.get_column_no(fits_file, col_name.into())?;
-                let mut status = 0;
+                let col_name = col_name.into();
+                let colno = hdu.get_column_no(fits_file, col_name.as_str())?;
+
+                // cfitsio silently truncates strings which do not fit in the column
+                let width = column_string_width(fits_file, colno as _)?;
+                let too_long = col_data
+                    .iter()
+                    .take(rows.end - rows.start)
+                    .find(|value| value.len() > width);
+                if let Some(value) = too_long {
+                    return Err(Error::Message(format!(
+                        "string of {} bytes is too long for column {:?}, which holds {} bytes",
+                        value.len(),
+                        col_name,
+                        width
+                    )));
+                }
 
+                let mut status = 0;
                 let start = rows.start;
                 let end = rows.end;
                 let n_elements = end - start;
                 let mut ptr_array = Vec::with_capacity(n_elements);
 
-                let rows = rows.clone();
-
                 // Have to free the memory for these pointers at the end
-                for i in rows {
-                    let s = ffi::CString::new(col_data[i].clone())?;
+                for value in col_data.iter().take(n_elements) {
+                    let s = ffi::CString::new(value.clone())?;
                     ptr_array.push(s.into_raw());
                 }
 
@@ -382,12 +525,206 @@ impl WritesCol for String {
     }
 }
 
-/// Trait derivable with custom derive
+/**
+Slices of several columns written together by
+[`FitsHdu::write_rows_chunk`](../hdu/struct.FitsHdu.html#method.write_rows_chunk)
+
+This is implemented for tuples of up to eight slices, for example `(&[f64], &[i32], &[String])`,
+where each slice holds the values of one column.
+*/
+pub trait RowsChunk {
+    #[doc(hidden)]
+    fn column_lengths(&self) -> Vec<usize>;
+
+    #[doc(hidden)]
+    fn write_chunk(
+        &self,
+        fits_file: &mut FitsFile,
+        hdu: &FitsHdu,
+        names: &[String],
+        rows: &Range<usize>,
+    ) -> Result<()>;
+}
+
+macro_rules! rows_chunk_impl {
+    ($($t:ident $idx:tt),+) => {
+        impl<'a, $($t: WritesCol),+> RowsChunk for ($(&'a [$t],)+) {
+            fn column_lengths(&self) -> Vec<usize> {
+                vec![$(self.$idx.len()),+]
+            }
+
+            fn write_chunk(
+                &self,
+                fits_file: &mut FitsFile,
+                hdu: &FitsHdu,
+                names: &[String],
+                rows: &Range<usize>,
+            ) -> Result<()> {
+                $($t::write_col_range(fits_file, hdu, names[$idx].as_str(), self.$idx, rows)?;)+
+                Ok(())
+            }
+        }
+    };
+}
+
+rows_chunk_impl!(A 0);
+rows_chunk_impl!(A 0, B 1);
+rows_chunk_impl!(A 0, B 1, C 2);
+rows_chunk_impl!(A 0, B 1, C 2, D 3);
+rows_chunk_impl!(A 0, B 1, C 2, D 3, E 4);
+rows_chunk_impl!(A 0, B 1, C 2, D 3, E 4, F 5);
+rows_chunk_impl!(A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+rows_chunk_impl!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
+
+/// The name of the column at `index`, used to read tuples and tuple structs by position
+#[doc(hidden)]
+pub fn column_at(column_names: &[String], index: usize) -> Result<&str> {
+    column_names
+        .get(index)
+        .map(|name| name.as_str())
+        .ok_or_else(|| Error::Message(format!("the table has no column at position {}", index)))
+}
+
+/**
+Trait derivable with custom derive
+
+This is also implemented for tuples of up to twelve cell types, which read the columns in the
+same position as each value, for example
+`let (ra, dec, mag): (f64, f64, f32) = hdu.row(&mut fptr, 0)?;`. Columns after the last value
+of the tuple are ignored.
+*/
 pub trait FitsRow: ::std::default::Default {
     #[doc(hidden)]
     fn from_table(tbl: &FitsHdu, fits_file: &mut FitsFile, idx: usize) -> Result<Self>
     where
         Self: Sized;
+
+    /// Read the rows in `range`, which derived implementations do reading each column once
+    #[doc(hidden)]
+    fn from_table_range(
+        tbl: &FitsHdu,
+        fits_file: &mut FitsFile,
+        range: &Range<usize>,
+    ) -> Result<Vec<Self>>
+    where
+        Self: Sized,
+    {
+        range
+            .clone()
+            .map(|idx| Self::from_table(tbl, fits_file, idx))
+            .collect()
+    }
+
+    #[doc(hidden)]
+    fn write_row(&self, _row: &mut RowBuffer) -> Result<()> {
+        Err("this row type does not support being written".into())
+    }
+}
+
+/// The next value of a column read for [`FitsRow::from_table_range`]
+#[doc(hidden)]
+pub fn next_cell<T>(values: &mut std::vec::IntoIter<T>) -> Result<T> {
+    values
+        .next()
+        .ok_or_else(|| "column has fewer values than the rows read".into())
+}
+
+macro_rules! fits_row_tuple_impl {
+    ($($t:ident $idx:tt),+) => {
+        impl<$($t: ReadsCol + WritesCell + Clone + Default),+> FitsRow for ($($t,)+) {
+            fn from_table(tbl: &FitsHdu, fits_file: &mut FitsFile, idx: usize) -> Result<Self> {
+                let column_names = tbl.column_names(fits_file)?;
+                Ok(($(tbl.read_cell_value::<$t>(fits_file, column_at(&column_names, $idx)?, idx)?,)+))
+            }
+
+            fn from_table_range(
+                tbl: &FitsHdu,
+                fits_file: &mut FitsFile,
+                range: &Range<usize>,
+            ) -> Result<Vec<Self>> {
+                let column_names = tbl.column_names(fits_file)?;
+                let mut columns = ($(
+                    $t::read_col_range(fits_file, column_at(&column_names, $idx)?, range)?.into_iter(),
+                )+);
+                let mut rows = Vec::with_capacity(range.len());
+                for _ in range.clone() {
+                    rows.push(($(next_cell(&mut columns.$idx)?,)+));
+                }
+                Ok(rows)
+            }
+
+            fn write_row(&self, row: &mut RowBuffer) -> Result<()> {
+                $(row.set_at($idx, self.$idx.clone())?;)+
+                Ok(())
+            }
+        }
+    };
+}
+
+fits_row_tuple_impl!(A 0);
+fits_row_tuple_impl!(A 0, B 1);
+fits_row_tuple_impl!(A 0, B 1, C 2);
+fits_row_tuple_impl!(A 0, B 1, C 2, D 3);
+fits_row_tuple_impl!(A 0, B 1, C 2, D 3, E 4);
+fits_row_tuple_impl!(A 0, B 1, C 2, D 3, E 4, F 5);
+fits_row_tuple_impl!(A 0, B 1, C 2, D 3, E 4, F 5, G 6);
+fits_row_tuple_impl!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7);
+fits_row_tuple_impl!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8);
+fits_row_tuple_impl!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9);
+fits_row_tuple_impl!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10);
+fits_row_tuple_impl!(A 0, B 1, C 2, D 3, E 4, F 5, G 6, H 7, I 8, J 9, K 10, L 11);
+
+/// Helper function to get the width of a column from its `TFORMn` keyword
+///
+/// String columns are as wide as their repeat count, even when `TFORMn` divides them into
+/// narrower substrings, so this is enough to hold any string stored in a cell.
+pub(crate) fn column_storage_width(
+    fits_file: &mut FitsFile,
+    column_number: usize,
+) -> Result<usize> {
+    let mut typecode = 0;
+    let mut repeat = 0;
+    let mut width = 0;
+    let mut status = 0;
+    unsafe {
+        fits_get_eqcoltypell(
+            fits_file.fptr.as_mut() as *mut _,
+            (column_number + 1) as _,
+            &mut typecode,
+            &mut repeat,
+            &mut width,
+            &mut status,
+        );
+    }
+    check_status(status)?;
+    // Type code 16 is `TSTRING`
+    if typecode.abs() == 16 {
+        Ok(repeat.max(width) as usize)
+    } else {
+        Ok(width as usize)
+    }
+}
+
+/// Helper function to get the width of each string of a string column
+///
+/// This is narrower than the repeat count when `TFORMn` has the form `rAw`, which stores
+/// `r / w` strings of width `w` in each row.
+fn column_string_width(fits_file: &mut FitsFile, column_number: usize) -> Result<usize> {
+    let mut typecode = 0;
+    let mut repeat = 0;
+    let mut width = 0;
+    let mut status = 0;
+    unsafe {
+        fits_get_eqcoltypell(
+            fits_file.fptr.as_mut() as *mut _,
+            (column_number + 1) as _,
+            &mut typecode,
+            &mut repeat,
+            &mut width,
+            &mut status,
+        );
+    }
+    check_status(status).map(|_| width as usize)
 }
 
 /// Helper function to get the display width of a column
@@ -485,12 +822,20 @@ pub struct ColumnDataDescription {
 
     /// What data type does the column store?
     pub typ: ColumnDataType,
+
+    /// Is the column a variable length array, and if so which descriptors locate its data?
+    pub descriptor: Option<ArrayDescriptor>,
 }
 
 impl ColumnDataDescription {
     /// Create a new column data description
     pub fn new(typ: ColumnDataType, repeat: usize, width: usize) -> Self {
-        ColumnDataDescription { repeat, width, typ }
+        ColumnDataDescription {
+            repeat,
+            width,
+            typ,
+            descriptor: None,
+        }
     }
 
     /// Shortcut for creating a scalar column
@@ -502,10 +847,46 @@ impl ColumnDataDescription {
     pub fn vector(typ: ColumnDataType, repeat: usize) -> Self {
         ColumnDataDescription::new(typ, repeat, 1)
     }
+
+    /// Number of bytes each row of the column takes up in a binary table
+    pub(crate) fn num_bytes(&self) -> usize {
+        use self::ColumnDataType::*;
+
+        let element_bytes = match (self.descriptor, self.typ) {
+            (Some(ArrayDescriptor::P), _) => 8,
+            (Some(ArrayDescriptor::Q), _) => 16,
+            (None, Bit) => return (self.repeat + 7) / 8,
+            (None, Bool) | (None, Logical) | (None, SignedByte) | (None, Text) | (None, String) => {
+                1
+            }
+            (None, Short) | (None, UnsignedShort) => 2,
+            (None, Int) | (None, UnsignedInt) | (None, Float) => 4,
+            (None, Long) | (None, UnsignedLong) | (None, Double) | (None, Complex) => 8,
+            (None, DoubleComplex) => 16,
+        };
+        element_bytes * self.repeat
+    }
+
+    /// Shortcut for creating a variable length array column
+    pub fn variable_length(typ: ColumnDataType, descriptor: ArrayDescriptor) -> Self {
+        ColumnDataDescription {
+            descriptor: Some(descriptor),
+            ..ColumnDataDescription::scalar(typ)
+        }
+    }
 }
 
 impl From<ColumnDataDescription> for String {
     fn from(orig: ColumnDataDescription) -> String {
+        if let Some(descriptor) = orig.descriptor {
+            return format!(
+                "{repeat}{descriptor}{data_type}",
+                repeat = orig.repeat,
+                descriptor = descriptor.code(),
+                data_type = String::from(orig.typ)
+            );
+        }
+
         match orig.typ {
             ColumnDataType::Text => {
                 if orig.width > 1 {
@@ -532,6 +913,29 @@ impl From<ColumnDataDescription> for String {
     }
 }
 
+/**
+Descriptors of a variable length array column
+
+Each row of a variable length array column stores a descriptor, giving the length and
+location of the row's values in the heap which follows the table.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArrayDescriptor {
+    /// 32 bit descriptors, TFORM code `P`
+    P,
+    /// 64 bit descriptors, TFORM code `Q`, for heaps larger than 2GB
+    Q,
+}
+
+impl ArrayDescriptor {
+    fn code(self) -> char {
+        match self {
+            ArrayDescriptor::P => 'P',
+            ArrayDescriptor::Q => 'Q',
+        }
+    }
+}
+
 /// Types a column can represent
 #[allow(missing_docs)]
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -545,6 +949,42 @@ pub enum ColumnDataType {
     Short,
     Long,
     String,
+    SignedByte,
+    UnsignedShort,
+    UnsignedInt,
+    UnsignedLong,
+    Complex,
+    DoubleComplex,
+    Logical,
+}
+
+impl ColumnDataType {
+    /// Convert a `cfitsio` column type code, e.g. from `fits_get_eqcoltype`
+    ///
+    /// Variable length array columns have negative type codes, and are converted to the type of
+    /// their elements.
+    pub(crate) fn from_typecode(typecode: i32) -> Result<Self> {
+        match typecode.abs() {
+            1 => Ok(ColumnDataType::Bit),
+            11 => Ok(ColumnDataType::Bool),
+            14 => Ok(ColumnDataType::Logical),
+            12 => Ok(ColumnDataType::SignedByte),
+            16 => Ok(ColumnDataType::String),
+            20 => Ok(ColumnDataType::UnsignedShort),
+            21 => Ok(ColumnDataType::Short),
+            30 | 40 => Ok(ColumnDataType::UnsignedInt),
+            31 | 41 => Ok(ColumnDataType::Int),
+            42 => Ok(ColumnDataType::Float),
+            80 => Ok(ColumnDataType::UnsignedLong),
+            81 => Ok(ColumnDataType::Long),
+            82 => Ok(ColumnDataType::Double),
+            83 => Ok(ColumnDataType::Complex),
+            163 => Ok(ColumnDataType::DoubleComplex),
+            _ => Err(format!("unsupported column type code {}", typecode)
+                .as_str()
+                .into()),
+        }
+    }
 }
 
 impl From<ColumnDataType> for String {
@@ -560,6 +1000,13 @@ impl From<ColumnDataType> for String {
             Double => "D",
             Short => "I",
             Long => "K",
+            SignedByte => "S",
+            UnsignedShort => "U",
+            UnsignedInt => "V",
+            UnsignedLong => "W",
+            Complex => "C",
+            DoubleComplex => "M",
+            Logical => "L",
         }
         .to_string()
     }
@@ -568,70 +1015,228 @@ impl From<ColumnDataType> for String {
 impl FromStr for ColumnDataDescription {
     type Err = Box<dyn (::std::error::Error)>;
 
+    /// Parse a binary table `TFORM` value
+    ///
+    /// This has the form `rTw`, with an optional repeat count `r`, the type code `T` and an
+    /// optional width `w`, or `rPT(max)` and `rQT(max)` for variable length arrays of type `T`.
+    /// The maximum length of variable length arrays is checked but not stored, as `cfitsio`
+    /// computes it when the file is written.
     fn from_str(s: &str) -> ::std::result::Result<Self, Self::Err> {
-        let chars: Vec<_> = s.chars().collect();
-
-        let mut repeat_str = Vec::new();
-        let mut last_position = 0;
-        for c in &chars {
-            if c.is_ascii_digit() {
-                repeat_str.push(c);
-                last_position += 1;
-            } else {
-                break;
-            }
-        }
+        let invalid = || -> Self::Err { format!("invalid TFORM value {:?}", s).into() };
 
-        let repeat = if repeat_str.is_empty() {
+        let type_start = s.find(|c: char| !c.is_ascii_digit()).ok_or_else(invalid)?;
+        let repeat = if type_start == 0 {
             1
         } else {
-            let repeat_str: String = repeat_str.into_iter().collect();
-            repeat_str.parse::<usize>()?
+            s[..type_start].parse::<usize>().map_err(|_| invalid())?
         };
 
-        let data_type_char = chars[last_position];
-        last_position += 1;
-
-        let mut width_str = Vec::new();
-        for c in chars.iter().skip(last_position) {
-            if c.is_ascii_digit() {
-                width_str.push(c);
-            } else {
-                break;
-            }
-        }
-
-        let width = if width_str.is_empty() {
-            1
-        } else {
-            let width_str: String = width_str.into_iter().collect();
-            width_str.parse::<usize>()?
+        let mut chars = s[type_start..].chars();
+        let mut data_type_char = chars.next().ok_or_else(invalid)?;
+        let descriptor = match data_type_char {
+            'P' => Some(ArrayDescriptor::P),
+            'Q' => Some(ArrayDescriptor::Q),
+            _ => None,
         };
+        if descriptor.is_some() {
+            data_type_char = chars.next().ok_or_else(invalid)?;
+        }
 
-        let data_type = match data_type_char {
+        let typ = match data_type_char {
             'X' => ColumnDataType::Bit,
             'B' => ColumnDataType::Bool,
-            'E' => ColumnDataType::Float,
-            'J' => ColumnDataType::Int,
-            'D' => ColumnDataType::Double,
+            'L' => ColumnDataType::Logical,
+            'S' => ColumnDataType::SignedByte,
             'I' => ColumnDataType::Short,
+            'U' => ColumnDataType::UnsignedShort,
+            'J' => ColumnDataType::Int,
+            'V' => ColumnDataType::UnsignedInt,
             'K' => ColumnDataType::Long,
+            'W' => ColumnDataType::UnsignedLong,
             'A' => ColumnDataType::String,
-            'L' => ColumnDataType::Bool,
-            _ => panic!(
-                "Have not implemented str -> ColumnDataType for {}",
-                data_type_char
-            ),
+            'E' => ColumnDataType::Float,
+            'D' => ColumnDataType::Double,
+            'C' => ColumnDataType::Complex,
+            'M' => ColumnDataType::DoubleComplex,
+            other => {
+                return Err(format!("unknown data type {:?} in TFORM value {:?}", other, s).into())
+            }
+        };
+
+        let rest = chars.as_str();
+        let width = match (descriptor, rest) {
+            (_, "") => 1,
+            (Some(_), rest) => {
+                rest.strip_prefix('(')
+                    .and_then(|rest| rest.strip_suffix(')'))
+                    .and_then(|max| max.parse::<usize>().ok())
+                    .ok_or_else(invalid)?;
+                1
+            }
+            (None, rest) => rest.parse::<usize>().map_err(|_| invalid())?,
         };
 
         Ok(ColumnDataDescription {
             repeat,
-            typ: data_type,
+            typ,
             width,
+            descriptor,
         })
     }
 }
 
+/// Read the bytes in the given range of a table row, without any conversion
+pub(crate) fn read_table_bytes(
+    fits_file: &mut FitsFile,
+    idx: usize,
+    bytes: &Range<usize>,
+) -> Result<Vec<u8>> {
+    let mut out = vec![0; bytes.len()];
+    let mut status = 0;
+    unsafe {
+        fits_read_tblbytes(
+            fits_file.fptr.as_mut() as *mut _,
+            (idx + 1) as _,
+            (bytes.start + 1) as _,
+            bytes.len() as _,
+            out.as_mut_ptr(),
+            &mut status,
+        );
+    }
+    check_status(status).map(|_| out)
+}
+
+/// Write bytes to a table, starting at byte `start` of row `idx`, without any conversion
+pub(crate) fn write_table_bytes(
+    fits_file: &mut FitsFile,
+    idx: usize,
+    start: usize,
+    bytes: &[u8],
+) -> Result<()> {
+    let mut status = 0;
+    unsafe {
+        fits_write_tblbytes(
+            fits_file.fptr.as_mut() as *mut _,
+            (idx + 1) as _,
+            (start + 1) as _,
+            bytes.len() as _,
+            bytes.as_ptr() as *mut _,
+            &mut status,
+        );
+    }
+    check_status(status)
+}
+
+/// Direction to sort table rows in, see [`FitsHdu::sort_rows`](../hdu/struct.FitsHdu.html#method.sort_rows)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Order {
+    /// Smallest values first
+    Ascending,
+    /// Largest values first
+    Descending,
+}
+
+/**
+How trailing blanks in string cells are handled on read, see
+[`FitsHdu::read_string_col`](../hdu/struct.FitsHdu.html#method.read_string_col)
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReadStringMode {
+    /// Trailing blanks are removed
+    Trimmed,
+    /// Every character up to the first nul byte, or the column width, is kept
+    Raw,
+}
+
+impl Default for ReadStringMode {
+    fn default() -> Self {
+        ReadStringMode::Trimmed
+    }
+}
+
+/**
+How string cells shorter than the column width are filled on write, see
+[`FitsHdu::write_string_col`](../hdu/struct.FitsHdu.html#method.write_string_col)
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WriteStringMode {
+    /// Padded with blanks to the column width, so trailing blanks in the value are
+    /// indistinguishable from the padding
+    Padded,
+    /// Ended with a nul byte, so trailing blanks in the value survive a
+    /// [`Raw`](enum.ReadStringMode.html#variant.Raw) read
+    NulTerminated,
+}
+
+impl Default for WriteStringMode {
+    fn default() -> Self {
+        WriteStringMode::Padded
+    }
+}
+
+/// Values of a column to sort table rows by
+pub(crate) enum SortKey {
+    Integer(Vec<i64>),
+    Float(Vec<f64>),
+    Logical(Vec<bool>),
+    Text(Vec<String>),
+}
+
+impl SortKey {
+    pub(crate) fn read(hdu: &FitsHdu, fits_file: &mut FitsFile, name: &str) -> Result<Self> {
+        use self::ColumnDataType::*;
+
+        let description = hdu.column_type(fits_file, name)?;
+        let is_string = matches!(description.typ, Text | String);
+        if description.descriptor.is_some() || (description.repeat != 1 && !is_string) {
+            return Err(format!("cannot sort by vector column {:?}", name)
+                .as_str()
+                .into());
+        }
+
+        match description.typ {
+            Text | String => hdu.read_col(fits_file, name).map(SortKey::Text),
+            Logical => hdu.read_col(fits_file, name).map(SortKey::Logical),
+            Float | Double | UnsignedLong => hdu.read_col(fits_file, name).map(SortKey::Float),
+            Bool | SignedByte | Short | UnsignedShort | Int | UnsignedInt | Long => {
+                hdu.read_col(fits_file, name).map(SortKey::Integer)
+            }
+            other => Err(
+                format!("cannot sort by column {:?} of type {:?}", name, other)
+                    .as_str()
+                    .into(),
+            ),
+        }
+    }
+
+    /// Compare the values in rows `a` and `b`, NaNs sort after every other value
+    pub(crate) fn compare(&self, a: usize, b: usize) -> std::cmp::Ordering {
+        match self {
+            SortKey::Integer(values) => values[a].cmp(&values[b]),
+            SortKey::Float(values) => values[a]
+                .partial_cmp(&values[b])
+                .unwrap_or_else(|| values[a].is_nan().cmp(&values[b].is_nan())),
+            SortKey::Logical(values) => values[a].cmp(&values[b]),
+            SortKey::Text(values) => values[a].cmp(&values[b]),
+        }
+    }
+}
+
+/// Summary statistics of a column, see [`FitsHdu::column_stats`](../hdu/struct.FitsHdu.html#method.column_stats)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColumnStats<T> {
+    /// Number of values, excluding undefined values
+    pub count: usize,
+    /// Smallest value
+    pub min: T,
+    /// Largest value
+    pub max: T,
+    /// Mean of the values
+    pub mean: f64,
+    /// Population standard deviation of the values
+    pub std: f64,
+}
+
 /// Way of describing a column location
 pub trait DescribesColumnLocation {
     /// Method by which the column number can be computed
@@ -686,91 +1291,1171 @@ datatype_into_impl!(u32);
 datatype_into_impl!(i64);
 datatype_into_impl!(u64);
 
-/// Columns of different types
+/**
+Columns of different types
+
+Along with its data, each column holds its name, its unit from the `TUNITn` key if one is
+given, and its repeat count.
+*/
 #[allow(missing_docs)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Column {
-    Int32 { name: String, data: Vec<i32> },
-    Int64 { name: String, data: Vec<i64> },
-    Float { name: String, data: Vec<f32> },
-    Double { name: String, data: Vec<f64> },
-    String { name: String, data: Vec<String> },
+    Int16 {
+        name: String,
+        unit: Option<String>,
+        repeat: usize,
+        data: Vec<i16>,
+    },
+    UInt16 {
+        name: String,
+        unit: Option<String>,
+        repeat: usize,
+        data: Vec<u16>,
+    },
+    Int32 {
+        name: String,
+        unit: Option<String>,
+        repeat: usize,
+        data: Vec<i32>,
+    },
+    UInt32 {
+        name: String,
+        unit: Option<String>,
+        repeat: usize,
+        data: Vec<u32>,
+    },
+    Int64 {
+        name: String,
+        unit: Option<String>,
+        repeat: usize,
+        data: Vec<i64>,
+    },
+    UInt64 {
+        name: String,
+        unit: Option<String>,
+        repeat: usize,
+        data: Vec<u64>,
+    },
+    Float {
+        name: String,
+        unit: Option<String>,
+        repeat: usize,
+        data: Vec<f32>,
+    },
+    Double {
+        name: String,
+        unit: Option<String>,
+        repeat: usize,
+        data: Vec<f64>,
+    },
+    String {
+        name: String,
+        unit: Option<String>,
+        repeat: usize,
+        data: Vec<String>,
+    },
+    Bool {
+        name: String,
+        unit: Option<String>,
+        repeat: usize,
+        data: Vec<bool>,
+    },
+    Bytes {
+        name: String,
+        unit: Option<String>,
+        repeat: usize,
+        data: Vec<u8>,
+    },
 }
 
-/// Iterator type for columns
-pub struct ColumnIterator<'a> {
-    current: usize,
-    column_descriptions: Vec<ConcreteColumnDescription>,
-    fits_file: &'a mut FitsFile,
+macro_rules! column_field {
+    ($column:expr, $field:ident) => {
+        match $column {
+            Column::Int16 { $field, .. }
+            | Column::UInt16 { $field, .. }
+            | Column::Int32 { $field, .. }
+            | Column::UInt32 { $field, .. }
+            | Column::Int64 { $field, .. }
+            | Column::UInt64 { $field, .. }
+            | Column::Float { $field, .. }
+            | Column::Double { $field, .. }
+            | Column::String { $field, .. }
+            | Column::Bool { $field, .. }
+            | Column::Bytes { $field, .. } => $field,
+        }
+    };
 }
 
-impl<'a> ColumnIterator<'a> {
-    pub(crate) fn new(fits_file: &'a mut FitsFile) -> Self {
-        match fits_file.fetch_hdu_info() {
-            Ok(HduInfo::TableInfo {
-                column_descriptions,
-                num_rows: _num_rows,
-            }) => ColumnIterator {
+impl Column {
+    /// The name of the column
+    pub fn name(&self) -> &str {
+        column_field!(self, name)
+    }
+
+    /// The unit of the column values, if any
+    pub fn unit(&self) -> Option<&str> {
+        column_field!(self, unit).as_deref()
+    }
+
+    /// The number of values in each row of the column
+    pub fn repeat(&self) -> usize {
+        *column_field!(self, repeat)
+    }
+}
+
+/// Read the type after scaling, repeat count and unit of a column in the current HDU
+fn column_metadata(
+    fits_file: &mut FitsFile,
+    colno: usize,
+) -> Result<(ColumnDataType, usize, Option<String>)> {
+    let mut typecode = 0;
+    let mut repeat = 0;
+    let mut width = 0;
+    let mut status = 0;
+    unsafe {
+        fits_get_eqcoltypell(
+            fits_file.fptr.as_mut() as *mut _,
+            (colno + 1) as _,
+            &mut typecode,
+            &mut repeat,
+            &mut width,
+            &mut status,
+        );
+    }
+    check_status(status)?;
+
+    let unit = read_key_optional(fits_file, &format!("TUNIT{}", colno + 1))?;
+    Ok((
+        ColumnDataType::from_typecode(typecode)?,
+        repeat as usize,
+        unit,
+    ))
+}
+
+/**
+Iterator type for columns
+
+Columns are only read when the iterator is advanced, and each item is the [`Column`] read, or
+the error encountered reading it. By default each column is read in full, use
+[`chunked`](#method.chunked) to bound the memory used for large tables.
+*/
+pub struct ColumnIterator<'a> {
+    current: usize,
+    row: usize,
+    num_rows: usize,
+    chunk_size: Option<usize>,
+    column_descriptions: Vec<ConcreteColumnDescription>,
+    fits_file: &'a mut FitsFile,
+}
+
+impl<'a> ColumnIterator<'a> {
+    pub(crate) fn new(fits_file: &'a mut FitsFile) -> Result<Self> {
+        match fits_file.fetch_hdu_info()? {
+            HduInfo::TableInfo {
+                column_descriptions,
+                num_rows,
+            } => Ok(ColumnIterator {
                 current: 0,
+                row: 0,
+                num_rows,
+                chunk_size: None,
                 column_descriptions,
                 fits_file,
-            },
-            Err(e) => panic!("{:?}", e),
-            _ => panic!("Unknown error occurred"),
+            }),
+            _ => Err("cannot iterate over the columns of a non-table hdu".into()),
+        }
+    }
+
+    /**
+    Read each column in chunks of at most `chunk_size` rows
+
+    Every chunk of the first column is returned, in row order, before the chunks of the second
+    column and so on.
+
+    # Panics
+
+    Panics if `chunk_size` is 0.
+
+    # Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    # let filename = "../testdata/full_example.fits";
+    # let mut fptr = fitsio::FitsFile::open(filename)?;
+    # let hdu = fptr.hdu("TESTEXT")?;
+    for column in hdu.columns(&mut fptr)?.chunked(20) {
+        // At most 20 rows of a column
+        let column = column?;
+    }
+    # Ok(())
+    # }
+    ```
+    */
+    pub fn chunked(mut self, chunk_size: usize) -> Self {
+        assert_ne!(chunk_size, 0, "chunk size must be non-zero");
+        self.chunk_size = Some(chunk_size);
+        self
+    }
+}
+
+impl<'a> Iterator for ColumnIterator<'a> {
+    type Item = Result<Column>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let colno = self.current;
+        let description = self.column_descriptions.get(colno)?;
+        let end = match self.chunk_size {
+            Some(chunk_size) => (self.row + chunk_size).min(self.num_rows),
+            None => self.num_rows,
+        };
+        let range = self.row..end;
+
+        if end < self.num_rows {
+            self.row = end;
+        } else {
+            self.current += 1;
+            self.row = 0;
+        }
+
+        let name = description.name.clone();
+        Some(read_column(self.fits_file, colno, name, &range))
+    }
+}
+
+/// Read a range of rows of a column of the current HDU, with the type given by the header
+fn read_column(
+    fits_file: &mut FitsFile,
+    colno: usize,
+    name: String,
+    range: &Range<usize>,
+) -> Result<Column> {
+    let (typ, repeat, unit) = column_metadata(fits_file, colno)?;
+
+    macro_rules! read_column {
+        ($t:ty, $variant:ident) => {
+            <$t>::read_col_range(fits_file, name.as_str(), range).map(|data| Column::$variant {
+                name,
+                unit,
+                repeat,
+                data,
+            })
+        };
+    }
+
+    match typ {
+        ColumnDataType::Short => read_column!(i16, Int16),
+        ColumnDataType::UnsignedShort => read_column!(u16, UInt16),
+        ColumnDataType::Int => read_column!(i32, Int32),
+        ColumnDataType::UnsignedInt => read_column!(u32, UInt32),
+        ColumnDataType::Long => read_column!(i64, Int64),
+        ColumnDataType::UnsignedLong => read_column!(u64, UInt64),
+        ColumnDataType::Float => read_column!(f32, Float),
+        ColumnDataType::Double => read_column!(f64, Double),
+        ColumnDataType::Text | ColumnDataType::String => read_column!(String, String),
+        ColumnDataType::Logical => read_column!(bool, Bool),
+        ColumnDataType::Bool => read_column!(u8, Bytes),
+        other => Err(Error::Message(format!(
+            "cannot read column {:?} of type {:?}",
+            name, other
+        ))),
+    }
+}
+
+/**
+Builder for reading a window of rows from several columns at once, created by
+[`FitsHdu::table_query`](../hdu/struct.FitsHdu.html#method.table_query)
+
+By default every column and every row is read. Rows past the end of the table are ignored, so a
+fixed page size can be used to page through a table, with the last page being shorter.
+*/
+pub struct TableQuery<'a> {
+    hdu: &'a FitsHdu,
+    fits_file: &'a mut FitsFile,
+    columns: Option<Vec<String>>,
+    rows: Option<Box<dyn IndexRange + 'a>>,
+}
+
+impl<'a> TableQuery<'a> {
+    pub(crate) fn new(hdu: &'a FitsHdu, fits_file: &'a mut FitsFile) -> Self {
+        TableQuery {
+            hdu,
+            fits_file,
+            columns: None,
+            rows: None,
+        }
+    }
+
+    /// Only read the named columns, in the given order
+    pub fn columns<T: AsRef<str>>(mut self, names: &[T]) -> Self {
+        self.columns = Some(names.iter().map(|n| n.as_ref().to_string()).collect());
+        self
+    }
+
+    /// Only read the rows in `rows`, see [`IndexRange`](../trait.IndexRange.html)
+    pub fn rows<R: IndexRange + 'a>(mut self, rows: R) -> Self {
+        self.rows = Some(Box::new(rows));
+        self
+    }
+
+    /// Read the selected columns and rows
+    pub fn read(self) -> Result<TableSlice> {
+        let TableQuery {
+            hdu,
+            fits_file,
+            columns,
+            rows,
+        } = self;
+        fits_file.make_current(hdu)?;
+        let (column_descriptions, num_rows) = match fits_file.fetch_hdu_info()? {
+            HduInfo::TableInfo {
+                column_descriptions,
+                num_rows,
+            } => (column_descriptions, num_rows),
+            _ => return Err("cannot query the rows of a non-table hdu".into()),
+        };
+
+        let rows = match rows {
+            Some(ref rows) => {
+                let rows = rows.to_range(num_rows);
+                let end = rows.end.min(num_rows);
+                rows.start.min(end)..end
+            }
+            None => 0..num_rows,
+        };
+
+        let columns = match columns {
+            Some(names) => names
+                .into_iter()
+                .map(|name| {
+                    let colno = hdu.get_column_no(fits_file, name.as_str())?;
+                    Ok((colno, column_descriptions[colno].name.clone()))
+                })
+                .collect::<Result<Vec<_>>>()?,
+            None => column_descriptions
+                .into_iter()
+                .enumerate()
+                .map(|(colno, description)| (colno, description.name))
+                .collect(),
+        };
+
+        let columns = columns
+            .into_iter()
+            .map(|(colno, name)| read_column(fits_file, colno, name, &rows))
+            .collect::<Result<Vec<_>>>()?;
+        Ok(TableSlice { rows, columns })
+    }
+}
+
+/// Rows of several columns, read by [`TableQuery::read`](struct.TableQuery.html#method.read)
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableSlice {
+    /// The zero-indexed rows which were read
+    pub rows: Range<usize>,
+    /// The columns which were read, in the order they were requested
+    pub columns: Vec<Column>,
+}
+
+impl TableSlice {
+    /// The number of rows read
+    pub fn num_rows(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Get a column by name, ignoring case as `cfitsio` does
+    pub fn column(&self, name: &str) -> Option<&Column> {
+        self.columns
+            .iter()
+            .find(|column| column.name().eq_ignore_ascii_case(name))
+    }
+}
+
+/// Apply an expression to the data vector of a column, whatever its type
+macro_rules! with_column_data {
+    ($column:expr, $data:ident => $e:expr) => {
+        match $column {
+            Column::Int16 { $data, .. } => $e,
+            Column::UInt16 { $data, .. } => $e,
+            Column::Int32 { $data, .. } => $e,
+            Column::UInt32 { $data, .. } => $e,
+            Column::Int64 { $data, .. } => $e,
+            Column::UInt64 { $data, .. } => $e,
+            Column::Float { $data, .. } => $e,
+            Column::Double { $data, .. } => $e,
+            Column::String { $data, .. } => $e,
+            Column::Bool { $data, .. } => $e,
+            Column::Bytes { $data, .. } => $e,
+        }
+    };
+}
+
+/**
+Values which can be appended to a column with a [`TableAppender`]
+
+This is implemented for every type a column can be read as (see [`ReadsCol`]), so any
+[`FitsRow`] which can be read can also be appended. `None` values of `Option` fields are written
+as undefined, which for integer columns requires a `TNULLn` key.
+*/
+pub trait WritesCell {
+    #[doc(hidden)]
+    fn push_cell(self, column: &mut BufferedColumn) -> Result<()>;
+}
+
+macro_rules! writes_cell_impl {
+    ($t:ty, $variant:ident) => {
+        impl WritesCell for $t {
+            fn push_cell(self, column: &mut BufferedColumn) -> Result<()> {
+                if column.data.is_none() {
+                    column.data = Some(Column::$variant {
+                        name: column.name.clone(),
+                        unit: None,
+                        repeat: 1,
+                        data: Vec::new(),
+                    });
+                }
+                match column.data {
+                    Some(Column::$variant { ref mut data, .. }) => {
+                        data.push(self);
+                        Ok(())
+                    }
+                    _ => Err(Error::Message(format!(
+                        "column {:?} was given values of different types",
+                        column.name
+                    ))),
+                }
+            }
+        }
+    };
+}
+
+writes_cell_impl!(i16, Int16);
+writes_cell_impl!(u16, UInt16);
+writes_cell_impl!(i32, Int32);
+writes_cell_impl!(u32, UInt32);
+writes_cell_impl!(i64, Int64);
+writes_cell_impl!(u64, UInt64);
+writes_cell_impl!(f32, Float);
+writes_cell_impl!(f64, Double);
+writes_cell_impl!(String, String);
+writes_cell_impl!(bool, Bool);
+writes_cell_impl!(u8, Bytes);
+
+macro_rules! writes_cell_nullable_impl {
+    ($t:ty) => {
+        impl WritesCell for Option<$t> {
+            fn push_cell(self, column: &mut BufferedColumn) -> Result<()> {
+                match self {
+                    Some(value) => value.push_cell(column),
+                    None => {
+                        let row = column.len();
+                        <$t>::default().push_cell(column)?;
+                        column.nulls.push(row);
+                        Ok(())
+                    }
+                }
+            }
+        }
+    };
+}
+
+writes_cell_nullable_impl!(i16);
+writes_cell_nullable_impl!(u16);
+writes_cell_nullable_impl!(i32);
+writes_cell_nullable_impl!(u32);
+writes_cell_nullable_impl!(i64);
+writes_cell_nullable_impl!(u64);
+writes_cell_nullable_impl!(f32);
+writes_cell_nullable_impl!(f64);
+
+/// Values of a single column waiting to be written by a [`TableAppender`]
+#[doc(hidden)]
+pub struct BufferedColumn {
+    name: String,
+    scalar: bool,
+    data: Option<Column>,
+    nulls: Vec<usize>,
+}
+
+impl BufferedColumn {
+    fn len(&self) -> usize {
+        match self.data {
+            Some(ref column) => with_column_data!(column, data => data.len()),
+            None => 0,
+        }
+    }
+
+    fn truncate(&mut self, len: usize) {
+        if let Some(ref mut column) = self.data {
+            with_column_data!(column, data => data.truncate(len));
+        }
+        self.nulls.retain(|&row| row < len);
+    }
+
+    /// Write the buffered values to `rows` of the current HDU, and empty the buffer
+    fn flush(&mut self, fits_file: &mut FitsFile, hdu: &FitsHdu, start: usize) -> Result<()> {
+        let column = match self.data {
+            Some(ref mut column) => column,
+            None => return Ok(()),
+        };
+        let rows = start..start + with_column_data!(column, data => data.len());
+        if rows.is_empty() {
+            return Ok(());
+        }
+
+        let name = self.name.as_str();
+        match column {
+            // There are no 16 bit column writers, so let cfitsio convert from 32 bits
+            Column::Int16 { data, .. } => {
+                let data: Vec<i32> = data.iter().map(|&v| i32::from(v)).collect();
+                i32::write_col_range(fits_file, hdu, name, &data, &rows)?;
+            }
+            Column::UInt16 { data, .. } => {
+                let data: Vec<u32> = data.iter().map(|&v| u32::from(v)).collect();
+                u32::write_col_range(fits_file, hdu, name, &data, &rows)?;
+            }
+            Column::Int32 { data, .. } => {
+                i32::write_col_range(fits_file, hdu, name, data, &rows)?;
+            }
+            Column::UInt32 { data, .. } => {
+                u32::write_col_range(fits_file, hdu, name, data, &rows)?;
+            }
+            Column::Int64 { data, .. } => {
+                i64::write_col_range(fits_file, hdu, name, data, &rows)?;
+            }
+            Column::UInt64 { data, .. } => {
+                u64::write_col_range(fits_file, hdu, name, data, &rows)?;
+            }
+            Column::Float { data, .. } => {
+                f32::write_col_range(fits_file, hdu, name, data, &rows)?;
+            }
+            Column::Double { data, .. } => {
+                f64::write_col_range(fits_file, hdu, name, data, &rows)?;
+            }
+            Column::String { data, .. } => {
+                String::write_col_range(fits_file, hdu, name, data, &rows)?;
+            }
+            Column::Bool { data, .. } => {
+                bool::write_col_range(fits_file, hdu, name, data, &rows)?;
+            }
+            Column::Bytes { data, .. } => {
+                u8::write_col_range(fits_file, hdu, name, data, &rows)?;
+            }
+        }
+
+        if !self.nulls.is_empty() {
+            let colno = hdu.get_column_no(fits_file, name)?;
+            for &row in &self.nulls {
+                let mut status = 0;
+                unsafe {
+                    fits_write_col_null(
+                        fits_file.fptr.as_mut() as *mut _,
+                        (colno + 1) as _,
+                        (start + row + 1) as _,
+                        1,
+                        1,
+                        &mut status,
+                    );
+                }
+                check_status(status).map_err(|e| match e {
+                    Error::Fits(e) => Error::Message(format!(
+                        "cannot write an undefined value to column {:?}: {}",
+                        name, e.message
+                    )),
+                    e => e,
+                })?;
+            }
+        }
+
+        with_column_data!(column, data => data.clear());
+        self.nulls.clear();
+        Ok(())
+    }
+}
+
+/**
+The values of a single row being appended by a [`TableAppender`]
+
+[`FitsRow`] implementations made with `fitsio-derive` fill this in automatically. Manual
+implementations call [`set`](#method.set) once for each column in the row.
+*/
+pub struct RowBuffer {
+    columns: Vec<BufferedColumn>,
+    len: usize,
+}
+
+impl RowBuffer {
+    /**
+    Set the value of column `name` in the current row
+
+    Columns are matched ignoring case, as `cfitsio` does. Only scalar and string columns can be
+    set.
+    */
+    pub fn set<T: WritesCell>(&mut self, name: &str, value: T) -> Result<()> {
+        let len = self.len;
+        let column = self
+            .columns
+            .iter_mut()
+            .find(|column| column.name.eq_ignore_ascii_case(name))
+            .ok_or_else(|| Error::Message(format!("no column named {:?} in the table", name)))?;
+        if !column.scalar {
+            return Err(Error::Message(format!(
+                "column {:?} holds more than one value per row, so cannot be appended to",
+                column.name
+            )));
+        }
+        if column.len() != len {
+            return Err(Error::Message(format!(
+                "column {:?} was set more than once in a row",
+                column.name
+            )));
+        }
+        value.push_cell(column)
+    }
+
+    /**
+    Set the value of the column at `index` in the current row, counting from 0
+
+    See [`set`](#method.set).
+    */
+    pub fn set_at<T: WritesCell>(&mut self, index: usize, value: T) -> Result<()> {
+        let name = match self.columns.get(index) {
+            Some(column) => column.name.clone(),
+            None => {
+                return Err(Error::Message(format!(
+                    "the table has no column at position {}",
+                    index
+                )))
+            }
+        };
+        self.set(&name, value)
+    }
+
+    /// Fields read with `#[fitsio(with = "...")]` cannot be converted back to the column type
+    #[doc(hidden)]
+    pub fn reject_converted(&mut self, name: &str) -> Result<()> {
+        Err(Error::Message(format!(
+            "column {:?} is read through a conversion function, so cannot be written",
+            name
+        )))
+    }
+
+    /// Check that every column written so far was set in the current row
+    fn check_row(&self) -> Result<()> {
+        for column in &self.columns {
+            if column.data.is_some() && column.len() != self.len + 1 {
+                return Err(Error::Message(format!(
+                    "column {:?} was not set in every row",
+                    column.name
+                )));
+            }
         }
+        Ok(())
+    }
+
+    fn truncate(&mut self, len: usize) {
+        for column in &mut self.columns {
+            column.truncate(len);
+        }
+    }
+}
+
+/**
+Appends rows to the end of a table, created by
+[`FitsHdu::table_appender`](../hdu/struct.FitsHdu.html#method.table_appender)
+
+Rows are buffered in memory and written a chunk of rows at a time, by default the number of rows
+`cfitsio` reports as optimal for its buffers. This is far faster than writing each row with
+[`write_col_range`](../hdu/struct.FitsHdu.html#method.write_col_range).
+
+Buffered rows are written when the appender is dropped, but any error is then lost, so call
+[`finish`](#method.finish) to check that every row was written. Columns which no row sets are
+left with their default value.
+*/
+pub struct TableAppender<'a> {
+    fits_file: &'a mut FitsFile,
+    hdu: FitsHdu,
+    next_row: usize,
+    chunk_rows: usize,
+    buffer: RowBuffer,
+}
+
+impl<'a> TableAppender<'a> {
+    pub(crate) fn new(fits_file: &'a mut FitsFile, hdu: FitsHdu) -> Result<Self> {
+        let (column_descriptions, num_rows) = match hdu.info {
+            HduInfo::TableInfo {
+                ref column_descriptions,
+                num_rows,
+            } => (column_descriptions, num_rows),
+            _ => return Err("cannot append rows to a non-table hdu".into()),
+        };
+        let columns = column_descriptions
+            .iter()
+            .map(|description| {
+                let data_type = &description.data_type;
+                BufferedColumn {
+                    name: description.name.clone(),
+                    scalar: data_type.descriptor.is_none()
+                        && (data_type.repeat == 1
+                            || data_type.typ == ColumnDataType::String
+                            || data_type.typ == ColumnDataType::Text),
+                    data: None,
+                    nulls: Vec::new(),
+                }
+            })
+            .collect();
+
+        // Re-read the header so cfitsio picks up any new TNULLn values
+        let mut status = 0;
+        unsafe {
+            fits_set_hdustruc(fits_file.fptr.as_mut() as *mut _, &mut status);
+        }
+        check_status(status)?;
+
+        let mut chunk_rows = 0;
+        unsafe {
+            fits_get_rowsize(
+                fits_file.fptr.as_mut() as *mut _,
+                &mut chunk_rows,
+                &mut status,
+            );
+        }
+        check_status(status)?;
+
+        Ok(TableAppender {
+            fits_file,
+            hdu,
+            next_row: num_rows,
+            chunk_rows: (chunk_rows as usize).max(1),
+            buffer: RowBuffer { columns, len: 0 },
+        })
+    }
+
+    /**
+    Write the buffered rows every `chunk_rows` rows
+
+    # Panics
+
+    Panics if `chunk_rows` is 0.
+    */
+    pub fn chunk_rows(mut self, chunk_rows: usize) -> Self {
+        assert_ne!(chunk_rows, 0, "chunk size must be non-zero");
+        self.chunk_rows = chunk_rows;
+        self
+    }
+
+    /**
+    Append a row to the table
+
+    If the row cannot be converted, nothing is appended. Rows are only written to the file every
+    chunk of rows, or when [`flush`](#method.flush) is called.
+    */
+    pub fn append_row<R: FitsRow>(&mut self, row: &R) -> Result<()> {
+        let len = self.buffer.len;
+        if let Err(e) = row
+            .write_row(&mut self.buffer)
+            .and_then(|_| self.buffer.check_row())
+        {
+            self.buffer.truncate(len);
+            return Err(e);
+        }
+        self.buffer.len += 1;
+
+        if self.buffer.len >= self.chunk_rows {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    /// The number of rows in the table, including rows which have not been written yet
+    pub fn num_rows(&self) -> usize {
+        self.next_row + self.buffer.len
+    }
+
+    /// Write any buffered rows to the file
+    pub fn flush(&mut self) -> Result<()> {
+        if self.buffer.len == 0 {
+            return Ok(());
+        }
+        self.fits_file.make_current(&self.hdu)?;
+        let mut result = Ok(());
+        for column in &mut self.buffer.columns {
+            result = column.flush(self.fits_file, &self.hdu, self.next_row);
+            if result.is_err() {
+                break;
+            }
+        }
+        if result.is_ok() {
+            self.next_row += self.buffer.len;
+        } else {
+            // The rows may have been partly written, so discard them. The next chunk is written
+            // over them, as they were not all written.
+            self.buffer.truncate(0);
+        }
+        self.buffer.len = 0;
+        result
+    }
+
+    /// Write any buffered rows, and return the updated table HDU
+    pub fn finish(mut self) -> Result<FitsHdu> {
+        self.flush()?;
+        self.hdu.refresh(self.fits_file)
+    }
+}
+
+impl Drop for TableAppender<'_> {
+    fn drop(&mut self) {
+        let _ = self.flush();
+    }
+}
+
+/// Identifies a chunk held by a [`TableCache`]: rows of one column read as one type, or whole
+/// rows when `column` is `None`
+#[derive(Debug, PartialEq, Eq)]
+struct ChunkKey {
+    column: Option<String>,
+    type_id: TypeId,
+    first_row: usize,
+}
+
+struct CachedChunk {
+    key: ChunkKey,
+    last_used: u64,
+    data: Box<dyn Any>,
+}
+
+/**
+Caches chunks of table columns for repeated reads of nearby cells, created by
+[`FitsHdu::table_cache`](../hdu/struct.FitsHdu.html#method.table_cache)
+
+Reading one cell at a time with
+[`read_cell_value`](../hdu/struct.FitsHdu.html#method.read_cell_value) or
+[`row`](../hdu/struct.FitsHdu.html#method.row) looks up the column and reads from the file for
+every cell. The cache instead reads a chunk of rows of a column at once, by default the number of
+rows `cfitsio` reports as optimal for its buffers, and keeps the most recently used chunks in
+memory, so random access to a table only reads each chunk once while it stays cached.
+
+The cache holds the file until it is dropped, so the table cannot change while it is in use.
+*/
+pub struct TableCache<'a> {
+    fits_file: &'a mut FitsFile,
+    hdu: FitsHdu,
+    num_rows: usize,
+    chunk_rows: usize,
+    max_chunks: usize,
+    chunks: Vec<CachedChunk>,
+    clock: u64,
+}
+
+impl<'a> TableCache<'a> {
+    pub(crate) fn new(fits_file: &'a mut FitsFile, hdu: FitsHdu) -> Result<Self> {
+        let num_rows = match hdu.info {
+            HduInfo::TableInfo { num_rows, .. } => num_rows,
+            _ => return Err("cannot cache a non-table hdu".into()),
+        };
+
+        let mut chunk_rows = 0;
+        let mut status = 0;
+        unsafe {
+            fits_get_rowsize(
+                fits_file.fptr.as_mut() as *mut _,
+                &mut chunk_rows,
+                &mut status,
+            );
+        }
+        check_status(status)?;
+
+        Ok(TableCache {
+            fits_file,
+            hdu,
+            num_rows,
+            chunk_rows: (chunk_rows as usize).max(1),
+            max_chunks: 16,
+            chunks: Vec::new(),
+            clock: 0,
+        })
+    }
+
+    /**
+    Read this many rows of a column at a time
+
+    # Panics
+
+    Panics if `chunk_rows` is 0.
+    */
+    pub fn chunk_rows(mut self, chunk_rows: usize) -> Self {
+        assert_ne!(chunk_rows, 0, "chunk size must be non-zero");
+        self.chunk_rows = chunk_rows;
+        self.chunks.clear();
+        self
+    }
+
+    /**
+    Keep at most this many chunks in memory, discarding the least recently used first
+
+    # Panics
+
+    Panics if `max_chunks` is 0.
+    */
+    pub fn max_chunks(mut self, max_chunks: usize) -> Self {
+        assert_ne!(max_chunks, 0, "the cache must hold at least one chunk");
+        self.max_chunks = max_chunks;
+        self.chunks.truncate(max_chunks);
+        self
+    }
+
+    /// The number of rows in the table
+    pub fn num_rows(&self) -> usize {
+        self.num_rows
+    }
+
+    /// Discard every cached chunk
+    pub fn clear(&mut self) {
+        self.chunks.clear();
+    }
+
+    /**
+    Read the value of a single cell, reading its chunk of the column if it is not cached
+
+    See [`FitsHdu::read_cell_value`](../hdu/struct.FitsHdu.html#method.read_cell_value).
+    */
+    pub fn read_cell_value<T>(&mut self, name: &str, idx: usize) -> Result<T>
+    where
+        T: ReadsCol + Clone + 'static,
+    {
+        let (first_row, chunk) = self.chunk(Some(name), idx, |fits_file, _, range| {
+            T::read_col_range(fits_file, name, range)
+        })?;
+        Ok(chunk[idx - first_row].clone())
+    }
+
+    /**
+    Read a single row, reading its chunk of rows if it is not cached
+
+    Derived rows and tuples read each column once for the whole chunk, see
+    [`FitsHdu::rows_range`](../hdu/struct.FitsHdu.html#method.rows_range).
+    */
+    pub fn row<F>(&mut self, idx: usize) -> Result<F>
+    where
+        F: FitsRow + Clone + 'static,
+    {
+        let (first_row, chunk) = self.chunk(None, idx, |fits_file, hdu, range| {
+            F::from_table_range(hdu, fits_file, range)
+        })?;
+        Ok(chunk[idx - first_row].clone())
+    }
+
+    /// Find the cached chunk holding row `idx`, calling `read` to read it if it is not cached
+    fn chunk<T, R>(&mut self, column: Option<&str>, idx: usize, read: R) -> Result<(usize, &Vec<T>)>
+    where
+        T: 'static,
+        R: FnOnce(&mut FitsFile, &FitsHdu, &Range<usize>) -> Result<Vec<T>>,
+    {
+        if idx >= self.num_rows {
+            return Err(IndexError {
+                message: "given index out of range".to_string(),
+                given: idx..idx + 1,
+            }
+            .into());
+        }
+
+        let first_row = idx - idx % self.chunk_rows;
+        let key = ChunkKey {
+            column: column.map(|name| name.to_string()),
+            type_id: TypeId::of::<T>(),
+            first_row,
+        };
+        self.clock += 1;
+
+        let position = match self.chunks.iter().position(|chunk| chunk.key == key) {
+            Some(position) => position,
+            None => {
+                let range = first_row..(first_row + self.chunk_rows).min(self.num_rows);
+                self.fits_file.make_current(&self.hdu)?;
+                let data = read(self.fits_file, &self.hdu, &range)?;
+
+                if self.chunks.len() >= self.max_chunks {
+                    if let Some(oldest) = self
+                        .chunks
+                        .iter()
+                        .enumerate()
+                        .min_by_key(|(_, chunk)| chunk.last_used)
+                        .map(|(position, _)| position)
+                    {
+                        self.chunks.swap_remove(oldest);
+                    }
+                }
+                self.chunks.push(CachedChunk {
+                    key,
+                    last_used: 0,
+                    data: Box::new(data),
+                });
+                self.chunks.len() - 1
+            }
+        };
+
+        let chunk = &mut self.chunks[position];
+        chunk.last_used = self.clock;
+        let data = chunk
+            .data
+            .downcast_ref::<Vec<T>>()
+            .expect("chunks are keyed by their type");
+        Ok((first_row, data))
+    }
+}
+
+/// How a column takes part in [`FitsHdu::iterate_columns`](../hdu/struct.FitsHdu.html#method.iterate_columns)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IteratorColumnMode {
+    /// Values are read from the file
+    Read,
+    /// Values are read from the file, and written back after each chunk
+    ReadWrite,
+    /// Values start undefined, and are written to the file after each chunk
+    Write,
+}
+
+/// A table column to pass to [`FitsHdu::iterate_columns`](../hdu/struct.FitsHdu.html#method.iterate_columns)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IteratorColumn<'a> {
+    /// Name of the column
+    pub name: &'a str,
+    /// Whether the column is read, written, or both
+    pub mode: IteratorColumnMode,
+}
+
+impl<'a> IteratorColumn<'a> {
+    /// A column which is only read
+    pub fn read(name: &'a str) -> Self {
+        IteratorColumn {
+            name,
+            mode: IteratorColumnMode::Read,
+        }
+    }
+
+    /// A column which is read, and updated in place
+    pub fn read_write(name: &'a str) -> Self {
+        IteratorColumn {
+            name,
+            mode: IteratorColumnMode::ReadWrite,
+        }
+    }
+
+    /// A column which is only written
+    pub fn write(name: &'a str) -> Self {
+        IteratorColumn {
+            name,
+            mode: IteratorColumnMode::Write,
+        }
+    }
+}
+
+/**
+A chunk of rows passed to the callback of
+[`FitsHdu::iterate_columns`](../hdu/struct.FitsHdu.html#method.iterate_columns)
+
+The column data is presented as `f64` values, in the order the columns were given. Columns with
+a repeat count greater than one contain `repeat` consecutive values per row.
+*/
+pub struct IteratorChunk<'a> {
+    columns: &'a mut [iteratorCol],
+    first_row: usize,
+    num_rows: usize,
+    total_rows: usize,
+}
+
+impl<'a> IteratorChunk<'a> {
+    /// Index of the first row in this chunk. Zero indexed.
+    pub fn first_row(&self) -> usize {
+        self.first_row
+    }
+
+    /// Number of rows in this chunk
+    pub fn num_rows(&self) -> usize {
+        self.num_rows
+    }
+
+    /// Number of rows in the whole iteration
+    pub fn total_rows(&self) -> usize {
+        self.total_rows
+    }
+
+    /// Number of columns being iterated over
+    pub fn num_columns(&self) -> usize {
+        self.columns.len()
+    }
+
+    /// Values of column `idx` for this chunk
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is out of bounds
+    pub fn column(&self, idx: usize) -> &[f64] {
+        let (ptr, len) = self.column_parts(idx);
+        unsafe { std::slice::from_raw_parts(ptr, len) }
+    }
+
+    /// Mutable values of column `idx` for this chunk
+    ///
+    /// Changes are only saved for columns created with
+    /// [`IteratorColumn::read_write`](struct.IteratorColumn.html#method.read_write) or
+    /// [`IteratorColumn::write`](struct.IteratorColumn.html#method.write).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `idx` is out of bounds
+    pub fn column_mut(&mut self, idx: usize) -> &mut [f64] {
+        let (ptr, len) = self.column_parts(idx);
+        unsafe { std::slice::from_raw_parts_mut(ptr, len) }
+    }
+
+    fn column_parts(&self, idx: usize) -> (*mut f64, usize) {
+        let column = &self.columns[idx];
+        let repeat = column.repeat.max(1) as usize;
+        // cfitsio stores the null value in the first element of the array
+        let ptr = unsafe { (column.array as *mut f64).add(1) };
+        (ptr, self.num_rows * repeat)
     }
 }
 
-impl<'a> Iterator for ColumnIterator<'a> {
-    type Item = Column;
+pub(crate) type IteratorCallback<'a> = dyn FnMut(&mut IteratorChunk) -> Result<()> + 'a;
 
-    fn next(&mut self) -> Option<Self::Item> {
-        let ncols = self.column_descriptions.len();
-
-        if self.current < ncols {
-            let description = &self.column_descriptions[self.current];
-            let current_name = description.name.as_str();
-            // let current_type = typechar_to_data_type(description.data_type.as_str());
-            let current_type = description.data_type.typ;
-
-            let retval = match current_type {
-                ColumnDataType::Int => i32::read_col(self.fits_file, current_name)
-                    .map(|data| Column::Int32 {
-                        name: current_name.to_string(),
-                        data,
-                    })
-                    .ok(),
-                ColumnDataType::Long => i64::read_col(self.fits_file, current_name)
-                    .map(|data| Column::Int64 {
-                        name: current_name.to_string(),
-                        data,
-                    })
-                    .ok(),
-                ColumnDataType::Float => f32::read_col(self.fits_file, current_name)
-                    .map(|data| Column::Float {
-                        name: current_name.to_string(),
-                        data,
-                    })
-                    .ok(),
-                ColumnDataType::Double => f64::read_col(self.fits_file, current_name)
-                    .map(|data| Column::Double {
-                        name: current_name.to_string(),
-                        data,
-                    })
-                    .ok(),
-                ColumnDataType::String => String::read_col(self.fits_file, current_name)
-                    .map(|data| Column::String {
-                        name: current_name.to_string(),
-                        data,
-                    })
-                    .ok(),
-                _ => unimplemented!(),
-            };
+/// State shared with the cfitsio work function
+pub(crate) struct IteratorState<'a, 'b> {
+    pub(crate) callback: &'a mut IteratorCallback<'b>,
+    pub(crate) error: Option<Error>,
+    pub(crate) panic: Option<Box<dyn std::any::Any + Send + 'static>>,
+}
 
-            self.current += 1;
+/// Work function passed to `fits_iterate_data`, which forwards each chunk to the user callback
+pub(crate) unsafe extern "C" fn iterator_work_fn(
+    totaln: c_long,
+    _offset: c_long,
+    firstn: c_long,
+    nvalues: c_long,
+    narrays: c_int,
+    data: *mut iteratorCol,
+    user_pointer: *mut c_void,
+) -> c_int {
+    let state = &mut *(user_pointer as *mut IteratorState);
+    let mut chunk = IteratorChunk {
+        columns: std::slice::from_raw_parts_mut(data, narrays as usize),
+        first_row: (firstn - 1) as usize,
+        num_rows: nvalues as usize,
+        total_rows: totaln as usize,
+    };
 
-            retval
-        } else {
-            None
+    // Panics must not unwind into C, so catch them and resume once cfitsio has returned
+    let callback = &mut state.callback;
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| (callback)(&mut chunk)));
+    match result {
+        Ok(Ok(())) => 0,
+        Ok(Err(e)) => {
+            state.error = Some(e);
+            // Stop the iteration without setting a cfitsio error status
+            -1
+        }
+        Err(payload) => {
+            state.panic = Some(payload);
+            -1
         }
     }
 }
@@ -778,9 +2463,7 @@ impl<'a> Iterator for ColumnIterator<'a> {
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::testhelpers::{
-        duplicate_test_file, floats_close_f32, floats_close_f64, with_temp_file,
-    };
+    use crate::testing::{duplicate_test_file, floats_close_f32, floats_close_f64, with_temp_file};
 
     #[test]
     fn test_parsing() {
@@ -791,6 +2474,7 @@ mod test {
                 repeat: 1,
                 width: 1,
                 typ: ColumnDataType::Float,
+                descriptor: None,
             }
         );
     }
@@ -804,6 +2488,7 @@ mod test {
                 repeat: 100,
                 width: 1,
                 typ: ColumnDataType::Float,
+                descriptor: None,
             }
         );
     }
@@ -817,10 +2502,61 @@ mod test {
                 repeat: 1,
                 width: 26,
                 typ: ColumnDataType::Float,
+                descriptor: None,
             }
         );
     }
 
+    #[test]
+    fn test_parse_all_type_codes() {
+        let codes = [
+            ("X", ColumnDataType::Bit),
+            ("B", ColumnDataType::Bool),
+            ("L", ColumnDataType::Logical),
+            ("I", ColumnDataType::Short),
+            ("J", ColumnDataType::Int),
+            ("K", ColumnDataType::Long),
+            ("A", ColumnDataType::String),
+            ("E", ColumnDataType::Float),
+            ("D", ColumnDataType::Double),
+            ("C", ColumnDataType::Complex),
+            ("M", ColumnDataType::DoubleComplex),
+        ];
+        for (code, typ) in &codes {
+            let description = format!("3{}", code)
+                .parse::<ColumnDataDescription>()
+                .unwrap();
+            assert_eq!(description, ColumnDataDescription::new(*typ, 3, 1));
+        }
+    }
+
+    #[test]
+    fn test_parse_variable_length() {
+        assert_eq!(
+            "1PE(100)".parse::<ColumnDataDescription>().unwrap(),
+            ColumnDataDescription::variable_length(ColumnDataType::Float, ArrayDescriptor::P)
+        );
+        assert_eq!(
+            "QD".parse::<ColumnDataDescription>().unwrap(),
+            ColumnDataDescription::variable_length(ColumnDataType::Double, ArrayDescriptor::Q)
+        );
+
+        let description =
+            ColumnDataDescription::variable_length(ColumnDataType::Int, ArrayDescriptor::Q);
+        assert_eq!(String::from(description), "1QJ");
+    }
+
+    #[test]
+    fn test_parse_invalid_tforms() {
+        for s in &["", "10", "1Z", "PE(", "1PE(x)", "1P", "1E2x", "1EE"] {
+            assert!(
+                s.parse::<ColumnDataDescription>().is_err(),
+                "{:?} should not parse",
+                s
+            );
+        }
+    }
+
     #[test]
     fn test_creating_data_description() {
         let concrete_desc = ColumnDescription::new("FOO")
@@ -845,6 +2581,90 @@ mod test {
         assert_eq!(width, 7);
     }
 
+    #[test]
+    fn test_string_column_with_narrow_display_width() {
+        with_temp_file(|filename| {
+            let mut f = FitsFile::create(filename).open().unwrap();
+            let table_description = vec![
+                ColumnDescription::new("names")
+                    .with_type(ColumnDataType::String)
+                    .that_repeats(12)
+                    .create()
+                    .unwrap(),
+                ColumnDescription::new("short")
+                    .with_type(ColumnDataType::String)
+                    .that_repeats(3)
+                    .create()
+                    .unwrap(),
+            ];
+            let hdu = f.create_table("DATA", &table_description).unwrap();
+            let names = vec!["abcdefghijkl".to_string(), "mno".to_string()];
+            hdu.write_col(&mut f, "names", &names).unwrap();
+            hdu.write_col(&mut f, "short", &["ab".to_string(), "cde".to_string()])
+                .unwrap();
+            hdu.write_key(&mut f, "TDISP1", "A4").unwrap();
+            hdu.write_key(&mut f, "TDISP2", "A20").unwrap();
+
+            assert_eq!(hdu.column_display_width(&mut f, "names").unwrap(), 4);
+            assert_eq!(hdu.column_type(&mut f, "names").unwrap().width, 12);
+            assert_eq!(hdu.column_display_width(&mut f, 1).unwrap(), 20);
+
+            let read: Vec<String> = hdu.read_col(&mut f, "names").unwrap();
+            assert_eq!(read, names);
+            let read: Vec<String> = hdu.read_col(&mut f, "short").unwrap();
+            assert_eq!(read, vec!["ab".to_string(), "cde".to_string()]);
+            let cell: String = hdu.read_cell_value(&mut f, "names", 0).unwrap();
+            assert_eq!(cell, "abcdefghijkl");
+        });
+    }
+
+    #[test]
+    fn test_long_string_cells() {
+        with_temp_file(|filename| {
+            let mut f = FitsFile::create(filename).open().unwrap();
+            let table_description = vec![
+                ColumnDescription::new("comment")
+                    .with_type(ColumnDataType::String)
+                    .that_repeats(200)
+                    .create()
+                    .unwrap(),
+                ColumnDescription::new("words")
+                    .with_type(ColumnDataType::Text)
+                    .that_repeats(120)
+                    .with_width(10)
+                    .create()
+                    .unwrap(),
+            ];
+            let hdu = f.create_table("DATA", &table_description).unwrap();
+            let comment: String = "seeing poor, ".repeat(12);
+            assert_eq!(comment.len(), 156);
+            let comments = vec![comment.clone(), "clear".to_string()];
+            hdu.write_col(&mut f, "comment", &comments).unwrap();
+
+            let read: Vec<String> = hdu.read_col(&mut f, "comment").unwrap();
+            assert_eq!(
+                read,
+                vec![comment.trim_end().to_string(), "clear".to_string()]
+            );
+            let cell: String = hdu.read_cell_value(&mut f, "comment", 0).unwrap();
+            assert_eq!(cell, comment.trim_end());
+
+            // Strings which do not fit are rejected rather than truncated
+            let too_long: String = "x".repeat(201);
+            assert!(hdu
+                .write_col(&mut f, "comment", &[too_long, "cloudy".to_string()])
+                .is_err());
+            let read: Vec<String> = hdu.read_col(&mut f, "comment").unwrap();
+            assert_eq!(read[1], "clear");
+
+            assert!(hdu
+                .write_col(&mut f, "words", &["abcdefghijk".to_string()])
+                .is_err());
+            hdu.write_col(&mut f, "words", &["abcdefghij".to_string()])
+                .unwrap();
+        });
+    }
+
     #[test]
     fn test_read_columns() {
         let mut f = FitsFile::open("../testdata/full_example.fits").unwrap();
@@ -916,11 +2736,27 @@ mod test {
         assert_eq!(intcol_data[1], 13);
     }
 
+    #[test]
+    fn test_read_column_range_bounds() {
+        let mut f = FitsFile::open("../testdata/full_example.fits").unwrap();
+        let hdu = f.hdu(1).unwrap();
+        let all_data: Vec<i32> = hdu.read_col(&mut f, "intcol").unwrap();
+
+        let data: Vec<i32> = hdu.read_col_range(&mut f, "intcol", 1..=3).unwrap();
+        assert_eq!(data, &all_data[1..=3]);
+        let data: Vec<i32> = hdu.read_col_range(&mut f, "intcol", 45..).unwrap();
+        assert_eq!(data, &all_data[45..]);
+        let data: Vec<i32> = hdu.read_col_range(&mut f, "intcol", ..2).unwrap();
+        assert_eq!(data, &all_data[..2]);
+        let data: Vec<i32> = hdu.read_col_range(&mut f, "intcol", ..).unwrap();
+        assert_eq!(data, all_data);
+    }
+
     #[test]
     fn test_read_invalid_column_range() {
         let mut f = FitsFile::open("../testdata/full_example.fits").unwrap();
         let hdu = f.hdu(1).unwrap();
-        match hdu.read_col_range::<i32>(&mut f, "intcol", &(0..1024)) {
+        match hdu.read_col_range::<i32, _>(&mut f, "intcol", &(0..1024)) {
             Err(Error::Index(IndexError { message, given })) => {
                 assert_eq!(message, "given indices out of range".to_string());
                 assert_eq!(given, (0..1024));
@@ -947,18 +2783,112 @@ mod test {
         assert!(result_data.is_err());
     }
 
+    #[test]
+    fn test_chunked_column_iterator() {
+        let mut f = FitsFile::open("../testdata/full_example.fits").unwrap();
+        let hdu = f.hdu(1).unwrap();
+        let chunks: Vec<Column> = hdu
+            .columns(&mut f)
+            .unwrap()
+            .chunked(20)
+            .collect::<Result<_>>()
+            .unwrap();
+
+        // 4 columns of 50 rows, in chunks of 20, 20 and 10 rows
+        assert_eq!(chunks.len(), 12);
+        let intcol: Vec<i32> = chunks[..3]
+            .iter()
+            .flat_map(|chunk| match chunk {
+                Column::Int32 { name, data, .. } if name == "intcol" => data.clone(),
+                _ => panic!("unexpected column {:?}", chunk),
+            })
+            .collect();
+        assert_eq!(intcol, hdu.read_col::<Vec<i32>>(&mut f, "intcol").unwrap());
+        match &chunks[11] {
+            Column::String { name, data, .. } => {
+                assert_eq!(name, "strcol");
+                assert_eq!(data.len(), 10);
+                assert_eq!(data[0], "value40");
+            }
+            other => panic!("unexpected column {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_column_iterator_metadata() {
+        with_temp_file(|filename| {
+            let mut f = FitsFile::create(filename).open().unwrap();
+            let table_description = vec![
+                ColumnDescription::new("flags")
+                    .with_type(ColumnDataType::Logical)
+                    .create()
+                    .unwrap(),
+                ColumnDescription::new("bytes")
+                    .with_type(ColumnDataType::Bool)
+                    .create()
+                    .unwrap(),
+                ColumnDescription::new("counts")
+                    .with_type(ColumnDataType::UnsignedShort)
+                    .create()
+                    .unwrap(),
+            ];
+            let hdu = f.create_table("DATA", &table_description).unwrap();
+            hdu.write_col(&mut f, "flags", &[true, false, true])
+                .unwrap();
+            hdu.write_col(&mut f, "bytes", &[1u8, 2, 255]).unwrap();
+            hdu.write_key(&mut f, "TUNIT2", "adu").unwrap();
+
+            let columns: Vec<Column> = hdu.columns(&mut f).unwrap().collect::<Result<_>>().unwrap();
+            assert_eq!(
+                columns[0],
+                Column::Bool {
+                    name: "flags".to_string(),
+                    unit: None,
+                    repeat: 1,
+                    data: vec![true, false, true],
+                }
+            );
+            assert_eq!(
+                columns[1],
+                Column::Bytes {
+                    name: "bytes".to_string(),
+                    unit: Some("adu".to_string()),
+                    repeat: 1,
+                    data: vec![1, 2, 255],
+                }
+            );
+            assert_eq!(columns[1].name(), "bytes");
+            assert_eq!(columns[1].unit(), Some("adu"));
+            assert_eq!(columns[2].repeat(), 1);
+            match &columns[2] {
+                // Unwritten rows are zero before TZERO is applied
+                Column::UInt16 { data, .. } => assert_eq!(data, &[32768, 32768, 32768]),
+                other => panic!("unexpected column {:?}", other),
+            }
+        });
+    }
+
+    #[test]
+    fn test_column_iterator_errors() {
+        let mut f = FitsFile::open("../testdata/full_example.fits").unwrap();
+        let hdu = f.primary_hdu().unwrap();
+        assert!(hdu.columns(&mut f).is_err());
+    }
+
     #[test]
     fn test_column_iterator() {
         let mut f = FitsFile::open("../testdata/full_example.fits").unwrap();
         let hdu = f.hdu(1).unwrap();
         let column_names: Vec<String> = hdu
             .columns(&mut f)
-            .map(|col| match col {
+            .unwrap()
+            .map(|col| match col.unwrap() {
                 Column::Int32 { name, .. } => name,
                 Column::Int64 { name, .. } => name,
                 Column::Float { name, .. } => name,
                 Column::Double { name, .. } => name,
                 Column::String { name, .. } => name,
+                other => panic!("unexpected column {:?}", other),
             })
             .collect();
 
@@ -973,6 +2903,47 @@ mod test {
         );
     }
 
+    #[test]
+    fn test_table_query() {
+        let mut f = FitsFile::open("../testdata/full_example.fits").unwrap();
+        let hdu = f.hdu("TESTEXT").unwrap();
+        let intcol: Vec<i32> = hdu.read_col(&mut f, "intcol").unwrap();
+        let strcol: Vec<String> = hdu.read_col(&mut f, "strcol").unwrap();
+
+        let page = hdu
+            .table_query(&mut f)
+            .columns(&["STRCOL", "intcol"])
+            .rows(10..=19)
+            .read()
+            .unwrap();
+        assert_eq!(page.rows, 10..20);
+        assert_eq!(page.columns.len(), 2);
+        assert_eq!(page.columns[0].name(), "strcol");
+        match page.column("intcol") {
+            Some(Column::Int32 { data, .. }) => assert_eq!(data[..], intcol[10..20]),
+            other => panic!("unexpected column {:?}", other),
+        }
+        match page.column("strcol") {
+            Some(Column::String { data, .. }) => assert_eq!(data[..], strcol[10..20]),
+            other => panic!("unexpected column {:?}", other),
+        }
+
+        // The final page is truncated, and pages past the end are empty
+        let page = hdu.table_query(&mut f).rows(45..55).read().unwrap();
+        assert_eq!(page.rows, 45..50);
+        assert_eq!(page.columns.len(), 4);
+        let page = hdu.table_query(&mut f).rows(60..70).read().unwrap();
+        assert_eq!(page.num_rows(), 0);
+
+        assert!(hdu
+            .table_query(&mut f)
+            .columns(&["missing"])
+            .read()
+            .is_err());
+        let primary = f.primary_hdu().unwrap();
+        assert!(primary.table_query(&mut f).read().is_err());
+    }
+
     #[test]
     fn test_column_number() {
         let mut f = FitsFile::open("../testdata/full_example.fits").unwrap();
@@ -1037,7 +3008,7 @@ mod test {
                     .create_table("foo".to_string(), &table_description)
                     .unwrap();
 
-                hdu.write_col_range(&mut f, "bar", &data_to_write, &(0..5))
+                hdu.write_col_range(&mut f, "bar", &data_to_write[..5], &(0..5))
                     .unwrap();
             }
 
@@ -1049,6 +3020,112 @@ mod test {
         });
     }
 
+    #[test]
+    fn test_write_column_range_bounds() {
+        with_temp_file(|filename| {
+            let mut f = FitsFile::create(filename).open().unwrap();
+            let table_description = vec![ColumnDescription::new("bar")
+                .with_type(ColumnDataType::Int)
+                .create()
+                .unwrap()];
+            let hdu = f
+                .create_table("foo".to_string(), &table_description)
+                .unwrap();
+
+            hdu.write_col_range(&mut f, "bar", &[1, 2, 3], ..).unwrap();
+            hdu.write_col_range(&mut f, "bar", &[4, 5], 3..=4).unwrap();
+            hdu.write_col_range(&mut f, "bar", &[6, 7], 5..).unwrap();
+            hdu.write_col_range(&mut f, "bar", &[10], ..1).unwrap();
+
+            let data: Vec<i32> = hdu.read_col(&mut f, "bar").unwrap();
+            assert_eq!(data, vec![10, 2, 3, 4, 5, 6, 7]);
+        });
+    }
+
+    #[test]
+    fn test_write_column_range_length_mismatch() {
+        with_temp_file(|filename| {
+            let mut f = FitsFile::create(filename).open().unwrap();
+            let table_description = vec![ColumnDescription::new("bar")
+                .with_type(ColumnDataType::Int)
+                .create()
+                .unwrap()];
+            let hdu = f
+                .create_table("foo".to_string(), &table_description)
+                .unwrap();
+
+            match hdu.write_col_range(&mut f, "bar", &[1, 2, 3], 0..5) {
+                Err(Error::Index(IndexError { given, .. })) => assert_eq!(given, 0..5),
+                _ => panic!("Should be error"),
+            }
+            match hdu.write_col_range(&mut f, "bar", &[1, 2, 3], 0..2) {
+                Err(Error::Index(IndexError { given, .. })) => assert_eq!(given, 0..2),
+                _ => panic!("Should be error"),
+            }
+
+            /* Nothing was written */
+            assert_eq!(hdu.num_rows(&mut f).unwrap(), 0);
+        });
+    }
+
+    #[test]
+    fn test_write_rows_chunk() {
+        with_temp_file(|filename| {
+            let mut f = FitsFile::create(filename).open().unwrap();
+            let columns = &[
+                ColumnDescription::new("time")
+                    .with_type(ColumnDataType::Double)
+                    .create()
+                    .unwrap(),
+                ColumnDescription::new("counts")
+                    .with_type(ColumnDataType::Int)
+                    .create()
+                    .unwrap(),
+                ColumnDescription::new("state")
+                    .with_type(ColumnDataType::String)
+                    .that_repeats(8)
+                    .create()
+                    .unwrap(),
+            ];
+            let hdu = f.create_table("TELEMETRY", columns).unwrap();
+
+            let times = [1.0, 2.0, 3.0];
+            let counts = [4, 5, 6];
+            let states: Vec<String> = vec!["a".into(), "b".into(), "c".into()];
+            let hdu = hdu
+                .write_rows_chunk(&mut f, 0, (&times[..], &counts[..], &states[..]))
+                .unwrap();
+            let hdu = hdu
+                .write_rows_chunk(&mut f, 3, (&times[1..], &counts[1..], &states[1..]))
+                .unwrap();
+            // Only the leading columns
+            let hdu = hdu
+                .write_rows_chunk(&mut f, 1, (&[10.0][..], &[20][..]))
+                .unwrap();
+
+            assert_eq!(hdu.num_rows(&mut f).unwrap(), 5);
+            let times: Vec<f64> = hdu.read_col(&mut f, "time").unwrap();
+            assert_eq!(times, vec![1.0, 10.0, 3.0, 2.0, 3.0]);
+            let counts: Vec<i32> = hdu.read_col(&mut f, "counts").unwrap();
+            assert_eq!(counts, vec![4, 20, 6, 5, 6]);
+            let states: Vec<String> = hdu.read_col(&mut f, "state").unwrap();
+            assert_eq!(states, vec!["a", "b", "c", "b", "c"]);
+
+            match hdu.write_rows_chunk(&mut f, 0, (&[1.0, 2.0][..], &[1][..])) {
+                Err(Error::Index(e)) => assert_eq!(e.given, 0..2),
+                other => panic!("unexpected result {:?}", other),
+            }
+            let too_many = (&[1.0][..], &[1][..], &states[..1], &[1u8][..]);
+            assert!(hdu.write_rows_chunk(&mut f, 0, too_many).is_err());
+            let empty: (&[f64], &[i32]) = (&[], &[]);
+            match hdu.write_rows_chunk(&mut f, 2, empty) {
+                Err(Error::Index(e)) => assert_eq!(e.given, 2..2),
+                other => panic!("unexpected result {:?}", other),
+            }
+            assert_eq!(hdu.num_rows(&mut f).unwrap(), 5);
+        });
+    }
+
     #[test]
     fn test_write_string_col() {
         with_temp_file(|filename| {
@@ -1100,7 +3177,7 @@ mod test {
                     .create_table("foo".to_string(), &table_description)
                     .unwrap();
 
-                hdu.write_col_range(&mut f, "bar", &data_to_write, &range)
+                hdu.write_col_range(&mut f, "bar", &data_to_write[range.clone()], &range)
                     .unwrap();
             }
 
@@ -1113,6 +3190,285 @@ mod test {
         });
     }
 
+    #[derive(Default)]
+    struct AppendedRow {
+        id: i64,
+        flux: Option<f64>,
+        count: Option<i16>,
+        label: String,
+    }
+
+    impl FitsRow for AppendedRow {
+        fn from_table(tbl: &FitsHdu, fits_file: &mut FitsFile, idx: usize) -> Result<Self> {
+            Ok(AppendedRow {
+                id: tbl.read_cell_value(fits_file, "id", idx)?,
+                flux: tbl.read_cell_value(fits_file, "flux", idx)?,
+                count: tbl.read_cell_value(fits_file, "count", idx)?,
+                label: tbl.read_cell_value(fits_file, "label", idx)?,
+            })
+        }
+
+        fn write_row(&self, row: &mut RowBuffer) -> Result<()> {
+            row.set("id", self.id)?;
+            row.set("flux", self.flux)?;
+            row.set("count", self.count)?;
+            row.set("label", self.label.clone())
+        }
+    }
+
+    fn appended_table(f: &mut FitsFile, tnull: bool) -> FitsHdu {
+        let columns = &[
+            ColumnDescription::new("id")
+                .with_type(ColumnDataType::Long)
+                .create()
+                .unwrap(),
+            ColumnDescription::new("flux")
+                .with_type(ColumnDataType::Double)
+                .create()
+                .unwrap(),
+            ColumnDescription::new("count")
+                .with_type(ColumnDataType::Short)
+                .create()
+                .unwrap(),
+            ColumnDescription::new("label")
+                .with_type(ColumnDataType::String)
+                .that_repeats(10)
+                .create()
+                .unwrap(),
+            ColumnDescription::new("vector")
+                .with_type(ColumnDataType::Int)
+                .that_repeats(3)
+                .create()
+                .unwrap(),
+        ];
+        let hdu = f.create_table("APPENDED", columns).unwrap();
+        if tnull {
+            hdu.write_key(f, "TNULL3", -1).unwrap();
+        }
+        hdu.refresh(f).unwrap()
+    }
+
+    #[test]
+    fn test_table_appender() {
+        with_temp_file(|filename| {
+            let mut f = FitsFile::create(filename).open().unwrap();
+            let hdu = appended_table(&mut f, true);
+
+            let mut appender = hdu.table_appender(&mut f).unwrap().chunk_rows(7);
+            for i in 0..25 {
+                appender
+                    .append_row(&AppendedRow {
+                        id: i,
+                        flux: if i % 5 == 0 {
+                            None
+                        } else {
+                            Some(i as f64 / 2.0)
+                        },
+                        count: if i % 3 == 0 { None } else { Some(i as i16) },
+                        label: format!("row{}", i),
+                    })
+                    .unwrap();
+            }
+            assert_eq!(appender.num_rows(), 25);
+            let hdu = appender.finish().unwrap();
+            assert_eq!(hdu.num_rows(&mut f).unwrap(), 25);
+
+            for i in 0..25 {
+                let row: AppendedRow = hdu.row(&mut f, i).unwrap();
+                assert_eq!(row.id, i as i64);
+                assert_eq!(row.flux.is_none(), i % 5 == 0);
+                assert_eq!(row.count, if i % 3 == 0 { None } else { Some(i as i16) });
+                assert_eq!(row.label, format!("row{}", i));
+            }
+
+            // A second appender continues from the end of the table, and writes when dropped
+            {
+                let mut appender = hdu.table_appender(&mut f).unwrap();
+                appender.append_row(&AppendedRow::default()).unwrap();
+            }
+            assert_eq!(hdu.num_rows(&mut f).unwrap(), 26);
+        });
+    }
+
+    #[test]
+    fn test_table_cache() {
+        with_temp_file(|filename| {
+            let mut f = FitsFile::create(filename).open().unwrap();
+            let hdu = appended_table(&mut f, true);
+            let mut appender = hdu.table_appender(&mut f).unwrap();
+            for i in 0..25 {
+                appender
+                    .append_row(&AppendedRow {
+                        id: i,
+                        flux: if i % 5 == 0 { None } else { Some(i as f64) },
+                        count: Some(i as i16),
+                        label: format!("row{}", i),
+                    })
+                    .unwrap();
+            }
+            let hdu = appender.finish().unwrap();
+
+            let mut cache = hdu.table_cache(&mut f).unwrap().chunk_rows(4).max_chunks(2);
+            assert_eq!(cache.num_rows(), 25);
+            for &idx in &[13, 2, 24, 13, 0, 7, 24] {
+                let id: i64 = cache.read_cell_value("id", idx).unwrap();
+                assert_eq!(id, idx as i64);
+                let label: String = cache.read_cell_value("label", idx).unwrap();
+                assert_eq!(label, format!("row{}", idx));
+                let flux: Option<f64> = cache.read_cell_value("flux", idx).unwrap();
+                assert_eq!(flux.is_none(), idx % 5 == 0);
+                assert!(cache.chunks.len() <= 2);
+            }
+            // The same column read as another type is cached separately
+            let id: f64 = cache.read_cell_value("id", 13).unwrap();
+            assert_eq!(id, 13.0);
+
+            let row: (i64, Option<f64>, Option<i16>, String) = cache.row(22).unwrap();
+            assert_eq!(row, (22, Some(22.0), Some(22), "row22".to_string()));
+            assert!(cache.read_cell_value::<i64>("id", 25).is_err());
+            assert!(cache.read_cell_value::<i64>("missing", 0).is_err());
+
+            cache.clear();
+            assert!(cache.chunks.is_empty());
+        });
+    }
+
+    #[test]
+    fn test_rows_range() {
+        with_temp_file(|filename| {
+            let mut f = FitsFile::create(filename).open().unwrap();
+            let hdu = appended_table(&mut f, true);
+            let mut appender = hdu.table_appender(&mut f).unwrap();
+            for i in 0..10 {
+                appender
+                    .append_row(&AppendedRow {
+                        id: i,
+                        flux: Some(i as f64),
+                        count: if i % 2 == 0 { None } else { Some(i as i16) },
+                        label: format!("row{}", i),
+                    })
+                    .unwrap();
+            }
+            let hdu = appender.finish().unwrap();
+
+            // Manual implementations read one row at a time
+            let rows: Vec<AppendedRow> = hdu.rows_range(&mut f, 3..=5).unwrap();
+            assert_eq!(rows.len(), 3);
+            assert_eq!(rows[0].id, 3);
+            assert_eq!(rows[1].count, None);
+            assert_eq!(rows[2].label, "row5");
+
+            let rows: Vec<(i64,)> = hdu.rows_range(&mut f, 8..).unwrap();
+            assert_eq!(rows, vec![(8,), (9,)]);
+            let rows: Vec<AppendedRow> = hdu.rows_range(&mut f, 10..).unwrap();
+            assert!(rows.is_empty());
+
+            // Invalid ranges are rejected before reading, whichever way the rows are read
+            let (start, end) = (6, 3);
+            for range in vec![5..20, 10..11, start..end] {
+                match hdu.rows_range::<(i64,), _>(&mut f, &range) {
+                    Err(Error::Index(IndexError { given, .. })) => assert_eq!(given, range),
+                    _ => panic!("expected an index error for {:?}", range),
+                }
+                assert!(hdu.rows_range::<AppendedRow, _>(&mut f, &range).is_err());
+            }
+        });
+    }
+
+    #[test]
+    fn test_tuple_rows() {
+        let mut f = FitsFile::open("../testdata/full_example.fits").unwrap();
+        let hdu = f.hdu("TESTEXT").unwrap();
+        let (intcol, _, _, strcol): (i32, f32, f64, String) = hdu.row(&mut f, 4).unwrap();
+        assert_eq!(intcol, 16);
+        assert_eq!(strcol, "value4");
+        let (intcol,): (i64,) = hdu.row(&mut f, 4).unwrap();
+        assert_eq!(intcol, 16);
+
+        with_temp_file(|filename| {
+            let mut f = FitsFile::create(filename).open().unwrap();
+            let hdu = appended_table(&mut f, true);
+
+            let rows = vec![
+                (1i64, Some(1.5), None, "first".to_string()),
+                (2, None, Some(3i16), "second".to_string()),
+            ];
+            let mut appender = hdu.table_appender(&mut f).unwrap();
+            for row in &rows {
+                appender.append_row(row).unwrap();
+            }
+            let hdu = appender.finish().unwrap();
+
+            for (i, expected) in rows.iter().enumerate() {
+                let row: (i64, Option<f64>, Option<i16>, String) = hdu.row(&mut f, i).unwrap();
+                assert_eq!(&row, expected);
+            }
+            let read: Vec<(i64, Option<f64>, Option<i16>, String)> =
+                hdu.rows_range(&mut f, ..).unwrap();
+            assert_eq!(read, rows);
+        });
+    }
+
+    #[derive(Default)]
+    struct PartialRow {
+        id: i64,
+        set_flux: bool,
+        vector: bool,
+    }
+
+    impl FitsRow for PartialRow {
+        fn from_table(_: &FitsHdu, _: &mut FitsFile, _: usize) -> Result<Self> {
+            Ok(PartialRow::default())
+        }
+
+        fn write_row(&self, row: &mut RowBuffer) -> Result<()> {
+            row.set("id", self.id)?;
+            if self.set_flux {
+                row.set("flux", 1.0f64)?;
+            }
+            if self.vector {
+                row.set("vector", 1i32)?;
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_table_appender_errors() {
+        with_temp_file(|filename| {
+            let mut f = FitsFile::create(filename).open().unwrap();
+            let hdu = appended_table(&mut f, false);
+
+            let mut appender = hdu.table_appender(&mut f).unwrap();
+            let row = |set_flux, vector| PartialRow {
+                id: 1,
+                set_flux,
+                vector,
+            };
+            appender.append_row(&row(true, false)).unwrap();
+            // Every row must set the same columns, and failed rows are not appended
+            assert!(appender.append_row(&row(false, false)).is_err());
+            assert!(appender.append_row(&row(true, true)).is_err());
+            assert_eq!(appender.num_rows(), 1);
+            appender.append_row(&row(true, false)).unwrap();
+            let hdu = appender.finish().unwrap();
+            let ids: Vec<i64> = hdu.read_col(&mut f, "id").unwrap();
+            assert_eq!(ids, vec![1, 1]);
+
+            // Undefined integers need a TNULL value, and rows which failed are not counted
+            let mut appender = hdu.table_appender(&mut f).unwrap();
+            appender.append_row(&AppendedRow::default()).unwrap();
+            assert!(appender.flush().is_err());
+            assert_eq!(appender.num_rows(), 2);
+            appender.append_row(&AppendedRow::default()).unwrap();
+            assert!(appender.finish().is_err());
+        });
+
+        let mut f = FitsFile::open("../testdata/full_example.fits").unwrap();
+        let hdu = f.hdu("TESTEXT").unwrap();
+        assert!(hdu.table_appender(&mut f).is_err());
+    }
+
     #[test]
     fn test_inserting_columns() {
         duplicate_test_file(|filename| {
@@ -1220,4 +3576,135 @@ mod test {
         let result: String = tbl_hdu.read_cell_value(&mut f, "strcol", 4).unwrap();
         assert_eq!(result, "value4".to_string());
     }
+
+    #[test]
+    fn test_read_nullable_values() {
+        with_temp_file(|filename| {
+            {
+                let mut f = FitsFile::create(filename).open().unwrap();
+                let columns = &[
+                    ColumnDescription::new("ints")
+                        .with_type(ColumnDataType::Int)
+                        .create()
+                        .unwrap(),
+                    ColumnDescription::new("doubles")
+                        .with_type(ColumnDataType::Double)
+                        .create()
+                        .unwrap(),
+                    ColumnDescription::new("big")
+                        .with_type(ColumnDataType::UnsignedLong)
+                        .create()
+                        .unwrap(),
+                ];
+                let hdu = f.create_table("DATA", columns).unwrap();
+                hdu.write_col(&mut f, "big", &[u64::MAX - 1, 5, 1 << 63])
+                    .unwrap();
+                hdu.write_key(&mut f, "TNULL1", -99).unwrap();
+                hdu.write_col(&mut f, "ints", &[1, -99, 3]).unwrap();
+                hdu.write_col(&mut f, "doubles", &[1.5, 2.5, f64::NAN])
+                    .unwrap();
+            }
+
+            let mut f = FitsFile::open(filename).unwrap();
+            let hdu = f.hdu("DATA").unwrap();
+
+            let ints: Vec<Option<i32>> = hdu.read_col(&mut f, "ints").unwrap();
+            assert_eq!(ints, vec![Some(1), None, Some(3)]);
+
+            let doubles: Vec<Option<f64>> = hdu.read_col(&mut f, "doubles").unwrap();
+            assert_eq!(doubles, vec![Some(1.5), Some(2.5), None]);
+
+            let big: Vec<Option<u64>> = hdu.read_col(&mut f, "big").unwrap();
+            assert_eq!(big, vec![Some(u64::MAX - 1), Some(5), Some(1 << 63)]);
+
+            let image = f.primary_hdu().unwrap();
+            let value: Result<Option<i32>> = image.read_cell_value(&mut f, "ints", 0);
+            assert!(value.is_err());
+
+            let value: Option<i32> = hdu.read_cell_value(&mut f, "ints", 1).unwrap();
+            assert_eq!(value, None);
+            let value: Option<f64> = hdu.read_cell_value(&mut f, "doubles", 0).unwrap();
+            assert_eq!(value, Some(1.5));
+
+            /* Non-nullable reads are unchanged */
+            let ints: Vec<i32> = hdu.read_col(&mut f, "ints").unwrap();
+            assert_eq!(ints, vec![1, -99, 3]);
+        });
+    }
+
+    #[test]
+    fn test_iterate_columns() {
+        let mut f = FitsFile::open("../testdata/full_example.fits").unwrap();
+        let hdu = f.hdu("TESTEXT").unwrap();
+        let expected: Vec<i32> = hdu.read_col(&mut f, "intcol").unwrap();
+
+        let mut values = Vec::new();
+        let mut first_rows = Vec::new();
+        hdu.iterate_columns(&mut f, &[IteratorColumn::read("intcol")], 20, |chunk| {
+            assert_eq!(chunk.total_rows(), 50);
+            assert_eq!(chunk.num_columns(), 1);
+            first_rows.push(chunk.first_row());
+            values.extend_from_slice(chunk.column(0));
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(first_rows, vec![0, 20, 40]);
+        let expected: Vec<f64> = expected.into_iter().map(f64::from).collect();
+        assert_eq!(values, expected);
+    }
+
+    #[test]
+    fn test_iterate_columns_read_write() {
+        duplicate_test_file(|filename| {
+            let mut f = FitsFile::edit(filename).unwrap();
+            let hdu = f.hdu("TESTEXT").unwrap();
+            let before: Vec<f64> = hdu.read_col(&mut f, "doublecol").unwrap();
+
+            let columns = [
+                IteratorColumn::read("intcol"),
+                IteratorColumn::read_write("doublecol"),
+            ];
+            hdu.iterate_columns(&mut f, &columns, 0, |chunk| {
+                let ints = chunk.column(0).to_vec();
+                for (value, int) in chunk.column_mut(1).iter_mut().zip(ints) {
+                    *value += int;
+                }
+                Ok(())
+            })
+            .unwrap();
+
+            let ints: Vec<f64> = hdu.read_col(&mut f, "intcol").unwrap();
+            let after: Vec<f64> = hdu.read_col(&mut f, "doublecol").unwrap();
+            for i in 0..after.len() {
+                assert!(floats_close_f64(after[i], before[i] + ints[i]));
+            }
+        });
+    }
+
+    #[test]
+    fn test_iterate_columns_stops_on_error() {
+        let mut f = FitsFile::open("../testdata/full_example.fits").unwrap();
+        let hdu = f.hdu("TESTEXT").unwrap();
+
+        let mut calls = 0;
+        let result = hdu.iterate_columns(&mut f, &[IteratorColumn::read("intcol")], 10, |_| {
+            calls += 1;
+            Err("stop".into())
+        });
+        assert_eq!(calls, 1);
+        match result {
+            Err(Error::Message(msg)) => assert_eq!(msg, "stop"),
+            _ => panic!("expected error"),
+        }
+    }
+
+    #[test]
+    fn test_iterate_columns_requires_write_access() {
+        let mut f = FitsFile::open("../testdata/full_example.fits").unwrap();
+        let hdu = f.hdu("TESTEXT").unwrap();
+        let result =
+            hdu.iterate_columns(&mut f, &[IteratorColumn::write("intcol")], 10, |_| Ok(()));
+        assert!(result.is_err());
+    }
 }
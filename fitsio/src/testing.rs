@@ -0,0 +1,573 @@
+/*!
+Helpers for writing tests against FITS files
+
+This module is only available with the `testing` feature, which is intended to be enabled in
+`[dev-dependencies]`:
+
+```toml
+[dev-dependencies]
+fitsio = { version = "*", features = ["testing"] }
+```
+
+It provides:
+
+* [`with_temp_file`](fn.with_temp_file.html) and [`with_temp_copy`](fn.with_temp_copy.html),
+  which give a test a file path which is cleaned up afterwards,
+* [`Fixture`](struct.Fixture.html), which builds small files of images and tables in code rather
+  than shipping binary fixtures,
+* [`generate`](generate/index.html), which creates reproducible synthetic images and tables for
+  fixtures, and
+* [`assert_images_close!`](../macro.assert_images_close.html), which compares the pixels of two
+  images within a tolerance, e.g. against a "golden" file.
+
+# Example
+
+```rust
+# fn main() -> Result<(), Box<dyn std::error::Error>> {
+use fitsio::assert_images_close;
+use fitsio::testing::{with_temp_file, Fixture};
+use fitsio::FitsFile;
+
+with_temp_file(|expected| {
+    Fixture::new()
+        .image(&[2, 3], vec![1.0f64, 2.0, 3.0, 4.0, 5.0, 6.0])
+        .table("CATALOG")
+        .column("id", vec![1i32, 2, 3])
+        .column("name", vec!["a", "b", "c"])
+        .create(expected)
+        .unwrap();
+
+    with_temp_file(|actual| {
+        Fixture::new()
+            .image(&[2, 3], vec![1.0f64, 2.0, 3.0, 4.0, 5.0, 6.0001])
+            .create(actual)
+            .unwrap();
+
+        let mut expected = FitsFile::open(expected).unwrap();
+        let mut actual = FitsFile::open(actual).unwrap();
+        assert_images_close!(&mut expected, &mut actual, 1e-3);
+    });
+});
+# Ok(())
+# }
+```
+*/
+use crate::errors::{Error, Result};
+use crate::fitsfile::FitsFile;
+use crate::hdu::{DescribesHdu, HduInfo};
+use crate::images::{ImageDescription, ImageType};
+use crate::tables::{ColumnDataType, ColumnDescription};
+use std::path::Path;
+use std::{f32, f64};
+use tempfile::Builder;
+
+pub mod generate;
+
+/**
+Call `callback` with the path of a file in a new temporary directory
+
+The file does not exist, so can be created by the callback. The directory is removed once the
+callback returns.
+*/
+pub fn with_temp_file<F>(callback: F)
+where
+    F: for<'a> Fn(&'a str),
+{
+    let tdir = Builder::new().prefix("fitsio-").tempdir().unwrap();
+    let tdir_path = tdir.path();
+    let filename = tdir_path.join("test.fits");
+
+    let filename_str = filename.to_str().expect("cannot create string filename");
+    callback(filename_str);
+}
+
+/**
+Call `callback` with the path of a temporary copy of `source`
+
+This allows a test to modify an existing file, without changing the original. The copy is
+removed once the callback returns.
+*/
+pub fn with_temp_copy<P, F>(source: P, callback: F)
+where
+    P: AsRef<Path>,
+    F: for<'a> Fn(&'a str),
+{
+    use std::fs;
+    with_temp_file(|filename| {
+        fs::copy(source.as_ref(), filename).expect("Could not copy test file");
+        callback(filename);
+    });
+}
+
+/// Function to create a temporary file and copy the example file
+#[cfg(test)]
+pub(crate) fn duplicate_test_file<F>(callback: F)
+where
+    F: for<'a> Fn(&'a str),
+{
+    with_temp_copy("../testdata/full_example.fits", callback);
+}
+
+/// Are two `f32` values within machine precision of each other?
+pub fn floats_close_f32(a: f32, b: f32) -> bool {
+    (a - b).abs() < f32::EPSILON
+}
+
+/// Are two `f64` values within machine precision of each other?
+pub fn floats_close_f64(a: f64, b: f64) -> bool {
+    (a - b).abs() < f64::EPSILON
+}
+
+/**
+Compare the pixels of an image in two files
+
+The HDU given by `hdu` is read from both files as `f64` values. Pixels match if they differ by no
+more than `tolerance`, or are both NaN. A description of the differences is returned if the HDUs
+are not images, the shapes differ or any pixels do not match.
+
+This is the check made by [`assert_images_close!`](../macro.assert_images_close.html).
+*/
+pub fn check_images_close<T: DescribesHdu + Clone>(
+    left: &mut FitsFile,
+    right: &mut FitsFile,
+    hdu: T,
+    tolerance: f64,
+) -> std::result::Result<(), String> {
+    let read = |fits_file: &mut FitsFile| -> Result<(Vec<usize>, Vec<f64>)> {
+        let hdu = fits_file.hdu(hdu.clone())?;
+        match hdu.info {
+            HduInfo::ImageInfo { ref shape, .. } => {
+                let pixels = if shape.is_empty() {
+                    Vec::new()
+                } else {
+                    hdu.read_image(fits_file)?
+                };
+                Ok((shape.clone(), pixels))
+            }
+            _ => Err("hdu is not an image".into()),
+        }
+    };
+    let (left_shape, left_pixels) = read(left).map_err(|e| format!("left image: {}", e))?;
+    let (right_shape, right_pixels) = read(right).map_err(|e| format!("right image: {}", e))?;
+
+    if left_shape != right_shape {
+        return Err(format!(
+            "image shapes differ: {:?} != {:?}",
+            left_shape, right_shape
+        ));
+    }
+
+    let mut count = 0;
+    let mut first = None;
+    let mut max_deviation = 0.0f64;
+    for (i, (a, b)) in left_pixels.iter().zip(&right_pixels).enumerate() {
+        let matches = if a.is_nan() || b.is_nan() {
+            a.is_nan() && b.is_nan()
+        } else {
+            (a - b).abs() <= tolerance
+        };
+        if !matches {
+            count += 1;
+            first.get_or_insert((i, *a, *b));
+            max_deviation = max_deviation.max((a - b).abs());
+        }
+    }
+
+    match first {
+        None => Ok(()),
+        Some((i, a, b)) => Err(format!(
+            "{} of {} pixels differ by more than {}, maximum deviation {}, first at index {} ({} != {})",
+            count,
+            left_pixels.len(),
+            tolerance,
+            max_deviation,
+            i,
+            a,
+            b
+        )),
+    }
+}
+
+/**
+Assert that the images of two files match within a tolerance
+
+The arguments are two `&mut FitsFile`s, optionally the HDU to compare (by default the primary
+HDU), and the largest allowed absolute difference between two pixels. On failure the number of
+differing pixels and the largest difference are reported.
+
+See [`check_images_close`](testing/fn.check_images_close.html) for how pixels are compared.
+
+# Example
+
+```rust
+# fn main() -> Result<(), Box<dyn std::error::Error>> {
+use fitsio::assert_images_close;
+
+let mut golden = fitsio::FitsFile::open("../testdata/full_example.fits")?;
+let mut output = fitsio::FitsFile::open("../testdata/full_example.fits")?;
+assert_images_close!(&mut golden, &mut output, 0, 0.5);
+# Ok(())
+# }
+```
+*/
+#[macro_export]
+macro_rules! assert_images_close {
+    ($left:expr, $right:expr, $tolerance:expr $(,)?) => {
+        $crate::assert_images_close!($left, $right, 0, $tolerance)
+    };
+    ($left:expr, $right:expr, $hdu:expr, $tolerance:expr $(,)?) => {
+        if let Err(message) =
+            $crate::testing::check_images_close($left, $right, $hdu, $tolerance as f64)
+        {
+            panic!("assertion failed: images are not close: {}", message);
+        }
+    };
+}
+
+/// Data of an image or table column in a [`Fixture`](struct.Fixture.html)
+#[derive(Debug, Clone, PartialEq)]
+pub enum FixtureData {
+    /// 32 bit integers, written as `LONG_IMG` images or `J` columns
+    Int(Vec<i32>),
+    /// 64 bit integers, written as `LONGLONG_IMG` images or `K` columns
+    Long(Vec<i64>),
+    /// Single precision floats, written as `FLOAT_IMG` images or `E` columns
+    Float(Vec<f32>),
+    /// Double precision floats, written as `DOUBLE_IMG` images or `D` columns
+    Double(Vec<f64>),
+    /// Strings, written as `A` columns as wide as the longest string
+    String(Vec<String>),
+}
+
+impl FixtureData {
+    fn len(&self) -> usize {
+        match self {
+            FixtureData::Int(data) => data.len(),
+            FixtureData::Long(data) => data.len(),
+            FixtureData::Float(data) => data.len(),
+            FixtureData::Double(data) => data.len(),
+            FixtureData::String(data) => data.len(),
+        }
+    }
+}
+
+macro_rules! fixture_data_from_impl {
+    ($t:ty, $variant:ident) => {
+        impl From<Vec<$t>> for FixtureData {
+            fn from(data: Vec<$t>) -> Self {
+                FixtureData::$variant(data)
+            }
+        }
+    };
+}
+
+fixture_data_from_impl!(i32, Int);
+fixture_data_from_impl!(i64, Long);
+fixture_data_from_impl!(f32, Float);
+fixture_data_from_impl!(f64, Double);
+fixture_data_from_impl!(String, String);
+
+impl<'a> From<Vec<&'a str>> for FixtureData {
+    fn from(data: Vec<&'a str>) -> Self {
+        FixtureData::String(data.into_iter().map(String::from).collect())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum FixtureHdu {
+    Image {
+        name: Option<String>,
+        shape: Vec<usize>,
+        data: FixtureData,
+    },
+    Table {
+        name: String,
+        columns: Vec<(String, FixtureData)>,
+    },
+}
+
+/**
+Builder for small files of images and tables
+
+HDUs are written in the order they are added. The first image added without a name is written
+to the primary HDU, otherwise the primary HDU is left empty.
+
+# Example
+
+```rust
+use fitsio::testing::{with_temp_file, Fixture};
+
+with_temp_file(|filename| {
+    let mut f = Fixture::new()
+        .named_image("SCI", &[2, 2], vec![1i32, 2, 3, 4])
+        .table("EVENTS")
+        .column("time", vec![0.5f64, 1.5])
+        .column("energy", vec![10.0f32, 20.0])
+        .create(filename)
+        .unwrap();
+
+    let hdu = f.hdu("EVENTS").unwrap();
+    let energy: Vec<f32> = hdu.read_col(&mut f, "energy").unwrap();
+    assert_eq!(energy, vec![10.0, 20.0]);
+});
+```
+*/
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Fixture {
+    hdus: Vec<FixtureHdu>,
+}
+
+impl Fixture {
+    /// Create an empty fixture
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /**
+    Add an image, with the shape in row-major order
+
+    The first image added without a name is written to the primary HDU.
+    */
+    pub fn image<D: Into<FixtureData>>(mut self, shape: &[usize], data: D) -> Self {
+        self.hdus.push(FixtureHdu::Image {
+            name: None,
+            shape: shape.to_vec(),
+            data: data.into(),
+        });
+        self
+    }
+
+    /// Add an image extension with the name `name`
+    pub fn named_image<D: Into<FixtureData>>(
+        mut self,
+        name: &str,
+        shape: &[usize],
+        data: D,
+    ) -> Self {
+        self.hdus.push(FixtureHdu::Image {
+            name: Some(name.to_string()),
+            shape: shape.to_vec(),
+            data: data.into(),
+        });
+        self
+    }
+
+    /// Add a binary table named `name`, with columns added by [`column`](#method.column)
+    pub fn table(mut self, name: &str) -> Self {
+        self.hdus.push(FixtureHdu::Table {
+            name: name.to_string(),
+            columns: Vec::new(),
+        });
+        self
+    }
+
+    /**
+    Add a column to the most recently added table
+
+    # Panics
+
+    If no table has been added yet, or another HDU was added after the last table
+    */
+    pub fn column<D: Into<FixtureData>>(mut self, name: &str, data: D) -> Self {
+        match self.hdus.last_mut() {
+            Some(FixtureHdu::Table { columns, .. }) => {
+                columns.push((name.to_string(), data.into()));
+            }
+            _ => panic!("columns can only be added directly after a table"),
+        }
+        self
+    }
+
+    /**
+    Write the fixture to a new file at `path`, returning the open file
+
+    An error is returned if the file already exists, the data of an image does not match its
+    shape, or the columns of a table have different lengths.
+    */
+    pub fn create<P: AsRef<Path>>(&self, path: P) -> Result<FitsFile> {
+        let primary = self
+            .hdus
+            .iter()
+            .position(|hdu| matches!(hdu, FixtureHdu::Image { name: None, .. }));
+
+        let mut fits_file = match primary.map(|i| &self.hdus[i]) {
+            Some(FixtureHdu::Image { shape, data, .. }) => {
+                let description = image_description(shape, data)?;
+                FitsFile::create(path)
+                    .with_custom_primary(&description)
+                    .open()?
+            }
+            _ => FitsFile::create(path).open()?,
+        };
+
+        for (i, hdu) in self.hdus.iter().enumerate() {
+            match hdu {
+                FixtureHdu::Image { name, shape, data } => {
+                    let hdu = if Some(i) == primary {
+                        fits_file.primary_hdu()?
+                    } else {
+                        let description = image_description(shape, data)?;
+                        let name = name.clone().unwrap_or_else(|| format!("IMAGE{}", i));
+                        fits_file.create_image(name, &description)?
+                    };
+                    match data {
+                        FixtureData::Int(data) => hdu.write_image(&mut fits_file, data)?,
+                        FixtureData::Long(data) => hdu.write_image(&mut fits_file, data)?,
+                        FixtureData::Float(data) => hdu.write_image(&mut fits_file, data)?,
+                        FixtureData::Double(data) => hdu.write_image(&mut fits_file, data)?,
+                        FixtureData::String(_) => unreachable!(),
+                    }
+                }
+                FixtureHdu::Table { name, columns } => {
+                    write_table(&mut fits_file, name, columns)?;
+                }
+            }
+        }
+        Ok(fits_file)
+    }
+}
+
+fn image_description<'a>(shape: &'a [usize], data: &FixtureData) -> Result<ImageDescription<'a>> {
+    let data_type = match data {
+        FixtureData::Int(_) => ImageType::Long,
+        FixtureData::Long(_) => ImageType::LongLong,
+        FixtureData::Float(_) => ImageType::Float,
+        FixtureData::Double(_) => ImageType::Double,
+        FixtureData::String(_) => return Err("images cannot contain strings".into()),
+    };
+    let num_pixels: usize = shape.iter().product();
+    if num_pixels != data.len() {
+        return Err(Error::Message(format!(
+            "image of shape {:?} has {} pixels, but {} were given",
+            shape,
+            num_pixels,
+            data.len()
+        )));
+    }
+    Ok(ImageDescription {
+        data_type,
+        dimensions: shape,
+    })
+}
+
+fn write_table(
+    fits_file: &mut FitsFile,
+    name: &str,
+    columns: &[(String, FixtureData)],
+) -> Result<()> {
+    let num_rows = columns.first().map(|(_, data)| data.len()).unwrap_or(0);
+    let mut descriptions = Vec::with_capacity(columns.len());
+    for (column, data) in columns {
+        if data.len() != num_rows {
+            return Err(Error::Message(format!(
+                "column {:?} has {} rows, but {} were expected",
+                column,
+                data.len(),
+                num_rows
+            )));
+        }
+        let mut description = ColumnDescription::new(column.as_str());
+        match data {
+            FixtureData::Int(_) => description.with_type(ColumnDataType::Int),
+            FixtureData::Long(_) => description.with_type(ColumnDataType::Long),
+            FixtureData::Float(_) => description.with_type(ColumnDataType::Float),
+            FixtureData::Double(_) => description.with_type(ColumnDataType::Double),
+            FixtureData::String(strings) => description
+                .with_type(ColumnDataType::String)
+                .that_repeats(strings.iter().map(String::len).max().unwrap_or(0).max(1)),
+        };
+        descriptions.push(description.create()?);
+    }
+
+    let hdu = fits_file.create_table(name, &descriptions)?;
+    for (column, data) in columns {
+        match data {
+            FixtureData::Int(data) => hdu.write_col(fits_file, column.as_str(), data)?,
+            FixtureData::Long(data) => hdu.write_col(fits_file, column.as_str(), data)?,
+            FixtureData::Float(data) => hdu.write_col(fits_file, column.as_str(), data)?,
+            FixtureData::Double(data) => hdu.write_col(fits_file, column.as_str(), data)?,
+            FixtureData::String(data) => hdu.write_col(fits_file, column.as_str(), data)?,
+        };
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixture() {
+        with_temp_file(|filename| {
+            let mut f = Fixture::new()
+                .table("FIRST")
+                .column("id", vec![1i64, 2, 3])
+                .column("name", vec!["a", "bb", "ccc"])
+                .image(&[2, 2], vec![1.5f32, 2.5, 3.5, 4.5])
+                .named_image("SECOND", &[3], vec![1i32, 2, 3])
+                .create(filename)
+                .unwrap();
+
+            assert_eq!(f.num_hdus().unwrap(), 3);
+            let primary = f.primary_hdu().unwrap();
+            let pixels: Vec<f32> = primary.read_image(&mut f).unwrap();
+            assert_eq!(pixels, vec![1.5, 2.5, 3.5, 4.5]);
+
+            let table = f.hdu("FIRST").unwrap();
+            let ids: Vec<i64> = table.read_col(&mut f, "id").unwrap();
+            assert_eq!(ids, vec![1, 2, 3]);
+            let names: Vec<String> = table.read_col(&mut f, "name").unwrap();
+            assert_eq!(names, vec!["a", "bb", "ccc"]);
+
+            let image = f.hdu("SECOND").unwrap();
+            let pixels: Vec<i32> = image.read_image(&mut f).unwrap();
+            assert_eq!(pixels, vec![1, 2, 3]);
+        });
+    }
+
+    #[test]
+    fn test_invalid_fixtures() {
+        with_temp_file(|filename| {
+            let result = Fixture::new().image(&[2, 2], vec![1i32]).create(filename);
+            assert!(result.is_err());
+        });
+        with_temp_file(|filename| {
+            let result = Fixture::new()
+                .table("TABLE")
+                .column("a", vec![1i32, 2])
+                .column("b", vec![1i32])
+                .create(filename);
+            assert!(result.is_err());
+        });
+    }
+
+    #[test]
+    fn test_check_images_close() {
+        with_temp_file(|filename| {
+            Fixture::new()
+                .image(&[2, 2], vec![1.0f64, 2.0, 3.0, f64::NAN])
+                .create(filename)
+                .unwrap();
+            with_temp_file(|other| {
+                let mut modified = FitsFile::open(filename).unwrap();
+                let mut original = Fixture::new()
+                    .image(&[2, 2], vec![1.0f64, 2.1, 3.0, f64::NAN])
+                    .create(other)
+                    .unwrap();
+
+                assert_images_close!(&mut original, &mut modified, 0.2);
+                let message =
+                    check_images_close(&mut original, &mut modified, 0, 0.01).unwrap_err();
+                assert!(message.starts_with("1 of 4 pixels differ"), "{}", message);
+            });
+
+            let mut modified = FitsFile::open(filename).unwrap();
+            let mut other = FitsFile::open("../testdata/full_example.fits").unwrap();
+            let message = check_images_close(&mut modified, &mut other, 0, 0.0).unwrap_err();
+            assert!(message.starts_with("image shapes differ"), "{}", message);
+            let mut same = FitsFile::open("../testdata/full_example.fits").unwrap();
+            let message = check_images_close(&mut other, &mut same, 1, 0.0).unwrap_err();
+            assert!(message.contains("hdu is not an image"), "{}", message);
+        });
+    }
+}
@@ -0,0 +1,370 @@
+/*!
+Reproducible synthetic images and tables
+
+The generators build data in memory from a seed, so examples, property tests and benchmarks can
+create realistic inputs of any size without shipping binary fixtures. The same seed always
+produces the same data.
+
+# Example
+
+```rust
+use fitsio::testing::generate::{ColumnValues, ImageGenerator, TableGenerator};
+use fitsio::testing::{with_temp_file, Fixture};
+
+with_temp_file(|filename| {
+    let image = ImageGenerator::new(&[64, 64])
+        .background(100.0)
+        .gaussian(&[32.0, 20.0], 2.5, 1000.0)
+        .noise(5.0, 42);
+
+    let catalog = TableGenerator::new(1000, 42)
+        .column("id", ColumnValues::Index)
+        .column("flux", ColumnValues::Normal { mean: 10.0, sigma: 2.0 })
+        .column("name", ColumnValues::Label("star".to_string()));
+
+    let fixture = image.add_to(Fixture::new());
+    let mut f = catalog.add_to(fixture, "CATALOG").create(filename).unwrap();
+
+    let hdu = f.hdu("CATALOG").unwrap();
+    assert_eq!(hdu.num_rows(&mut f).unwrap(), 1000);
+});
+```
+*/
+use super::{Fixture, FixtureData};
+
+/// Small, fast and portable random number generator (SplitMix64)
+#[derive(Debug, Clone)]
+struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        Rng { state: seed }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform value in `[0, 1)`
+    fn uniform(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Value from the standard normal distribution, using the Box-Muller transform
+    fn normal(&mut self) -> f64 {
+        let u1 = 1.0 - self.uniform();
+        let u2 = self.uniform();
+        (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct GaussianSource {
+    centre: Vec<f64>,
+    sigma: f64,
+    amplitude: f64,
+}
+
+/**
+Builder for synthetic images
+
+Pixel values are the sum of a constant background, a linear gradient, any number of Gaussian
+sources and Gaussian noise. Shapes and coordinates are in row-major order, the same as
+[`ImageDescription`](../../images/struct.ImageDescription.html), so for a two dimensional image
+the coordinates are `[y, x]`.
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImageGenerator {
+    shape: Vec<usize>,
+    background: f64,
+    gradient: Vec<f64>,
+    sources: Vec<GaussianSource>,
+    noise: Option<(f64, u64)>,
+}
+
+impl ImageGenerator {
+    /// Create a generator for an image of zeros with the given shape
+    pub fn new(shape: &[usize]) -> Self {
+        ImageGenerator {
+            shape: shape.to_vec(),
+            background: 0.0,
+            gradient: vec![0.0; shape.len()],
+            sources: Vec::new(),
+            noise: None,
+        }
+    }
+
+    /// Add a constant value to every pixel
+    pub fn background(mut self, value: f64) -> Self {
+        self.background = value;
+        self
+    }
+
+    /**
+    Add a linear gradient, increasing by `slopes[i]` per pixel along axis `i`
+
+    # Panics
+
+    If the number of slopes does not match the number of axes
+    */
+    pub fn gradient(mut self, slopes: &[f64]) -> Self {
+        assert_eq!(
+            slopes.len(),
+            self.shape.len(),
+            "one slope is required per image axis"
+        );
+        self.gradient = slopes.to_vec();
+        self
+    }
+
+    /**
+    Add a circular Gaussian source with peak value `amplitude`
+
+    # Panics
+
+    If the number of coordinates of `centre` does not match the number of axes
+    */
+    pub fn gaussian(mut self, centre: &[f64], sigma: f64, amplitude: f64) -> Self {
+        assert_eq!(
+            centre.len(),
+            self.shape.len(),
+            "one centre coordinate is required per image axis"
+        );
+        self.sources.push(GaussianSource {
+            centre: centre.to_vec(),
+            sigma,
+            amplitude,
+        });
+        self
+    }
+
+    /// Add Gaussian noise with standard deviation `sigma`, drawn reproducibly from `seed`
+    pub fn noise(mut self, sigma: f64, seed: u64) -> Self {
+        self.noise = Some((sigma, seed));
+        self
+    }
+
+    /// The shape of the image
+    pub fn shape(&self) -> &[usize] {
+        &self.shape
+    }
+
+    /// Generate the pixel values, in row-major order
+    pub fn pixels(&self) -> Vec<f64> {
+        let num_pixels = if self.shape.is_empty() {
+            0
+        } else {
+            self.shape.iter().product()
+        };
+        let mut rng = self.noise.map(|(_, seed)| Rng::new(seed));
+        let mut coords = vec![0usize; self.shape.len()];
+
+        let mut pixels = Vec::with_capacity(num_pixels);
+        for _ in 0..num_pixels {
+            let mut value = self.background;
+            for (coord, slope) in coords.iter().zip(&self.gradient) {
+                value += *coord as f64 * slope;
+            }
+            for source in &self.sources {
+                let r2: f64 = coords
+                    .iter()
+                    .zip(&source.centre)
+                    .map(|(coord, centre)| (*coord as f64 - centre).powi(2))
+                    .sum();
+                value += source.amplitude * (-r2 / (2.0 * source.sigma * source.sigma)).exp();
+            }
+            if let (Some(rng), Some((sigma, _))) = (rng.as_mut(), self.noise) {
+                value += sigma * rng.normal();
+            }
+            pixels.push(value);
+
+            // Advance the coordinates, with the last axis varying fastest
+            for axis in (0..coords.len()).rev() {
+                coords[axis] += 1;
+                if coords[axis] < self.shape[axis] {
+                    break;
+                }
+                coords[axis] = 0;
+            }
+        }
+        pixels
+    }
+
+    /// Add the generated image to `fixture`, see [`Fixture::image`](../struct.Fixture.html#method.image)
+    pub fn add_to(&self, fixture: Fixture) -> Fixture {
+        fixture.image(&self.shape, self.pixels())
+    }
+}
+
+/// How the values of a generated column are chosen
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColumnValues {
+    /// The zero-indexed row number, as 64 bit integers
+    Index,
+    /// 32 bit integers drawn uniformly from `min..max`
+    UniformInt {
+        /// Smallest value, inclusive
+        min: i32,
+        /// Largest value, exclusive
+        max: i32,
+    },
+    /// Doubles drawn uniformly from `min..max`
+    Uniform {
+        /// Smallest value, inclusive
+        min: f64,
+        /// Largest value, exclusive
+        max: f64,
+    },
+    /// Doubles drawn from a normal distribution
+    Normal {
+        /// Mean of the distribution
+        mean: f64,
+        /// Standard deviation of the distribution
+        sigma: f64,
+    },
+    /// Strings of the given prefix followed by the row number, e.g. `star0`, `star1`, ...
+    Label(String),
+}
+
+/**
+Builder for synthetic tables
+
+Each column is drawn from its own random sequence, derived from the seed and the position of the
+column, so adding a column does not change the values of the others.
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableGenerator {
+    num_rows: usize,
+    seed: u64,
+    columns: Vec<(String, ColumnValues)>,
+}
+
+impl TableGenerator {
+    /// Create a generator for a table of `num_rows` rows, drawing values from `seed`
+    pub fn new(num_rows: usize, seed: u64) -> Self {
+        TableGenerator {
+            num_rows,
+            seed,
+            columns: Vec::new(),
+        }
+    }
+
+    /// Add a column named `name`
+    pub fn column(mut self, name: &str, values: ColumnValues) -> Self {
+        self.columns.push((name.to_string(), values));
+        self
+    }
+
+    /// Generate the data of each column
+    pub fn columns(&self) -> Vec<(String, FixtureData)> {
+        self.columns
+            .iter()
+            .enumerate()
+            .map(|(i, (name, values))| {
+                let mut rng = Rng::new(self.seed.wrapping_add(i as u64));
+                let rows = 0..self.num_rows;
+                let data = match values {
+                    ColumnValues::Index => FixtureData::Long(rows.map(|row| row as i64).collect()),
+                    ColumnValues::UniformInt { min, max } => {
+                        let span = (i64::from(*max) - i64::from(*min)).max(1) as u64;
+                        FixtureData::Int(
+                            rows.map(|_| (i64::from(*min) + (rng.next_u64() % span) as i64) as i32)
+                                .collect(),
+                        )
+                    }
+                    ColumnValues::Uniform { min, max } => FixtureData::Double(
+                        rows.map(|_| min + (max - min) * rng.uniform()).collect(),
+                    ),
+                    ColumnValues::Normal { mean, sigma } => {
+                        FixtureData::Double(rows.map(|_| mean + sigma * rng.normal()).collect())
+                    }
+                    ColumnValues::Label(prefix) => {
+                        FixtureData::String(rows.map(|row| format!("{}{}", prefix, row)).collect())
+                    }
+                };
+                (name.clone(), data)
+            })
+            .collect()
+    }
+
+    /// Add the generated table to `fixture` as a binary table named `name`
+    pub fn add_to(&self, fixture: Fixture, name: &str) -> Fixture {
+        self.columns()
+            .into_iter()
+            .fold(fixture.table(name), |fixture, (column, data)| {
+                fixture.column(&column, data)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::with_temp_file;
+
+    #[test]
+    fn test_generated_image() {
+        let pixels = ImageGenerator::new(&[3, 4])
+            .background(1.0)
+            .gradient(&[10.0, 1.0])
+            .pixels();
+        assert_eq!(pixels.len(), 12);
+        assert_eq!(pixels[0], 1.0);
+        assert_eq!(pixels[1], 2.0);
+        assert_eq!(pixels[4], 11.0);
+        assert_eq!(pixels[11], 24.0);
+
+        let source = ImageGenerator::new(&[11, 11]).gaussian(&[5.0, 5.0], 1.5, 100.0);
+        let pixels = source.pixels();
+        assert_eq!(pixels[5 * 11 + 5], 100.0);
+        assert!(pixels.iter().all(|&p| p > 0.0 && p <= 100.0));
+
+        // Noise is reproducible from the seed
+        let noisy = source.clone().noise(1.0, 7).pixels();
+        assert_eq!(noisy, source.clone().noise(1.0, 7).pixels());
+        assert_ne!(noisy, source.noise(1.0, 8).pixels());
+    }
+
+    #[test]
+    fn test_noise_statistics() {
+        let pixels = ImageGenerator::new(&[200, 200]).noise(2.0, 1).pixels();
+        let n = pixels.len() as f64;
+        let mean = pixels.iter().sum::<f64>() / n;
+        let std = (pixels.iter().map(|p| (p - mean).powi(2)).sum::<f64>() / n).sqrt();
+        assert!(mean.abs() < 0.05, "mean {}", mean);
+        assert!((std - 2.0).abs() < 0.05, "std {}", std);
+    }
+
+    #[test]
+    fn test_generated_table() {
+        let generator = TableGenerator::new(100, 3)
+            .column("id", ColumnValues::Index)
+            .column("bin", ColumnValues::UniformInt { min: -2, max: 3 })
+            .column("x", ColumnValues::Uniform { min: 5.0, max: 6.0 })
+            .column("name", ColumnValues::Label("src".to_string()));
+        assert_eq!(generator.columns(), generator.columns());
+
+        with_temp_file(|filename| {
+            let mut f = generator
+                .add_to(Fixture::new(), "GENERATED")
+                .create(filename)
+                .unwrap();
+            let hdu = f.hdu("GENERATED").unwrap();
+
+            let ids: Vec<i64> = hdu.read_col(&mut f, "id").unwrap();
+            assert_eq!(ids, (0..100).collect::<Vec<_>>());
+            let bins: Vec<i32> = hdu.read_col(&mut f, "bin").unwrap();
+            assert!(bins.iter().all(|b| (-2..3).contains(b)));
+            let xs: Vec<f64> = hdu.read_col(&mut f, "x").unwrap();
+            assert!(xs.iter().all(|x| (5.0..6.0).contains(x)));
+            let names: Vec<String> = hdu.read_col(&mut f, "name").unwrap();
+            assert_eq!(names[42], "src42");
+        });
+    }
+}
@@ -2,7 +2,26 @@
 
 use crate::errors::Result;
 use crate::fitsfile::FitsFile;
-use std::sync::{Arc, Mutex, MutexGuard};
+use crate::memfile::MemoryBuffer;
+use std::ops::{Deref, DerefMut};
+use std::path::Path;
+use std::ptr;
+use std::sync::{Arc, Mutex, MutexGuard, Once, PoisonError};
+
+/// Serialises all access to a `cfitsio` library that was not built to be reentrant
+///
+/// `Mutex::new` cannot be used in a `static` before Rust 1.63, so the lock is created on first use.
+fn cfitsio_lock() -> &'static Mutex<()> {
+    static INIT: Once = Once::new();
+    static mut LOCK: *const Mutex<()> = ptr::null();
+
+    // Safety: `LOCK` is only written once, inside `call_once`, which also synchronises with every
+    // reader. The mutex is leaked, so the reference is valid for the rest of the program.
+    unsafe {
+        INIT.call_once(|| LOCK = Box::into_raw(Box::new(Mutex::new(()))));
+        &*LOCK
+    }
+}
 
 /** Thread-safe [`FitsFile`][fits-file] representation.
 
@@ -11,12 +30,20 @@ This struct wraps an `Arc<Mutex<FitsFile>>` and implements `Send`.
 To get a [`ThreadsafeFitsfile`][threadsafe-fitsfile] from a [`FitsFile`][fits-file], call the
 [`threadsafe`][fits-file-threadsafe] method.
 
+If the linked `cfitsio` library was built with `--enable-reentrant`, locking only prevents
+concurrent access to the same file. Otherwise `cfitsio` cannot be used from more than one
+thread at a time, so locking any `ThreadsafeFitsFile` blocks until every other lock has been
+released.
+
 [fits-file]: ../fitsfile/struct.FitsFile.html
 [threadsafe-fitsfile]: struct.ThreadsafeFitsFile.html
 [fits-file-threadsafe]: ../fitsfile/struct.FitsFile.html#method.threadsafe
 */
 #[derive(Clone)]
-pub struct ThreadsafeFitsFile(Arc<Mutex<FitsFile>>);
+pub struct ThreadsafeFitsFile {
+    file: Arc<Mutex<FitsFile>>,
+    reentrant: bool,
+}
 
 // Ensure that the new struct is safe to send to other threads. Note: we cannot let the user wrap
 // the type with a, Arc<Mutex<...>> as Rust will not let them.
@@ -35,7 +62,86 @@ impl FitsFile {
      */
     pub fn threadsafe(self) -> ThreadsafeFitsFile {
         #[allow(clippy::arc_with_non_send_sync)]
-        ThreadsafeFitsFile(Arc::new(Mutex::new(self)))
+        ThreadsafeFitsFile {
+            file: Arc::new(Mutex::new(self)),
+            reentrant: crate::sys::capabilities().reentrant,
+        }
+    }
+
+    /**
+    Wrap the file in a [`SendFitsFile`][send-fits-file], which can be moved to another thread
+
+    This fails if the linked `cfitsio` was not built with `--enable-reentrant`, in which case
+    use [`threadsafe`](#method.threadsafe) instead.
+
+    # Example
+
+    ```rust
+    # fn main() -> Result<(), Box<dyn std::error::Error>> {
+    use fitsio::FitsFile;
+    use std::thread;
+
+    let f = FitsFile::open("../testdata/full_example.fits")?;
+    if fitsio::sys_info().capabilities.reentrant {
+        let mut f = f.into_send()?;
+        let num_hdus = thread::spawn(move || f.num_hdus()).join().unwrap()?;
+        assert_eq!(num_hdus, 2);
+    } else {
+        assert!(f.into_send().is_err());
+    }
+    # Ok(())
+    # }
+    ```
+
+    [send-fits-file]: ../threadsafe_fitsfile/struct.SendFitsFile.html
+    */
+    pub fn into_send(self) -> Result<SendFitsFile> {
+        if !crate::sys::capabilities().reentrant {
+            return Err(
+                "cfitsio was not built to be reentrant, so the file cannot be sent to another thread; use `threadsafe` instead"
+                    .into(),
+            );
+        }
+        Ok(SendFitsFile(self))
+    }
+}
+
+/**
+A [`FitsFile`][fits-file] which can be moved to another thread.
+
+Created with [`into_send`][fits-file-into-send], which checks that the linked `cfitsio` is
+reentrant. The file can only be used by one thread at a time, so it does not implement `Sync`.
+As `cfitsio` shares its internal state between handles to the same file, the same file should
+not be opened in more than one thread at once.
+
+[fits-file]: ../fitsfile/struct.FitsFile.html
+[fits-file-into-send]: ../fitsfile/struct.FitsFile.html#method.into_send
+*/
+pub struct SendFitsFile(FitsFile);
+
+// Safety: a `SendFitsFile` is only created when the linked cfitsio was built with
+// `--enable-reentrant`, so the file can be used from a thread other than the one which opened it.
+// It is only used by one thread at a time, which `&mut self` enforces.
+unsafe impl Send for SendFitsFile {}
+
+impl SendFitsFile {
+    /// Return the underlying [`FitsFile`](../fitsfile/struct.FitsFile.html)
+    pub fn into_inner(self) -> FitsFile {
+        self.0
+    }
+}
+
+impl Deref for SendFitsFile {
+    type Target = FitsFile;
+
+    fn deref(&self) -> &FitsFile {
+        &self.0
+    }
+}
+
+impl DerefMut for SendFitsFile {
+    fn deref_mut(&mut self) -> &mut FitsFile {
+        &mut self.0
     }
 }
 
@@ -43,8 +149,181 @@ impl ThreadsafeFitsFile {
     /**
     Lock the underlying mutex to return exclusive access to the FitsFile.
     */
-    pub fn lock(&self) -> Result<MutexGuard<'_, FitsFile>> {
-        self.0.lock().map_err(From::from)
+    pub fn lock(&self) -> Result<ThreadsafeFitsFileGuard<'_>> {
+        // Always take the global lock before the file lock, so that two threads cannot each hold
+        // one of them while waiting for the other.
+        let global = if self.reentrant {
+            None
+        } else {
+            Some(
+                cfitsio_lock()
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner),
+            )
+        };
+        let file = self.file.lock()?;
+        Ok(ThreadsafeFitsFileGuard {
+            file,
+            _global: global,
+        })
+    }
+
+    /// Whether locking this file allows other files to be used concurrently
+    pub fn is_reentrant(&self) -> bool {
+        self.reentrant
+    }
+}
+
+/**
+Exclusive access to the [`FitsFile`][fits-file] inside a
+[`ThreadsafeFitsFile`][threadsafe-fitsfile], released when dropped.
+
+[fits-file]: ../fitsfile/struct.FitsFile.html
+[threadsafe-fitsfile]: struct.ThreadsafeFitsFile.html
+*/
+pub struct ThreadsafeFitsFileGuard<'a> {
+    file: MutexGuard<'a, FitsFile>,
+    _global: Option<MutexGuard<'static, ()>>,
+}
+
+impl Deref for ThreadsafeFitsFileGuard<'_> {
+    type Target = FitsFile;
+
+    fn deref(&self) -> &FitsFile {
+        &self.file
+    }
+}
+
+impl DerefMut for ThreadsafeFitsFileGuard<'_> {
+    fn deref_mut(&mut self) -> &mut FitsFile {
+        &mut self.file
+    }
+}
+
+/**
+Read only [`FitsFile`][fits-file] which can be used from many threads at once.
+
+Created with [`into_shared`][fits-file-into-shared], which reads the whole file into memory.
+Rather than locking a single file, each call to [`handle`](#method.handle) borrows a separate
+`cfitsio` handle to the copy in memory, opening a new one when none are free. Each handle keeps track of its own current HDU, so the only lock
+taken is around borrowing and returning handles, and threads read the file concurrently.
+Handles are returned to the pool when dropped, so the number of open handles never exceeds the
+number of threads using the file at once.
+
+[fits-file]: ../fitsfile/struct.FitsFile.html
+[fits-file-into-shared]: ../fitsfile/struct.FitsFile.html#method.into_shared
+*/
+pub struct SharedFitsFile {
+    bytes: Arc<Vec<u8>>,
+    strict: bool,
+    idle: Mutex<Vec<FitsFile>>,
+}
+
+// Safety: a `SharedFitsFile` is only created when the linked cfitsio was built with
+// `--enable-reentrant`, so separate handles may be used from separate threads. Each handle is
+// opened with the memory driver, which does not attach it to any other open handle (unlike
+// opening the same path twice), and the bytes they read from are never written. Every handle is
+// only ever used by the one thread which borrowed it, and the pool of idle handles is behind a
+// mutex.
+unsafe impl Send for SharedFitsFile {}
+unsafe impl Sync for SharedFitsFile {}
+
+impl SharedFitsFile {
+    pub(crate) fn new(file: FitsFile, filename: &Path) -> Result<Self> {
+        let strict = file.is_strict();
+        // Close the file before reading its contents, so no handle shares state with it
+        drop(file);
+        let bytes = Arc::new(std::fs::read(filename)?);
+        let shared = SharedFitsFile {
+            bytes,
+            strict,
+            idle: Mutex::new(Vec::new()),
+        };
+        let file = shared.open_handle()?;
+        shared
+            .idle
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(file);
+        Ok(shared)
+    }
+
+    /// Open a new handle onto the bytes of the file
+    fn open_handle(&self) -> Result<FitsFile> {
+        let memory = MemoryBuffer::from_shared(Arc::clone(&self.bytes))?;
+        let mut file = FitsFile::from_memory(memory)?;
+        file.set_strict(self.strict);
+        Ok(file)
+    }
+
+    /**
+    Borrow a handle to the file, for use by the current thread only
+
+    The handle starts on whichever HDU it was left on by its previous user, so select an HDU
+    before reading.
+    */
+    pub fn handle(&self) -> Result<SharedFitsFileHandle<'_>> {
+        let idle = self
+            .idle
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .pop();
+        let file = match idle {
+            Some(file) => file,
+            None => self.open_handle()?,
+        };
+        Ok(SharedFitsFileHandle {
+            file: Some(file),
+            shared: self,
+        })
+    }
+
+    /// The number of handles which are open but not currently borrowed
+    pub fn num_idle_handles(&self) -> usize {
+        self.idle
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .len()
+    }
+}
+
+/**
+A handle to a [`SharedFitsFile`][shared-fits-file], returned to it when dropped.
+
+[shared-fits-file]: struct.SharedFitsFile.html
+*/
+pub struct SharedFitsFileHandle<'a> {
+    file: Option<FitsFile>,
+    shared: &'a SharedFitsFile,
+}
+
+impl Deref for SharedFitsFileHandle<'_> {
+    type Target = FitsFile;
+
+    fn deref(&self) -> &FitsFile {
+        self.file
+            .as_ref()
+            .expect("handle has already been returned")
+    }
+}
+
+impl DerefMut for SharedFitsFileHandle<'_> {
+    fn deref_mut(&mut self) -> &mut FitsFile {
+        self.file
+            .as_mut()
+            .expect("handle has already been returned")
+    }
+}
+
+impl Drop for SharedFitsFileHandle<'_> {
+    fn drop(&mut self) {
+        if let Some(file) = self.file.take() {
+            self.shared
+                .idle
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .push(file);
+        }
     }
 }
 
@@ -90,4 +369,128 @@ mod tests {
             handle.join().unwrap();
         }
     }
+
+    #[test]
+    fn test_locking_strategy_follows_cfitsio() {
+        let f = FitsFile::open("../testdata/full_example.fits")
+            .unwrap()
+            .threadsafe();
+        assert_eq!(f.is_reentrant(), crate::sys::capabilities().reentrant);
+    }
+
+    #[test]
+    fn test_send_file() {
+        let f = FitsFile::open("../testdata/full_example.fits").unwrap();
+        if !crate::sys::capabilities().reentrant {
+            assert!(f.into_send().is_err());
+            return;
+        }
+
+        let mut f = f.into_send().unwrap();
+        let name = thread::spawn(move || {
+            let hdu = f.hdu(1).unwrap();
+            let name: String = hdu.read_key(&mut f, "EXTNAME").unwrap();
+            (name, f)
+        });
+        let (name, f) = name.join().unwrap();
+        assert_eq!(name, "TESTEXT");
+        assert_eq!(f.into_inner().num_hdus().unwrap(), 2);
+    }
+
+    #[test]
+    fn test_lock_multiple_files() {
+        let f1 = FitsFile::open("../testdata/full_example.fits")
+            .unwrap()
+            .threadsafe();
+        let f2 = FitsFile::open("../testdata/image.fits")
+            .unwrap()
+            .threadsafe();
+
+        let handle = {
+            let f2 = f2.clone();
+            thread::spawn(move || {
+                let mut t = f2.lock().unwrap();
+                t.hdu(0).unwrap();
+            })
+        };
+        {
+            let mut t = f1.lock().unwrap();
+            t.hdu(1).unwrap();
+        }
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn test_shared_file() {
+        let shared = Arc::new(
+            FitsFile::open("../testdata/full_example.fits")
+                .unwrap()
+                .into_shared()
+                .unwrap(),
+        );
+
+        let expected: Vec<i32> = {
+            let mut f = shared.handle().unwrap();
+            let hdu = f.hdu(0).unwrap();
+            hdu.read_section(&mut f, 0, 100).unwrap()
+        };
+        let expected = Arc::new(expected);
+
+        let mut handles = Vec::new();
+        for i in 0..8 {
+            let expected = Arc::clone(&expected);
+            let shared = Arc::clone(&shared);
+            handles.push(thread::spawn(move || {
+                for _ in 0..20 {
+                    let mut f = shared.handle().unwrap();
+                    if i % 2 == 0 {
+                        let hdu = f.hdu(0).unwrap();
+                        let pixels: Vec<i32> = hdu.read_section(&mut f, 0, 100).unwrap();
+                        assert_eq!(&pixels, &*expected);
+                    } else {
+                        let hdu = f.hdu("TESTEXT").unwrap();
+                        let col: Vec<i32> = hdu.read_col(&mut f, "intcol").unwrap();
+                        assert_eq!(col.len(), 50);
+                    }
+                }
+            }));
+        }
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        // Every handle has been returned to the pool
+        let idle = shared.num_idle_handles();
+        assert!((1..=8).contains(&idle));
+        {
+            let _a = shared.handle().unwrap();
+            let _b = shared.handle().unwrap();
+        }
+        assert!(shared.num_idle_handles() >= 2);
+    }
+
+    #[test]
+    fn test_shared_file_is_read_into_memory() {
+        crate::testing::duplicate_test_file(|filename| {
+            let shared = FitsFile::open(filename).unwrap().into_shared().unwrap();
+            std::fs::remove_file(filename).unwrap();
+
+            let mut a = shared.handle().unwrap();
+            let mut b = shared.handle().unwrap();
+            let hdu = a.hdu("TESTEXT").unwrap();
+            b.hdu(0).unwrap();
+            assert_eq!(hdu.num_rows(&mut a).unwrap(), 50);
+        });
+    }
+
+    #[test]
+    fn test_shared_file_requires_read_only_path() {
+        crate::testing::duplicate_test_file(|filename| {
+            assert!(FitsFile::edit(filename).unwrap().into_shared().is_err());
+        });
+
+        let file = std::fs::File::open("../testdata/full_example.fits").unwrap();
+        let f = FitsFile::from_reader(file).unwrap();
+        assert!(f.into_shared().is_err());
+    }
 }
@@ -47,6 +47,7 @@ mod test {
         let image_info = HduInfo::ImageInfo {
             shape: Vec::new(),
             image_type: ImageType::LongLong,
+            raw_bitpix: 64,
         };
 
         let table_info = HduInfo::TableInfo {
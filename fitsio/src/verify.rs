@@ -0,0 +1,1109 @@
+/*!
+Verification of FITS files
+
+Files can be checked in two ways:
+
+* [`FitsFile::verify`](../struct.FitsFile.html#method.verify) checks the checksums and mandatory
+  keywords of every HDU of an open file, using `cfitsio`.
+* [`lint`](fn.lint.html) and [`lint_file`](fn.lint_file.html) check the raw contents of a file
+  against the FITS standard, in the style of `fitsverify`, reporting the location of every
+  problem.
+*/
+use crate::errors::{check_status, Error, FitsError, Result};
+use crate::fitsfile::FitsFile;
+use crate::headers::{key_exists, unquote, ReadsKey};
+use crate::longnam::*;
+use std::fmt;
+use std::fs;
+use std::ops::RangeInclusive;
+use std::path::Path;
+
+/// Status returned by cfitsio when moving past the last HDU
+const END_OF_FILE: i32 = 107;
+
+/// Result of checking a `CHECKSUM` or `DATASUM` keyword
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChecksumStatus {
+    /// The checksum matches the contents of the HDU
+    Valid,
+    /// The checksum keyword is not present
+    Missing,
+    /// The checksum does not match, so the HDU has been modified or corrupted
+    Invalid,
+}
+
+impl ChecksumStatus {
+    fn from_cfitsio(status: i32) -> Self {
+        match status {
+            1 => ChecksumStatus::Valid,
+            0 => ChecksumStatus::Missing,
+            _ => ChecksumStatus::Invalid,
+        }
+    }
+}
+
+/// Verification report of a single HDU
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HduVerification {
+    /// The HDU number within the fits file. Zero indexed.
+    pub index: usize,
+    /// The `EXTNAME` of the HDU, or an empty string if it is not set
+    pub name: String,
+    /// Status of the `CHECKSUM` keyword, covering the whole HDU
+    pub checksum: ChecksumStatus,
+    /// Status of the `DATASUM` keyword, covering the data unit
+    pub datasum: ChecksumStatus,
+    /// Mandatory keywords which are not present in the header
+    pub missing_keywords: Vec<String>,
+    /// Problems which prevented the HDU from being checked completely
+    pub errors: Vec<String>,
+}
+
+impl HduVerification {
+    /// Whether no problems were found in the HDU
+    ///
+    /// Missing checksums are not considered problems, as they are optional.
+    pub fn is_valid(&self) -> bool {
+        self.checksum != ChecksumStatus::Invalid
+            && self.datasum != ChecksumStatus::Invalid
+            && self.missing_keywords.is_empty()
+            && self.errors.is_empty()
+    }
+}
+
+/// Verification report of a whole file, returned by
+/// [`FitsFile::verify`](../struct.FitsFile.html#method.verify)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileVerification {
+    /// The report of each HDU, in order
+    pub hdus: Vec<HduVerification>,
+    /// Problems which prevented the file from being checked completely
+    pub errors: Vec<String>,
+}
+
+impl FileVerification {
+    /// Whether no problems were found in the file
+    pub fn is_valid(&self) -> bool {
+        self.errors.is_empty() && self.hdus.iter().all(HduVerification::is_valid)
+    }
+}
+
+/// Check every HDU of a file, collecting problems rather than stopping at the first
+pub(crate) fn verify_file(f: &mut FitsFile) -> FileVerification {
+    let mut report = FileVerification {
+        hdus: Vec::new(),
+        errors: Vec::new(),
+    };
+
+    let num_hdus = match f.num_hdus() {
+        Ok(num_hdus) => num_hdus,
+        Err(e) => {
+            report.errors.push(e.to_string());
+            return report;
+        }
+    };
+
+    for index in 0..num_hdus {
+        if let Err(e) = f.change_hdu(index) {
+            report
+                .errors
+                .push(format!("cannot move to hdu {}: {}", index, e));
+            break;
+        }
+        report.hdus.push(verify_current_hdu(f, index));
+    }
+
+    // cfitsio stops counting HDUs at the first one it cannot read, rather than reporting it
+    if report.errors.is_empty() {
+        match f.change_hdu(num_hdus) {
+            Err(Error::Fits(FitsError {
+                status: END_OF_FILE,
+                ..
+            })) => {}
+            Err(e) => report
+                .errors
+                .push(format!("cannot read hdu {}: {}", num_hdus, e)),
+            Ok(()) => {}
+        }
+    }
+    report
+}
+
+fn verify_current_hdu(f: &mut FitsFile, index: usize) -> HduVerification {
+    let mut report = HduVerification {
+        index,
+        name: String::read_key(f, "EXTNAME").unwrap_or_default(),
+        checksum: ChecksumStatus::Missing,
+        datasum: ChecksumStatus::Missing,
+        missing_keywords: Vec::new(),
+        errors: Vec::new(),
+    };
+
+    let mut datastatus = 0;
+    let mut hdustatus = 0;
+    let mut status = 0;
+    unsafe {
+        fits_verify_chksum(
+            f.fptr.as_mut() as *mut _,
+            &mut datastatus,
+            &mut hdustatus,
+            &mut status,
+        );
+    }
+    match check_status(status) {
+        Ok(()) => {
+            report.checksum = ChecksumStatus::from_cfitsio(hdustatus);
+            report.datasum = ChecksumStatus::from_cfitsio(datastatus);
+        }
+        Err(e) => report
+            .errors
+            .push(format!("cannot verify checksums: {}", e)),
+    }
+
+    match mandatory_keywords(f, index) {
+        Ok(keywords) => {
+            for keyword in keywords {
+                match key_exists(f, &keyword) {
+                    Ok(true) => {}
+                    Ok(false) => report.missing_keywords.push(keyword),
+                    Err(e) => report
+                        .errors
+                        .push(format!("cannot read keyword {}: {}", keyword, e)),
+                }
+            }
+        }
+        Err(e) => report
+            .errors
+            .push(format!("cannot determine mandatory keywords: {}", e)),
+    }
+
+    report
+}
+
+/// The keywords every HDU of this type must contain
+fn mandatory_keywords(f: &mut FitsFile, index: usize) -> Result<Vec<String>> {
+    let mut keywords: Vec<String> = if index == 0 {
+        vec!["SIMPLE".into(), "BITPIX".into(), "NAXIS".into()]
+    } else {
+        vec!["XTENSION".into(), "BITPIX".into(), "NAXIS".into()]
+    };
+
+    let naxis = i64::read_key(f, "NAXIS").unwrap_or(0);
+    keywords.extend((1..=naxis).map(|i| format!("NAXIS{}", i)));
+    if index == 0 {
+        return Ok(keywords);
+    }
+
+    keywords.push("PCOUNT".into());
+    keywords.push("GCOUNT".into());
+
+    let mut hdu_type = 0;
+    let mut status = 0;
+    unsafe {
+        fits_get_hdu_type(f.fptr.as_mut() as *mut _, &mut hdu_type, &mut status);
+    }
+    check_status(status)?;
+
+    // ASCII (1) and binary (2) tables describe each column
+    if hdu_type == 1 || hdu_type == 2 {
+        keywords.push("TFIELDS".into());
+        let tfields = i64::read_key(f, "TFIELDS").unwrap_or(0);
+        for i in 1..=tfields {
+            keywords.push(format!("TFORM{}", i));
+            if hdu_type == 1 {
+                keywords.push(format!("TBCOL{}", i));
+            }
+        }
+    }
+    Ok(keywords)
+}
+
+/// Size of a FITS block, which headers and data units are padded to
+const BLOCK_SIZE: usize = 2880;
+
+/// Size of a header card
+const CARD_SIZE: usize = 80;
+
+/// Keywords which may appear any number of times in a header
+const REPEATABLE_KEYWORDS: &[&str] = &["", "COMMENT", "HISTORY", "CONTINUE", "HIERARCH"];
+
+/// How serious a problem found by [`lint`](fn.lint.html) is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    /// The file can be read, but does not follow a recommendation of the standard
+    Warning,
+    /// The file does not conform to the standard
+    Error,
+}
+
+/// A problem found by [`lint`](fn.lint.html)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// How serious the problem is
+    pub severity: Severity,
+    /// The HDU number within the fits file. Zero indexed.
+    pub hdu: usize,
+    /// The number of the header card within the HDU, starting from 1, if the problem is in a card
+    pub card: Option<usize>,
+    /// Description of the problem
+    pub message: String,
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        match self.card {
+            Some(card) => write!(
+                f,
+                "{}: hdu {}, card {}: {}",
+                severity, self.hdu, card, self.message
+            ),
+            None => write!(f, "{}: hdu {}: {}", severity, self.hdu, self.message),
+        }
+    }
+}
+
+/**
+Check a file on disk against the FITS standard
+
+See [`lint`](fn.lint.html) for the checks which are made.
+
+# Example
+
+```rust
+# fn main() -> Result<(), Box<dyn std::error::Error>> {
+# let filename = "../testdata/full_example.fits";
+use fitsio::verify::{lint_file, Severity};
+
+let diagnostics = lint_file(filename)?;
+for diagnostic in &diagnostics {
+    println!("{}", diagnostic);
+}
+assert!(diagnostics.iter().all(|d| d.severity != Severity::Error));
+# Ok(())
+# }
+```
+*/
+pub fn lint_file<P: AsRef<Path>>(path: P) -> Result<Vec<Diagnostic>> {
+    let data = fs::read(path)?;
+    Ok(lint(&data))
+}
+
+/**
+Check the contents of a FITS file against the FITS standard
+
+The file is parsed directly, rather than through `cfitsio`, so files which `cfitsio` refuses to
+open can still be checked. Every problem found is returned, in the order it appears in the file.
+The checks are:
+
+* the file is made of whole 2880 byte blocks,
+* header cards only contain printable ASCII characters and have valid keyword names,
+* the mandatory keywords of each type of HDU are present, in the required order, with valid
+  values,
+* keywords are not repeated, other than commentary keywords,
+* every header ends with an `END` card, and the rest of its last block is blank, and
+* every data unit is complete and padded with zeros, or blanks for ASCII tables.
+
+Checking stops at the first HDU whose size cannot be determined.
+*/
+pub fn lint(data: &[u8]) -> Vec<Diagnostic> {
+    let mut linter = Linter {
+        diagnostics: Vec::new(),
+        hdu: 0,
+    };
+
+    if data.is_empty() {
+        linter.error(None, "file is empty");
+        return linter.diagnostics;
+    }
+    if data.len() % BLOCK_SIZE != 0 {
+        linter.error(
+            None,
+            format!(
+                "file size of {} bytes is not a multiple of {} bytes",
+                data.len(),
+                BLOCK_SIZE
+            ),
+        );
+    }
+
+    let mut offset = 0;
+    while offset < data.len() {
+        match linter.lint_hdu(&data[offset..]) {
+            Some(size) => offset += size,
+            None => break,
+        }
+        linter.hdu += 1;
+    }
+    linter.diagnostics
+}
+
+/// A header card split into its keyword and value
+struct Card {
+    keyword: String,
+    value: Option<String>,
+}
+
+impl Card {
+    fn parse(card: &str) -> Self {
+        let keyword = card[..8.min(card.len())].trim_end().to_string();
+        let value = if card.len() > 10 && &card[8..10] == "= " {
+            let value = card[10..].trim_start();
+            if value.starts_with('\'') {
+                Some(value[..quoted_len(value).unwrap_or(value.len())].to_string())
+            } else {
+                value
+                    .split('/')
+                    .next()
+                    .map(|value| value.trim().to_string())
+            }
+        } else {
+            None
+        };
+        Card { keyword, value }
+    }
+
+    /// The value as an integer
+    fn int(&self) -> Option<i64> {
+        self.value.as_ref().and_then(|value| value.parse().ok())
+    }
+}
+
+/// Length of a quoted string value including both quotes, or `None` if it is not terminated
+fn quoted_len(value: &str) -> Option<usize> {
+    let bytes = value.as_bytes();
+    let mut i = 1;
+    while i < bytes.len() {
+        if bytes[i] == b'\'' {
+            // Two quotes in a row are an escaped quote
+            if bytes.get(i + 1) == Some(&b'\'') {
+                i += 2;
+                continue;
+            }
+            return Some(i + 1);
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Number of the first card with the keyword, starting from 1
+fn card_number(cards: &[Card], keyword: &str) -> Option<usize> {
+    cards
+        .iter()
+        .position(|card| card.keyword == keyword)
+        .map(|i| i + 1)
+}
+
+fn is_valid_keyword(keyword: &str) -> bool {
+    keyword
+        .bytes()
+        .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == b'-' || c == b'_')
+}
+
+struct Linter {
+    diagnostics: Vec<Diagnostic>,
+    hdu: usize,
+}
+
+impl Linter {
+    fn report<S: Into<String>>(&mut self, severity: Severity, card: Option<usize>, message: S) {
+        self.diagnostics.push(Diagnostic {
+            severity,
+            hdu: self.hdu,
+            card,
+            message: message.into(),
+        });
+    }
+
+    fn error<S: Into<String>>(&mut self, card: Option<usize>, message: S) {
+        self.report(Severity::Error, card, message)
+    }
+
+    fn warning<S: Into<String>>(&mut self, card: Option<usize>, message: S) {
+        self.report(Severity::Warning, card, message)
+    }
+
+    /// Check the HDU at the start of `data`, returning its padded size if it could be determined
+    fn lint_hdu(&mut self, data: &[u8]) -> Option<usize> {
+        let cards = self.lint_header_cards(data)?;
+        // Cards are followed by the END card
+        let header_size = round_up_to_block((cards.len() + 1) * CARD_SIZE);
+        if header_size > data.len() {
+            self.error(None, "header is truncated");
+            return None;
+        }
+        if data[(cards.len() + 1) * CARD_SIZE..header_size]
+            .iter()
+            .any(|&c| c != b' ')
+        {
+            self.error(
+                None,
+                "header fill area after END contains non-blank characters",
+            );
+        }
+
+        let layout = self.lint_mandatory_keywords(&cards)?;
+        let data_size = match layout.data_size() {
+            Some(data_size) => data_size,
+            None => {
+                self.error(None, "size of the data unit is too large");
+                return None;
+            }
+        };
+        let available = data.len() - header_size;
+        if data_size > available {
+            self.error(
+                None,
+                format!(
+                    "data unit is truncated, expected {} bytes but found {}",
+                    data_size, available
+                ),
+            );
+            return None;
+        }
+
+        let padded_size = round_up_to_block(data_size).min(available);
+        let fill = &data[header_size + data_size..header_size + padded_size];
+        if fill.iter().any(|&c| c != layout.fill_byte) {
+            self.error(
+                None,
+                if layout.fill_byte == b' ' {
+                    "data fill area contains non-blank characters"
+                } else {
+                    "data fill area contains non-zero bytes"
+                },
+            );
+        }
+
+        Some(header_size + padded_size)
+    }
+
+    /// Check each card of a header, returning the cards before `END`
+    fn lint_header_cards(&mut self, data: &[u8]) -> Option<Vec<Card>> {
+        let mut cards: Vec<Card> = Vec::new();
+        for (i, raw) in data.chunks_exact(CARD_SIZE).enumerate() {
+            let number = i + 1;
+            if raw.iter().any(|&c| !(b' '..=b'~').contains(&c)) {
+                self.error(Some(number), "card contains non-printable ASCII characters");
+                cards.push(Card {
+                    keyword: String::new(),
+                    value: None,
+                });
+                continue;
+            }
+
+            // Only printable ASCII characters remain, so the card is valid UTF-8
+            let card = Card::parse(std::str::from_utf8(raw).unwrap());
+            if card.keyword == "END" {
+                if raw[3..].iter().any(|&c| c != b' ') {
+                    self.error(Some(number), "END card contains non-blank characters");
+                }
+                return Some(cards);
+            }
+
+            if raw[..8]
+                .iter()
+                .skip_while(|&&c| c != b' ')
+                .any(|&c| c != b' ')
+                || !is_valid_keyword(&card.keyword)
+            {
+                self.error(
+                    Some(number),
+                    format!(
+                        "invalid keyword name {:?}",
+                        &std::str::from_utf8(&raw[..8]).unwrap()
+                    ),
+                );
+            }
+            if let Some(ref value) = card.value {
+                if value.starts_with('\'') && quoted_len(value).is_none() {
+                    self.error(Some(number), "string value is missing its closing quote");
+                }
+            }
+            if !REPEATABLE_KEYWORDS.contains(&card.keyword.as_str())
+                && cards.iter().any(|other| other.keyword == card.keyword)
+            {
+                self.warning(
+                    Some(number),
+                    format!("keyword {} appears more than once", card.keyword),
+                );
+            }
+            cards.push(card);
+        }
+
+        self.error(None, "END keyword not found");
+        None
+    }
+
+    /**
+    Find a mandatory keyword which should be at `position`
+
+    A keyword which is present but out of order is reported, and its value is still used.
+    */
+    fn mandatory<'a>(
+        &mut self,
+        cards: &'a [Card],
+        position: usize,
+        keyword: &str,
+    ) -> Option<&'a Card> {
+        match cards.get(position) {
+            Some(card) if card.keyword == keyword => return Some(card),
+            _ => {}
+        }
+        match card_number(cards, keyword) {
+            Some(number) => {
+                self.error(
+                    Some(number),
+                    format!(
+                        "mandatory keyword {} is out of order, it should be card {}",
+                        keyword,
+                        position + 1
+                    ),
+                );
+                Some(&cards[number - 1])
+            }
+            None => {
+                self.error(None, format!("mandatory keyword {} is missing", keyword));
+                None
+            }
+        }
+    }
+
+    /// Read a mandatory integer keyword, which must be within `range`
+    fn mandatory_int(
+        &mut self,
+        cards: &[Card],
+        position: usize,
+        keyword: &str,
+        range: RangeInclusive<i64>,
+    ) -> Option<i64> {
+        let card = self.mandatory(cards, position, keyword)?;
+        match card.int() {
+            Some(value) if range.contains(&value) => Some(value),
+            _ => {
+                self.error(
+                    card_number(cards, keyword),
+                    format!(
+                        "{} must be an integer between {} and {}",
+                        keyword,
+                        range.start(),
+                        range.end()
+                    ),
+                );
+                None
+            }
+        }
+    }
+
+    /// Check the mandatory keywords, returning the layout of the data unit they describe
+    fn lint_mandatory_keywords(&mut self, cards: &[Card]) -> Option<DataLayout> {
+        let extension = if self.hdu == 0 {
+            match cards.first() {
+                Some(card) if card.keyword == "SIMPLE" => {
+                    if card.value.as_deref() != Some("T") {
+                        self.warning(
+                            Some(1),
+                            "SIMPLE is not T, so the file does not conform to the standard",
+                        );
+                    }
+                }
+                _ => {
+                    self.error(Some(1), "first keyword of the primary hdu must be SIMPLE");
+                    return None;
+                }
+            }
+            None
+        } else {
+            match cards.first() {
+                Some(card) if card.keyword == "XTENSION" => {
+                    let extension = card.value.as_deref().map(unquote).unwrap_or_default();
+                    if !["IMAGE", "TABLE", "BINTABLE"].contains(&extension.as_str()) {
+                        self.warning(
+                            Some(1),
+                            format!("non-standard extension type {:?}", extension),
+                        );
+                    }
+                    Some(extension)
+                }
+                _ => {
+                    self.error(Some(1), "first keyword of an extension must be XTENSION");
+                    return None;
+                }
+            }
+        };
+        let is_table = matches!(extension.as_deref(), Some("TABLE") | Some("BINTABLE"));
+
+        let bitpix = self.mandatory_int(cards, 1, "BITPIX", -64..=64)?;
+        if ![8, 16, 32, 64, -32, -64].contains(&bitpix) {
+            self.error(Some(2), format!("invalid BITPIX value {}", bitpix));
+            return None;
+        }
+        let naxis = self.mandatory_int(cards, 2, "NAXIS", 0..=999)? as usize;
+        let mut axes = Vec::with_capacity(naxis);
+        for i in 1..=naxis {
+            let keyword = format!("NAXIS{}", i);
+            axes.push(self.mandatory_int(cards, 2 + i, &keyword, 0..=i64::MAX)? as usize);
+        }
+        if is_table && (bitpix != 8 || naxis != 2) {
+            self.error(None, "tables must have BITPIX = 8 and NAXIS = 2");
+        }
+
+        let mut layout = DataLayout {
+            bitpix,
+            axes,
+            pcount: 0,
+            gcount: 1,
+            random_groups: false,
+            fill_byte: 0,
+        };
+
+        match extension {
+            None => {
+                let groups = cards.iter().find(|card| card.keyword == "GROUPS");
+                if layout.axes.first() == Some(&0)
+                    && groups.and_then(|card| card.value.as_deref()) == Some("T")
+                {
+                    layout.random_groups = true;
+                    layout.pcount = cards
+                        .iter()
+                        .find(|card| card.keyword == "PCOUNT")
+                        .and_then(Card::int)
+                        .unwrap_or(0) as usize;
+                    layout.gcount = cards
+                        .iter()
+                        .find(|card| card.keyword == "GCOUNT")
+                        .and_then(Card::int)
+                        .unwrap_or(1) as usize;
+                }
+            }
+            Some(ref extension) => {
+                let pcount = self.mandatory_int(cards, 3 + naxis, "PCOUNT", 0..=i64::MAX)?;
+                let gcount = self.mandatory_int(cards, 4 + naxis, "GCOUNT", 0..=i64::MAX)?;
+                if (extension == "IMAGE" || extension == "TABLE") && pcount != 0 {
+                    self.error(
+                        card_number(cards, "PCOUNT"),
+                        format!("PCOUNT must be 0 in a {} extension", extension),
+                    );
+                }
+                if (is_table || extension == "IMAGE") && gcount != 1 {
+                    self.error(
+                        card_number(cards, "GCOUNT"),
+                        format!("GCOUNT must be 1 in a {} extension", extension),
+                    );
+                }
+                layout.pcount = pcount as usize;
+                layout.gcount = gcount as usize;
+
+                if is_table {
+                    let tfields = self.mandatory_int(cards, 5 + naxis, "TFIELDS", 0..=999)?;
+                    for i in 1..=tfields {
+                        let mut keywords = vec![format!("TFORM{}", i)];
+                        if extension == "TABLE" {
+                            keywords.push(format!("TBCOL{}", i));
+                        }
+                        for keyword in keywords {
+                            if !cards.iter().any(|card| card.keyword == keyword) {
+                                self.error(
+                                    None,
+                                    format!("mandatory keyword {} is missing", keyword),
+                                );
+                            }
+                        }
+                    }
+                }
+                if extension == "TABLE" {
+                    layout.fill_byte = b' ';
+                }
+            }
+        }
+        Some(layout)
+    }
+}
+
+/// The size of a data unit, as described by the mandatory keywords
+struct DataLayout {
+    bitpix: i64,
+    axes: Vec<usize>,
+    pcount: usize,
+    gcount: usize,
+    random_groups: bool,
+    /// Byte used to pad the data unit to a whole block
+    fill_byte: u8,
+}
+
+impl DataLayout {
+    /// Size of the data unit in bytes, excluding padding, or `None` if it overflows
+    fn data_size(&self) -> Option<usize> {
+        if self.axes.is_empty() {
+            return Some(0);
+        }
+        // NAXIS1 is zero for random groups, and is not part of the size
+        let axes = if self.random_groups {
+            &self.axes[1..]
+        } else {
+            &self.axes[..]
+        };
+        let pixels = axes
+            .iter()
+            .try_fold(1usize, |pixels, &axis| pixels.checked_mul(axis))?;
+        (self.bitpix.unsigned_abs() as usize / 8)
+            .checked_mul(self.gcount)?
+            .checked_mul(self.pcount.checked_add(pixels)?)
+    }
+}
+
+fn round_up_to_block(size: usize) -> usize {
+    (size + BLOCK_SIZE - 1) / BLOCK_SIZE * BLOCK_SIZE
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::images::{ImageDescription, ImageType};
+    use crate::testing::{duplicate_test_file, with_temp_file};
+    use std::ffi;
+
+    #[test]
+    fn test_verify_file() {
+        let mut f = FitsFile::open("../testdata/full_example.fits").unwrap();
+        let report = f.verify().unwrap();
+        assert!(report.is_valid(), "{:?}", report);
+        assert_eq!(report.hdus.len(), 2);
+        assert_eq!(report.hdus[1].name, "TESTEXT");
+        assert_eq!(report.hdus[1].checksum, ChecksumStatus::Missing);
+        assert_eq!(report.hdus[1].datasum, ChecksumStatus::Missing);
+    }
+
+    #[test]
+    fn test_verify_checksums() {
+        duplicate_test_file(|filename| {
+            let mut f = FitsFile::edit(filename).unwrap();
+            for index in 0..2 {
+                f.change_hdu(index).unwrap();
+                let mut status = 0;
+                unsafe {
+                    crate::sys::ffpcks(f.as_raw(), &mut status);
+                }
+                assert_eq!(status, 0);
+            }
+
+            let report = f.verify().unwrap();
+            assert!(report.is_valid(), "{:?}", report);
+            assert_eq!(report.hdus[0].checksum, ChecksumStatus::Valid);
+            assert_eq!(report.hdus[1].datasum, ChecksumStatus::Valid);
+
+            /* Changing the header after the checksum was written invalidates it */
+            let hdu = f.hdu(1).unwrap();
+            hdu.write_key(&mut f, "FOO", 1i64).unwrap();
+            let report = f.verify().unwrap();
+            assert!(!report.is_valid());
+            assert!(report.hdus[0].is_valid());
+            assert_eq!(report.hdus[1].checksum, ChecksumStatus::Invalid);
+            assert_eq!(report.hdus[1].datasum, ChecksumStatus::Valid);
+        });
+    }
+
+    /// Delete a keyword from the given HDU, bypassing the checks of `FitsHdu`
+    fn delete_key(f: &mut FitsFile, index: usize, name: &str) {
+        f.change_hdu(index).unwrap();
+        let name = ffi::CString::new(name).unwrap();
+        let mut status = 0;
+        unsafe {
+            crate::sys::ffdkey(f.as_raw(), name.as_ptr(), &mut status);
+        }
+        assert_eq!(status, 0);
+    }
+
+    #[test]
+    fn test_verify_missing_keywords() {
+        with_temp_file(|filename| {
+            {
+                let mut f = FitsFile::create(filename).open().unwrap();
+                let description = ImageDescription {
+                    data_type: ImageType::Long,
+                    dimensions: &[10, 10],
+                };
+                f.create_image("IMAGE".to_string(), &description).unwrap();
+                delete_key(&mut f, 1, "PCOUNT");
+            }
+
+            let mut f = FitsFile::open(filename).unwrap();
+            let report = f.verify().unwrap();
+            assert!(!report.is_valid());
+            assert!(report.hdus[0].is_valid());
+            assert_eq!(report.hdus[1].name, "IMAGE");
+            assert_eq!(report.hdus[1].missing_keywords, vec!["PCOUNT".to_string()]);
+        });
+    }
+
+    #[test]
+    fn test_verify_unreadable_hdu() {
+        duplicate_test_file(|filename| {
+            {
+                let mut f = FitsFile::edit(filename).unwrap();
+                delete_key(&mut f, 1, "TFORM2");
+            }
+
+            /* The table can no longer be read, which is reported rather than skipped */
+            let mut f = FitsFile::open(filename).unwrap();
+            let report = f.verify().unwrap();
+            assert!(!report.is_valid());
+            assert_eq!(report.hdus.len(), 1);
+            assert_eq!(report.errors.len(), 1);
+            assert!(report.errors[0].starts_with("cannot read hdu 1"));
+        });
+    }
+
+    /// Format a header card with a value
+    fn card(keyword: &str, value: &str) -> String {
+        format!("{:<8}= {:>20}", keyword, value)
+    }
+
+    /// Build a header from cards, ending with `END` and padded to a whole block
+    fn header(cards: &[String]) -> Vec<u8> {
+        let mut data: Vec<u8> = cards
+            .iter()
+            .chain(Some(&"END".to_string()))
+            .flat_map(|card| format!("{:<80}", card).into_bytes())
+            .collect();
+        data.resize(round_up_to_block(data.len()), b' ');
+        data
+    }
+
+    fn primary(extra: &[String]) -> Vec<u8> {
+        let mut cards = vec![card("SIMPLE", "T"), card("BITPIX", "8"), card("NAXIS", "0")];
+        cards.extend_from_slice(extra);
+        header(&cards)
+    }
+
+    fn messages(diagnostics: &[Diagnostic]) -> Vec<(Severity, Option<usize>, &str)> {
+        diagnostics
+            .iter()
+            .map(|d| (d.severity, d.card, d.message.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn test_lint_file() {
+        let diagnostics = lint_file("../testdata/full_example.fits").unwrap();
+        assert!(diagnostics.is_empty(), "{:?}", diagnostics);
+        assert!(lint_file("../testdata/no_such_file.fits").is_err());
+
+        assert!(lint(&primary(&[])).is_empty());
+        assert_eq!(
+            messages(&lint(&[])),
+            vec![(Severity::Error, None, "file is empty")]
+        );
+    }
+
+    #[test]
+    fn test_lint_mandatory_keywords() {
+        let data = header(&[card("SIMPLE", "T"), card("NAXIS", "0"), card("BITPIX", "8")]);
+        assert_eq!(
+            messages(&lint(&data)),
+            vec![
+                (
+                    Severity::Error,
+                    Some(3),
+                    "mandatory keyword BITPIX is out of order, it should be card 2"
+                ),
+                (
+                    Severity::Error,
+                    Some(2),
+                    "mandatory keyword NAXIS is out of order, it should be card 3"
+                ),
+            ]
+        );
+
+        let data = header(&[
+            card("SIMPLE", "T"),
+            card("BITPIX", "12"),
+            card("NAXIS", "0"),
+        ]);
+        assert_eq!(
+            messages(&lint(&data)),
+            vec![(Severity::Error, Some(2), "invalid BITPIX value 12")]
+        );
+
+        /* Checking stops when the size of the data is unknown */
+        let mut data = primary(&[]);
+        data.extend(header(&[
+            card("XTENSION", "'IMAGE   '"),
+            card("BITPIX", "16"),
+            card("NAXIS", "1"),
+            card("PCOUNT", "0"),
+            card("GCOUNT", "1"),
+        ]));
+        data.extend(primary(&[]));
+        let diagnostics = lint(&data);
+        assert_eq!(
+            messages(&diagnostics),
+            vec![(Severity::Error, None, "mandatory keyword NAXIS1 is missing")]
+        );
+        assert_eq!(diagnostics[0].hdu, 1);
+
+        let mut data = primary(&[]);
+        data.extend(header(&[
+            card("XTENSION", "'BINTABLE'"),
+            card("BITPIX", "8"),
+            card("NAXIS", "2"),
+            card("NAXIS1", "0"),
+            card("NAXIS2", "0"),
+            card("PCOUNT", "0"),
+            card("GCOUNT", "2"),
+            card("TFIELDS", "2"),
+            card("TFORM1", "'J       '"),
+        ]));
+        assert_eq!(
+            messages(&lint(&data)),
+            vec![
+                (
+                    Severity::Error,
+                    Some(7),
+                    "GCOUNT must be 1 in a BINTABLE extension"
+                ),
+                (Severity::Error, None, "mandatory keyword TFORM2 is missing"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lint_header_cards() {
+        let mut data = primary(&[
+            card("lower", "1"),
+            card("OBJECT", "'M31"),
+            card("EXPTIME", "1.0"),
+            card("EXPTIME", "2.0"),
+            "COMMENT one".to_string(),
+            "COMMENT two".to_string(),
+            card("NOTE", "'caf   '"),
+        ]);
+        // Replace the 'f' of the last value with a non-ASCII character
+        let last_card = 9 * CARD_SIZE..10 * CARD_SIZE;
+        let position = data[last_card.clone()]
+            .iter()
+            .position(|&c| c == b'f')
+            .unwrap();
+        data[last_card.start + position] = 0xe9;
+        data[BLOCK_SIZE - 1] = b'x';
+
+        assert_eq!(
+            messages(&lint(&data)),
+            vec![
+                (
+                    Severity::Error,
+                    Some(4),
+                    "invalid keyword name \"lower   \""
+                ),
+                (
+                    Severity::Error,
+                    Some(5),
+                    "string value is missing its closing quote"
+                ),
+                (
+                    Severity::Warning,
+                    Some(7),
+                    "keyword EXPTIME appears more than once"
+                ),
+                (
+                    Severity::Error,
+                    Some(10),
+                    "card contains non-printable ASCII characters"
+                ),
+                (
+                    Severity::Error,
+                    None,
+                    "header fill area after END contains non-blank characters"
+                ),
+            ]
+        );
+
+        let data = header(&[card("SIMPLE", "T")])[..CARD_SIZE].to_vec();
+        assert_eq!(
+            messages(&lint(&data)),
+            vec![
+                (
+                    Severity::Error,
+                    None,
+                    "file size of 80 bytes is not a multiple of 2880 bytes"
+                ),
+                (Severity::Error, None, "END keyword not found"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lint_data_unit() {
+        let mut data = header(&[
+            card("SIMPLE", "T"),
+            card("BITPIX", "16"),
+            card("NAXIS", "2"),
+            card("NAXIS1", "10"),
+            card("NAXIS2", "3"),
+        ]);
+        let header_size = data.len();
+        data.extend(vec![1; 60]);
+        data.resize(header_size + BLOCK_SIZE, 0);
+        assert!(lint(&data).is_empty());
+
+        data[header_size + 60] = 1;
+        assert_eq!(
+            messages(&lint(&data)),
+            vec![(
+                Severity::Error,
+                None,
+                "data fill area contains non-zero bytes"
+            )]
+        );
+
+        data.truncate(header_size);
+        assert_eq!(
+            messages(&lint(&data)),
+            vec![(
+                Severity::Error,
+                None,
+                "data unit is truncated, expected 60 bytes but found 0"
+            )]
+        );
+
+        let data = header(&[
+            card("SIMPLE", "T"),
+            card("BITPIX", "64"),
+            card("NAXIS", "2"),
+            card("NAXIS1", &i64::MAX.to_string()),
+            card("NAXIS2", &i64::MAX.to_string()),
+        ]);
+        assert_eq!(
+            messages(&lint(&data)),
+            vec![(Severity::Error, None, "size of the data unit is too large")]
+        );
+    }
+
+    #[test]
+    fn test_diagnostic_display() {
+        let diagnostic = Diagnostic {
+            severity: Severity::Warning,
+            hdu: 1,
+            card: Some(12),
+            message: "keyword EXPTIME appears more than once".to_string(),
+        };
+        assert_eq!(
+            diagnostic.to_string(),
+            "warning: hdu 1, card 12: keyword EXPTIME appears more than once"
+        );
+
+        let diagnostic = Diagnostic {
+            card: None,
+            severity: Severity::Error,
+            ..diagnostic
+        };
+        assert_eq!(
+            diagnostic.to_string(),
+            "error: hdu 1: keyword EXPTIME appears more than once"
+        );
+    }
+}
@@ -1,8 +1,10 @@
 /* Custom derives
 */
-use fitsio::tables::FitsRow;
+use fitsio::headers::FitsHeader;
+use fitsio::tables::{ColumnDataType, ColumnDescription, FitsRow};
 use fitsio::FitsFile;
-use fitsio_derive::FitsRow;
+use fitsio_derive::{FitsHeader, FitsRow};
+use std::convert::TryFrom;
 
 #[derive(Default, FitsRow)]
 struct Row {
@@ -22,3 +24,393 @@ fn test_read_row_as_struct() {
     assert_eq!(result.intfoo, 16);
     assert_eq!(result.foobar, "value4");
 }
+
+#[derive(Default, FitsRow)]
+#[fitsio(writable)]
+#[fitsio(rename_all = "UPPERCASE")]
+struct UppercaseRow {
+    obj_id: i32,
+    #[fitsio(colname = "MagValue")]
+    magnitude: f32,
+    #[fitsio(skip)]
+    not_a_column: Vec<u8>,
+    #[fitsio(colname = "LABEL", with = "parse_label")]
+    label_number: usize,
+}
+
+#[derive(Default, FitsRow)]
+#[fitsio(rename_all = "PascalCase")]
+struct PascalRow {
+    mag_value: f32,
+}
+
+fn parse_label(value: String) -> fitsio::errors::Result<usize> {
+    value
+        .trim_start_matches("obj")
+        .parse()
+        .map_err(|_| "invalid label".into())
+}
+
+#[test]
+fn test_read_row_with_attributes() {
+    let tdir = tempfile::Builder::new()
+        .prefix("fitsio-")
+        .tempdir()
+        .unwrap();
+    let filename = tdir.path().join("test.fits");
+    let mut f = FitsFile::create(filename).open().unwrap();
+
+    let columns = &[
+        ColumnDescription::new("OBJ_ID")
+            .with_type(ColumnDataType::Int)
+            .create()
+            .unwrap(),
+        ColumnDescription::new("MagValue")
+            .with_type(ColumnDataType::Float)
+            .create()
+            .unwrap(),
+        ColumnDescription::new("LABEL")
+            .with_type(ColumnDataType::String)
+            .that_repeats(8)
+            .create()
+            .unwrap(),
+    ];
+    let hdu = f.create_table("DATA", columns).unwrap();
+    hdu.write_col(&mut f, "OBJ_ID", &[10, 11]).unwrap();
+    hdu.write_col(&mut f, "MagValue", &[12.5f32, 13.5]).unwrap();
+    hdu.write_col(&mut f, "LABEL", &["obj10".to_string(), "obj11".to_string()])
+        .unwrap();
+
+    let result: UppercaseRow = hdu.row(&mut f, 1).unwrap();
+    assert_eq!(result.obj_id, 11);
+    assert_eq!(result.magnitude, 13.5);
+    assert!(result.not_a_column.is_empty());
+    assert_eq!(result.label_number, 11);
+
+    let result: PascalRow = hdu.row(&mut f, 0).unwrap();
+    assert_eq!(result.mag_value, 12.5);
+
+    let results: Vec<UppercaseRow> = hdu.rows_range(&mut f, 0..2).unwrap();
+    assert_eq!(results[0].obj_id, 10);
+    assert_eq!(results[1].magnitude, 13.5);
+    assert_eq!(results[1].label_number, 11);
+}
+
+#[derive(Default, FitsRow)]
+struct NullableRow {
+    id: i32,
+    flux: Option<f64>,
+    count: Option<i32>,
+}
+
+#[test]
+fn test_read_row_with_null_values() {
+    let tdir = tempfile::Builder::new()
+        .prefix("fitsio-")
+        .tempdir()
+        .unwrap();
+    let filename = tdir.path().join("test.fits");
+    {
+        let mut f = FitsFile::create(&filename).open().unwrap();
+        let columns = &[
+            ColumnDescription::new("id")
+                .with_type(ColumnDataType::Int)
+                .create()
+                .unwrap(),
+            ColumnDescription::new("flux")
+                .with_type(ColumnDataType::Double)
+                .create()
+                .unwrap(),
+            ColumnDescription::new("count")
+                .with_type(ColumnDataType::Int)
+                .create()
+                .unwrap(),
+        ];
+        let hdu = f.create_table("DATA", columns).unwrap();
+        hdu.write_key(&mut f, "TNULL3", -1).unwrap();
+        hdu.write_col(&mut f, "id", &[1, 2]).unwrap();
+        hdu.write_col(&mut f, "flux", &[f64::NAN, 10.0]).unwrap();
+        hdu.write_col(&mut f, "count", &[5, -1]).unwrap();
+    }
+
+    let mut f = FitsFile::open(&filename).unwrap();
+    let hdu = f.hdu("DATA").unwrap();
+
+    let result: NullableRow = hdu.row(&mut f, 0).unwrap();
+    assert_eq!(result.id, 1);
+    assert_eq!(result.flux, None);
+    assert_eq!(result.count, Some(5));
+
+    let result: NullableRow = hdu.row(&mut f, 1).unwrap();
+    assert_eq!(result.id, 2);
+    assert_eq!(result.flux, Some(10.0));
+    assert_eq!(result.count, None);
+
+    let results: Vec<NullableRow> = hdu.rows_range(&mut f, ..).unwrap();
+    assert_eq!(results[0].flux, None);
+    assert_eq!(results[1].count, None);
+}
+
+#[derive(Debug, PartialEq, FitsHeader)]
+struct Observation {
+    #[fitsio(key = "EXPTIME")]
+    exposure: f64,
+    #[fitsio(key = "OBJECT")]
+    target: String,
+    airmass: f32,
+    #[fitsio(skip)]
+    notes: Vec<String>,
+}
+
+#[test]
+fn test_header_struct_round_trip() {
+    let tdir = tempfile::Builder::new()
+        .prefix("fitsio-")
+        .tempdir()
+        .unwrap();
+    let filename = tdir.path().join("test.fits");
+    let mut f = FitsFile::create(filename).open().unwrap();
+    let hdu = f.primary_hdu().unwrap();
+
+    let observation = Observation {
+        exposure: 30.0,
+        target: "M31".to_string(),
+        airmass: 1.25,
+        notes: vec!["not written".to_string()],
+    };
+    observation.write_to_header(&hdu, &mut f).unwrap();
+
+    assert_eq!(hdu.read_key::<f64>(&mut f, "EXPTIME").unwrap(), 30.0);
+    assert_eq!(hdu.read_key::<String>(&mut f, "OBJECT").unwrap(), "M31");
+
+    let result = Observation::from_header(&hdu, &mut f).unwrap();
+    assert_eq!(result.exposure, 30.0);
+    assert_eq!(result.target, "M31");
+    assert_eq!(result.airmass, 1.25);
+    assert!(result.notes.is_empty());
+}
+
+#[derive(Debug, FitsHeader)]
+#[fitsio(rename_all = "UPPERCASE")]
+struct TestHeader {
+    inttest: i64,
+    dbltest: f64,
+}
+
+#[test]
+fn test_read_header_struct() {
+    let filename = "../testdata/full_example.fits";
+    let mut f = FitsFile::open(filename).unwrap();
+    let hdu = f.primary_hdu().unwrap();
+
+    let result = TestHeader::from_header(&hdu, &mut f).unwrap();
+    assert_eq!(result.inttest, 42);
+    assert_eq!(result.dbltest, 0.09375);
+}
+
+#[test]
+fn test_read_header_struct_missing_key() {
+    let filename = "../testdata/full_example.fits";
+    let mut f = FitsFile::open(filename).unwrap();
+    let hdu = f.hdu("TESTEXT").unwrap();
+
+    assert!(TestHeader::from_header(&hdu, &mut f).is_err());
+}
+
+#[derive(Debug, Default, PartialEq, FitsRow)]
+#[fitsio(writable)]
+#[fitsio(rename_all = "UPPERCASE")]
+struct EventRow {
+    time: f64,
+    energy: i32,
+    #[fitsio(colname = "DETECTOR")]
+    detector_name: String,
+    #[fitsio(skip)]
+    cache: Vec<u8>,
+}
+
+#[test]
+fn test_append_derived_rows() {
+    let tdir = tempfile::Builder::new()
+        .prefix("fitsio-")
+        .tempdir()
+        .unwrap();
+    let filename = tdir.path().join("test.fits");
+    let mut f = FitsFile::create(filename).open().unwrap();
+
+    let columns = &[
+        ColumnDescription::new("TIME")
+            .with_type(ColumnDataType::Double)
+            .create()
+            .unwrap(),
+        ColumnDescription::new("ENERGY")
+            .with_type(ColumnDataType::Int)
+            .create()
+            .unwrap(),
+        ColumnDescription::new("DETECTOR")
+            .with_type(ColumnDataType::String)
+            .that_repeats(8)
+            .create()
+            .unwrap(),
+    ];
+    let hdu = f.create_table("EVENTS", columns).unwrap();
+
+    let events: Vec<EventRow> = (0..1000)
+        .map(|i| EventRow {
+            time: f64::from(i) * 0.5,
+            energy: i,
+            detector_name: format!("det{}", i % 3),
+            cache: Vec::new(),
+        })
+        .collect();
+    let mut appender = hdu.table_appender(&mut f).unwrap();
+    for event in &events {
+        appender.append_row(event).unwrap();
+    }
+    let hdu = appender.finish().unwrap();
+
+    assert_eq!(hdu.num_rows(&mut f).unwrap(), events.len());
+    for i in [0, 1, 500, 999] {
+        let row: EventRow = hdu.row(&mut f, i).unwrap();
+        assert_eq!(row, events[i]);
+    }
+}
+
+#[test]
+fn test_append_converted_rows_fails() {
+    let tdir = tempfile::Builder::new()
+        .prefix("fitsio-")
+        .tempdir()
+        .unwrap();
+    let filename = tdir.path().join("test.fits");
+    let mut f = FitsFile::create(filename).open().unwrap();
+    let columns = &[
+        ColumnDescription::new("OBJ_ID")
+            .with_type(ColumnDataType::Int)
+            .create()
+            .unwrap(),
+        ColumnDescription::new("MagValue")
+            .with_type(ColumnDataType::Float)
+            .create()
+            .unwrap(),
+        ColumnDescription::new("LABEL")
+            .with_type(ColumnDataType::String)
+            .that_repeats(8)
+            .create()
+            .unwrap(),
+    ];
+    let hdu = f.create_table("DATA", columns).unwrap();
+
+    let mut appender = hdu.table_appender(&mut f).unwrap();
+    assert!(appender.append_row(&UppercaseRow::default()).is_err());
+    // Rows are only written when the derive is marked writable
+    assert!(appender.append_row(&PascalRow::default()).is_err());
+    assert_eq!(appender.num_rows(), 0);
+}
+
+#[derive(Debug, Default, PartialEq, FitsRow)]
+struct TupleRow(i32, f32, f64, #[fitsio(colname = "strcol")] String);
+
+#[test]
+fn test_read_tuple_struct_row() {
+    let filename = "../testdata/full_example.fits";
+    let mut f = FitsFile::open(filename).unwrap();
+    let tbl_hdu = f.hdu("TESTEXT").unwrap();
+
+    let result: TupleRow = tbl_hdu.row(&mut f, 4).unwrap();
+    assert_eq!(result.0, 16);
+    assert_eq!(result.3, "value4");
+
+    let results: Vec<TupleRow> = tbl_hdu.rows_range(&mut f, 3..5).unwrap();
+    assert_eq!(results[1], result);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Quality {
+    Good = 0,
+    Saturated = 1,
+    Cosmic = 4,
+}
+
+impl Default for Quality {
+    fn default() -> Self {
+        Quality::Good
+    }
+}
+
+impl TryFrom<i16> for Quality {
+    type Error = ();
+
+    fn try_from(value: i16) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Quality::Good),
+            1 => Ok(Quality::Saturated),
+            4 => Ok(Quality::Cosmic),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Debug, Default, PartialEq, FitsRow)]
+#[fitsio(writable)]
+struct FlaggedRow(f64, #[fitsio(repr = "i16")] Quality);
+
+#[derive(Debug, Default, PartialEq, FitsRow)]
+struct NamedFlaggedRow {
+    #[fitsio(colname = "FLUX")]
+    flux: f64,
+    #[fitsio(colname = "QUALITY", repr = "i16")]
+    quality: Quality,
+}
+
+#[test]
+fn test_tuple_struct_and_enum_columns() {
+    let tdir = tempfile::Builder::new()
+        .prefix("fitsio-")
+        .tempdir()
+        .unwrap();
+    let filename = tdir.path().join("test.fits");
+    let mut f = FitsFile::create(filename).open().unwrap();
+
+    let columns = &[
+        ColumnDescription::new("FLUX")
+            .with_type(ColumnDataType::Double)
+            .create()
+            .unwrap(),
+        ColumnDescription::new("QUALITY")
+            .with_type(ColumnDataType::Short)
+            .create()
+            .unwrap(),
+    ];
+    let hdu = f.create_table("DATA", columns).unwrap();
+
+    let rows = vec![
+        FlaggedRow(1.5, Quality::Good),
+        FlaggedRow(2.5, Quality::Cosmic),
+        FlaggedRow(3.5, Quality::Saturated),
+    ];
+    let mut appender = hdu.table_appender(&mut f).unwrap();
+    for row in &rows {
+        appender.append_row(row).unwrap();
+    }
+    let hdu = appender.finish().unwrap();
+
+    let quality: Vec<i16> = hdu.read_col(&mut f, "QUALITY").unwrap();
+    assert_eq!(quality, vec![0, 4, 1]);
+    for (i, expected) in rows.iter().enumerate() {
+        let row: FlaggedRow = hdu.row(&mut f, i).unwrap();
+        assert_eq!(&row, expected);
+    }
+    let row: NamedFlaggedRow = hdu.row(&mut f, 1).unwrap();
+    assert_eq!(row.quality, Quality::Cosmic);
+    let read: Vec<FlaggedRow> = hdu.rows_range(&mut f, ..).unwrap();
+    assert_eq!(read, rows);
+    let read: Vec<NamedFlaggedRow> = hdu.rows_range(&mut f, 1..3).unwrap();
+    assert_eq!(read[1].quality, Quality::Saturated);
+
+    // Values without a matching variant are errors
+    hdu.write_col_range(&mut f, "QUALITY", &[7i32], 0..1)
+        .unwrap();
+    assert!(hdu.row::<FlaggedRow>(&mut f, 0).is_err());
+    assert!(hdu.rows_range::<FlaggedRow, _>(&mut f, ..).is_err());
+}
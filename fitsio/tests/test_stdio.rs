@@ -0,0 +1,48 @@
+use fitsio::FitsFile;
+use std::io::{Cursor, Write};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+
+/// The `fitscopy` example, which `cargo test` builds alongside the tests
+fn fitscopy_path() -> PathBuf {
+    let mut path = std::env::current_exe().unwrap();
+    path.pop();
+    if path.ends_with("deps") {
+        path.pop();
+    }
+    path.join("examples")
+        .join(format!("fitscopy{}", std::env::consts::EXE_SUFFIX))
+}
+
+#[test]
+fn test_copy_through_pipes() {
+    let path = fitscopy_path();
+    assert!(
+        path.exists(),
+        "{} has not been built, run the tests with `cargo test`",
+        path.display()
+    );
+
+    let input = std::fs::read("../testdata/full_example.fits").unwrap();
+    let mut child = Command::new(&path)
+        .arg("1")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .unwrap();
+    let mut stdin = child.stdin.take().unwrap();
+    let writer = std::thread::spawn(move || stdin.write_all(&input));
+    let output = child.wait_with_output().unwrap();
+    writer.join().unwrap().unwrap();
+    assert!(output.status.success());
+
+    let mut copy = FitsFile::from_reader(Cursor::new(output.stdout)).unwrap();
+    assert_eq!(copy.num_hdus().unwrap(), 2);
+    let hdu = copy.hdu("TESTEXT").unwrap();
+    let copied: Vec<i32> = hdu.read_col(&mut copy, "intcol").unwrap();
+
+    let mut original = FitsFile::open("../testdata/full_example.fits").unwrap();
+    let hdu = original.hdu("TESTEXT").unwrap();
+    let expected: Vec<i32> = hdu.read_col(&mut original, "intcol").unwrap();
+    assert_eq!(copied, expected);
+}
@@ -0,0 +1,8 @@
+#![no_main]
+use fitsio::headers::HeaderCard;
+use libfuzzer_sys::fuzz_target;
+
+// Invalid UTF-8 is replaced rather than skipped, so non-ASCII characters reach the parser
+fuzz_target!(|data: &[u8]| {
+    let _ = String::from_utf8_lossy(data).parse::<HeaderCard>();
+});
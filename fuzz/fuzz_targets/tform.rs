@@ -0,0 +1,9 @@
+#![no_main]
+use fitsio::tables::ColumnDataDescription;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(s) = std::str::from_utf8(data) {
+        let _ = s.parse::<ColumnDataDescription>();
+    }
+});